@@ -2,15 +2,19 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
-use crate::datasources::{IDataSource, ITable};
-use crate::error::FuseQueryResult;
+use crate::datasources::{FunctionsTable, IDataSource, ITable};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{AggregateUDF, FunctionFactory, ScalarUDF, UDAFFunction, UDFFunction};
 
 pub struct FuseQueryContext {
     pub worker_threads: usize,
     default_db: Mutex<String>,
+    materialize_cte: Mutex<bool>,
     datasource: Arc<Mutex<dyn IDataSource>>,
+    // The user-defined functions of the context, the plans share the registry when it is not changed.
+    functions: RwLock<Arc<FunctionFactory>>,
 }
 
 impl FuseQueryContext {
@@ -18,7 +22,9 @@ impl FuseQueryContext {
         FuseQueryContext {
             worker_threads,
             default_db: Mutex::new("default".to_string()),
+            materialize_cte: Mutex::new(false),
             datasource,
+            functions: RwLock::new(Arc::new(FunctionFactory::create_empty())),
         }
     }
 
@@ -31,7 +37,60 @@ impl FuseQueryContext {
         Ok(())
     }
 
+    /// Whether the CTE referenced multiple times is executed only once.
+    pub fn get_materialize_cte(&self) -> FuseQueryResult<bool> {
+        Ok(*self.materialize_cte.lock()?)
+    }
+
+    pub fn set_materialize_cte(&self, materialize: bool) -> FuseQueryResult<()> {
+        *self.materialize_cte.lock()? = materialize;
+        Ok(())
+    }
+
     pub fn get_table(&self, db_name: &str, table_name: &str) -> FuseQueryResult<Arc<dyn ITable>> {
-        self.datasource.lock()?.get_table(db_name, table_name)
+        let table = self.datasource.lock()?.get_table(db_name, table_name)?;
+        // The system.functions lists the functions of the context too.
+        if db_name == "system" && table.name() == "functions" {
+            return Ok(Arc::new(FunctionsTable::create_with_functions(
+                self.functions.read()?.clone(),
+            )));
+        }
+        Ok(table)
+    }
+
+    /// The registry of the context if the function is registered to the context,
+    /// otherwise the function is of the global registry.
+    pub fn get_function_factory(
+        &self,
+        name: &str,
+    ) -> FuseQueryResult<Option<Arc<FunctionFactory>>> {
+        let functions = self.functions.read()?;
+        Ok(match functions.contains(name) {
+            true => Some(functions.clone()),
+            false => None,
+        })
+    }
+
+    /// Register the scalar UDF, the function is only visible to the context.
+    pub fn register_udf(&self, udf: ScalarUDF) -> FuseQueryResult<()> {
+        Self::check_function_name(&udf.name)?;
+        UDFFunction::register(Arc::make_mut(&mut *self.functions.write()?), udf)
+    }
+
+    /// Register the aggregate UDF, the function is only visible to the context.
+    pub fn register_udaf(&self, udaf: AggregateUDF) -> FuseQueryResult<()> {
+        Self::check_function_name(&udaf.name)?;
+        UDAFFunction::register(Arc::make_mut(&mut *self.functions.write()?), udaf)
+    }
+
+    // The UDF cannot hide the function of the global registry.
+    fn check_function_name(name: &str) -> FuseQueryResult<()> {
+        if FunctionFactory::instance().read()?.contains(name) {
+            return Err(FuseQueryError::Internal(format!(
+                "Function {} already exists",
+                name
+            )));
+        }
+        Ok(())
     }
 }
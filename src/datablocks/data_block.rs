@@ -4,8 +4,9 @@
 
 use std::sync::Arc;
 
-use crate::datavalues::{DataArrayRef, DataSchema, DataSchemaRef};
-use crate::error::FuseQueryResult;
+use crate::datavalues;
+use crate::datavalues::{DataArrayRef, DataSchema, DataSchemaRef, UInt32Array};
+use crate::error::{FuseQueryError, FuseQueryResult};
 
 #[derive(Debug, Clone)]
 pub struct DataBlock {
@@ -59,4 +60,38 @@ impl DataBlock {
         let idx = self.schema.index_of(name)?;
         Ok(&self.columns[idx])
     }
+
+    pub fn columns(&self) -> &[DataArrayRef] {
+        &self.columns
+    }
+
+    /// Take the rows at `indices` from all the columns into a new block.
+    pub fn take(&self, indices: &[u32]) -> FuseQueryResult<DataBlock> {
+        let indices = UInt32Array::from(indices.to_vec());
+        let mut columns = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            columns.push(datavalues::data_array_take(column, &indices)?);
+        }
+        Ok(DataBlock::create(self.schema.clone(), columns))
+    }
+
+    /// Concat the blocks of the same schema into one block.
+    pub fn concat_blocks(blocks: &[DataBlock]) -> FuseQueryResult<DataBlock> {
+        if blocks.is_empty() {
+            return Err(FuseQueryError::Internal(
+                "Can't concat empty blocks".to_string(),
+            ));
+        }
+
+        let first = &blocks[0];
+        let mut columns = Vec::with_capacity(first.num_columns());
+        for i in 0..first.num_columns() {
+            let arrays = blocks
+                .iter()
+                .map(|block| block.column(i).clone())
+                .collect::<Vec<_>>();
+            columns.push(datavalues::data_array_concat(&arrays)?);
+        }
+        Ok(DataBlock::create(first.schema.clone(), columns))
+    }
 }
@@ -26,5 +26,14 @@ fn test_datablock() -> crate::error::FuseQueryResult<()> {
     assert_eq!(3, block.column_by_name("a")?.len());
     assert_eq!(3, block.column(0).len());
 
+    let taken = block.take(&[2, 0])?;
+    let actual = taken
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    let expect = &Int64Array::from(vec![3, 1]);
+    assert_eq!(expect, actual);
+
     Ok(())
 }
@@ -28,6 +28,8 @@ impl DataSource {
         datasource.add_database("system")?;
         // Register system.numbers table.
         datasource.add_table("system", Arc::new(system::NumbersTable::create()))?;
+        // Register system.functions table.
+        datasource.add_table("system", Arc::new(system::FunctionsTable::create()))?;
 
         Ok(datasource)
     }
@@ -11,4 +11,5 @@ mod table;
 
 pub use self::datasource::{DataSource, IDataSource};
 pub use self::partition::{Partition, Partitions};
+pub use self::system::FunctionsTable;
 pub use self::table::ITable;
@@ -0,0 +1,111 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::datablocks::DataBlock;
+use crate::datasources::{ITable, Partition};
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues::{
+    BooleanArray, DataField, DataSchema, DataSchemaRef, DataType, StringArray,
+};
+use crate::error::FuseQueryResult;
+use crate::functions::FunctionFactory;
+use crate::planners::{PlanNode, ReadDataSourcePlan};
+
+/// The functions of the global registry and the user-defined functions of the context.
+pub struct FunctionsTable {
+    schema: DataSchemaRef,
+    context_functions: Option<Arc<FunctionFactory>>,
+}
+
+impl FunctionsTable {
+    pub fn create() -> Self {
+        FunctionsTable {
+            schema: Arc::new(DataSchema::new(vec![
+                DataField::new("name", DataType::Utf8, false),
+                DataField::new("is_aggregate", DataType::Boolean, false),
+                DataField::new("alias_to", DataType::Utf8, false),
+            ])),
+            context_functions: None,
+        }
+    }
+
+    /// The table of the context, which has the functions registered to the context.
+    pub fn create_with_functions(functions: Arc<FunctionFactory>) -> Self {
+        FunctionsTable {
+            context_functions: Some(functions),
+            ..Self::create()
+        }
+    }
+}
+
+#[async_trait]
+impl ITable for FunctionsTable {
+    fn name(&self) -> &str {
+        "functions"
+    }
+
+    fn schema(&self) -> FuseQueryResult<DataSchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn read_plan(&self, _push_down_plan: PlanNode) -> FuseQueryResult<ReadDataSourcePlan> {
+        Ok(ReadDataSourcePlan {
+            db: "system".to_string(),
+            table: self.name().to_string(),
+            table_type: "System",
+            schema: self.schema.clone(),
+            partitions: vec![Partition {
+                name: "".to_string(),
+                version: 0,
+            }],
+            description: "(Read from system.functions table)".to_string(),
+        })
+    }
+
+    async fn read(&self, parts: Vec<Partition>) -> FuseQueryResult<SendableDataBlockStream> {
+        // The functions are in one partition.
+        if parts.is_empty() {
+            return Ok(Box::pin(DataBlockStream::create(
+                self.schema.clone(),
+                None,
+                vec![],
+            )));
+        }
+
+        let mut descriptions = FunctionFactory::instance().read()?.descriptions();
+        if let Some(ref functions) = self.context_functions {
+            descriptions.extend(functions.descriptions());
+            descriptions.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        let names = descriptions
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>();
+        let is_aggregates = descriptions
+            .iter()
+            .map(|v| v.is_aggregate)
+            .collect::<Vec<_>>();
+        let alias_tos = descriptions
+            .iter()
+            .map(|v| v.alias_to.as_deref().unwrap_or(""))
+            .collect::<Vec<_>>();
+        let block = DataBlock::create(
+            self.schema.clone(),
+            vec![
+                Arc::new(StringArray::from(names)),
+                Arc::new(BooleanArray::from(is_aggregates)),
+                Arc::new(StringArray::from(alias_tos)),
+            ],
+        );
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            vec![block],
+        )))
+    }
+}
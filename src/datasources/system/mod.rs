@@ -2,8 +2,10 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+mod functions_table;
 mod numbers_stream;
 mod numbers_table;
 
+pub use self::functions_table::FunctionsTable;
 pub use self::numbers_stream::NumbersStream;
 pub use self::numbers_table::NumbersTable;
@@ -17,3 +17,9 @@ pub type UInt64Array = arrow::array::UInt64Array;
 pub type Float32Array = arrow::array::Float32Array;
 pub type Float64Array = arrow::array::Float64Array;
 pub type StringArray = arrow::array::StringArray;
+pub type ListArray = arrow::array::ListArray;
+pub type StructArray = arrow::array::StructArray;
+pub type Date32Array = arrow::array::Date32Array;
+pub type TimestampSecondArray = arrow::array::TimestampSecondArray;
+/// The decimals are the i64 of the nanosecond timestamp array, see `decimal_type`.
+pub type DecimalArray = arrow::array::TimestampNanosecondArray;
@@ -2,12 +2,16 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use arrow::datatypes::TimeUnit;
+
+use crate::datavalues::data_array_decimal::data_array_decimal_sum;
 use crate::datavalues::{
-    DataArrayRef, DataType, DataValue, DataValueAggregateOperator, StringArray,
+    decimal_precision_scale, is_decimal, DataArrayRef, DataType, DataValue,
+    DataValueAggregateOperator, StringArray, DECIMAL_MAX_PRECISION,
 };
 use crate::datavalues::{
-    Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, UInt16Array,
-    UInt32Array, UInt64Array, UInt8Array,
+    Date32Array, DecimalArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    Int8Array, TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
 use crate::error::{FuseQueryError, FuseQueryResult};
 
@@ -16,6 +20,11 @@ pub fn data_array_aggregate_op(
     value: DataArrayRef,
 ) -> FuseQueryResult<DataValue> {
     Ok(match value.data_type() {
+        // The aggregates of the NULLs are NULL except count.
+        DataType::Null => match op {
+            DataValueAggregateOperator::Count => DataValue::UInt64(Some(0)),
+            _ => DataValue::Null,
+        },
         DataType::Int8 => match op {
             DataValueAggregateOperator::Min => {
                 typed_array_min_max_to_data_value!(value, Int8Array, Int8, min)
@@ -26,7 +35,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, Int8Array, Int8)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::Int16 => match op {
             DataValueAggregateOperator::Min => {
@@ -38,7 +49,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, Int16Array, Int16)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::Int32 => match op {
             DataValueAggregateOperator::Min => {
@@ -50,7 +63,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, Int32Array, Int32)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::Int64 => match op {
             DataValueAggregateOperator::Min => {
@@ -62,7 +77,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, Int64Array, Int64)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::UInt8 => match op {
             DataValueAggregateOperator::Min => {
@@ -74,7 +91,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, UInt8Array, UInt8)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::UInt16 => match op {
             DataValueAggregateOperator::Min => {
@@ -86,7 +105,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, UInt16Array, UInt16)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::UInt32 => match op {
             DataValueAggregateOperator::Min => {
@@ -98,7 +119,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, UInt32Array, UInt32)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::UInt64 => match op {
             DataValueAggregateOperator::Min => {
@@ -110,7 +133,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, UInt64Array, UInt64)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::Float32 => match op {
             DataValueAggregateOperator::Min => {
@@ -122,7 +147,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, Float32Array, Float32)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::Float64 => match op {
             DataValueAggregateOperator::Min => {
@@ -134,7 +161,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Sum => {
                 typed_array_sum_to_data_value!(value, Float64Array, Float64)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
         },
         DataType::Utf8 => match op {
             DataValueAggregateOperator::Min => {
@@ -143,7 +172,9 @@ pub fn data_array_aggregate_op(
             DataValueAggregateOperator::Max => {
                 typed_array_min_max_string_to_data_value!(value, StringArray, String, max_string)
             }
-            DataValueAggregateOperator::Count => DataValue::UInt64(Some(value.len() as u64)),
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
             _ => {
                 return Err(FuseQueryError::Internal(format!(
                     "Unsupported data_array_{} for data type: {:?}",
@@ -152,6 +183,66 @@ pub fn data_array_aggregate_op(
                 )))
             }
         },
+        DataType::Date32(_) => match op {
+            DataValueAggregateOperator::Min => {
+                typed_array_min_max_to_data_value!(value, Date32Array, Date32, min)
+            }
+            DataValueAggregateOperator::Max => {
+                typed_array_min_max_to_data_value!(value, Date32Array, Date32, max)
+            }
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported data_array_{} for data type: {:?}",
+                    op,
+                    value.data_type()
+                )))
+            }
+        },
+        DataType::Timestamp(TimeUnit::Second, _) => match op {
+            DataValueAggregateOperator::Min => {
+                typed_array_min_max_to_data_value!(value, TimestampSecondArray, Timestamp, min)
+            }
+            DataValueAggregateOperator::Max => {
+                typed_array_min_max_to_data_value!(value, TimestampSecondArray, Timestamp, max)
+            }
+            DataValueAggregateOperator::Count => {
+                DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+            }
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported data_array_{} for data type: {:?}",
+                    op,
+                    value.data_type()
+                )))
+            }
+        },
+        data_type if is_decimal(data_type) => {
+            let (precision, scale) = decimal_precision_scale(data_type).unwrap_or_default();
+            match op {
+                DataValueAggregateOperator::Min => DataValue::Decimal(
+                    arrow::compute::min(downcast_array!(value, DecimalArray)?),
+                    precision,
+                    scale,
+                ),
+                DataValueAggregateOperator::Max => DataValue::Decimal(
+                    arrow::compute::max(downcast_array!(value, DecimalArray)?),
+                    precision,
+                    scale,
+                ),
+                // The sum is in the decimal of the maximum precision.
+                DataValueAggregateOperator::Sum => DataValue::Decimal(
+                    data_array_decimal_sum(&value)?,
+                    DECIMAL_MAX_PRECISION,
+                    scale,
+                ),
+                DataValueAggregateOperator::Count => {
+                    DataValue::UInt64(Some((value.len() - value.null_count()) as u64))
+                }
+            }
+        }
         _ => {
             return Err(FuseQueryError::Internal(format!(
                 "Unsupported data_array_{} for data type: {:?}",
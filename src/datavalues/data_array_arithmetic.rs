@@ -4,6 +4,13 @@
 
 use std::sync::Arc;
 
+use arrow::array::{as_primitive_array, Array, PrimitiveArrayOps, PrimitiveBuilder};
+use arrow::datatypes::{
+    ArrowNumericType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use num::Zero;
+
 use crate::datavalues::{DataArrayRef, DataColumnarValue, DataType, DataValueArithmeticOperator};
 use crate::datavalues::{
     Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, UInt16Array,
@@ -36,8 +43,25 @@ pub fn data_array_arithmetic_op(
         &left_array.data_type(),
         &right_array.data_type(),
     )?;
-    let left_array = arrow::compute::cast(&left_array, &coercion_type)?;
-    let right_array = arrow::compute::cast(&right_array, &coercion_type)?;
+    // The arithmetic with NULL is NULL.
+    if left_array.data_type() == &DataType::Null || right_array.data_type() == &DataType::Null {
+        let len = left_array.len().max(right_array.len());
+        let nulls = super::DataValue::try_into_data_array(
+            &vec![super::DataValue::Null; len],
+            &coercion_type,
+        )?;
+        return Ok(nulls);
+    }
+    if super::is_decimal(&coercion_type) {
+        return super::data_array_decimal::data_array_decimal_arithmetic(
+            op,
+            &left_array,
+            &right_array,
+            &coercion_type,
+        );
+    }
+    let left_array = super::data_array_cast(&left_array, &coercion_type)?;
+    let right_array = super::data_array_cast(&right_array, &coercion_type)?;
     match op {
         DataValueArithmeticOperator::Add => {
             arrow_primitive_array_op!(&left_array, &right_array, add)
@@ -51,5 +75,47 @@ pub fn data_array_arithmetic_op(
         DataValueArithmeticOperator::Div => {
             arrow_primitive_array_op!(&left_array, &right_array, divide)
         }
+        DataValueArithmeticOperator::Modulo => match left_array.data_type() {
+            DataType::Int8 => modulus::<Int8Type>(&left_array, &right_array, i8::wrapping_rem),
+            DataType::Int16 => modulus::<Int16Type>(&left_array, &right_array, i16::wrapping_rem),
+            DataType::Int32 => modulus::<Int32Type>(&left_array, &right_array, i32::wrapping_rem),
+            DataType::Int64 => modulus::<Int64Type>(&left_array, &right_array, i64::wrapping_rem),
+            DataType::UInt8 => modulus::<UInt8Type>(&left_array, &right_array, u8::wrapping_rem),
+            DataType::UInt16 => modulus::<UInt16Type>(&left_array, &right_array, u16::wrapping_rem),
+            DataType::UInt32 => modulus::<UInt32Type>(&left_array, &right_array, u32::wrapping_rem),
+            DataType::UInt64 => modulus::<UInt64Type>(&left_array, &right_array, u64::wrapping_rem),
+            DataType::Float32 => modulus::<Float32Type>(&left_array, &right_array, |a, b| a % b),
+            DataType::Float64 => modulus::<Float64Type>(&left_array, &right_array, |a, b| a % b),
+            other => Err(FuseQueryError::Internal(format!(
+                "Unsupported arithmetic_compute::modulus for data type: {:?}",
+                other
+            ))),
+        },
+    }
+}
+
+/// The remainder of the division, the zero divisor is an error like the division.
+/// The remainder of the minimum integer by -1 is 0, which overflows in the `%` of Rust.
+fn modulus<T>(
+    left: &DataArrayRef,
+    right: &DataArrayRef,
+    rem: fn(T::Native, T::Native) -> T::Native,
+) -> FuseQueryResult<DataArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: Zero,
+{
+    let left = as_primitive_array::<T>(left);
+    let right = as_primitive_array::<T>(right);
+    let mut builder = PrimitiveBuilder::<T>::new(left.len());
+    for i in 0..left.len() {
+        if left.is_null(i) || right.is_null(i) {
+            builder.append_null()?;
+        } else if right.value(i).is_zero() {
+            return Err(FuseQueryError::Internal("Divide by zero error".to_string()));
+        } else {
+            builder.append_value(rem(left.value(i), right.value(i)))?;
+        }
     }
+    Ok(Arc::new(builder.finish()))
 }
@@ -220,6 +220,40 @@ fn test_array_arithmetic() {
             ],
             error: vec!["Internal Error: Unsupported (Utf8) * (Utf8)"],
         },
+        ArrayTest {
+            name: "modulo-passed",
+            args: vec![
+                vec![
+                    Arc::new(Int8Array::from(vec![4, 3, 2, 1])),
+                    Arc::new(Int8Array::from(vec![1, 2, 3, 4])),
+                ],
+                vec![
+                    Arc::new(UInt64Array::from(vec![Some(7), None, Some(9)])),
+                    Arc::new(UInt64Array::from(vec![Some(4), Some(0), Some(5)])),
+                ],
+                vec![
+                    Arc::new(Float64Array::from(vec![4.5, 3.0])),
+                    Arc::new(Float64Array::from(vec![2.0, 2.0])),
+                ],
+                vec![
+                    Arc::new(Int32Array::from(vec![4, 3])),
+                    Arc::new(Int32Array::from(vec![1, 0])),
+                ],
+                vec![
+                    Arc::new(Int64Array::from(vec![i64::MIN, -7])),
+                    Arc::new(Int64Array::from(vec![-1, -2])),
+                ],
+            ],
+            op: DataValueArithmeticOperator::Modulo,
+            expect: vec![
+                Arc::new(Int8Array::from(vec![0, 1, 2, 1])),
+                Arc::new(UInt64Array::from(vec![Some(3), None, Some(4)])),
+                Arc::new(Float64Array::from(vec![0.5, 1.0])),
+                Arc::new(Int32Array::from(vec![0, 0])),
+                Arc::new(Int64Array::from(vec![0, -1])),
+            ],
+            error: vec!["", "", "", "Internal Error: Divide by zero error", ""],
+        },
         ArrayTest {
             name: "div-passed",
             args: vec![
@@ -356,6 +390,14 @@ fn test_array_scalar_arithmetic() {
             expect: Arc::new(Int8Array::from(vec![2, 2, 2, 2])),
             error: "",
         },
+        ArrayTest {
+            name: "modulo-passed",
+            array: Arc::new(Int8Array::from(vec![4, 5, 6, 7])),
+            scalar: DataValue::Int8(Some(3)),
+            op: DataValueArithmeticOperator::Modulo,
+            expect: Arc::new(Int8Array::from(vec![1, 2, 0, 1])),
+            error: "",
+        },
     ];
 
     for t in tests {
@@ -425,6 +467,14 @@ fn test_scalar_array_arithmetic() {
             expect: Arc::new(Int8Array::from(vec![2, 2, 2, 2])),
             error: "",
         },
+        ArrayTest {
+            name: "modulo-passed",
+            array: Arc::new(Int8Array::from(vec![4, 5, 6, 7])),
+            scalar: DataValue::Int8(Some(3)),
+            op: DataValueArithmeticOperator::Modulo,
+            expect: Arc::new(Int8Array::from(vec![3, 3, 3, 3])),
+            error: "",
+        },
     ];
 
     for t in tests {
@@ -0,0 +1,106 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::array::{make_array, Array, ArrayData};
+
+use crate::datavalues::data_array_date::data_array_temporal_cast;
+use crate::datavalues::data_array_decimal::data_array_decimal_cast;
+use crate::datavalues::data_type::{is_decimal, is_temporal, DATE_TYPE, TIMESTAMP_TYPE};
+use crate::datavalues::{BooleanArray, DataArrayRef, DataType, DataValue, ListArray, StringArray};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+/// Cast the array to the data type, the values which cannot be cast are NULLs.
+/// The NULL array is cast to the typed NULLs, the strings are parsed as the dates and timestamps.
+pub fn data_array_cast(
+    array: &DataArrayRef,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    if array.data_type() == data_type {
+        return Ok(array.clone());
+    }
+    match (array.data_type(), data_type) {
+        (DataType::Null, _) => {
+            DataValue::try_into_data_array(&vec![DataValue::Null; array.len()], data_type)
+        }
+        // The arrays keep their offsets and NULLs, the elements are cast.
+        (DataType::List(_), DataType::List(element)) => {
+            let list = downcast_array!(array, ListArray)?;
+            let values = data_array_cast(&list.values(), element)?;
+            let data = array.data();
+            Ok(make_array(Arc::new(ArrayData::new(
+                data_type.clone(),
+                data.len(),
+                Some(data.null_count()),
+                data.null_buffer().cloned(),
+                data.offset(),
+                data.buffers().to_vec(),
+                vec![values.data()],
+            ))))
+        }
+        (DataType::Utf8, DataType::Boolean) => {
+            let array = downcast_array!(array, StringArray)?;
+            let values = (0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        return None;
+                    }
+                    match array.value(i).to_lowercase().as_str() {
+                        "true" | "1" => Some(true),
+                        "false" | "0" => Some(false),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>();
+            Ok(Arc::new(BooleanArray::from(values)))
+        }
+        (from, to) if is_decimal(from) || is_decimal(to) => {
+            data_array_decimal_cast(array, data_type)
+        }
+        // The temporal types are cast between themselves and the strings by the days and seconds,
+        // the numbers are cast to the days and seconds first.
+        (from, to) if (is_temporal(from) || from == &DataType::Utf8) && is_temporal(to) => {
+            data_array_temporal_cast(array, data_type)
+        }
+        (from, DataType::Utf8) if is_temporal(from) => data_array_temporal_cast(array, data_type),
+        (from, _) if from == &DATE_TYPE => {
+            data_array_cast(&arrow::compute::cast(array, &DataType::Int32)?, data_type)
+        }
+        (from, _) if from == &TIMESTAMP_TYPE => {
+            data_array_cast(&arrow::compute::cast(array, &DataType::Int64)?, data_type)
+        }
+        (_, to) if to == &DATE_TYPE => Ok(arrow::compute::cast(
+            &data_array_cast(array, &DataType::Int32)?,
+            data_type,
+        )?),
+        (_, to) if to == &TIMESTAMP_TYPE => Ok(arrow::compute::cast(
+            &data_array_cast(array, &DataType::Int64)?,
+            data_type,
+        )?),
+        _ => Ok(arrow::compute::cast(array, data_type)?),
+    }
+}
+
+/// Cast the array to the data type, the values which cannot be cast are errors,
+/// such as the overflow of the integers and the strings which are not numbers.
+pub fn data_array_try_cast(
+    array: &DataArrayRef,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    let result = data_array_cast(array, data_type)?;
+    if array.data_type() == &DataType::Null || result.null_count() == array.null_count() {
+        return Ok(result);
+    }
+
+    match (0..array.len()).find(|i| array.is_valid(*i) && result.is_null(*i)) {
+        Some(i) => Err(FuseQueryError::Internal(format!(
+            "Cannot cast value {:?} from {:?} to {:?}",
+            DataValue::try_from_array(array, i)?,
+            array.data_type(),
+            data_type
+        ))),
+        None => Ok(result),
+    }
+}
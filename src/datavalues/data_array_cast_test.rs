@@ -0,0 +1,89 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_cast() {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        array: DataArrayRef,
+        data_type: DataType,
+        expect: DataArrayRef,
+        try_expect: DataArrayRef,
+        try_error: &'static str,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "int64-to-string-passed",
+            array: Arc::new(Int64Array::from(vec![Some(1), None, Some(-3)])),
+            data_type: DataType::Utf8,
+            expect: Arc::new(StringArray::from(vec![Some("1"), None, Some("-3")])),
+            try_expect: Arc::new(StringArray::from(vec![Some("1"), None, Some("-3")])),
+            try_error: "",
+        },
+        ArrayTest {
+            name: "string-to-float64-passed",
+            array: Arc::new(StringArray::from(vec!["1.5", "abc"])),
+            data_type: DataType::Float64,
+            expect: Arc::new(Float64Array::from(vec![Some(1.5), None])),
+            try_expect: Arc::new(Float64Array::from(vec![Some(1.5), None])),
+            try_error: "Internal Error: Cannot cast value abc from Utf8 to Float64",
+        },
+        ArrayTest {
+            name: "int64-to-uint8-overflow",
+            array: Arc::new(Int64Array::from(vec![255, 256, -1])),
+            data_type: DataType::UInt8,
+            expect: Arc::new(UInt8Array::from(vec![Some(255), None, None])),
+            try_expect: Arc::new(UInt8Array::from(vec![Some(255), None, None])),
+            try_error: "Internal Error: Cannot cast value 256 from Int64 to UInt8",
+        },
+        ArrayTest {
+            name: "string-to-boolean-passed",
+            array: Arc::new(StringArray::from(vec!["true", "0", "FALSE", "1"])),
+            data_type: DataType::Boolean,
+            expect: Arc::new(BooleanArray::from(vec![true, false, false, true])),
+            try_expect: Arc::new(BooleanArray::from(vec![true, false, false, true])),
+            try_error: "",
+        },
+        ArrayTest {
+            name: "null-to-int32-passed",
+            array: Arc::new(NullArray::new(2)),
+            data_type: DataType::Int32,
+            expect: Arc::new(Int32Array::from(vec![None, None])),
+            try_expect: Arc::new(Int32Array::from(vec![None, None])),
+            try_error: "",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_cast(&t.array, &t.data_type);
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert!(false, "{}: {}", t.name, e),
+        }
+
+        let result = data_array_try_cast(&t.array, &t.data_type);
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.try_expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.try_expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert_eq!(t.try_error, e.to_string()),
+        }
+    }
+}
@@ -4,11 +4,12 @@
 
 use std::sync::Arc;
 
-use crate::datavalues::{DataArrayRef, DataColumnarValue, DataType, DataValueComparisonOperator};
 use crate::datavalues::{
-    Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, StringArray,
-    UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+    BooleanArray, Date32Array, DecimalArray, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, StringArray, TimestampSecondArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
 };
+use crate::datavalues::{DataArrayRef, DataColumnarValue, DataType, DataValueComparisonOperator};
 use crate::error::{FuseQueryError, FuseQueryResult};
 
 pub fn data_array_comparison_op(
@@ -16,6 +17,21 @@ pub fn data_array_comparison_op(
     left: &DataColumnarValue,
     right: &DataColumnarValue,
 ) -> FuseQueryResult<DataArrayRef> {
+    // The comparison with NULL is NULL.
+    let is_null = |value: &DataColumnarValue| match value {
+        DataColumnarValue::Array(array) => array.data_type() == &DataType::Null,
+        DataColumnarValue::Scalar(scalar) => scalar.is_null(),
+    };
+    if is_null(left) || is_null(right) {
+        let len = match (left, right) {
+            (DataColumnarValue::Array(array), _) | (_, DataColumnarValue::Array(array)) => {
+                array.len()
+            }
+            _ => 1,
+        };
+        return Ok(Arc::new(BooleanArray::from(vec![None; len])));
+    }
+
     match (left, right) {
         (DataColumnarValue::Array(left_array), DataColumnarValue::Array(right_array)) => {
             let coercion_type = super::data_type::equal_coercion(
@@ -23,11 +39,14 @@ pub fn data_array_comparison_op(
                 &left_array.data_type(),
                 &right_array.data_type(),
             )?;
-            let left_array = arrow::compute::cast(&left_array, &coercion_type)?;
-            let right_array = arrow::compute::cast(&right_array, &coercion_type)?;
+            let left_array = super::data_array_cast(&left_array, &coercion_type)?;
+            let right_array = super::data_array_cast(&right_array, &coercion_type)?;
 
             match op {
                 DataValueComparisonOperator::Eq => arrow_array_op!(&left_array, &right_array, eq),
+                DataValueComparisonOperator::NotEq => {
+                    arrow_array_op!(&left_array, &right_array, neq)
+                }
                 DataValueComparisonOperator::Lt => arrow_array_op!(&left_array, &right_array, lt),
                 DataValueComparisonOperator::LtEq => {
                     arrow_array_op!(&left_array, &right_array, lt_eq)
@@ -45,12 +64,26 @@ pub fn data_array_comparison_op(
                 &array.data_type(),
                 &scalar.data_type(),
             )?;
-            let left_array = arrow::compute::cast(&array, &coercion_type)?;
-            let right_array = arrow::compute::cast(&scalar.to_array(1)?, &coercion_type)?;
+            // The temporal and decimal scalars are compared as the arrays.
+            if super::is_temporal(&coercion_type) || super::is_decimal(&coercion_type) {
+                return data_array_comparison_op(
+                    op,
+                    left,
+                    &DataColumnarValue::Array(super::DataValue::try_into_data_array(
+                        &vec![scalar.clone(); array.len()],
+                        &scalar.data_type(),
+                    )?),
+                );
+            }
+            let left_array = super::data_array_cast(&array, &coercion_type)?;
+            let right_array = super::data_array_cast(&scalar.to_array(1)?, &coercion_type)?;
             let scalar = super::DataValue::try_from_array(&right_array, 0)?;
 
             match op {
                 DataValueComparisonOperator::Eq => arrow_array_op_scalar!(left_array, scalar, eq),
+                DataValueComparisonOperator::NotEq => {
+                    arrow_array_op_scalar!(left_array, scalar, neq)
+                }
                 DataValueComparisonOperator::Lt => arrow_array_op_scalar!(left_array, scalar, lt),
                 DataValueComparisonOperator::LtEq => {
                     arrow_array_op_scalar!(left_array, scalar, lt_eq)
@@ -68,12 +101,25 @@ pub fn data_array_comparison_op(
                 &array.data_type(),
                 &scalar.data_type(),
             )?;
-            let left_array = arrow::compute::cast(&scalar.to_array(1)?, &coercion_type)?;
-            let right_array = arrow::compute::cast(&array, &coercion_type)?;
+            if super::is_temporal(&coercion_type) || super::is_decimal(&coercion_type) {
+                return data_array_comparison_op(
+                    op,
+                    &DataColumnarValue::Array(super::DataValue::try_into_data_array(
+                        &vec![scalar.clone(); array.len()],
+                        &scalar.data_type(),
+                    )?),
+                    right,
+                );
+            }
+            let left_array = super::data_array_cast(&scalar.to_array(1)?, &coercion_type)?;
+            let right_array = super::data_array_cast(&array, &coercion_type)?;
             let scalar = super::DataValue::try_from_array(&left_array, 0)?;
 
             match op {
                 DataValueComparisonOperator::Eq => arrow_array_op_scalar!(right_array, scalar, eq),
+                DataValueComparisonOperator::NotEq => {
+                    arrow_array_op_scalar!(right_array, scalar, neq)
+                }
                 DataValueComparisonOperator::Lt => arrow_array_op_scalar!(right_array, scalar, gt),
                 DataValueComparisonOperator::LtEq => {
                     arrow_array_op_scalar!(right_array, scalar, gt_eq)
@@ -47,6 +47,18 @@ fn test_array_comparison() {
             ],
             error: vec![""],
         },
+        ArrayTest {
+            name: "not-eq-passed",
+            args: vec![vec![
+                Arc::new(Int8Array::from(vec![4, 3, 1, 1])),
+                Arc::new(Int8Array::from(vec![4, 3, 2, 1])),
+            ]],
+            op: DataValueComparisonOperator::NotEq,
+            expect: vec![Arc::new(BooleanArray::from(vec![
+                false, false, true, false,
+            ]))],
+            error: vec![""],
+        },
         ArrayTest {
             name: "lt-passed",
             args: vec![vec![
@@ -147,6 +159,14 @@ fn test_array_scalar_comparison() {
             expect: Arc::new(BooleanArray::from(vec![false, true, false, false])),
             error: "",
         },
+        ArrayTest {
+            name: "not-eq-passed",
+            array: Arc::new(Int8Array::from(vec![4, 3, 2, 1])),
+            scalar: DataValue::Int8(Some(3)),
+            op: DataValueComparisonOperator::NotEq,
+            expect: Arc::new(BooleanArray::from(vec![true, false, true, true])),
+            error: "",
+        },
         ArrayTest {
             name: "lt-passed",
             array: Arc::new(Int8Array::from(vec![4, 3, 2, 1])),
@@ -233,6 +253,14 @@ fn test_scalar_array_comparison() {
             expect: Arc::new(BooleanArray::from(vec![false, true, false, false])),
             error: "",
         },
+        ArrayTest {
+            name: "not-eq-passed",
+            array: Arc::new(Int8Array::from(vec![4, 3, 2, 1])),
+            scalar: DataValue::Int8(Some(3)),
+            op: DataValueComparisonOperator::NotEq,
+            expect: Arc::new(BooleanArray::from(vec![true, false, true, true])),
+            error: "",
+        },
         ArrayTest {
             name: "lt-passed",
             array: Arc::new(Int8Array::from(vec![4, 3, 2, 1])),
@@ -0,0 +1,77 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use arrow::array::{ArrayData, PrimitiveArrayOps};
+
+use crate::datavalues::{DataArrayRef, DataType, DataValue, UInt32Array};
+use crate::error::FuseQueryResult;
+
+// The same buffers in the data type, the arrow kernels drop the timezone name of the decimals.
+fn data_array_with_type(array: &DataArrayRef, data_type: &DataType) -> DataArrayRef {
+    if array.data_type() == data_type {
+        return array.clone();
+    }
+    let data = array.data();
+    arrow::array::make_array(std::sync::Arc::new(ArrayData::new(
+        data_type.clone(),
+        data.len(),
+        Some(data.null_count()),
+        data.null_buffer().cloned(),
+        data.offset(),
+        data.buffers().to_vec(),
+        data.child_data().to_vec(),
+    )))
+}
+
+// The arrays and the tuples are rebuilt from the values, the arrow kernels lose the nested types.
+fn is_nested(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::List(_) | DataType::Struct(_))
+}
+
+fn data_array_values(array: &DataArrayRef) -> FuseQueryResult<Vec<DataValue>> {
+    (0..array.len())
+        .map(|i| DataValue::try_from_array(array, i))
+        .collect()
+}
+
+/// Take the values at the indices, the data type is kept.
+pub fn data_array_take(
+    array: &DataArrayRef,
+    indices: &UInt32Array,
+) -> FuseQueryResult<DataArrayRef> {
+    if is_nested(array.data_type()) {
+        let values = data_array_values(array)?;
+        let taken = (0..indices.len())
+            .map(|i| values[indices.value(i) as usize].clone())
+            .collect::<Vec<_>>();
+        return DataValue::try_into_data_array(&taken, array.data_type());
+    }
+    let taken = arrow::compute::take(array, indices, None)?;
+    Ok(data_array_with_type(&taken, array.data_type()))
+}
+
+/// Concat the arrays of the same data type, the data type is kept.
+pub fn data_array_concat(arrays: &[DataArrayRef]) -> FuseQueryResult<DataArrayRef> {
+    match arrays.first().map(|array| array.data_type()) {
+        Some(DataType::Timestamp(unit, Some(name))) => {
+            let arrays = arrays
+                .iter()
+                .map(|array| data_array_with_type(array, &DataType::Timestamp(unit.clone(), None)))
+                .collect::<Vec<_>>();
+            let concated = arrow::compute::concat(&arrays)?;
+            Ok(data_array_with_type(
+                &concated,
+                &DataType::Timestamp(unit.clone(), Some(name.clone())),
+            ))
+        }
+        Some(data_type) if is_nested(data_type) => {
+            let mut values = vec![];
+            for array in arrays {
+                values.extend(data_array_values(array)?);
+            }
+            DataValue::try_into_data_array(&values, data_type)
+        }
+        _ => Ok(arrow::compute::concat(arrays)?),
+    }
+}
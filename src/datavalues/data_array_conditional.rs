@@ -0,0 +1,44 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use arrow::array::{Array, PrimitiveArrayOps};
+
+use crate::datavalues::{data_array_cast, BooleanArray, DataArrayRef, DataType, UInt32Array};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+/// Select the rows of the left array where the mask is true, otherwise the rows of the right array.
+/// The NULLs of the mask select the right array, both arrays are cast to the data type first.
+pub fn data_array_select(
+    mask: &DataArrayRef,
+    left: &DataArrayRef,
+    right: &DataArrayRef,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    let mask = downcast_array!(mask, BooleanArray)?;
+    let len = mask.len();
+    if left.len() != len || right.len() != len {
+        return Err(FuseQueryError::Internal(format!(
+            "Cannot select arrays of different lengths, mask:{}, left:{}, right:{}",
+            len,
+            left.len(),
+            right.len()
+        )));
+    }
+
+    // Take from the concatenated arrays, the right rows are after the left rows.
+    let indices = (0..len)
+        .map(|i| {
+            if mask.is_valid(i) && mask.value(i) {
+                i as u32
+            } else {
+                (len + i) as u32
+            }
+        })
+        .collect::<Vec<u32>>();
+    let values = super::data_array_concat(&[
+        data_array_cast(left, data_type)?,
+        data_array_cast(right, data_type)?,
+    ])?;
+    super::data_array_take(&values, &UInt32Array::from(indices))
+}
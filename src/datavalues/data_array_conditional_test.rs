@@ -0,0 +1,84 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_conditional() {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        mask: DataArrayRef,
+        left: DataArrayRef,
+        right: DataArrayRef,
+        data_type: DataType,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "select-passed",
+            mask: Arc::new(BooleanArray::from(vec![true, false, true, false])),
+            left: Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            right: Arc::new(Int64Array::from(vec![5, 6, 7, 8])),
+            data_type: DataType::Int64,
+            expect: Arc::new(Int64Array::from(vec![1, 6, 3, 8])),
+            error: "",
+        },
+        ArrayTest {
+            name: "select-null-mask-passed",
+            mask: Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])),
+            left: Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            right: Arc::new(StringArray::from(vec!["x", "y", "z"])),
+            data_type: DataType::Utf8,
+            expect: Arc::new(StringArray::from(vec!["a", "y", "z"])),
+            error: "",
+        },
+        ArrayTest {
+            name: "select-cast-passed",
+            mask: Arc::new(BooleanArray::from(vec![true, false])),
+            left: Arc::new(Int32Array::from(vec![1, 2])),
+            right: Arc::new(Int64Array::from(vec![3, 4])),
+            data_type: DataType::Int64,
+            expect: Arc::new(Int64Array::from(vec![1, 4])),
+            error: "",
+        },
+        ArrayTest {
+            name: "select-null-array-passed",
+            mask: Arc::new(BooleanArray::from(vec![true, false])),
+            left: Arc::new(NullArray::new(2)),
+            right: Arc::new(UInt64Array::from(vec![3, 4])),
+            data_type: DataType::UInt64,
+            expect: Arc::new(UInt64Array::from(vec![None, Some(4)])),
+            error: "",
+        },
+        ArrayTest {
+            name: "select-length-error",
+            mask: Arc::new(BooleanArray::from(vec![true, false])),
+            left: Arc::new(Int64Array::from(vec![1])),
+            right: Arc::new(Int64Array::from(vec![3, 4])),
+            data_type: DataType::Int64,
+            expect: Arc::new(Int64Array::from(vec![1, 4])),
+            error:
+                "Internal Error: Cannot select arrays of different lengths, mask:2, left:1, right:2",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_select(&t.mask, &t.left, &t.right, &t.data_type);
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert_eq!(t.error, e.to_string()),
+        }
+    }
+}
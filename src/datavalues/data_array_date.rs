@@ -0,0 +1,330 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{Array, PrimitiveArrayOps};
+use arrow::datatypes::TimeUnit;
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::datavalues::data_type::{DATE_TYPE, TIMESTAMP_TYPE};
+use crate::datavalues::{
+    DataArrayRef, DataType, DataValueDateOperator, DataValueIntervalUnit, Date32Array, Int64Array,
+    StringArray, TimestampSecondArray, UInt16Array, UInt8Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+fn to_datetime(seconds: i64) -> Option<NaiveDateTime> {
+    chrono::DateTime::from_timestamp(seconds, 0).map(|v| v.naive_utc())
+}
+
+fn to_seconds(datetime: NaiveDateTime) -> i64 {
+    datetime.and_utc().timestamp()
+}
+
+fn to_days(date: NaiveDate) -> Option<i32> {
+    i32::try_from(to_seconds(date.and_hms_opt(0, 0, 0)?).div_euclid(SECONDS_PER_DAY)).ok()
+}
+
+pub(crate) fn date_to_string(days: i64) -> String {
+    match to_datetime(days * SECONDS_PER_DAY) {
+        Some(v) => v.format("%Y-%m-%d").to_string(),
+        None => days.to_string(),
+    }
+}
+
+pub(crate) fn timestamp_to_string(seconds: i64) -> String {
+    match to_datetime(seconds) {
+        Some(v) => v.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => seconds.to_string(),
+    }
+}
+
+/// Parse the date YYYY-MM-DD, the time of the timestamp is dropped.
+pub(crate) fn parse_date(value: &str) -> Option<i32> {
+    match NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d") {
+        Ok(date) => to_days(date),
+        Err(_) => parse_timestamp(value).map(|v| v.div_euclid(SECONDS_PER_DAY) as i32),
+    }
+}
+
+/// Parse the timestamp YYYY-MM-DD hh:mm:ss, the date is the start of the day.
+pub(crate) fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    for format in &[
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S%.f",
+    ] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(to_seconds(datetime));
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(to_seconds)
+}
+
+/// The seconds of the date or timestamp array, the NULL array is all NULLs.
+fn array_to_seconds(array: &DataArrayRef) -> FuseQueryResult<Vec<Option<i64>>> {
+    match array.data_type() {
+        DataType::Date32(_) => {
+            let array = downcast_array!(array, Date32Array)?;
+            Ok((0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        None
+                    } else {
+                        Some(array.value(i) as i64 * SECONDS_PER_DAY)
+                    }
+                })
+                .collect())
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            let array = downcast_array!(array, TimestampSecondArray)?;
+            Ok((0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        None
+                    } else {
+                        Some(array.value(i))
+                    }
+                })
+                .collect())
+        }
+        DataType::Null => Ok(vec![None; array.len()]),
+        other => Err(FuseQueryError::Internal(format!(
+            "Unsupported date array ({:?})",
+            other
+        ))),
+    }
+}
+
+fn seconds_to_array(values: Vec<Option<i64>>, data_type: &DataType) -> DataArrayRef {
+    if data_type == &DATE_TYPE {
+        Arc::new(Date32Array::from(
+            values
+                .into_iter()
+                .map(|v| v.and_then(|v| i32::try_from(v.div_euclid(SECONDS_PER_DAY)).ok()))
+                .collect::<Vec<_>>(),
+        ))
+    } else {
+        Arc::new(TimestampSecondArray::from_opt_vec(values, None))
+    }
+}
+
+fn add_months(datetime: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let abs = Months::new(u32::try_from(months.checked_abs()?).ok()?);
+    if months >= 0 {
+        datetime.checked_add_months(abs)
+    } else {
+        datetime.checked_sub_months(abs)
+    }
+}
+
+fn add_interval(seconds: i64, unit: &DataValueIntervalUnit, delta: i64) -> Option<i64> {
+    match unit {
+        DataValueIntervalUnit::Year => {
+            add_months(to_datetime(seconds)?, delta.checked_mul(12)?).map(to_seconds)
+        }
+        DataValueIntervalUnit::Month => add_months(to_datetime(seconds)?, delta).map(to_seconds),
+        DataValueIntervalUnit::Day => seconds.checked_add(delta.checked_mul(SECONDS_PER_DAY)?),
+        DataValueIntervalUnit::Hour => seconds.checked_add(delta.checked_mul(3600)?),
+        DataValueIntervalUnit::Minute => seconds.checked_add(delta.checked_mul(60)?),
+        DataValueIntervalUnit::Second => seconds.checked_add(delta),
+    }
+}
+
+// The intervals are aligned to the UNIX epoch, the months and the years are aligned to the calendar.
+fn start_of_interval(seconds: i64, n: i64, unit: &DataValueIntervalUnit) -> Option<i64> {
+    let step = match unit {
+        DataValueIntervalUnit::Year => {
+            let datetime = to_datetime(seconds)?;
+            let year = datetime.year() as i64;
+            let date = NaiveDate::from_ymd_opt((year - year.rem_euclid(n)) as i32, 1, 1)?;
+            return Some(to_seconds(date.and_hms_opt(0, 0, 0)?));
+        }
+        DataValueIntervalUnit::Month => {
+            let datetime = to_datetime(seconds)?;
+            let months = (datetime.year() as i64 - 1970) * 12 + datetime.month0() as i64;
+            let months = months - months.rem_euclid(n);
+            let date = NaiveDate::from_ymd_opt(
+                (1970 + months.div_euclid(12)) as i32,
+                months.rem_euclid(12) as u32 + 1,
+                1,
+            )?;
+            return Some(to_seconds(date.and_hms_opt(0, 0, 0)?));
+        }
+        DataValueIntervalUnit::Day => n.checked_mul(SECONDS_PER_DAY)?,
+        DataValueIntervalUnit::Hour => n.checked_mul(3600)?,
+        DataValueIntervalUnit::Minute => n.checked_mul(60)?,
+        DataValueIntervalUnit::Second => n,
+    };
+    Some(seconds - seconds.rem_euclid(step))
+}
+
+fn date_part<T>(seconds: &[Option<i64>], f: impl Fn(NaiveDateTime) -> T) -> Vec<Option<T>> {
+    seconds
+        .iter()
+        .map(|v| v.and_then(to_datetime).map(&f))
+        .collect()
+}
+
+/// Apply the date function to the date or timestamp array,
+/// the intervals are added by the second argument.
+pub fn data_array_date_op(
+    op: DataValueDateOperator,
+    args: &[DataArrayRef],
+) -> FuseQueryResult<DataArrayRef> {
+    let data_type = super::data_type::date_coercion(&op, args[0].data_type())?;
+    let seconds = array_to_seconds(&args[0])?;
+
+    Ok(match op {
+        DataValueDateOperator::ToYear => {
+            Arc::new(UInt16Array::from(date_part(&seconds, |v| v.year() as u16)))
+        }
+        DataValueDateOperator::ToMonth => {
+            Arc::new(UInt8Array::from(date_part(&seconds, |v| v.month() as u8)))
+        }
+        DataValueDateOperator::ToDayOfMonth => {
+            Arc::new(UInt8Array::from(date_part(&seconds, |v| v.day() as u8)))
+        }
+        // Monday is 1 and Sunday is 7.
+        DataValueDateOperator::ToDayOfWeek => {
+            Arc::new(UInt8Array::from(date_part(&seconds, |v| {
+                v.weekday().number_from_monday() as u8
+            })))
+        }
+        DataValueDateOperator::ToHour => {
+            Arc::new(UInt8Array::from(date_part(&seconds, |v| v.hour() as u8)))
+        }
+        DataValueDateOperator::ToMinute => {
+            Arc::new(UInt8Array::from(date_part(&seconds, |v| v.minute() as u8)))
+        }
+        DataValueDateOperator::ToSecond => {
+            Arc::new(UInt8Array::from(date_part(&seconds, |v| v.second() as u8)))
+        }
+        DataValueDateOperator::ToStartOfDay => seconds_to_array(
+            seconds
+                .iter()
+                .map(|v| v.and_then(|v| start_of_interval(v, 1, &DataValueIntervalUnit::Day)))
+                .collect(),
+            &data_type,
+        ),
+        DataValueDateOperator::ToStartOfMonth => seconds_to_array(
+            seconds
+                .iter()
+                .map(|v| v.and_then(|v| start_of_interval(v, 1, &DataValueIntervalUnit::Month)))
+                .collect(),
+            &data_type,
+        ),
+        DataValueDateOperator::ToStartOfYear => seconds_to_array(
+            seconds
+                .iter()
+                .map(|v| v.and_then(|v| start_of_interval(v, 1, &DataValueIntervalUnit::Year)))
+                .collect(),
+            &data_type,
+        ),
+        DataValueDateOperator::ToStartOfInterval(n, ref unit) => {
+            if n <= 0 {
+                return Err(FuseQueryError::Internal(format!(
+                    "The interval of {} must be positive, but got {}",
+                    op, n
+                )));
+            }
+            seconds_to_array(
+                seconds
+                    .iter()
+                    .map(|v| v.and_then(|v| start_of_interval(v, n, unit)))
+                    .collect(),
+                &data_type,
+            )
+        }
+        DataValueDateOperator::AddInterval(ref unit)
+        | DataValueDateOperator::SubtractInterval(ref unit) => {
+            let sign = if matches!(op, DataValueDateOperator::AddInterval(_)) {
+                1
+            } else {
+                -1
+            };
+            let deltas = super::data_array_cast(&args[1], &DataType::Int64)?;
+            let deltas = downcast_array!(deltas, Int64Array)?;
+            seconds_to_array(
+                seconds
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        if deltas.is_null(i) {
+                            return None;
+                        }
+                        add_interval((*v)?, unit, deltas.value(i).checked_mul(sign)?)
+                    })
+                    .collect(),
+                &data_type,
+            )
+        }
+        DataValueDateOperator::Now | DataValueDateOperator::Today => {
+            return Err(FuseQueryError::Internal(format!(
+                "Unsupported data_array_date_op for {}",
+                op
+            )))
+        }
+    })
+}
+
+/// Cast the date or timestamp array to the other temporal type or the string,
+/// the strings which cannot be parsed are NULLs.
+pub(crate) fn data_array_temporal_cast(
+    array: &DataArrayRef,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    match (array.data_type(), data_type) {
+        (DataType::Utf8, _) => {
+            let array = downcast_array!(array, StringArray)?;
+            let values = (0..array.len()).map(|i| {
+                if array.is_null(i) {
+                    None
+                } else {
+                    Some(array.value(i))
+                }
+            });
+            Ok(if data_type == &DATE_TYPE {
+                Arc::new(Date32Array::from(
+                    values.map(|v| v.and_then(parse_date)).collect::<Vec<_>>(),
+                ))
+            } else {
+                Arc::new(TimestampSecondArray::from_opt_vec(
+                    values.map(|v| v.and_then(parse_timestamp)).collect(),
+                    None,
+                ))
+            })
+        }
+        (_, DataType::Utf8) => {
+            let is_date = array.data_type() == &DATE_TYPE;
+            let values = array_to_seconds(array)?
+                .into_iter()
+                .map(|v| {
+                    v.map(|v| match is_date {
+                        true => date_to_string(v.div_euclid(SECONDS_PER_DAY)),
+                        false => timestamp_to_string(v),
+                    })
+                })
+                .collect::<Vec<_>>();
+            Ok(Arc::new(StringArray::from(
+                values.iter().map(|v| v.as_deref()).collect::<Vec<_>>(),
+            )))
+        }
+        _ if data_type == &TIMESTAMP_TYPE || data_type == &DATE_TYPE => {
+            Ok(seconds_to_array(array_to_seconds(array)?, data_type))
+        }
+        (from, to) => Err(FuseQueryError::Internal(format!(
+            "Unsupported cast from {:?} to {:?}",
+            from, to
+        ))),
+    }
+}
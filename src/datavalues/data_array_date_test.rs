@@ -0,0 +1,197 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_date_op() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        op: DataValueDateOperator,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    // 2021-03-04 is the day 18690, 2021-03-04 10:20:30 is the second 1614853230.
+    let dates: DataArrayRef = Arc::new(Date32Array::from(vec![Some(18690), None]));
+    let timestamps: DataArrayRef = Arc::new(TimestampSecondArray::from_opt_vec(
+        vec![Some(1614853230), None],
+        None,
+    ));
+
+    let tests = vec![
+        ArrayTest {
+            name: "to-year-date-passed",
+            args: vec![dates.clone()],
+            op: DataValueDateOperator::ToYear,
+            expect: Arc::new(UInt16Array::from(vec![Some(2021), None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-day-of-week-date-passed",
+            args: vec![dates.clone()],
+            op: DataValueDateOperator::ToDayOfWeek,
+            expect: Arc::new(UInt8Array::from(vec![Some(4), None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-hour-timestamp-passed",
+            args: vec![timestamps.clone()],
+            op: DataValueDateOperator::ToHour,
+            expect: Arc::new(UInt8Array::from(vec![Some(10), None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-start-of-day-timestamp-passed",
+            args: vec![timestamps.clone()],
+            op: DataValueDateOperator::ToStartOfDay,
+            expect: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614816000), None],
+                None,
+            )),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-start-of-month-timestamp-passed",
+            args: vec![timestamps.clone()],
+            op: DataValueDateOperator::ToStartOfMonth,
+            expect: Arc::new(Date32Array::from(vec![Some(18687), None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-start-of-interval-minute-passed",
+            args: vec![timestamps.clone()],
+            op: DataValueDateOperator::ToStartOfInterval(15, DataValueIntervalUnit::Minute),
+            expect: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614852900), None],
+                None,
+            )),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-start-of-interval-zero-error",
+            args: vec![timestamps.clone()],
+            op: DataValueDateOperator::ToStartOfInterval(0, DataValueIntervalUnit::Day),
+            expect: Arc::new(Date32Array::from(vec![Some(18690), None])),
+            error: "Internal Error: The interval of toStartOfInterval must be positive, but got 0",
+        },
+        ArrayTest {
+            name: "add-months-end-of-month-passed",
+            args: vec![
+                Arc::new(Date32Array::from(vec![18658])),
+                Arc::new(Int64Array::from(vec![1])),
+            ],
+            op: DataValueDateOperator::AddInterval(DataValueIntervalUnit::Month),
+            expect: Arc::new(Date32Array::from(vec![18686])),
+            error: "",
+        },
+        ArrayTest {
+            name: "subtract-hours-date-passed",
+            args: vec![dates.clone(), Arc::new(Int64Array::from(vec![1, 1]))],
+            op: DataValueDateOperator::SubtractInterval(DataValueIntervalUnit::Hour),
+            expect: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614812400), None],
+                None,
+            )),
+            error: "",
+        },
+        ArrayTest {
+            name: "to-year-int64-error",
+            args: vec![Arc::new(Int64Array::from(vec![1]))],
+            op: DataValueDateOperator::ToYear,
+            expect: Arc::new(UInt16Array::from(vec![1])),
+            error: "Internal Error: Unsupported toYear (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_date_op(t.op.clone(), &t.args);
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert_eq!(t.error, e.to_string(), "{}", t.name),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_array_temporal_cast() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        array: DataArrayRef,
+        data_type: DataType,
+        expect: DataArrayRef,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "string-to-date-passed",
+            array: Arc::new(StringArray::from(vec![
+                "2021-03-04",
+                "2021-03-04 10:20:30",
+                "abc",
+            ])),
+            data_type: DATE_TYPE,
+            expect: Arc::new(Date32Array::from(vec![Some(18690), Some(18690), None])),
+        },
+        ArrayTest {
+            name: "string-to-timestamp-passed",
+            array: Arc::new(StringArray::from(vec!["2021-03-04 10:20:30", "2021-03-04"])),
+            data_type: TIMESTAMP_TYPE,
+            expect: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614853230), Some(1614816000)],
+                None,
+            )),
+        },
+        ArrayTest {
+            name: "timestamp-to-string-passed",
+            array: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614853230), None],
+                None,
+            )),
+            data_type: DataType::Utf8,
+            expect: Arc::new(StringArray::from(vec![Some("2021-03-04 10:20:30"), None])),
+        },
+        ArrayTest {
+            name: "timestamp-to-date-passed",
+            array: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614853230), Some(-1)],
+                None,
+            )),
+            data_type: DATE_TYPE,
+            expect: Arc::new(Date32Array::from(vec![18690, -1])),
+        },
+        ArrayTest {
+            name: "date-to-int32-passed",
+            array: Arc::new(Date32Array::from(vec![18690])),
+            data_type: DataType::Int32,
+            expect: Arc::new(Int32Array::from(vec![18690])),
+        },
+    ];
+
+    for t in tests {
+        let v = data_array_cast(&t.array, &t.data_type)?;
+        // Result check.
+        if !v.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,314 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{Array, PrimitiveArrayOps};
+
+use crate::datavalues::data_type::{decimal_precision_scale, decimal_type, DECIMAL_MAX_PRECISION};
+use crate::datavalues::{
+    BooleanArray, DataArrayRef, DataType, DataValueArithmeticOperator, DecimalArray, Float64Array,
+    Int64Array, StringArray,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+fn pow10(scale: usize) -> i128 {
+    10_i128.pow(scale as u32)
+}
+
+// The value fits the precision if it has at most precision digits.
+fn fit(value: i128, precision: usize) -> Option<i64> {
+    if value.abs() < pow10(precision) {
+        i64::try_from(value).ok()
+    } else {
+        None
+    }
+}
+
+// Rescale the value, the dropped digits are rounded half away from zero.
+fn rescale(value: i128, from: usize, to: usize) -> Option<i128> {
+    if to >= from {
+        return value.checked_mul(pow10(to - from));
+    }
+    let divisor = pow10(from - to);
+    let (quotient, remainder) = (value / divisor, value % divisor);
+    Some(if remainder.abs() * 2 >= divisor {
+        quotient + value.signum()
+    } else {
+        quotient
+    })
+}
+
+pub(crate) fn decimal_to_string(value: i64, scale: usize) -> String {
+    if scale == 0 {
+        return value.to_string();
+    }
+    let divisor = pow10(scale);
+    let value = value as i128;
+    let sign = if value < 0 { "-" } else { "" };
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        (value / divisor).abs(),
+        (value % divisor).abs(),
+        width = scale
+    )
+}
+
+/// Parse the decimal such as -12.345, the extra digits of the scale are rounded.
+pub(crate) fn parse_decimal(value: &str, precision: usize, scale: usize) -> Option<i64> {
+    let value = value.trim();
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (integral, fraction) = match digits.find('.') {
+        Some(i) => (&digits[..i], &digits[i + 1..]),
+        None => (digits, ""),
+    };
+    if integral.is_empty() && fraction.is_empty()
+        || !integral
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        || integral.len() + fraction.len() > 38
+    {
+        return None;
+    }
+
+    let unscaled = format!("{}{}", integral, fraction).parse::<i128>().ok()?;
+    let value = rescale(unscaled, fraction.len(), scale)?;
+    fit(if negative { -value } else { value }, precision)
+}
+
+// The unscaled values and the scale of the decimal array.
+fn decimal_values(array: &DataArrayRef) -> FuseQueryResult<(Vec<Option<i128>>, usize)> {
+    let (_, scale) = decimal_precision_scale(array.data_type()).ok_or_else(|| {
+        FuseQueryError::Internal(format!(
+            "Cannot downcast_array from datatype:{:?} item to:DecimalArray",
+            array.data_type()
+        ))
+    })?;
+    let array = downcast_array!(array, DecimalArray)?;
+    let values = (0..array.len())
+        .map(|i| {
+            if array.is_null(i) {
+                None
+            } else {
+                Some(array.value(i) as i128)
+            }
+        })
+        .collect();
+    Ok((values, scale))
+}
+
+fn decimal_array(values: Vec<Option<i64>>, data_type: &DataType) -> DataArrayRef {
+    // The type name is the timezone of the storage array.
+    let name = match data_type {
+        DataType::Timestamp(_, name) => name.clone(),
+        _ => None,
+    };
+    Arc::new(DecimalArray::from_opt_vec(values, name))
+}
+
+/// Cast the array to or from the decimal type, the values which overflow are NULLs.
+pub(crate) fn data_array_decimal_cast(
+    array: &DataArrayRef,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    if let Some((precision, scale)) = decimal_precision_scale(data_type) {
+        let values: Vec<Option<i64>> = match array.data_type() {
+            from if decimal_precision_scale(from).is_some() => {
+                let (values, from_scale) = decimal_values(array)?;
+                values
+                    .into_iter()
+                    .map(|v| v.and_then(|v| fit(rescale(v, from_scale, scale)?, precision)))
+                    .collect()
+            }
+            DataType::Utf8 => {
+                let array = downcast_array!(array, StringArray)?;
+                (0..array.len())
+                    .map(|i| {
+                        if array.is_null(i) {
+                            None
+                        } else {
+                            parse_decimal(array.value(i), precision, scale)
+                        }
+                    })
+                    .collect()
+            }
+            DataType::Float32 | DataType::Float64 => {
+                let array = arrow::compute::cast(array, &DataType::Float64)?;
+                let array = downcast_array!(array, Float64Array)?;
+                (0..array.len())
+                    .map(|i| {
+                        let value = (array.value(i) * pow10(scale) as f64).round();
+                        if array.is_null(i) || !value.is_finite() || value.abs() >= 1e38 {
+                            None
+                        } else {
+                            fit(value as i128, precision)
+                        }
+                    })
+                    .collect()
+            }
+            DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64 => {
+                let array = arrow::compute::cast(array, &DataType::Int64)?;
+                let array = downcast_array!(array, Int64Array)?;
+                (0..array.len())
+                    .map(|i| {
+                        if array.is_null(i) {
+                            None
+                        } else {
+                            fit(rescale(array.value(i) as i128, 0, scale)?, precision)
+                        }
+                    })
+                    .collect()
+            }
+            from => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported cast from {:?} to {:?}",
+                    from, data_type
+                )))
+            }
+        };
+        return Ok(decimal_array(values, data_type));
+    }
+
+    let (values, scale) = decimal_values(array)?;
+    match data_type {
+        DataType::Utf8 => {
+            let values = values
+                .iter()
+                .map(|v| v.map(|v| decimal_to_string(v as i64, scale)))
+                .collect::<Vec<_>>();
+            Ok(Arc::new(StringArray::from(
+                values.iter().map(|v| v.as_deref()).collect::<Vec<_>>(),
+            )))
+        }
+        DataType::Boolean => Ok(Arc::new(BooleanArray::from(
+            values.iter().map(|v| v.map(|v| v != 0)).collect::<Vec<_>>(),
+        ))),
+        DataType::Float32 | DataType::Float64 => {
+            let array: DataArrayRef = Arc::new(Float64Array::from(
+                values
+                    .iter()
+                    .map(|v| v.map(|v| v as f64 / pow10(scale) as f64))
+                    .collect::<Vec<_>>(),
+            ));
+            Ok(arrow::compute::cast(&array, data_type)?)
+        }
+        // The integers are truncated toward zero.
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => {
+            let array: DataArrayRef = Arc::new(Int64Array::from(
+                values
+                    .iter()
+                    .map(|v| v.map(|v| (v / pow10(scale)) as i64))
+                    .collect::<Vec<_>>(),
+            ));
+            Ok(arrow::compute::cast(&array, data_type)?)
+        }
+        to => Err(FuseQueryError::Internal(format!(
+            "Unsupported cast from {:?} to {:?}",
+            array.data_type(),
+            to
+        ))),
+    }
+}
+
+/// The exact decimal arithmetic in the result type, the overflow is an error.
+pub(crate) fn data_array_decimal_arithmetic(
+    op: DataValueArithmeticOperator,
+    left: &DataArrayRef,
+    right: &DataArrayRef,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    let (precision, scale) = decimal_precision_scale(data_type).ok_or_else(|| {
+        FuseQueryError::Internal(format!("Unsupported decimal {} ({:?})", op, data_type))
+    })?;
+    // The integers are the decimals of scale 0.
+    let as_decimal = |array: &DataArrayRef| match decimal_precision_scale(array.data_type()) {
+        Some(_) => decimal_values(array),
+        None => decimal_values(&data_array_decimal_cast(
+            array,
+            &decimal_type(DECIMAL_MAX_PRECISION, 0),
+        )?),
+    };
+    let (lhs, s1) = as_decimal(left)?;
+    let (rhs, s2) = as_decimal(right)?;
+
+    let overflow = || FuseQueryError::Internal(format!("Decimal overflow of {}", op));
+    let mut values = Vec::with_capacity(lhs.len());
+    for (a, b) in lhs.into_iter().zip(rhs) {
+        let (a, b) = match (a, b) {
+            (Some(a), Some(b)) => (a, b),
+            _ => {
+                values.push(None);
+                continue;
+            }
+        };
+        let value = match op {
+            DataValueArithmeticOperator::Add => rescale(a, s1, scale)
+                .zip(rescale(b, s2, scale))
+                .and_then(|(a, b)| a.checked_add(b)),
+            DataValueArithmeticOperator::Sub => rescale(a, s1, scale)
+                .zip(rescale(b, s2, scale))
+                .and_then(|(a, b)| a.checked_sub(b)),
+            DataValueArithmeticOperator::Mul => {
+                a.checked_mul(b).and_then(|v| rescale(v, s1 + s2, scale))
+            }
+            DataValueArithmeticOperator::Div | DataValueArithmeticOperator::Modulo if b == 0 => {
+                return Err(FuseQueryError::Internal("Divide by zero error".to_string()))
+            }
+            // The quotient is truncated toward zero.
+            DataValueArithmeticOperator::Div => rescale(a, s1, scale + s2).map(|a| a / b),
+            DataValueArithmeticOperator::Modulo => rescale(a, s1, scale)
+                .zip(rescale(b, s2, scale))
+                .map(|(a, b)| a % b),
+        };
+        values.push(Some(
+            value.and_then(|v| fit(v, precision)).ok_or_else(overflow)?,
+        ));
+    }
+    Ok(decimal_array(values, data_type))
+}
+
+/// The negation of the decimal array, the type is kept.
+pub(crate) fn data_array_decimal_negate(array: &DataArrayRef) -> FuseQueryResult<DataArrayRef> {
+    let (values, _) = decimal_values(array)?;
+    Ok(decimal_array(
+        values.into_iter().map(|v| v.map(|v| -v as i64)).collect(),
+        array.data_type(),
+    ))
+}
+
+/// The sum of the decimal array in the decimal of the maximum precision, None if all are NULLs.
+pub(crate) fn data_array_decimal_sum(array: &DataArrayRef) -> FuseQueryResult<Option<i64>> {
+    let (values, _) = decimal_values(array)?;
+    let mut sum: Option<i128> = None;
+    for value in values.into_iter().flatten() {
+        sum = Some(sum.unwrap_or(0) + value);
+    }
+    match sum {
+        Some(sum) => fit(sum, DECIMAL_MAX_PRECISION)
+            .map(Some)
+            .ok_or_else(|| FuseQueryError::Internal("Decimal overflow of sum".to_string())),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_decimal_cast() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        array: DataArrayRef,
+        data_type: DataType,
+        expect: DataArrayRef,
+    }
+
+    let decimal = |values: Vec<Option<i64>>, p: usize, s: usize| -> DataArrayRef {
+        Arc::new(DecimalArray::from_opt_vec(
+            values,
+            Some(Arc::new(format!("Decimal({}, {})", p, s))),
+        ))
+    };
+
+    let tests = vec![
+        ArrayTest {
+            name: "string-to-decimal-passed",
+            array: Arc::new(StringArray::from(vec![
+                "1.25",
+                "-0.005",
+                "123456789.1",
+                "abc",
+            ])),
+            data_type: decimal_type(10, 2),
+            expect: decimal(vec![Some(125), Some(-1), None, None], 10, 2),
+        },
+        ArrayTest {
+            name: "int64-to-decimal-passed",
+            array: Arc::new(Int64Array::from(vec![3, -1000])),
+            data_type: decimal_type(4, 1),
+            expect: decimal(vec![Some(30), None], 4, 1),
+        },
+        ArrayTest {
+            name: "decimal-to-decimal-passed",
+            array: decimal(vec![Some(12345), None], 10, 3),
+            data_type: decimal_type(10, 1),
+            expect: decimal(vec![Some(123), None], 10, 1),
+        },
+        ArrayTest {
+            name: "decimal-to-string-passed",
+            array: decimal(vec![Some(-105), Some(7), None], 10, 2),
+            data_type: DataType::Utf8,
+            expect: Arc::new(StringArray::from(vec![Some("-1.05"), Some("0.07"), None])),
+        },
+        ArrayTest {
+            name: "decimal-to-int32-passed",
+            array: decimal(vec![Some(-199), Some(250)], 10, 2),
+            data_type: DataType::Int32,
+            expect: Arc::new(Int32Array::from(vec![-1, 2])),
+        },
+        ArrayTest {
+            name: "decimal-to-float64-passed",
+            array: decimal(vec![Some(125)], 10, 2),
+            data_type: DataType::Float64,
+            expect: Arc::new(Float64Array::from(vec![1.25])),
+        },
+    ];
+
+    for t in tests {
+        let v = data_array_cast(&t.array, &t.data_type)?;
+        // Result check.
+        if !v.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_array_decimal_arithmetic() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        op: DataValueArithmeticOperator,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let decimal = |values: Vec<Option<i64>>, p: usize, s: usize| -> DataArrayRef {
+        Arc::new(DecimalArray::from_opt_vec(
+            values,
+            Some(Arc::new(format!("Decimal({}, {})", p, s))),
+        ))
+    };
+
+    let tests = vec![
+        ArrayTest {
+            name: "add-decimal-passed",
+            args: vec![
+                decimal(vec![Some(125), None], 10, 2),
+                decimal(vec![Some(25), Some(1)], 10, 1),
+            ],
+            op: DataValueArithmeticOperator::Add,
+            expect: decimal(vec![Some(375), None], 12, 2),
+            error: "",
+        },
+        ArrayTest {
+            name: "sub-decimal-int64-passed",
+            args: vec![
+                decimal(vec![Some(10), Some(-10)], 10, 1),
+                Arc::new(Int64Array::from(vec![1, 1])),
+            ],
+            op: DataValueArithmeticOperator::Sub,
+            expect: decimal(vec![Some(0), Some(-20)], 18, 1),
+            error: "",
+        },
+        ArrayTest {
+            name: "mul-decimal-passed",
+            args: vec![
+                decimal(vec![Some(15)], 4, 1),
+                decimal(vec![Some(-25)], 4, 2),
+            ],
+            op: DataValueArithmeticOperator::Mul,
+            expect: decimal(vec![Some(-375)], 8, 3),
+            error: "",
+        },
+        ArrayTest {
+            name: "div-decimal-int64-passed",
+            args: vec![
+                decimal(vec![Some(1000)], 10, 2),
+                Arc::new(Int64Array::from(vec![3])),
+            ],
+            op: DataValueArithmeticOperator::Div,
+            expect: decimal(vec![Some(333)], 18, 2),
+            error: "",
+        },
+        ArrayTest {
+            name: "div-decimal-zero-error",
+            args: vec![
+                decimal(vec![Some(1000)], 10, 2),
+                decimal(vec![Some(0)], 10, 2),
+            ],
+            op: DataValueArithmeticOperator::Div,
+            expect: decimal(vec![None], 18, 2),
+            error: "Internal Error: Divide by zero error",
+        },
+        ArrayTest {
+            name: "mul-decimal-overflow-error",
+            args: vec![
+                decimal(vec![Some(999_999_999)], 18, 0),
+                decimal(vec![Some(999_999_999_999)], 18, 0),
+            ],
+            op: DataValueArithmeticOperator::Mul,
+            expect: decimal(vec![None], 18, 0),
+            error: "Internal Error: Decimal overflow of *",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_arithmetic_op(
+            t.op.clone(),
+            &DataColumnarValue::Array(t.args[0].clone()),
+            &DataColumnarValue::Array(t.args[1].clone()),
+        );
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert_eq!(t.error, e.to_string(), "{}", t.name),
+        }
+    }
+
+    // The negation keeps the type.
+    let v = data_array_unary_op(
+        DataValueUnaryOperator::Negate,
+        &DataColumnarValue::Array(decimal(vec![Some(125), None], 10, 2)),
+    )?;
+    assert!(v.equals(&*decimal(vec![Some(-125), None], 10, 2)));
+
+    // The sum is in the decimal of the maximum precision.
+    let v = data_array_aggregate_op(
+        DataValueAggregateOperator::Sum,
+        decimal(vec![Some(125), None, Some(-25)], 10, 2),
+    )?;
+    assert_eq!("1.00", format!("{:?}", v));
+    assert_eq!(decimal_type(DECIMAL_MAX_PRECISION, 2), v.data_type());
+    Ok(())
+}
@@ -0,0 +1,333 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::convert::TryInto;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use arrow::array::{PrimitiveArrayOps, StringBuilder};
+use arrow::datatypes::TimeUnit;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use siphasher::sip::SipHasher24;
+use twox_hash::XxHash64;
+
+use crate::datavalues::data_type::hash_coercion;
+use crate::datavalues::{
+    data_array_cast, is_decimal, BooleanArray, DataArrayRef, DataType, DataValueHashOperator,
+    Date32Array, DecimalArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    Int8Array, StringArray, TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array,
+    UInt8Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+// The CityHash64 of the version 1.0.2, which is the cityHash64 of ClickHouse.
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+const K3: u64 = 0xc949_d7c7_509e_6557;
+
+fn fetch64(s: &[u8]) -> u64 {
+    u64::from_le_bytes(s[..8].try_into().unwrap())
+}
+
+fn fetch32(s: &[u8]) -> u64 {
+    u32::from_le_bytes(s[..4].try_into().unwrap()) as u64
+}
+
+fn shift_mix(v: u64) -> u64 {
+    v ^ (v >> 47)
+}
+
+fn hash128_to_64(low: u64, high: u64) -> u64 {
+    const MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+    let mut a = (low ^ high).wrapping_mul(MUL);
+    a ^= a >> 47;
+    let mut b = (high ^ a).wrapping_mul(MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(MUL)
+}
+
+fn hash_len0to16(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len > 8 {
+        let a = fetch64(s);
+        let b = fetch64(&s[len - 8..]);
+        return hash128_to_64(a, b.wrapping_add(len as u64).rotate_right(len as u32)) ^ b;
+    }
+    if len >= 4 {
+        let a = fetch32(s);
+        return hash128_to_64((len as u64).wrapping_add(a << 3), fetch32(&s[len - 4..]));
+    }
+    if len > 0 {
+        let y = s[0] as u32 + ((s[len >> 1] as u32) << 8);
+        let z = len as u32 + ((s[len - 1] as u32) << 2);
+        return shift_mix((y as u64).wrapping_mul(K2) ^ (z as u64).wrapping_mul(K3))
+            .wrapping_mul(K2);
+    }
+    K2
+}
+
+fn hash_len17to32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let a = fetch64(s).wrapping_mul(K1);
+    let b = fetch64(&s[8..]);
+    let c = fetch64(&s[len - 8..]).wrapping_mul(K2);
+    let d = fetch64(&s[len - 16..]).wrapping_mul(K0);
+    hash128_to_64(
+        a.wrapping_sub(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add((b ^ K3).rotate_right(20))
+            .wrapping_sub(c)
+            .wrapping_add(len as u64),
+    )
+}
+
+fn weak_hash_len32_with_seeds(s: &[u8], a: u64, b: u64) -> (u64, u64) {
+    let (w, x, y, z) = (
+        fetch64(s),
+        fetch64(&s[8..]),
+        fetch64(&s[16..]),
+        fetch64(&s[24..]),
+    );
+    let mut a = a.wrapping_add(w);
+    let mut b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+fn hash_len33to64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mut z = fetch64(&s[24..]);
+    let mut a = fetch64(s).wrapping_add(
+        (len as u64)
+            .wrapping_add(fetch64(&s[len - 16..]))
+            .wrapping_mul(K0),
+    );
+    let mut b = a.wrapping_add(z).rotate_right(52);
+    let mut c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(&s[8..]));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(&s[16..]));
+    let vf = a.wrapping_add(z);
+    let vs = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+    a = fetch64(&s[16..]).wrapping_add(fetch64(&s[len - 32..]));
+    z = fetch64(&s[len - 8..]);
+    b = a.wrapping_add(z).rotate_right(52);
+    c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(&s[len - 24..]));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(&s[len - 16..]));
+    let wf = a.wrapping_add(z);
+    let ws = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+    let r = shift_mix(
+        vf.wrapping_add(ws)
+            .wrapping_mul(K2)
+            .wrapping_add(wf.wrapping_add(vs).wrapping_mul(K0)),
+    );
+    shift_mix(r.wrapping_mul(K0).wrapping_add(vs)).wrapping_mul(K2)
+}
+
+fn city_hash64(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len <= 16 {
+        return hash_len0to16(s);
+    }
+    if len <= 32 {
+        return hash_len17to32(s);
+    }
+    if len <= 64 {
+        return hash_len33to64(s);
+    }
+
+    // The end is hashed first, then the 64-byte chunks with the state of v, w, x, y and z.
+    let mut x = fetch64(s);
+    let mut y = fetch64(&s[len - 16..]) ^ K1;
+    let mut z = fetch64(&s[len - 56..]) ^ K0;
+    let mut v = weak_hash_len32_with_seeds(&s[len - 64..], len as u64, y);
+    let mut w = weak_hash_len32_with_seeds(&s[len - 32..], (len as u64).wrapping_mul(K1), K0);
+    z = z.wrapping_add(shift_mix(v.1).wrapping_mul(K1));
+    x = z.wrapping_add(x).rotate_right(39).wrapping_mul(K1);
+    y = y.rotate_right(33).wrapping_mul(K1);
+
+    for chunk in s[..(len - 1) & !63].chunks(64) {
+        x = x
+            .wrapping_add(y)
+            .wrapping_add(v.0)
+            .wrapping_add(fetch64(&chunk[16..]))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        y = y
+            .wrapping_add(v.1)
+            .wrapping_add(fetch64(&chunk[48..]))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        x ^= w.1;
+        y ^= v.0;
+        z = (z ^ w.0).rotate_right(33);
+        v = weak_hash_len32_with_seeds(chunk, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len32_with_seeds(&chunk[32..], z.wrapping_add(w.1), y);
+        std::mem::swap(&mut z, &mut x);
+    }
+    hash128_to_64(
+        hash128_to_64(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash128_to_64(v.1, w.1).wrapping_add(x),
+    )
+}
+
+fn push_hex(bytes: &[u8], buffer: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    for byte in bytes {
+        buffer.push(HEX[(byte >> 4) as usize] as char);
+        buffer.push(HEX[(byte & 0xf) as usize] as char);
+    }
+}
+
+fn hash64(op: &DataValueHashOperator, s: &[u8]) -> u64 {
+    match op {
+        DataValueHashOperator::XxHash64 => {
+            let mut hasher = XxHash64::with_seed(0);
+            hasher.write(s);
+            hasher.finish()
+        }
+        DataValueHashOperator::SipHash64 => {
+            let mut hasher = SipHasher24::new_with_keys(0, 0);
+            hasher.write(s);
+            hasher.finish()
+        }
+        _ => city_hash64(s),
+    }
+}
+
+type ValueEncoder<'a> = Box<dyn Fn(usize, &mut Vec<u8>) + 'a>;
+
+macro_rules! typed_value_encoder {
+    ($ARRAY:expr, $ARRAYTYPE:ident, $MAP:expr) => {{
+        let array = downcast_array!($ARRAY, $ARRAYTYPE)?;
+        Box::new(move |row: usize, buffer: &mut Vec<u8>| {
+            buffer.extend_from_slice(&$MAP(array.value(row)).to_le_bytes())
+        }) as ValueEncoder
+    }};
+}
+
+fn is_fixed_width(dt: &DataType) -> bool {
+    matches!(
+        dt,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Date32(_)
+            | DataType::Timestamp(TimeUnit::Second, _)
+    ) || is_decimal(dt)
+}
+
+// The fixed-width values are the little-endian bytes, the strings are the UTF-8 bytes.
+fn value_encoder(array: &DataArrayRef) -> FuseQueryResult<ValueEncoder<'_>> {
+    Ok(match array.data_type() {
+        DataType::Boolean => typed_value_encoder!(array, BooleanArray, |v: bool| v as u8),
+        DataType::Int8 => typed_value_encoder!(array, Int8Array, |v: i8| v),
+        DataType::Int16 => typed_value_encoder!(array, Int16Array, |v: i16| v),
+        DataType::Int32 => typed_value_encoder!(array, Int32Array, |v: i32| v),
+        DataType::Int64 => typed_value_encoder!(array, Int64Array, |v: i64| v),
+        DataType::UInt8 => typed_value_encoder!(array, UInt8Array, |v: u8| v),
+        DataType::UInt16 => typed_value_encoder!(array, UInt16Array, |v: u16| v),
+        DataType::UInt32 => typed_value_encoder!(array, UInt32Array, |v: u32| v),
+        DataType::UInt64 => typed_value_encoder!(array, UInt64Array, |v: u64| v),
+        DataType::Float32 => typed_value_encoder!(array, Float32Array, |v: f32| v),
+        DataType::Float64 => typed_value_encoder!(array, Float64Array, |v: f64| v),
+        DataType::Date32(_) => typed_value_encoder!(array, Date32Array, |v: i32| v),
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            typed_value_encoder!(array, TimestampSecondArray, |v: i64| v)
+        }
+        dt if is_decimal(dt) => typed_value_encoder!(array, DecimalArray, |v: i64| v),
+        _ => {
+            let array = downcast_array!(array, StringArray)?;
+            Box::new(move |row: usize, buffer: &mut Vec<u8>| {
+                buffer.extend_from_slice(array.value(row).as_bytes())
+            }) as ValueEncoder
+        }
+    })
+}
+
+/// The hash functions of the rows, the row is NULL if any argument is NULL.
+/// The numbers, the dates and the decimals are hashed by the little-endian bytes of the values,
+/// the other arguments by the bytes of the strings, so cityHash64(1) is not cityHash64('1').
+/// The hashes of the arguments are combined in order.
+pub fn data_array_hash_op(
+    op: DataValueHashOperator,
+    arrays: &[DataArrayRef],
+) -> FuseQueryResult<DataArrayRef> {
+    let data_types = arrays
+        .iter()
+        .map(|array| array.data_type().clone())
+        .collect::<Vec<_>>();
+    hash_coercion(&op, &data_types)?;
+    let rows = arrays.first().map(|array| array.len()).unwrap_or(0);
+    let arrays = arrays
+        .iter()
+        .map(|array| match is_fixed_width(array.data_type()) {
+            true => Ok(array.clone()),
+            false => data_array_cast(array, &DataType::Utf8),
+        })
+        .collect::<FuseQueryResult<Vec<_>>>()?;
+    let is_null = |row: usize| arrays.iter().any(|array| array.is_null(row));
+
+    match op {
+        DataValueHashOperator::Md5 | DataValueHashOperator::Sha256 => {
+            let strings = downcast_array!(arrays[0], StringArray)?;
+            let mut builder = StringBuilder::new(rows);
+            let mut buffer = String::new();
+            for row in 0..rows {
+                if is_null(row) {
+                    builder.append_null()?;
+                    continue;
+                }
+                buffer.clear();
+                let value = strings.value(row).as_bytes();
+                match op {
+                    DataValueHashOperator::Md5 => push_hex(&Md5::digest(value), &mut buffer),
+                    _ => push_hex(&Sha256::digest(value), &mut buffer),
+                }
+                builder.append_value(&buffer)?;
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+        _ => {
+            let encoders = arrays
+                .iter()
+                .map(value_encoder)
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            let mut buffer = vec![];
+            Ok(Arc::new(UInt64Array::from(
+                (0..rows)
+                    .map(|row| match is_null(row) {
+                        true => None,
+                        false => encoders
+                            .iter()
+                            .map(|encoder| {
+                                buffer.clear();
+                                encoder(row, &mut buffer);
+                                hash64(&op, &buffer)
+                            })
+                            .reduce(hash128_to_64),
+                    })
+                    .collect::<Vec<_>>(),
+            )))
+        }
+    }
+}
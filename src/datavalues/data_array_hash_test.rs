@@ -0,0 +1,125 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_hash_op() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        op: DataValueHashOperator,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let long = (0..1000)
+        .map(|i| (b'a' + (i % 26) as u8) as char)
+        .collect::<String>();
+    let strings = Arc::new(StringArray::from(vec![
+        Some(""),
+        Some("abc"),
+        Some("hello world"),
+        Some("0123456789abcdef0"),
+        Some("The quick brown fox jumps over the lazy dog"),
+        Some("The quick brown fox jumps over the lazy dog. The quick brown fox jumps over the lazy dog. 0123456789"),
+        Some(long.as_str()),
+        None,
+    ]));
+
+    let tests = vec![
+        ArrayTest {
+            name: "city-hash64-passed",
+            args: vec![strings.clone()],
+            op: DataValueHashOperator::CityHash64,
+            expect: vec![
+                "11160318154034397263",
+                "4220206313085259313",
+                "12386028635079221413",
+                "5496319037578566379",
+                "16697807905646383735",
+                "633378944582694010",
+                "10005932599788055761",
+                "NULL",
+            ],
+            error: "",
+        },
+        ArrayTest {
+            name: "city-hash64-int64-passed",
+            args: vec![Arc::new(Int64Array::from(vec![Some(1), None]))],
+            op: DataValueHashOperator::CityHash64,
+            expect: vec!["10143279697293281662", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "xx-hash64-passed",
+            args: vec![Arc::new(StringArray::from(vec![""]))],
+            op: DataValueHashOperator::XxHash64,
+            expect: vec!["17241709254077376921"],
+            error: "",
+        },
+        ArrayTest {
+            name: "sip-hash64-passed",
+            args: vec![Arc::new(StringArray::from(vec![""]))],
+            op: DataValueHashOperator::SipHash64,
+            expect: vec!["2202906307356721367"],
+            error: "",
+        },
+        ArrayTest {
+            name: "md5-passed",
+            args: vec![Arc::new(StringArray::from(vec![Some("abc"), None]))],
+            op: DataValueHashOperator::Md5,
+            expect: vec!["900150983cd24fb0d6963f7d28e17f72", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "sha256-passed",
+            args: vec![Arc::new(StringArray::from(vec!["abc"]))],
+            op: DataValueHashOperator::Sha256,
+            expect: vec!["ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"],
+            error: "",
+        },
+        ArrayTest {
+            name: "md5-int64-error",
+            args: vec![Arc::new(Int64Array::from(vec![1]))],
+            op: DataValueHashOperator::Md5,
+            expect: vec![],
+            error: "Internal Error: Unsupported md5 (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_hash_op(t.op.clone(), &t.args);
+        match result {
+            Ok(v) => {
+                let actual = (0..v.len())
+                    .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+                    .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+                assert_eq!(t.expect, actual, "{}", t.name);
+            }
+            Err(e) => assert_eq!(t.error, e.to_string(), "{}", t.name),
+        }
+    }
+
+    // The hashes of the arguments are combined in order.
+    let lhs: DataArrayRef = Arc::new(StringArray::from(vec!["a"]));
+    let rhs: DataArrayRef = Arc::new(StringArray::from(vec!["b"]));
+    let ab = data_array_hash_op(
+        DataValueHashOperator::CityHash64,
+        &[lhs.clone(), rhs.clone()],
+    )?;
+    let ba = data_array_hash_op(DataValueHashOperator::CityHash64, &[rhs, lhs])?;
+    assert!(!ab.equals(&*ba));
+
+    // The numbers are hashed by the bytes of the values, not by the strings.
+    let number: DataArrayRef = Arc::new(Int64Array::from(vec![1]));
+    let string: DataArrayRef = Arc::new(StringArray::from(vec!["1"]));
+    let number = data_array_hash_op(DataValueHashOperator::CityHash64, &[number])?;
+    let string = data_array_hash_op(DataValueHashOperator::CityHash64, &[string])?;
+    assert!(!number.equals(&*string));
+    Ok(())
+}
@@ -0,0 +1,263 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use arrow::array::{Array, PrimitiveArrayOps};
+use arrow::datatypes::TimeUnit;
+
+use crate::datavalues::{
+    data_array_try_cast, is_decimal, BooleanArray, DataArrayRef, DataType, DataValue, Date32Array,
+    DecimalArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
+    StringArray, TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+/// The lists longer than this are looked up in a hash set, the shorter ones are scanned.
+const IN_LIST_HASH_THRESHOLD: usize = 16;
+
+#[derive(Clone, Debug)]
+enum InValues<T: Hash + Eq> {
+    List(Vec<T>),
+    Set(HashSet<T>),
+}
+
+impl<T: Hash + Eq> InValues<T> {
+    fn create(values: Vec<T>) -> Self {
+        if values.len() > IN_LIST_HASH_THRESHOLD {
+            InValues::Set(values.into_iter().collect())
+        } else {
+            InValues::List(values)
+        }
+    }
+
+    fn contains<Q: Hash + Eq + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        match self {
+            InValues::List(values) => values.iter().any(|v| v.borrow() == value),
+            InValues::Set(values) => values.contains(value),
+        }
+    }
+}
+
+// The floats are compared by the bits, the negative zero is the zero.
+#[derive(Clone, Debug)]
+enum TypedInValues {
+    Boolean(InValues<bool>),
+    Int8(InValues<i8>),
+    Int16(InValues<i16>),
+    Int32(InValues<i32>),
+    Int64(InValues<i64>),
+    UInt8(InValues<u8>),
+    UInt16(InValues<u16>),
+    UInt32(InValues<u32>),
+    UInt64(InValues<u64>),
+    Float32(InValues<u32>),
+    Float64(InValues<u64>),
+    Utf8(InValues<String>),
+    Date32(InValues<i32>),
+    Timestamp(InValues<i64>),
+    Decimal(InValues<i64>),
+}
+
+macro_rules! typed_in_values {
+    ($ARRAY:expr, $ARRAYTYPE:ident, $MAP:expr) => {{
+        let array = downcast_array!($ARRAY, $ARRAYTYPE)?;
+        InValues::create(
+            (0..array.len())
+                .map(|i| $MAP(array.value(i)))
+                .collect::<Vec<_>>(),
+        )
+    }};
+}
+
+macro_rules! typed_in_array {
+    ($ARRAY:expr, $ARRAYTYPE:ident, $VALUES:expr, $MAP:expr, $SET:expr, $NEGATED:expr) => {{
+        let array = downcast_array!($ARRAY, $ARRAYTYPE)?;
+        (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    None
+                } else {
+                    $SET.matched($VALUES.contains(&$MAP(array.value(i))), $NEGATED)
+                }
+            })
+            .collect::<Vec<_>>()
+    }};
+}
+
+fn float32_bits(v: f32) -> u32 {
+    if v == 0.0 {
+        0
+    } else {
+        v.to_bits()
+    }
+}
+
+fn float64_bits(v: f64) -> u64 {
+    if v == 0.0 {
+        0
+    } else {
+        v.to_bits()
+    }
+}
+
+/// The values of the IN list compiled to the data type, the long lists are hash sets.
+#[derive(Clone, Debug)]
+pub struct InListSet {
+    data_type: DataType,
+    values: TypedInValues,
+    has_null: bool,
+}
+
+impl InListSet {
+    pub fn try_create(values: &[DataValue], data_type: &DataType) -> FuseQueryResult<Self> {
+        let mut casted = Vec::with_capacity(values.len());
+        let mut has_null = false;
+        for value in values {
+            if value.is_null() {
+                has_null = true;
+                continue;
+            }
+            let array = data_array_try_cast(&value.to_array(1)?, data_type)?;
+            casted.push(DataValue::try_from_array(&array, 0)?);
+        }
+        let array = DataValue::try_into_data_array(&casted, data_type)?;
+
+        let values = match data_type {
+            DataType::Boolean => {
+                TypedInValues::Boolean(typed_in_values!(array, BooleanArray, |v| v))
+            }
+            DataType::Int8 => TypedInValues::Int8(typed_in_values!(array, Int8Array, |v| v)),
+            DataType::Int16 => TypedInValues::Int16(typed_in_values!(array, Int16Array, |v| v)),
+            DataType::Int32 => TypedInValues::Int32(typed_in_values!(array, Int32Array, |v| v)),
+            DataType::Int64 => TypedInValues::Int64(typed_in_values!(array, Int64Array, |v| v)),
+            DataType::UInt8 => TypedInValues::UInt8(typed_in_values!(array, UInt8Array, |v| v)),
+            DataType::UInt16 => TypedInValues::UInt16(typed_in_values!(array, UInt16Array, |v| v)),
+            DataType::UInt32 => TypedInValues::UInt32(typed_in_values!(array, UInt32Array, |v| v)),
+            DataType::UInt64 => TypedInValues::UInt64(typed_in_values!(array, UInt64Array, |v| v)),
+            DataType::Float32 => {
+                TypedInValues::Float32(typed_in_values!(array, Float32Array, float32_bits))
+            }
+            DataType::Float64 => {
+                TypedInValues::Float64(typed_in_values!(array, Float64Array, float64_bits))
+            }
+            DataType::Utf8 => {
+                TypedInValues::Utf8(typed_in_values!(array, StringArray, |v: &str| v.to_string()))
+            }
+            DataType::Date32(_) => {
+                TypedInValues::Date32(typed_in_values!(array, Date32Array, |v| v))
+            }
+            DataType::Timestamp(TimeUnit::Second, _) => {
+                TypedInValues::Timestamp(typed_in_values!(array, TimestampSecondArray, |v| v))
+            }
+            // The decimals are compared by the unscaled values in the scale of the set.
+            DataType::Timestamp(TimeUnit::Nanosecond, _) if is_decimal(data_type) => {
+                TypedInValues::Decimal(typed_in_values!(array, DecimalArray, |v| v))
+            }
+            other => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported in ({:?})",
+                    other
+                )))
+            }
+        };
+
+        Ok(InListSet {
+            data_type: data_type.clone(),
+            values,
+            has_null,
+        })
+    }
+
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    // The value not in the list is NULL if the list has NULL.
+    fn matched(&self, contains: bool, negated: bool) -> Option<bool> {
+        if contains {
+            Some(!negated)
+        } else if self.has_null {
+            None
+        } else {
+            Some(negated)
+        }
+    }
+}
+
+/// Check whether the values of the array are in the set, the NULLs are NULLs.
+pub fn data_array_in(
+    array: &DataArrayRef,
+    set: &InListSet,
+    negated: bool,
+) -> FuseQueryResult<DataArrayRef> {
+    if array.data_type() == &DataType::Null {
+        return Ok(Arc::new(BooleanArray::from(vec![None; array.len()])));
+    }
+
+    let array = super::data_array_cast(array, &set.data_type)?;
+    let values = match &set.values {
+        TypedInValues::Boolean(values) => {
+            typed_in_array!(array, BooleanArray, values, |v| v, set, negated)
+        }
+        TypedInValues::Int8(values) => {
+            typed_in_array!(array, Int8Array, values, |v| v, set, negated)
+        }
+        TypedInValues::Int16(values) => {
+            typed_in_array!(array, Int16Array, values, |v| v, set, negated)
+        }
+        TypedInValues::Int32(values) => {
+            typed_in_array!(array, Int32Array, values, |v| v, set, negated)
+        }
+        TypedInValues::Int64(values) => {
+            typed_in_array!(array, Int64Array, values, |v| v, set, negated)
+        }
+        TypedInValues::UInt8(values) => {
+            typed_in_array!(array, UInt8Array, values, |v| v, set, negated)
+        }
+        TypedInValues::UInt16(values) => {
+            typed_in_array!(array, UInt16Array, values, |v| v, set, negated)
+        }
+        TypedInValues::UInt32(values) => {
+            typed_in_array!(array, UInt32Array, values, |v| v, set, negated)
+        }
+        TypedInValues::UInt64(values) => {
+            typed_in_array!(array, UInt64Array, values, |v| v, set, negated)
+        }
+        TypedInValues::Float32(values) => {
+            typed_in_array!(array, Float32Array, values, float32_bits, set, negated)
+        }
+        TypedInValues::Float64(values) => {
+            typed_in_array!(array, Float64Array, values, float64_bits, set, negated)
+        }
+        TypedInValues::Date32(values) => {
+            typed_in_array!(array, Date32Array, values, |v| v, set, negated)
+        }
+        TypedInValues::Timestamp(values) => {
+            typed_in_array!(array, TimestampSecondArray, values, |v| v, set, negated)
+        }
+        TypedInValues::Decimal(values) => {
+            typed_in_array!(array, DecimalArray, values, |v| v, set, negated)
+        }
+        TypedInValues::Utf8(values) => {
+            let array = downcast_array!(array, StringArray)?;
+            (0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        None
+                    } else {
+                        set.matched(values.contains(array.value(i)), negated)
+                    }
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+    Ok(Arc::new(BooleanArray::from(values)))
+}
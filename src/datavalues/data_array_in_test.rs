@@ -0,0 +1,113 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_in() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        array: DataArrayRef,
+        values: Vec<DataValue>,
+        data_type: DataType,
+        negated: bool,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "in-int64-passed",
+            array: Arc::new(Int64Array::from(vec![Some(1), None, Some(-3), Some(4)])),
+            values: vec![DataValue::UInt64(Some(1)), DataValue::Int64(Some(-3))],
+            data_type: DataType::Int64,
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![
+                Some(true),
+                None,
+                Some(true),
+                Some(false),
+            ])),
+            error: "",
+        },
+        ArrayTest {
+            name: "not-in-int64-passed",
+            array: Arc::new(Int64Array::from(vec![Some(1), None, Some(-3), Some(4)])),
+            values: vec![DataValue::UInt64(Some(1)), DataValue::Int64(Some(-3))],
+            data_type: DataType::Int64,
+            negated: true,
+            expect: Arc::new(BooleanArray::from(vec![
+                Some(false),
+                None,
+                Some(false),
+                Some(true),
+            ])),
+            error: "",
+        },
+        ArrayTest {
+            name: "in-hash-set-passed",
+            array: Arc::new(UInt64Array::from(vec![0, 50, 99, 100])),
+            values: (0..100).map(|v| DataValue::UInt64(Some(v))).collect(),
+            data_type: DataType::UInt64,
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![true, true, true, false])),
+            error: "",
+        },
+        ArrayTest {
+            name: "in-with-null-passed",
+            array: Arc::new(UInt8Array::from(vec![1, 2])),
+            values: vec![DataValue::UInt64(Some(1)), DataValue::Null],
+            data_type: DataType::UInt64,
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![Some(true), None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "in-float64-passed",
+            array: Arc::new(Float64Array::from(vec![-0.0, 1.5, 2.0])),
+            values: vec![DataValue::UInt64(Some(0)), DataValue::Float64(Some(1.5))],
+            data_type: DataType::Float64,
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![true, true, false])),
+            error: "",
+        },
+        ArrayTest {
+            name: "in-string-passed",
+            array: Arc::new(StringArray::from(vec![Some("a"), Some("b"), None])),
+            values: vec![DataValue::String(Some("a".to_string()))],
+            data_type: DataType::Utf8,
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![Some(true), Some(false), None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "in-cast-error",
+            array: Arc::new(Int64Array::from(vec![1])),
+            values: vec![DataValue::String(Some("a".to_string()))],
+            data_type: DataType::Int64,
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![false])),
+            error: "Internal Error: Cannot cast value a from Utf8 to Int64",
+        },
+    ];
+
+    for t in tests {
+        let result = InListSet::try_create(&t.values, &t.data_type)
+            .and_then(|set| data_array_in(&t.array, &set, t.negated));
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert_eq!(t.error, e.to_string()),
+        }
+    }
+    Ok(())
+}
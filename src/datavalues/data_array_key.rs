@@ -0,0 +1,140 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use arrow::array::{Array, PrimitiveArrayOps};
+use arrow::datatypes::TimeUnit;
+
+use crate::datavalues::{
+    is_decimal, BooleanArray, DataArrayRef, DataType, DataValue, Date32Array, DecimalArray,
+    Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, StringArray,
+    TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+/// The binary keys of the rows of the arrays, the keys of two rows are equal iff the rows are equal.
+/// The keys are in one buffer, they are used by the hash tables of GROUP BY.
+pub struct DataArrayRowKeys {
+    buffer: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl DataArrayRowKeys {
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn key(&self, row: usize) -> &[u8] {
+        &self.buffer[self.offsets[row]..self.offsets[row + 1]]
+    }
+}
+
+type KeyEncoder<'a> = Box<dyn Fn(usize, &mut Vec<u8>) -> FuseQueryResult<()> + 'a>;
+
+// Each value is the NULL flag and the little-endian bytes of the value.
+macro_rules! typed_key_encoder {
+    ($ARRAY:expr, $ARRAYTYPE:ident, $MAP:expr) => {{
+        let array = downcast_array!($ARRAY, $ARRAYTYPE)?;
+        Box::new(move |row: usize, key: &mut Vec<u8>| {
+            if array.is_null(row) {
+                key.push(0);
+            } else {
+                key.push(1);
+                key.extend_from_slice(&$MAP(array.value(row)).to_le_bytes());
+            }
+            Ok(())
+        }) as KeyEncoder
+    }};
+}
+
+fn key_encoder(array: &DataArrayRef) -> FuseQueryResult<KeyEncoder<'_>> {
+    Ok(match array.data_type() {
+        DataType::Boolean => typed_key_encoder!(array, BooleanArray, |v: bool| v as u8),
+        DataType::Int8 => typed_key_encoder!(array, Int8Array, |v: i8| v),
+        DataType::Int16 => typed_key_encoder!(array, Int16Array, |v: i16| v),
+        DataType::Int32 => typed_key_encoder!(array, Int32Array, |v: i32| v),
+        DataType::Int64 => typed_key_encoder!(array, Int64Array, |v: i64| v),
+        DataType::UInt8 => typed_key_encoder!(array, UInt8Array, |v: u8| v),
+        DataType::UInt16 => typed_key_encoder!(array, UInt16Array, |v: u16| v),
+        DataType::UInt32 => typed_key_encoder!(array, UInt32Array, |v: u32| v),
+        DataType::UInt64 => typed_key_encoder!(array, UInt64Array, |v: u64| v),
+        // The negative zero is the zero, the NaNs are one value.
+        DataType::Float32 => typed_key_encoder!(array, Float32Array, |v: f32| match v.is_nan() {
+            true => f32::NAN.to_bits(),
+            false => (v + 0.0).to_bits(),
+        }),
+        DataType::Float64 => typed_key_encoder!(array, Float64Array, |v: f64| match v.is_nan() {
+            true => f64::NAN.to_bits(),
+            false => (v + 0.0).to_bits(),
+        }),
+        DataType::Date32(_) => typed_key_encoder!(array, Date32Array, |v: i32| v),
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            typed_key_encoder!(array, TimestampSecondArray, |v: i64| v)
+        }
+        dt if is_decimal(dt) => typed_key_encoder!(array, DecimalArray, |v: i64| v),
+        DataType::Utf8 => {
+            let array = downcast_array!(array, StringArray)?;
+            Box::new(move |row: usize, key: &mut Vec<u8>| {
+                if array.is_null(row) {
+                    key.push(0);
+                } else {
+                    let value = array.value(row).as_bytes();
+                    key.push(1);
+                    key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+                    key.extend_from_slice(value);
+                }
+                Ok(())
+            }) as KeyEncoder
+        }
+        // The other values such as the arrays and the tuples are serialized.
+        _ => Box::new(move |row: usize, key: &mut Vec<u8>| {
+            let value = serde_json::to_vec(&DataValue::try_from_array(array, row)?)?;
+            key.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            key.extend_from_slice(&value);
+            Ok(())
+        }) as KeyEncoder,
+    })
+}
+
+/// The keys of the rows of the arrays, which have the same length.
+pub fn data_array_row_keys(arrays: &[DataArrayRef]) -> FuseQueryResult<DataArrayRowKeys> {
+    let rows = arrays.first().map(|array| array.len()).unwrap_or(0);
+    let encoders = arrays
+        .iter()
+        .map(key_encoder)
+        .collect::<FuseQueryResult<Vec<_>>>()?;
+
+    let mut buffer = Vec::with_capacity(rows * 9 * arrays.len());
+    let mut offsets = Vec::with_capacity(rows + 1);
+    offsets.push(0);
+    for row in 0..rows {
+        for encoder in &encoders {
+            encoder(row, &mut buffer)?;
+        }
+        offsets.push(buffer.len());
+    }
+    Ok(DataArrayRowKeys { buffer, offsets })
+}
+
+/// The key of one row of the arrays, which is the key of the row in data_array_row_keys.
+pub fn data_array_row_key(
+    arrays: &[DataArrayRef],
+    row: usize,
+    key: &mut Vec<u8>,
+) -> FuseQueryResult<()> {
+    for array in arrays {
+        if row >= array.len() {
+            return Err(FuseQueryError::Internal(format!(
+                "The row {} is out of the array of {} rows",
+                row,
+                array.len()
+            )));
+        }
+        key_encoder(array)?(row, key)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_row_keys() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    let arrays: Vec<DataArrayRef> = vec![
+        Arc::new(Int64Array::from(vec![
+            Some(1),
+            Some(1),
+            None,
+            Some(1),
+            None,
+        ])),
+        Arc::new(StringArray::from(vec![
+            Some("a"),
+            Some("a"),
+            Some("a"),
+            Some("ab"),
+            None,
+        ])),
+        Arc::new(Float64Array::from(vec![0.0, -0.0, 0.0, 0.0, 0.0])),
+    ];
+    let keys = data_array_row_keys(&arrays)?;
+    assert_eq!(5, keys.len());
+
+    // The rows 0 and 1 are equal, the others are different.
+    assert_eq!(keys.key(0), keys.key(1));
+    for (a, b) in [(0, 2), (0, 3), (0, 4), (2, 4), (3, 4)].iter() {
+        assert_ne!(keys.key(*a), keys.key(*b), "{} {}", a, b);
+    }
+
+    // The key of one row is the same.
+    for row in 0..5 {
+        let mut key = vec![];
+        data_array_row_key(&arrays, row, &mut key)?;
+        assert_eq!(keys.key(row), key.as_slice());
+    }
+
+    // The strings are prefixed by the lengths.
+    let arrays: Vec<DataArrayRef> = vec![
+        Arc::new(StringArray::from(vec!["ab", "a"])),
+        Arc::new(StringArray::from(vec!["c", "bc"])),
+    ];
+    let keys = data_array_row_keys(&arrays)?;
+    assert_ne!(keys.key(0), keys.key(1));
+
+    let keys = data_array_row_keys(&[])?;
+    assert_eq!(true, keys.is_empty());
+    Ok(())
+}
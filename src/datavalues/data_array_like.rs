@@ -0,0 +1,153 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::array::Array;
+
+use crate::datavalues::{BooleanArray, DataArrayRef, DataType, StringArray};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+#[derive(Clone, Debug, PartialEq)]
+enum LikeToken {
+    Literal(String),
+    // _ matches one character.
+    One,
+    // % matches any characters.
+    Any,
+}
+
+/// The compiled LIKE pattern, % matches any characters and _ matches one character,
+/// the backslash escapes the next character.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LikePattern {
+    tokens: Vec<LikeToken>,
+    case_insensitive: bool,
+}
+
+impl LikePattern {
+    pub fn compile(pattern: &str, case_insensitive: bool) -> Self {
+        let pattern = if case_insensitive {
+            pattern.to_lowercase()
+        } else {
+            pattern.to_string()
+        };
+
+        let mut tokens = vec![];
+        let mut literal = String::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '%' => LikeToken::Any,
+                '_' => LikeToken::One,
+                '\\' => {
+                    literal.push(chars.next().unwrap_or('\\'));
+                    continue;
+                }
+                _ => {
+                    literal.push(c);
+                    continue;
+                }
+            };
+            if !literal.is_empty() {
+                tokens.push(LikeToken::Literal(std::mem::take(&mut literal)));
+            }
+            // The adjacent % are the same as one.
+            if token != LikeToken::Any || tokens.last() != Some(&LikeToken::Any) {
+                tokens.push(token);
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(LikeToken::Literal(literal));
+        }
+
+        LikePattern {
+            tokens,
+            case_insensitive,
+        }
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        if self.case_insensitive {
+            self.match_tokens(&value.to_lowercase())
+        } else {
+            self.match_tokens(value)
+        }
+    }
+
+    // Match the tokens from left to right, on a mismatch the last % takes one more character.
+    fn match_tokens(&self, value: &str) -> bool {
+        let (mut token, mut pos) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+        loop {
+            if token < self.tokens.len() {
+                match &self.tokens[token] {
+                    LikeToken::Any => {
+                        token += 1;
+                        backtrack = Some((token, pos));
+                        continue;
+                    }
+                    LikeToken::One => {
+                        if let Some(c) = value[pos..].chars().next() {
+                            token += 1;
+                            pos += c.len_utf8();
+                            continue;
+                        }
+                    }
+                    LikeToken::Literal(literal) => {
+                        if value[pos..].starts_with(literal.as_str()) {
+                            token += 1;
+                            pos += literal.len();
+                            continue;
+                        }
+                    }
+                }
+            } else if pos == value.len() {
+                return true;
+            }
+
+            match backtrack {
+                Some((backtrack_token, backtrack_pos)) => {
+                    match value[backtrack_pos..].chars().next() {
+                        Some(c) => {
+                            token = backtrack_token;
+                            pos = backtrack_pos + c.len_utf8();
+                            backtrack = Some((token, pos));
+                        }
+                        None => return false,
+                    }
+                }
+                None => return false,
+            }
+        }
+    }
+}
+
+/// Match the string array with the pattern, the NULLs are NULLs.
+pub fn data_array_like(
+    array: &DataArrayRef,
+    pattern: &LikePattern,
+    negated: bool,
+) -> FuseQueryResult<DataArrayRef> {
+    match array.data_type() {
+        DataType::Utf8 => {
+            let array = downcast_array!(array, StringArray)?;
+            let values = (0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        None
+                    } else {
+                        Some(pattern.is_match(array.value(i)) != negated)
+                    }
+                })
+                .collect::<Vec<_>>();
+            Ok(Arc::new(BooleanArray::from(values)))
+        }
+        DataType::Null => Ok(Arc::new(BooleanArray::from(vec![None; array.len()]))),
+        other => Err(FuseQueryError::Internal(format!(
+            "Unsupported like ({:?})",
+            other
+        ))),
+    }
+}
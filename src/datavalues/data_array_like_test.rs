@@ -0,0 +1,136 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_like_pattern() {
+    use super::*;
+
+    struct PatternTest {
+        pattern: &'static str,
+        case_insensitive: bool,
+        matched: Vec<&'static str>,
+        unmatched: Vec<&'static str>,
+    }
+
+    let tests = vec![
+        PatternTest {
+            pattern: "abc",
+            case_insensitive: false,
+            matched: vec!["abc"],
+            unmatched: vec!["", "ab", "abcd", "ABC"],
+        },
+        PatternTest {
+            pattern: "a%",
+            case_insensitive: false,
+            matched: vec!["a", "abc", "a%"],
+            unmatched: vec!["", "ba"],
+        },
+        PatternTest {
+            pattern: "%b%c",
+            case_insensitive: false,
+            matched: vec!["bc", "abbbc", "abcbc", "bxc"],
+            unmatched: vec!["ab", "bcd", "cb"],
+        },
+        PatternTest {
+            pattern: "_é_",
+            case_insensitive: false,
+            matched: vec!["aéb", "ééé"],
+            unmatched: vec!["é", "aé", "aébc"],
+        },
+        PatternTest {
+            pattern: "100\\%%",
+            case_insensitive: false,
+            matched: vec!["100%", "100% sure"],
+            unmatched: vec!["100", "1000"],
+        },
+        PatternTest {
+            pattern: "%%",
+            case_insensitive: false,
+            matched: vec!["", "anything"],
+            unmatched: vec![],
+        },
+        PatternTest {
+            pattern: "AB_",
+            case_insensitive: true,
+            matched: vec!["abc", "ABC", "aBx"],
+            unmatched: vec!["ab", "abcd"],
+        },
+    ];
+
+    for t in tests {
+        let pattern = LikePattern::compile(t.pattern, t.case_insensitive);
+        for v in t.matched {
+            assert!(pattern.is_match(v), "{} like {}", v, t.pattern);
+        }
+        for v in t.unmatched {
+            assert!(!pattern.is_match(v), "{} not like {}", v, t.pattern);
+        }
+    }
+}
+
+#[test]
+fn test_array_like() {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        array: DataArrayRef,
+        pattern: LikePattern,
+        negated: bool,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "like-passed",
+            array: Arc::new(StringArray::from(vec![Some("abc"), None, Some("xbc")])),
+            pattern: LikePattern::compile("a%", false),
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])),
+            error: "",
+        },
+        ArrayTest {
+            name: "not-like-passed",
+            array: Arc::new(StringArray::from(vec![Some("abc"), None, Some("xbc")])),
+            pattern: LikePattern::compile("a%", false),
+            negated: true,
+            expect: Arc::new(BooleanArray::from(vec![Some(false), None, Some(true)])),
+            error: "",
+        },
+        ArrayTest {
+            name: "like-null-passed",
+            array: Arc::new(NullArray::new(2)),
+            pattern: LikePattern::compile("a%", false),
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![None, None])),
+            error: "",
+        },
+        ArrayTest {
+            name: "like-int64-error",
+            array: Arc::new(Int64Array::from(vec![1])),
+            pattern: LikePattern::compile("a%", false),
+            negated: false,
+            expect: Arc::new(BooleanArray::from(vec![false])),
+            error: "Internal Error: Unsupported like (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_like(&t.array, &t.pattern, t.negated);
+        match result {
+            Ok(ref v) => {
+                // Result check.
+                if !v.equals(&*t.expect) {
+                    println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+                    assert!(false);
+                }
+            }
+            Err(e) => assert_eq!(t.error, e.to_string()),
+        }
+    }
+}
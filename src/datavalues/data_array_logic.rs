@@ -4,28 +4,78 @@
 
 use std::sync::Arc;
 
-use crate::datavalues::{BooleanArray, DataArrayRef, DataColumnarValue, DataValueLogicOperator};
+use arrow::array::{Array, PrimitiveArrayOps};
+
+use crate::datavalues::{
+    BooleanArray, DataArrayRef, DataColumnarValue, DataType, DataValueLogicOperator,
+};
 use crate::error::{FuseQueryError, FuseQueryResult};
 
+/// The three-valued AND/OR of SQL: NULL AND false is false, NULL OR true is true,
+/// the others with NULL are NULL.
 pub fn data_array_logic_op(
     op: DataValueLogicOperator,
     left: &DataColumnarValue,
     right: &DataColumnarValue,
 ) -> FuseQueryResult<DataArrayRef> {
-    match (left, right) {
-        (DataColumnarValue::Array(left_array), DataColumnarValue::Array(right_array)) => match op {
-            DataValueLogicOperator::And => {
-                array_boolean_op!(left_array, right_array, and, BooleanArray)
-            }
-            DataValueLogicOperator::Or => {
-                array_boolean_op!(left_array, right_array, or, BooleanArray)
-            }
-        },
-        _ => Err(FuseQueryError::Internal(format!(
-            "Cannot do data_array {}, left:{:?}, right:{:?}",
-            op,
-            left.data_type(),
-            right.data_type()
+    let (left_array, right_array) = match (left, right) {
+        (DataColumnarValue::Array(left_array), DataColumnarValue::Array(right_array)) => {
+            (left_array.clone(), right_array.clone())
+        }
+        (DataColumnarValue::Array(array), DataColumnarValue::Scalar(scalar)) => {
+            (array.clone(), scalar.to_array(array.len())?)
+        }
+        (DataColumnarValue::Scalar(scalar), DataColumnarValue::Array(array)) => {
+            (scalar.to_array(array.len())?, array.clone())
+        }
+        (DataColumnarValue::Scalar(left_scalar), DataColumnarValue::Scalar(right_scalar)) => {
+            (left_scalar.to_array(1)?, right_scalar.to_array(1)?)
+        }
+    };
+
+    let lhs = boolean_values(&op, &left_array)?;
+    let rhs = boolean_values(&op, &right_array)?;
+    let values = lhs
+        .into_iter()
+        .zip(rhs)
+        .map(|(l, r)| match op {
+            DataValueLogicOperator::And => match (l, r) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            DataValueLogicOperator::Or => match (l, r) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+        })
+        .collect::<Vec<_>>();
+    Ok(Arc::new(BooleanArray::from(values)))
+}
+
+// The NULL array is the booleans of all NULLs.
+fn boolean_values(
+    op: &DataValueLogicOperator,
+    array: &DataArrayRef,
+) -> FuseQueryResult<Vec<Option<bool>>> {
+    match array.data_type() {
+        DataType::Null => Ok(vec![None; array.len()]),
+        DataType::Boolean => {
+            let array = downcast_array!(array, BooleanArray)?;
+            Ok((0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        None
+                    } else {
+                        Some(array.value(i))
+                    }
+                })
+                .collect())
+        }
+        other => Err(FuseQueryError::Internal(format!(
+            "Cannot do data_array {}, the argument must be Boolean, but got {:?}",
+            op, other
         ))),
     }
 }
@@ -38,6 +38,48 @@ fn test_array_logic() {
             expect: vec![Arc::new(BooleanArray::from(vec![true, true]))],
             error: vec![""],
         },
+        ArrayTest {
+            name: "and-kleene-passed",
+            args: vec![vec![
+                Arc::new(BooleanArray::from(vec![None, None, None, Some(true)])),
+                Arc::new(BooleanArray::from(vec![Some(true), Some(false), None, None])),
+            ]],
+            op: DataValueLogicOperator::And,
+            expect: vec![Arc::new(BooleanArray::from(vec![
+                None,
+                Some(false),
+                None,
+                None,
+            ]))],
+            error: vec![""],
+        },
+        ArrayTest {
+            name: "or-kleene-passed",
+            args: vec![vec![
+                Arc::new(BooleanArray::from(vec![None, None, None, Some(false)])),
+                Arc::new(BooleanArray::from(vec![Some(true), Some(false), None, None])),
+            ]],
+            op: DataValueLogicOperator::Or,
+            expect: vec![Arc::new(BooleanArray::from(vec![
+                Some(true),
+                None,
+                None,
+                None,
+            ]))],
+            error: vec![""],
+        },
+        ArrayTest {
+            name: "and-int64-error",
+            args: vec![vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(BooleanArray::from(vec![true])),
+            ]],
+            op: DataValueLogicOperator::And,
+            expect: vec![Arc::new(BooleanArray::from(vec![true]))],
+            error: vec![
+                "Internal Error: Cannot do data_array and, the argument must be Boolean, but got Int64",
+            ],
+        },
     ];
 
     for t in tests {
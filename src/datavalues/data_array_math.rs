@@ -0,0 +1,276 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{Array, PrimitiveArrayOps};
+
+use crate::datavalues::data_type::math_coercion;
+use crate::datavalues::{
+    data_array_cast, data_array_comparison_op, data_array_select, data_array_take,
+    data_array_try_cast, DataArrayRef, DataColumnarValue, DataType, DataValueComparisonOperator,
+    DataValueMathOperator, Float64Array, Int64Array, UInt32Array, UInt64Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+fn is_unsigned(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64
+    )
+}
+
+fn is_float(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Float16 | DataType::Float32 | DataType::Float64
+    )
+}
+
+// The values of the array as the Float64, NULL is None.
+fn float_values(array: &DataArrayRef) -> FuseQueryResult<Vec<Option<f64>>> {
+    let array = data_array_cast(array, &DataType::Float64)?;
+    let array = downcast_array!(array, Float64Array)?;
+    Ok((0..array.len())
+        .map(|i| match array.is_null(i) {
+            true => None,
+            false => Some(array.value(i)),
+        })
+        .collect())
+}
+
+// The values of the integer array as the i128, the floats are truncated.
+fn integer_values(
+    op: &DataValueMathOperator,
+    array: &DataArrayRef,
+) -> FuseQueryResult<Vec<Option<i128>>> {
+    if is_float(array.data_type()) {
+        return float_values(array)?
+            .into_iter()
+            .map(|v| match v {
+                Some(v) if v.is_finite() => Ok(Some(v.trunc() as i128)),
+                Some(v) => Err(FuseQueryError::Internal(format!(
+                    "Cannot convert {} to the integer in {}",
+                    v, op
+                ))),
+                None => Ok(None),
+            })
+            .collect();
+    }
+    if is_unsigned(array.data_type()) {
+        let array = data_array_cast(array, &DataType::UInt64)?;
+        let array = downcast_array!(array, UInt64Array)?;
+        return Ok((0..array.len())
+            .map(|i| match array.is_null(i) {
+                true => None,
+                false => Some(array.value(i) as i128),
+            })
+            .collect());
+    }
+    let array = data_array_cast(array, &DataType::Int64)?;
+    let array = downcast_array!(array, Int64Array)?;
+    Ok((0..array.len())
+        .map(|i| match array.is_null(i) {
+            true => None,
+            false => Some(array.value(i) as i128),
+        })
+        .collect())
+}
+
+// The integer array of the data type from the i128 values, the overflow is an error.
+fn integer_array(
+    op: &DataValueMathOperator,
+    values: Vec<Option<i128>>,
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    let overflow = |v: i128| {
+        FuseQueryError::Internal(format!(
+            "The result {} of {} is overflow of {:?}",
+            v, op, data_type
+        ))
+    };
+    let array: DataArrayRef = if is_unsigned(data_type) {
+        Arc::new(UInt64Array::from(
+            values
+                .into_iter()
+                .map(|v| {
+                    v.map(|v| u64::try_from(v).map_err(|_| overflow(v)))
+                        .transpose()
+                })
+                .collect::<FuseQueryResult<Vec<_>>>()?,
+        ))
+    } else {
+        Arc::new(Int64Array::from(
+            values
+                .into_iter()
+                .map(|v| {
+                    v.map(|v| i64::try_from(v).map_err(|_| overflow(v)))
+                        .transpose()
+                })
+                .collect::<FuseQueryResult<Vec<_>>>()?,
+        ))
+    };
+    data_array_try_cast(&array, data_type)
+}
+
+// Round the integer to the negative decimal places, the value is kept for the others.
+fn round_integer(op: &DataValueMathOperator, value: i128, places: i64) -> i128 {
+    if places >= 0 {
+        return value;
+    }
+    let factor = 10i128.pow(places.unsigned_abs().min(20) as u32);
+    match op {
+        DataValueMathOperator::Floor => value.div_euclid(factor) * factor,
+        DataValueMathOperator::Ceil => -((-value).div_euclid(factor) * factor),
+        _ => value.signum() * ((value.abs() + factor / 2) / factor * factor),
+    }
+}
+
+// Round the float to the decimal places, the half is rounded away from zero.
+fn round_float(op: &DataValueMathOperator, value: f64, places: i64) -> f64 {
+    let round = |v: f64| match op {
+        DataValueMathOperator::Floor => v.floor(),
+        DataValueMathOperator::Ceil => v.ceil(),
+        _ => v.round(),
+    };
+    let factor = 10f64.powi(places.clamp(-400, 400) as i32);
+    let scaled = value * factor;
+    match scaled.is_finite() {
+        true => round(scaled) / factor,
+        false => value,
+    }
+}
+
+/// The math functions of the rows, the row is NULL if any argument is NULL.
+/// The result types are from the math_coercion.
+pub fn data_array_math_op(
+    op: DataValueMathOperator,
+    arrays: &[DataArrayRef],
+) -> FuseQueryResult<DataArrayRef> {
+    let data_types = arrays
+        .iter()
+        .map(|array| array.data_type().clone())
+        .collect::<Vec<_>>();
+    let data_type = math_coercion(&op, &data_types)?;
+    let rows = arrays.first().map(|array| array.len()).unwrap_or(0);
+
+    match op {
+        DataValueMathOperator::Greatest | DataValueMathOperator::Least => {
+            if data_type == DataType::Null {
+                return Ok(arrays[0].clone());
+            }
+            let arrays = arrays
+                .iter()
+                .map(|array| data_array_cast(array, &data_type))
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            let compare = match op {
+                DataValueMathOperator::Greatest => DataValueComparisonOperator::Gt,
+                _ => DataValueComparisonOperator::Lt,
+            };
+            let mut result = arrays[0].clone();
+            for array in &arrays[1..] {
+                let mask = data_array_comparison_op(
+                    compare.clone(),
+                    &DataColumnarValue::Array(array.clone()),
+                    &DataColumnarValue::Array(result.clone()),
+                )?;
+                result = data_array_select(&mask, array, &result, &data_type)?;
+            }
+            if arrays.iter().any(|array| array.null_count() > 0) {
+                let indices = (0..rows)
+                    .map(|i| match arrays.iter().any(|array| array.is_null(i)) {
+                        true => None,
+                        false => Some(i as u32),
+                    })
+                    .collect::<Vec<_>>();
+                result = data_array_take(&result, &UInt32Array::from(indices))?;
+            }
+            Ok(result)
+        }
+        // The integer division is truncated toward zero.
+        DataValueMathOperator::IntDiv => {
+            let lhs = integer_values(&op, &arrays[0])?;
+            let rhs = integer_values(&op, &arrays[1])?;
+            let values = lhs
+                .into_iter()
+                .zip(rhs)
+                .map(|(lhs, rhs)| match (lhs, rhs) {
+                    (Some(_), Some(0)) => Err(FuseQueryError::Internal(format!(
+                        "Division by zero in {}",
+                        op
+                    ))),
+                    (Some(lhs), Some(rhs)) => Ok(Some(lhs / rhs)),
+                    _ => Ok(None),
+                })
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            integer_array(&op, values, &data_type)
+        }
+        DataValueMathOperator::Abs if is_unsigned(&data_type) => {
+            let values = integer_values(&op, &arrays[0])?
+                .into_iter()
+                .map(|v| v.map(|v| v.abs()))
+                .collect();
+            integer_array(&op, values, &data_type)
+        }
+        DataValueMathOperator::Round
+        | DataValueMathOperator::Floor
+        | DataValueMathOperator::Ceil => {
+            let places = match arrays.get(1) {
+                Some(array) => integer_values(&op, array)?
+                    .into_iter()
+                    .map(|v| v.map(|v| v as i64))
+                    .collect(),
+                None => vec![Some(0); rows],
+            };
+            if is_float(&data_type) {
+                let values = float_values(&arrays[0])?
+                    .into_iter()
+                    .zip(places)
+                    .map(|(v, places)| Some(round_float(&op, v?, places?)))
+                    .collect::<Vec<_>>();
+                let array: DataArrayRef = Arc::new(Float64Array::from(values));
+                return data_array_cast(&array, &data_type);
+            }
+            let values = integer_values(&op, &arrays[0])?
+                .into_iter()
+                .zip(places)
+                .map(|(v, places)| Some(round_integer(&op, v?, places?)))
+                .collect();
+            integer_array(&op, values, &data_type)
+        }
+        _ => {
+            let f: fn(f64) -> f64 = match op {
+                DataValueMathOperator::Abs => f64::abs,
+                DataValueMathOperator::Sqrt => f64::sqrt,
+                DataValueMathOperator::Exp => f64::exp,
+                DataValueMathOperator::Ln => f64::ln,
+                DataValueMathOperator::Log2 => f64::log2,
+                DataValueMathOperator::Log10 => f64::log10,
+                DataValueMathOperator::Sin => f64::sin,
+                DataValueMathOperator::Cos => f64::cos,
+                DataValueMathOperator::Tan => f64::tan,
+                DataValueMathOperator::Asin => f64::asin,
+                DataValueMathOperator::Acos => f64::acos,
+                DataValueMathOperator::Atan => f64::atan,
+                _ => {
+                    let base = float_values(&arrays[0])?;
+                    let exponent = float_values(&arrays[1])?;
+                    let values = base
+                        .into_iter()
+                        .zip(exponent)
+                        .map(|(base, exponent)| Some(base?.powf(exponent?)))
+                        .collect::<Vec<_>>();
+                    return Ok(Arc::new(Float64Array::from(values)));
+                }
+            };
+            let values = float_values(&arrays[0])?
+                .into_iter()
+                .map(|v| v.map(f))
+                .collect::<Vec<_>>();
+            let array: DataArrayRef = Arc::new(Float64Array::from(values));
+            data_array_cast(&array, &data_type)
+        }
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_math_op() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        op: DataValueMathOperator,
+        expect: Vec<&'static str>,
+        expect_type: DataType,
+        error: &'static str,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "abs-int8-passed",
+            args: vec![Arc::new(Int8Array::from(vec![Some(-128), Some(3), None]))],
+            op: DataValueMathOperator::Abs,
+            expect: vec!["128", "3", "NULL"],
+            expect_type: DataType::UInt8,
+            error: "",
+        },
+        ArrayTest {
+            name: "abs-float32-passed",
+            args: vec![Arc::new(Float32Array::from(vec![-1.5]))],
+            op: DataValueMathOperator::Abs,
+            expect: vec!["1.5"],
+            expect_type: DataType::Float32,
+            error: "",
+        },
+        ArrayTest {
+            name: "round-float64-passed",
+            args: vec![
+                Arc::new(Float64Array::from(vec![2.5, -2.5, 1.2345, 1234.5])),
+                Arc::new(Int64Array::from(vec![0, 0, 2, -2])),
+            ],
+            op: DataValueMathOperator::Round,
+            expect: vec!["3", "-3", "1.23", "1200"],
+            expect_type: DataType::Float64,
+            error: "",
+        },
+        ArrayTest {
+            name: "floor-ceil-int64-passed",
+            args: vec![
+                Arc::new(Int64Array::from(vec![-15, 15, 15])),
+                Arc::new(Int64Array::from(vec![-1, -1, 1])),
+            ],
+            op: DataValueMathOperator::Floor,
+            expect: vec!["-20", "10", "15"],
+            expect_type: DataType::Int64,
+            error: "",
+        },
+        ArrayTest {
+            name: "ceil-float64-passed",
+            args: vec![Arc::new(Float64Array::from(vec![-1.5, 1.2]))],
+            op: DataValueMathOperator::Ceil,
+            expect: vec!["-1", "2"],
+            expect_type: DataType::Float64,
+            error: "",
+        },
+        ArrayTest {
+            name: "round-uint8-overflow-error",
+            args: vec![
+                Arc::new(UInt8Array::from(vec![250])),
+                Arc::new(Int64Array::from(vec![-2])),
+            ],
+            op: DataValueMathOperator::Round,
+            expect: vec![],
+            expect_type: DataType::UInt8,
+            error: "Internal Error: Cannot cast value 300 from UInt64 to UInt8",
+        },
+        ArrayTest {
+            name: "sqrt-passed",
+            args: vec![Arc::new(UInt64Array::from(vec![Some(16), None]))],
+            op: DataValueMathOperator::Sqrt,
+            expect: vec!["4", "NULL"],
+            expect_type: DataType::Float64,
+            error: "",
+        },
+        ArrayTest {
+            name: "pow-passed",
+            args: vec![
+                Arc::new(Int64Array::from(vec![2, 4])),
+                Arc::new(Float64Array::from(vec![10.0, 0.5])),
+            ],
+            op: DataValueMathOperator::Pow,
+            expect: vec!["1024", "2"],
+            expect_type: DataType::Float64,
+            error: "",
+        },
+        ArrayTest {
+            name: "log10-passed",
+            args: vec![Arc::new(Int64Array::from(vec![1000]))],
+            op: DataValueMathOperator::Log10,
+            expect: vec!["3"],
+            expect_type: DataType::Float64,
+            error: "",
+        },
+        ArrayTest {
+            name: "int-div-passed",
+            args: vec![
+                Arc::new(Int64Array::from(vec![Some(7), Some(-7), None])),
+                Arc::new(Int8Array::from(vec![2, 2, 2])),
+            ],
+            op: DataValueMathOperator::IntDiv,
+            expect: vec!["3", "-3", "NULL"],
+            expect_type: DataType::Int64,
+            error: "",
+        },
+        ArrayTest {
+            name: "int-div-float64-passed",
+            args: vec![
+                Arc::new(Float64Array::from(vec![7.9])),
+                Arc::new(Float64Array::from(vec![2.0])),
+            ],
+            op: DataValueMathOperator::IntDiv,
+            expect: vec!["3"],
+            expect_type: DataType::Int64,
+            error: "",
+        },
+        ArrayTest {
+            name: "int-div-zero-error",
+            args: vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(Int64Array::from(vec![0])),
+            ],
+            op: DataValueMathOperator::IntDiv,
+            expect: vec![],
+            expect_type: DataType::Int64,
+            error: "Internal Error: Division by zero in intDiv",
+        },
+        ArrayTest {
+            name: "greatest-passed",
+            args: vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(5), Some(3)])),
+                Arc::new(Float64Array::from(vec![Some(2.5), Some(4.0), None])),
+                Arc::new(UInt8Array::from(vec![Some(0), Some(9), Some(1)])),
+            ],
+            op: DataValueMathOperator::Greatest,
+            expect: vec!["2.5", "9", "NULL"],
+            expect_type: DataType::Float64,
+            error: "",
+        },
+        ArrayTest {
+            name: "least-string-passed",
+            args: vec![
+                Arc::new(StringArray::from(vec!["b", "a"])),
+                Arc::new(StringArray::from(vec!["a", "c"])),
+            ],
+            op: DataValueMathOperator::Least,
+            expect: vec!["a", "a"],
+            expect_type: DataType::Utf8,
+            error: "",
+        },
+        ArrayTest {
+            name: "sqrt-string-error",
+            args: vec![Arc::new(StringArray::from(vec!["a"]))],
+            op: DataValueMathOperator::Sqrt,
+            expect: vec![],
+            expect_type: DataType::Float64,
+            error: "Internal Error: Unsupported sqrt (Utf8)",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_math_op(t.op.clone(), &t.args);
+        match result {
+            Ok(v) => {
+                assert_eq!(&t.expect_type, v.data_type(), "{}", t.name);
+                let actual = (0..v.len())
+                    .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+                    .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+                assert_eq!(t.expect, actual, "{}", t.name);
+            }
+            Err(e) => assert_eq!(t.error, e.to_string(), "{}", t.name),
+        }
+    }
+    Ok(())
+}
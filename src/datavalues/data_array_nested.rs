@@ -0,0 +1,221 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::array::{Array, PrimitiveArrayOps};
+
+use crate::datavalues::data_type::{equal_coercion, nested_coercion};
+use crate::datavalues::{
+    data_array_cast, data_array_concat, data_array_take, BooleanArray, DataArrayRef, DataType,
+    DataValue, DataValueNestedOperator, Int64Array, ListArray, StructArray, UInt32Array,
+    UInt64Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+// The elements of the array at the row in the data type, None if the array is NULL.
+fn list_values(
+    array: &ListArray,
+    row: usize,
+    data_type: &DataType,
+) -> FuseQueryResult<Option<Vec<DataValue>>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let values = data_array_cast(&array.value(row), data_type)?;
+    Ok(Some(
+        (0..values.len())
+            .map(|i| DataValue::try_from_array(&values, i))
+            .collect::<FuseQueryResult<Vec<_>>>()?,
+    ))
+}
+
+// The NULL is the array of NULLs.
+fn as_list_array(array: &DataArrayRef) -> FuseQueryResult<DataArrayRef> {
+    match array.data_type() {
+        DataType::Null => data_array_cast(array, &DataType::List(Box::new(DataType::Null))),
+        _ => Ok(array.clone()),
+    }
+}
+
+/// The array and tuple functions of the rows, the array elements are indexed from 1
+/// and the negative indexes are from the end, the elements out of the range are NULLs.
+pub fn data_array_nested_op(
+    op: DataValueNestedOperator,
+    arrays: &[DataArrayRef],
+) -> FuseQueryResult<DataArrayRef> {
+    let data_types = arrays
+        .iter()
+        .map(|array| array.data_type().clone())
+        .collect::<Vec<_>>();
+    let data_type = nested_coercion(&op, &data_types)?;
+    let rows = arrays.first().map(|array| array.len()).unwrap_or(0);
+
+    match op {
+        DataValueNestedOperator::Array => {
+            let element = match &data_type {
+                DataType::List(element) => element.as_ref().clone(),
+                _ => DataType::Null,
+            };
+            let arrays = arrays
+                .iter()
+                .map(|array| data_array_cast(array, &element))
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            let mut values = Vec::with_capacity(rows);
+            for row in 0..rows {
+                values.push(DataValue::List(
+                    Some(
+                        arrays
+                            .iter()
+                            .map(|array| DataValue::try_from_array(array, row))
+                            .collect::<FuseQueryResult<Vec<_>>>()?,
+                    ),
+                    element.clone(),
+                ));
+            }
+            DataValue::try_into_data_array(&values, &data_type)
+        }
+        DataValueNestedOperator::Tuple => {
+            let mut values = Vec::with_capacity(rows);
+            for row in 0..rows {
+                values.push(DataValue::Struct(
+                    arrays
+                        .iter()
+                        .map(|array| DataValue::try_from_array(array, row))
+                        .collect::<FuseQueryResult<Vec<_>>>()?,
+                ));
+            }
+            DataValue::try_into_data_array(&values, &data_type)
+        }
+        DataValueNestedOperator::Has => {
+            let list = as_list_array(&arrays[0])?;
+            let list = downcast_array!(list, ListArray)?;
+            let element = match list.data_type() {
+                DataType::List(element) => element.as_ref().clone(),
+                _ => DataType::Null,
+            };
+            let common = equal_coercion(&op.to_string(), &element, arrays[1].data_type())?;
+            let value = data_array_cast(&arrays[1], &common)?;
+            let mut result = Vec::with_capacity(rows);
+            for row in 0..rows {
+                let value = DataValue::try_from_array(&value, row)?;
+                result.push(list_values(list, row, &common)?.map(|values| values.contains(&value)));
+            }
+            Ok(Arc::new(BooleanArray::from(result)))
+        }
+        DataValueNestedOperator::Length => {
+            let list = as_list_array(&arrays[0])?;
+            let list = downcast_array!(list, ListArray)?;
+            Ok(Arc::new(UInt64Array::from(
+                (0..rows)
+                    .map(|row| match list.is_null(row) {
+                        true => None,
+                        false => Some(list.value_length(row) as u64),
+                    })
+                    .collect::<Vec<_>>(),
+            )))
+        }
+        DataValueNestedOperator::ArrayElement => {
+            let list = as_list_array(&arrays[0])?;
+            let list = downcast_array!(list, ListArray)?;
+            let index = data_array_cast(&arrays[1], &DataType::Int64)?;
+            let index = downcast_array!(index, Int64Array)?;
+            let null = DataValue::try_from(&data_type)?;
+            let mut result = Vec::with_capacity(rows);
+            for row in 0..rows {
+                let values = match (index.is_null(row), list_values(list, row, &data_type)?) {
+                    (false, Some(values)) => values,
+                    _ => {
+                        result.push(null.clone());
+                        continue;
+                    }
+                };
+                let i = index.value(row);
+                let position = match i {
+                    i if i > 0 => Some(i as usize - 1),
+                    i if i < 0 && i.unsigned_abs() as usize <= values.len() => {
+                        Some(values.len() - i.unsigned_abs() as usize)
+                    }
+                    _ => None,
+                };
+                result.push(match position.and_then(|i| values.get(i)) {
+                    Some(value) => value.clone(),
+                    None => null.clone(),
+                });
+            }
+            DataValue::try_into_data_array(&result, &data_type)
+        }
+        DataValueNestedOperator::TupleElement(n) => {
+            let array = downcast_array!(arrays[0], StructArray)?;
+            Ok(array.column(n - 1).clone())
+        }
+        // The arrays are unnested to the rows by the projection.
+        DataValueNestedOperator::ArrayJoin => as_list_array(&arrays[0]),
+        DataValueNestedOperator::GroupArray => Err(FuseQueryError::Internal(format!(
+            "Unsupported scalar operation for function {}",
+            op
+        ))),
+    }
+}
+
+/// Unnest the rows by the arrays of the unnested columns, the other columns are repeated.
+/// The row of the empty or NULL array is dropped, the arrays of a row must be of the same length.
+pub fn data_array_unnest(
+    columns: &[DataArrayRef],
+    unnested: &[bool],
+) -> FuseQueryResult<Vec<DataArrayRef>> {
+    let lists = columns
+        .iter()
+        .zip(unnested)
+        .filter(|(_, unnested)| **unnested)
+        .map(|(column, _)| downcast_array!(column, ListArray))
+        .collect::<FuseQueryResult<Vec<_>>>()?;
+    let rows = columns.first().map(|column| column.len()).unwrap_or(0);
+
+    let mut indices = vec![];
+    for row in 0..rows {
+        let mut length = None;
+        for list in &lists {
+            let n = match list.is_null(row) {
+                true => 0,
+                false => list.value_length(row) as usize,
+            };
+            if length.is_some_and(|length| length != n) {
+                return Err(FuseQueryError::Internal(
+                    "The arrays of arrayJoin must be of the same length".to_string(),
+                ));
+            }
+            length = Some(n);
+        }
+        indices.extend(std::iter::repeat_n(row as u32, length.unwrap_or(1)));
+    }
+    let indices = UInt32Array::from(indices);
+
+    let mut result = Vec::with_capacity(columns.len());
+    for (column, unnested) in columns.iter().zip(unnested) {
+        if !*unnested {
+            result.push(data_array_take(column, &indices)?);
+            continue;
+        }
+        let list = downcast_array!(column, ListArray)?;
+        let elements = (0..rows)
+            .filter(|row| !list.is_null(*row) && list.value_length(*row) > 0)
+            .map(|row| list.value(row))
+            .collect::<Vec<_>>();
+        result.push(match elements.is_empty() {
+            true => match list.data_type() {
+                DataType::List(element) => DataValue::try_into_data_array(&[], element.as_ref())?,
+                other => {
+                    return Err(FuseQueryError::Internal(format!(
+                        "Cannot unnest the array of type {:?}",
+                        other
+                    )))
+                }
+            },
+            false => data_array_concat(&elements)?,
+        });
+    }
+    Ok(result)
+}
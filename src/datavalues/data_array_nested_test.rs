@@ -0,0 +1,156 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_nested_op() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        op: DataValueNestedOperator,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let list = |values: Vec<Option<Vec<i64>>>| -> crate::error::FuseQueryResult<DataArrayRef> {
+        let values = values
+            .into_iter()
+            .map(|v| {
+                DataValue::List(
+                    v.map(|v| v.into_iter().map(|v| DataValue::Int64(Some(v))).collect()),
+                    DataType::Int64,
+                )
+            })
+            .collect::<Vec<_>>();
+        DataValue::try_into_data_array(&values, &DataType::List(Box::new(DataType::Int64)))
+    };
+
+    let tests = vec![
+        ArrayTest {
+            name: "array-passed",
+            args: vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(UInt8Array::from(vec![Some(3), None])),
+            ],
+            op: DataValueNestedOperator::Array,
+            expect: vec!["[1, 3]", "[2, NULL]"],
+            error: "",
+        },
+        ArrayTest {
+            name: "tuple-passed",
+            args: vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["a", "b"])),
+            ],
+            op: DataValueNestedOperator::Tuple,
+            expect: vec!["(1, 'a')", "(2, 'b')"],
+            error: "",
+        },
+        ArrayTest {
+            name: "has-passed",
+            args: vec![
+                list(vec![Some(vec![1, 2]), Some(vec![]), None])?,
+                Arc::new(UInt64Array::from(vec![2, 2, 2])),
+            ],
+            op: DataValueNestedOperator::Has,
+            expect: vec!["true", "false", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "length-passed",
+            args: vec![list(vec![Some(vec![1, 2]), Some(vec![]), None])?],
+            op: DataValueNestedOperator::Length,
+            expect: vec!["2", "0", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "array-element-passed",
+            args: vec![
+                list(vec![
+                    Some(vec![1, 2]),
+                    Some(vec![1, 2]),
+                    Some(vec![1, 2]),
+                    None,
+                ])?,
+                Arc::new(Int64Array::from(vec![1, -1, 3, 1])),
+            ],
+            op: DataValueNestedOperator::ArrayElement,
+            expect: vec!["1", "2", "NULL", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "tuple-element-passed",
+            args: vec![data_array_nested_op(
+                DataValueNestedOperator::Tuple,
+                &[
+                    Arc::new(Int64Array::from(vec![1])),
+                    Arc::new(StringArray::from(vec!["a"])),
+                ],
+            )?],
+            op: DataValueNestedOperator::TupleElement(2),
+            expect: vec!["a"],
+            error: "",
+        },
+        ArrayTest {
+            name: "length-int64-error",
+            args: vec![Arc::new(Int64Array::from(vec![1]))],
+            op: DataValueNestedOperator::Length,
+            expect: vec![],
+            error: "Internal Error: Unsupported length (Int64)",
+        },
+        ArrayTest {
+            name: "array-element-string-error",
+            args: vec![
+                list(vec![Some(vec![1])])?,
+                Arc::new(StringArray::from(vec!["1"])),
+            ],
+            op: DataValueNestedOperator::ArrayElement,
+            expect: vec![],
+            error: "Internal Error: Unsupported arrayElement (List(Int64), Utf8)",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_nested_op(t.op.clone(), &t.args);
+        match result {
+            Ok(v) => {
+                let actual = (0..v.len())
+                    .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+                    .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+                assert_eq!(t.expect, actual, "{}", t.name);
+            }
+            Err(e) => assert_eq!(t.error, e.to_string(), "{}", t.name),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_array_unnest() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    let values = vec![
+        DataValue::List(
+            Some(vec![DataValue::Int64(Some(1)), DataValue::Int64(Some(2))]),
+            DataType::Int64,
+        ),
+        DataValue::List(Some(vec![]), DataType::Int64),
+        DataValue::List(None, DataType::Int64),
+        DataValue::List(Some(vec![DataValue::Int64(Some(3))]), DataType::Int64),
+    ];
+    let list = DataValue::try_into_data_array(&values, &DataType::List(Box::new(DataType::Int64)))?;
+    let other: DataArrayRef = Arc::new(StringArray::from(vec!["a", "b", "c", "d"]));
+
+    // The rows of the empty and NULL arrays are dropped.
+    let result = data_array_unnest(&[other, list], &[false, true])?;
+    assert!(result[0].equals(&StringArray::from(vec!["a", "a", "d"])));
+    assert!(result[1].equals(&Int64Array::from(vec![1, 2, 3])));
+    Ok(())
+}
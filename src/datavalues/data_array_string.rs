@@ -0,0 +1,363 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ListBuilder, PrimitiveArrayOps, StringBuilder};
+
+use crate::datavalues::data_type::string_coercion;
+use crate::datavalues::{
+    data_array_cast, data_array_nested_op, BooleanArray, DataArrayRef, DataType,
+    DataValueNestedOperator, DataValueStringOperator, Int64Array, StringArray, UInt64Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+// Build the strings in the reused buffer row by row, the row is NULL if the builder returns false.
+fn build_strings<F>(rows: usize, mut f: F) -> FuseQueryResult<DataArrayRef>
+where
+    F: FnMut(usize, &mut String) -> FuseQueryResult<bool>,
+{
+    let mut builder = StringBuilder::new(rows);
+    let mut buffer = String::new();
+    for row in 0..rows {
+        buffer.clear();
+        match f(row, &mut buffer)? {
+            true => builder.append_value(&buffer)?,
+            false => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+// The byte index of the char index, the length of the string if it is out of the range.
+fn char_to_byte(value: &str, index: usize) -> usize {
+    value
+        .char_indices()
+        .nth(index)
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| value.len())
+}
+
+// The substring of the chars from the offset, the offset is from 1 and the negative one is from the end.
+fn substring(value: &str, offset: i64, length: Option<i64>) -> &str {
+    let chars = value.chars().count() as i64;
+    let start = match offset {
+        offset if offset > 0 => offset - 1,
+        offset if offset < 0 && -offset <= chars => chars + offset,
+        _ => return "",
+    };
+    let end = match length {
+        Some(length) if length <= 0 => return "",
+        Some(length) => start.saturating_add(length).min(chars),
+        None => chars,
+    };
+    if start >= chars {
+        return "";
+    }
+    &value[char_to_byte(value, start as usize)..char_to_byte(value, end as usize)]
+}
+
+// The {} are the arguments in order, the {n} is the argument n from 0, {{ and }} are the braces.
+fn format_pattern(pattern: &str, args: &[&str], buffer: &mut String) -> FuseQueryResult<()> {
+    let error = |message: &str| {
+        FuseQueryError::Internal(format!(
+            "Invalid pattern of format {:?}: {}",
+            pattern, message
+        ))
+    };
+    let mut next = 0;
+    let mut rest = pattern;
+    while let Some(i) = rest.find(['{', '}']) {
+        buffer.push_str(&rest[..i]);
+        let escaped = rest[i..].get(..2);
+        if matches!(escaped, Some("{{") | Some("}}")) {
+            buffer.push_str(&rest[i..i + 1]);
+            rest = &rest[i + 2..];
+            continue;
+        }
+        if rest[i..].starts_with('}') {
+            return Err(error("single '}'"));
+        }
+        let end = rest[i..].find('}').ok_or_else(|| error("unclosed '{'"))? + i;
+        let index = match &rest[i + 1..end] {
+            "" => {
+                next += 1;
+                next - 1
+            }
+            index => index
+                .parse::<usize>()
+                .map_err(|_| error("the index is not a number"))?,
+        };
+        buffer.push_str(args.get(index).ok_or_else(|| error("too few arguments"))?);
+        rest = &rest[end + 1..];
+    }
+    buffer.push_str(rest);
+    Ok(())
+}
+
+// Pad the string to the length in chars by the repeated pad, the longer string is truncated.
+fn pad(value: &str, length: i64, pad: &str, left: bool, buffer: &mut String) -> bool {
+    if length < 0 {
+        return false;
+    }
+    let length = length as usize;
+    let chars = value.chars().count();
+    if chars >= length {
+        buffer.push_str(&value[..char_to_byte(value, length)]);
+        return true;
+    }
+    if !left {
+        buffer.push_str(value);
+    }
+    buffer.extend(pad.chars().cycle().take(length - chars));
+    if left {
+        buffer.push_str(value);
+    }
+    true
+}
+
+/// The string functions of the rows, the row is NULL if any argument is NULL.
+/// The length is in bytes, the positions of substring, position and the pads are in chars from 1.
+pub fn data_array_string_op(
+    op: DataValueStringOperator,
+    arrays: &[DataArrayRef],
+) -> FuseQueryResult<DataArrayRef> {
+    let data_types = arrays
+        .iter()
+        .map(|array| array.data_type().clone())
+        .collect::<Vec<_>>();
+    string_coercion(&op, &data_types)?;
+    if let (DataValueStringOperator::Length, DataType::List(_)) = (&op, &data_types[0]) {
+        return data_array_nested_op(DataValueNestedOperator::Length, arrays);
+    }
+    let rows = arrays.first().map(|array| array.len()).unwrap_or(0);
+
+    // The integer arguments are the Int64Arrays, the others are the StringArrays.
+    let integer = |i: usize| match op {
+        DataValueStringOperator::Substring | DataValueStringOperator::Repeat => i > 0,
+        DataValueStringOperator::LeftPad | DataValueStringOperator::RightPad => i == 1,
+        _ => false,
+    };
+    let arrays = arrays
+        .iter()
+        .enumerate()
+        .map(|(i, array)| match integer(i) {
+            true => data_array_cast(array, &DataType::Int64),
+            false => data_array_cast(array, &DataType::Utf8),
+        })
+        .collect::<FuseQueryResult<Vec<_>>>()?;
+    let strings = |i: usize| downcast_array!(arrays[i], StringArray);
+    let integers = |i: usize| downcast_array!(arrays[i], Int64Array);
+    let is_null = |row: usize| arrays.iter().any(|array| array.is_null(row));
+
+    match op {
+        DataValueStringOperator::Length => {
+            let s = strings(0)?;
+            Ok(Arc::new(UInt64Array::from(
+                (0..rows)
+                    .map(|row| match s.is_null(row) {
+                        true => None,
+                        false => Some(s.value_length(row) as u64),
+                    })
+                    .collect::<Vec<_>>(),
+            )))
+        }
+        DataValueStringOperator::Lower | DataValueStringOperator::Upper => {
+            let s = strings(0)?;
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                for c in s.value(row).chars() {
+                    match op {
+                        DataValueStringOperator::Lower => buffer.extend(c.to_lowercase()),
+                        _ => buffer.extend(c.to_uppercase()),
+                    }
+                }
+                Ok(true)
+            })
+        }
+        DataValueStringOperator::Concat => {
+            let args = (0..arrays.len())
+                .map(strings)
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                for arg in &args {
+                    buffer.push_str(arg.value(row));
+                }
+                Ok(true)
+            })
+        }
+        DataValueStringOperator::Substring => {
+            let (s, offset) = (strings(0)?, integers(1)?);
+            let length = match arrays.len() {
+                3 => Some(integers(2)?),
+                _ => None,
+            };
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                let value = s.value(row);
+                buffer.push_str(substring(
+                    value,
+                    offset.value(row),
+                    length.map(|length| length.value(row)),
+                ));
+                Ok(true)
+            })
+        }
+        DataValueStringOperator::Trim => {
+            let s = strings(0)?;
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                buffer.push_str(s.value(row).trim());
+                Ok(true)
+            })
+        }
+        DataValueStringOperator::Replace => {
+            let (s, from, to) = (strings(0)?, strings(1)?, strings(2)?);
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                let (value, from, to) = (s.value(row), from.value(row), to.value(row));
+                if from.is_empty() {
+                    buffer.push_str(value);
+                    return Ok(true);
+                }
+                let mut last = 0;
+                for (i, _) in value.match_indices(from) {
+                    buffer.push_str(&value[last..i]);
+                    buffer.push_str(to);
+                    last = i + from.len();
+                }
+                buffer.push_str(&value[last..]);
+                Ok(true)
+            })
+        }
+        // The position of the needle in chars from 1, 0 if it is not found.
+        DataValueStringOperator::Position => {
+            let (s, needle) = (strings(0)?, strings(1)?);
+            Ok(Arc::new(UInt64Array::from(
+                (0..rows)
+                    .map(|row| match is_null(row) {
+                        true => None,
+                        false => {
+                            let value = s.value(row);
+                            Some(match value.find(needle.value(row)) {
+                                Some(i) => value[..i].chars().count() as u64 + 1,
+                                None => 0,
+                            })
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            )))
+        }
+        DataValueStringOperator::StartsWith | DataValueStringOperator::EndsWith => {
+            let (s, affix) = (strings(0)?, strings(1)?);
+            Ok(Arc::new(BooleanArray::from(
+                (0..rows)
+                    .map(|row| match (is_null(row), &op) {
+                        (true, _) => None,
+                        (false, DataValueStringOperator::StartsWith) => {
+                            Some(s.value(row).starts_with(affix.value(row)))
+                        }
+                        (false, _) => Some(s.value(row).ends_with(affix.value(row))),
+                    })
+                    .collect::<Vec<_>>(),
+            )))
+        }
+        // The splitByChar(separator, s), the separator is one char.
+        DataValueStringOperator::SplitByChar => {
+            let (separator, s) = (strings(0)?, strings(1)?);
+            let mut builder = ListBuilder::new(StringBuilder::new(rows));
+            for row in 0..rows {
+                if is_null(row) {
+                    builder.append(false)?;
+                    continue;
+                }
+                let mut chars = separator.value(row).chars();
+                let c = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(FuseQueryError::Internal(format!(
+                            "The separator of splitByChar must be one char, but got {:?}",
+                            separator.value(row)
+                        )))
+                    }
+                };
+                for part in s.value(row).split(c) {
+                    builder.values().append_value(part)?;
+                }
+                builder.append(true)?;
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+        DataValueStringOperator::Reverse => {
+            let s = strings(0)?;
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                buffer.extend(s.value(row).chars().rev());
+                Ok(true)
+            })
+        }
+        DataValueStringOperator::Repeat => {
+            let (s, n) = (strings(0)?, integers(1)?);
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                for _ in 0..n.value(row).max(0) {
+                    buffer.push_str(s.value(row));
+                }
+                Ok(true)
+            })
+        }
+        DataValueStringOperator::Format => {
+            let args = (0..arrays.len())
+                .map(strings)
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            let mut values = Vec::with_capacity(args.len() - 1);
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                values.clear();
+                values.extend(args[1..].iter().map(|arg| arg.value(row)));
+                format_pattern(args[0].value(row), &values, buffer)?;
+                Ok(true)
+            })
+        }
+        // The pad is a space by default.
+        DataValueStringOperator::LeftPad | DataValueStringOperator::RightPad => {
+            let (s, length) = (strings(0)?, integers(1)?);
+            let pad_string = match arrays.len() {
+                3 => Some(strings(2)?),
+                _ => None,
+            };
+            let left = matches!(op, DataValueStringOperator::LeftPad);
+            build_strings(rows, |row, buffer| {
+                if is_null(row) {
+                    return Ok(false);
+                }
+                let pad_string = pad_string.map(|v| v.value(row)).unwrap_or(" ");
+                Ok(pad(
+                    s.value(row),
+                    length.value(row),
+                    pad_string,
+                    left,
+                    buffer,
+                ))
+            })
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_string_op() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        op: DataValueStringOperator,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let strings =
+        |values: Vec<Option<&str>>| -> DataArrayRef { Arc::new(StringArray::from(values)) };
+    let s = strings(vec![Some("Hello, Wörld"), Some(""), None]);
+
+    let tests = vec![
+        ArrayTest {
+            name: "length-passed",
+            args: vec![s.clone()],
+            op: DataValueStringOperator::Length,
+            expect: vec!["13", "0", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "upper-passed",
+            args: vec![s.clone()],
+            op: DataValueStringOperator::Upper,
+            expect: vec!["HELLO, WÖRLD", "", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "concat-int64-passed",
+            args: vec![s.clone(), Arc::new(Int64Array::from(vec![1, 2, 3]))],
+            op: DataValueStringOperator::Concat,
+            expect: vec!["Hello, Wörld1", "2", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "substring-passed",
+            args: vec![
+                s.clone(),
+                Arc::new(Int64Array::from(vec![-5, 1, 1])),
+                Arc::new(Int64Array::from(vec![3, 2, 2])),
+            ],
+            op: DataValueStringOperator::Substring,
+            expect: vec!["Wör", "", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "trim-passed",
+            args: vec![strings(vec![Some("  a b  ")])],
+            op: DataValueStringOperator::Trim,
+            expect: vec!["a b"],
+            error: "",
+        },
+        ArrayTest {
+            name: "replace-passed",
+            args: vec![
+                strings(vec![Some("a-b-c"), Some("abc")]),
+                strings(vec![Some("-"), Some("")]),
+                strings(vec![Some("+"), Some("x")]),
+            ],
+            op: DataValueStringOperator::Replace,
+            expect: vec!["a+b+c", "abc"],
+            error: "",
+        },
+        ArrayTest {
+            name: "position-passed",
+            args: vec![s.clone(), strings(vec![Some("rl"), Some("x"), Some("")])],
+            op: DataValueStringOperator::Position,
+            expect: vec!["10", "0", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "starts-with-passed",
+            args: vec![s.clone(), strings(vec![Some("Hell"), Some("a"), Some("")])],
+            op: DataValueStringOperator::StartsWith,
+            expect: vec!["true", "false", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "split-by-char-passed",
+            args: vec![
+                strings(vec![Some(","), Some(",")]),
+                strings(vec![Some("a,b,"), None]),
+            ],
+            op: DataValueStringOperator::SplitByChar,
+            expect: vec!["['a', 'b', '']", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "reverse-passed",
+            args: vec![s.clone()],
+            op: DataValueStringOperator::Reverse,
+            expect: vec!["dlröW ,olleH", "", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "repeat-passed",
+            args: vec![
+                strings(vec![Some("ab"), Some("ab")]),
+                Arc::new(UInt8Array::from(vec![3, 0])),
+            ],
+            op: DataValueStringOperator::Repeat,
+            expect: vec!["ababab", ""],
+            error: "",
+        },
+        ArrayTest {
+            name: "format-passed",
+            args: vec![
+                strings(vec![Some("{} {{{1}}} {}")]),
+                strings(vec![Some("a")]),
+                Arc::new(Int64Array::from(vec![2])),
+            ],
+            op: DataValueStringOperator::Format,
+            expect: vec!["a {2} 2"],
+            error: "",
+        },
+        ArrayTest {
+            name: "lpad-rpad-passed",
+            args: vec![
+                strings(vec![Some("ab"), Some("abcd"), Some("ab")]),
+                Arc::new(Int64Array::from(vec![5, 3, -1])),
+                strings(vec![Some("xy"), Some("xy"), Some("xy")]),
+            ],
+            op: DataValueStringOperator::LeftPad,
+            expect: vec!["xyxab", "abc", "NULL"],
+            error: "",
+        },
+        ArrayTest {
+            name: "format-unclosed-error",
+            args: vec![strings(vec![Some("{")])],
+            op: DataValueStringOperator::Format,
+            expect: vec![],
+            error: "Internal Error: Invalid pattern of format \"{\": unclosed '{'",
+        },
+        ArrayTest {
+            name: "split-by-char-separator-error",
+            args: vec![strings(vec![Some(", ")]), strings(vec![Some("a")])],
+            op: DataValueStringOperator::SplitByChar,
+            expect: vec![],
+            error: "Internal Error: The separator of splitByChar must be one char, but got \", \"",
+        },
+        ArrayTest {
+            name: "lower-int64-error",
+            args: vec![Arc::new(Int64Array::from(vec![1]))],
+            op: DataValueStringOperator::Lower,
+            expect: vec![],
+            error: "Internal Error: Unsupported lower (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let result = data_array_string_op(t.op.clone(), &t.args);
+        match result {
+            Ok(v) => {
+                let actual = (0..v.len())
+                    .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+                    .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+                assert_eq!(t.expect, actual, "{}", t.name);
+            }
+            Err(e) => assert_eq!(t.error, e.to_string(), "{}", t.name),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::array::{as_primitive_array, Array, PrimitiveArrayOps, PrimitiveBuilder};
+use arrow::datatypes::{
+    ArrowNumericType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+};
+
+use crate::datavalues::{
+    BooleanArray, DataArrayRef, DataColumnarValue, DataType, DataValue, DataValueUnaryOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+
+pub fn data_array_unary_op(
+    op: DataValueUnaryOperator,
+    value: &DataColumnarValue,
+) -> FuseQueryResult<DataArrayRef> {
+    let array = match value {
+        DataColumnarValue::Array(array) => array.clone(),
+        DataColumnarValue::Scalar(scalar) => scalar.to_array(1)?,
+    };
+
+    match op {
+        // The negation is in the result type, the negation of the minimum integer is an overflow.
+        DataValueUnaryOperator::Negate => {
+            let data_type =
+                super::data_type::negate_coercion(format!("{}", op).as_str(), array.data_type())?;
+            if super::is_decimal(&data_type) {
+                return super::data_array_decimal::data_array_decimal_negate(&array);
+            }
+            let array = super::data_array_try_cast(&array, &data_type)?;
+            match data_type {
+                DataType::Int8 => negate::<Int8Type>(&array, i8::checked_neg),
+                DataType::Int16 => negate::<Int16Type>(&array, i16::checked_neg),
+                DataType::Int32 => negate::<Int32Type>(&array, i32::checked_neg),
+                DataType::Int64 => negate::<Int64Type>(&array, i64::checked_neg),
+                DataType::Float32 => negate::<Float32Type>(&array, |v| Some(-v)),
+                DataType::Float64 => negate::<Float64Type>(&array, |v| Some(-v)),
+                other => Err(FuseQueryError::Internal(format!(
+                    "Unsupported {} ({:?})",
+                    op, other
+                ))),
+            }
+        }
+        DataValueUnaryOperator::Not => match array.data_type() {
+            DataType::Boolean => {
+                let array = downcast_array!(array, BooleanArray)?;
+                Ok(Arc::new(arrow::compute::not(array)?))
+            }
+            DataType::Null => DataValue::try_into_data_array(
+                &vec![DataValue::Null; array.len()],
+                &DataType::Boolean,
+            ),
+            other => Err(FuseQueryError::Internal(format!(
+                "Unsupported {} ({:?})",
+                op, other
+            ))),
+        },
+        // The NULL array has no validity bitmap, all of its values are NULLs.
+        DataValueUnaryOperator::IsNull | DataValueUnaryOperator::IsNotNull => {
+            let is_null = op == DataValueUnaryOperator::IsNull;
+            if array.data_type() == &DataType::Null {
+                return Ok(Arc::new(BooleanArray::from(vec![is_null; array.len()])));
+            }
+            if is_null {
+                Ok(Arc::new(arrow::compute::is_null(&array)?))
+            } else {
+                Ok(Arc::new(arrow::compute::is_not_null(&array)?))
+            }
+        }
+    }
+}
+
+fn negate<T>(
+    array: &DataArrayRef,
+    neg: fn(T::Native) -> Option<T::Native>,
+) -> FuseQueryResult<DataArrayRef>
+where
+    T: ArrowNumericType,
+    T::Native: std::fmt::Display,
+{
+    let array = as_primitive_array::<T>(array);
+    let mut builder = PrimitiveBuilder::<T>::new(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            builder.append_null()?;
+        } else {
+            let value = array.value(i);
+            builder.append_value(neg(value).ok_or_else(|| {
+                FuseQueryError::Internal(format!(
+                    "The result of negate({}) is overflow of {:?}",
+                    value,
+                    array.data_type()
+                ))
+            })?)?;
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
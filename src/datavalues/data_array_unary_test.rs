@@ -0,0 +1,119 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_array_unary() {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        args: Vec<DataArrayRef>,
+        expect: Vec<DataArrayRef>,
+        error: Vec<&'static str>,
+        op: DataValueUnaryOperator,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "negate-passed",
+            args: vec![
+                Arc::new(StringArray::from(vec!["xx"])),
+                Arc::new(Int8Array::from(vec![Some(1), None, Some(-3)])),
+                Arc::new(Int64Array::from(vec![1, -2])),
+                Arc::new(UInt8Array::from(vec![1, 255])),
+                Arc::new(UInt16Array::from(vec![1, 2])),
+                Arc::new(UInt32Array::from(vec![1, 2])),
+                Arc::new(UInt64Array::from(vec![1, 2])),
+                Arc::new(Float64Array::from(vec![1.5, -2.0])),
+            ],
+            op: DataValueUnaryOperator::Negate,
+            expect: vec![
+                Arc::new(StringArray::from(vec![""])),
+                Arc::new(Int8Array::from(vec![Some(-1), None, Some(3)])),
+                Arc::new(Int64Array::from(vec![-1, 2])),
+                Arc::new(Int16Array::from(vec![-1, -255])),
+                Arc::new(Int32Array::from(vec![-1, -2])),
+                Arc::new(Int64Array::from(vec![-1, -2])),
+                Arc::new(Int64Array::from(vec![-1, -2])),
+                Arc::new(Float64Array::from(vec![-1.5, 2.0])),
+            ],
+            error: vec!["Internal Error: Unsupported negate (Utf8)"],
+        },
+        ArrayTest {
+            name: "negate-overflow",
+            args: vec![
+                Arc::new(Int8Array::from(vec![Some(-127), None, Some(-128)])),
+                Arc::new(Int64Array::from(vec![i64::MAX, i64::MIN])),
+            ],
+            op: DataValueUnaryOperator::Negate,
+            expect: vec![],
+            error: vec![
+                "Internal Error: The result of negate(-128) is overflow of Int8",
+                "Internal Error: The result of negate(-9223372036854775808) is overflow of Int64",
+            ],
+        },
+        ArrayTest {
+            name: "not-passed",
+            args: vec![
+                Arc::new(Int8Array::from(vec![1])),
+                Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])),
+                Arc::new(NullArray::new(2)),
+            ],
+            op: DataValueUnaryOperator::Not,
+            expect: vec![
+                Arc::new(BooleanArray::from(vec![false])),
+                Arc::new(BooleanArray::from(vec![Some(false), None, Some(true)])),
+                Arc::new(BooleanArray::from(vec![None, None])),
+            ],
+            error: vec!["Internal Error: Unsupported not (Int8)"],
+        },
+        ArrayTest {
+            name: "is-null-passed",
+            args: vec![
+                Arc::new(Int8Array::from(vec![Some(1), None])),
+                Arc::new(StringArray::from(vec![None, Some("x")])),
+                Arc::new(NullArray::new(2)),
+            ],
+            op: DataValueUnaryOperator::IsNull,
+            expect: vec![
+                Arc::new(BooleanArray::from(vec![false, true])),
+                Arc::new(BooleanArray::from(vec![true, false])),
+                Arc::new(BooleanArray::from(vec![true, true])),
+            ],
+            error: vec![],
+        },
+        ArrayTest {
+            name: "is-not-null-passed",
+            args: vec![
+                Arc::new(Int8Array::from(vec![Some(1), None])),
+                Arc::new(NullArray::new(2)),
+            ],
+            op: DataValueUnaryOperator::IsNotNull,
+            expect: vec![
+                Arc::new(BooleanArray::from(vec![true, false])),
+                Arc::new(BooleanArray::from(vec![false, false])),
+            ],
+            error: vec![],
+        },
+    ];
+
+    for t in tests {
+        for (i, arg) in t.args.iter().enumerate() {
+            let result = data_array_unary_op(t.op.clone(), &DataColumnarValue::Array(arg.clone()));
+            match result {
+                Ok(ref v) => {
+                    // Result check.
+                    if !v.equals(&*t.expect[i]) {
+                        println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect[i], v);
+                        assert!(false);
+                    }
+                }
+                Err(e) => assert_eq!(t.error[i], e.to_string()),
+            }
+        }
+    }
+}
@@ -2,11 +2,129 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::sync::Arc;
+
+use crate::datavalues::{
+    DataValueDateOperator, DataValueHashOperator, DataValueIntervalUnit, DataValueMathOperator,
+    DataValueNestedOperator, DataValueStatisticOperator, DataValueStringOperator,
+};
 use crate::error::{FuseQueryError, FuseQueryResult};
 use arrow::datatypes;
+use arrow::datatypes::{DateUnit, TimeUnit};
 
 pub type DataType = datatypes::DataType;
 
+/// The date is the days since the UNIX epoch.
+pub const DATE_TYPE: DataType = DataType::Date32(DateUnit::Day);
+/// The timestamp is the seconds since the UNIX epoch in UTC.
+pub const TIMESTAMP_TYPE: DataType = DataType::Timestamp(TimeUnit::Second, None);
+
+pub fn is_temporal(dt: &DataType) -> bool {
+    dt == &DATE_TYPE || dt == &TIMESTAMP_TYPE
+}
+
+/// The decimal is the i64 scaled by 10^scale, so the precision is at most 18.
+pub const DECIMAL_MAX_PRECISION: usize = 18;
+
+/// Arrow 2.0 has no decimal type, the decimal is stored in the nanosecond timestamp array
+/// whose timezone is the type name such as "Decimal(10, 2)".
+pub fn decimal_type(precision: usize, scale: usize) -> DataType {
+    DataType::Timestamp(
+        TimeUnit::Nanosecond,
+        Some(Arc::new(format!("Decimal({}, {})", precision, scale))),
+    )
+}
+
+/// The precision and the scale of the decimal type, None if it is not a decimal.
+pub fn decimal_precision_scale(dt: &DataType) -> Option<(usize, usize)> {
+    match dt {
+        DataType::Timestamp(TimeUnit::Nanosecond, Some(name)) => {
+            let args = name.strip_prefix("Decimal(")?.strip_suffix(")")?;
+            let mut args = args.split(", ");
+            let precision = args.next()?.parse().ok()?;
+            let scale = args.next()?.parse().ok()?;
+            Some((precision, scale))
+        }
+        _ => None,
+    }
+}
+
+pub fn is_decimal(dt: &DataType) -> bool {
+    decimal_precision_scale(dt).is_some()
+}
+
+/// The name of the data type in the plans and the column names, the decimal is "Decimal(p, s)".
+pub fn data_type_name(dt: &DataType) -> String {
+    match decimal_precision_scale(dt) {
+        Some((precision, scale)) => format!("Decimal({}, {})", precision, scale),
+        None => format!("{:?}", dt),
+    }
+}
+
+/// The tuple is the struct whose fields are named by the positions from 1.
+pub fn tuple_type(data_types: &[DataType]) -> DataType {
+    DataType::Struct(
+        data_types
+            .iter()
+            .enumerate()
+            .map(|(i, dt)| datatypes::Field::new(&(i + 1).to_string(), dt.clone(), true))
+            .collect(),
+    )
+}
+
+// The integers are the decimals of scale 0 which hold all their values.
+fn integer_precision(dt: &DataType) -> Option<usize> {
+    match dt {
+        DataType::Int8 | DataType::UInt8 => Some(3),
+        DataType::Int16 | DataType::UInt16 => Some(5),
+        DataType::Int32 | DataType::UInt32 => Some(10),
+        DataType::Int64 | DataType::UInt64 => Some(DECIMAL_MAX_PRECISION),
+        _ => None,
+    }
+}
+
+/// The result type of the decimal operation, the integers are coerced to the decimals
+/// and the floats make the result Float64.
+pub fn decimal_coercion(
+    op: &str,
+    lhs_type: &DataType,
+    rhs_type: &DataType,
+) -> FuseQueryResult<DataType> {
+    let precision_scale = |dt: &DataType| {
+        decimal_precision_scale(dt).or_else(|| integer_precision(dt).map(|p| (p, 0)))
+    };
+    let (p1, s1, p2, s2) = match (precision_scale(lhs_type), precision_scale(rhs_type)) {
+        (Some((p1, s1)), Some((p2, s2))) => (p1, s1, p2, s2),
+        _ if matches!(lhs_type, DataType::Float32 | DataType::Float64)
+            || matches!(rhs_type, DataType::Float32 | DataType::Float64) =>
+        {
+            return Ok(DataType::Float64)
+        }
+        _ => {
+            return Err(FuseQueryError::Internal(format!(
+                "Unsupported ({:?}) {} ({:?})",
+                lhs_type, op, rhs_type,
+            )))
+        }
+    };
+
+    let scale = s1.max(s2);
+    let integral = (p1 - s1).max(p2 - s2);
+    let (precision, scale) = match op {
+        "+" | "-" => (integral + scale + 1, scale),
+        "*" => (p1 + p2, s1 + s2),
+        "/" => (DECIMAL_MAX_PRECISION, scale),
+        _ => (integral + scale, scale),
+    };
+    if scale > DECIMAL_MAX_PRECISION {
+        return Err(FuseQueryError::Internal(format!(
+            "The scale of ({:?}) {} ({:?}) is out of the bound {}",
+            lhs_type, op, rhs_type, DECIMAL_MAX_PRECISION
+        )));
+    }
+    Ok(decimal_type(precision.min(DECIMAL_MAX_PRECISION), scale))
+}
+
 fn is_numeric(dt: &DataType) -> bool {
     matches!(
         dt,
@@ -31,6 +149,20 @@ pub fn numerical_coercion(
 ) -> FuseQueryResult<DataType> {
     use arrow::datatypes::DataType::*;
 
+    // NULL is coerced to the other type.
+    match (lhs_type, rhs_type) {
+        (Null, other) | (other, Null)
+            if other == &Null || is_numeric(other) || is_decimal(other) =>
+        {
+            return Ok(other.clone())
+        }
+        _ => {}
+    }
+
+    if is_decimal(lhs_type) || is_decimal(rhs_type) {
+        return decimal_coercion(op, lhs_type, rhs_type);
+    }
+
     // error on any non-numeric type
     if !is_numeric(lhs_type) || !is_numeric(rhs_type) {
         return Err(FuseQueryError::Internal(format!(
@@ -94,5 +226,377 @@ pub fn equal_coercion(
     if lhs_type == rhs_type {
         return Ok(lhs_type.clone());
     }
-    numerical_coercion(op, lhs_type, rhs_type)
+    match (lhs_type, rhs_type) {
+        (DataType::Null, _) => return Ok(rhs_type.clone()),
+        (_, DataType::Null) => return Ok(lhs_type.clone()),
+        // The arrays are of the common type of the elements, [] is the array of NULLs.
+        (DataType::List(l), DataType::List(r)) => {
+            return Ok(DataType::List(Box::new(equal_coercion(op, l, r)?)))
+        }
+        _ => {}
+    }
+    // The date is compared as the timestamp at the start of the day,
+    // the strings are parsed as the temporal or decimal type.
+    match (lhs_type, rhs_type) {
+        _ if is_temporal(lhs_type) && is_temporal(rhs_type) => Ok(TIMESTAMP_TYPE),
+        (_, DataType::Utf8) if is_temporal(lhs_type) => Ok(lhs_type.clone()),
+        (DataType::Utf8, _) if is_temporal(rhs_type) => Ok(rhs_type.clone()),
+        (_, DataType::Utf8) if is_decimal(lhs_type) => Ok(lhs_type.clone()),
+        (DataType::Utf8, _) if is_decimal(rhs_type) => Ok(rhs_type.clone()),
+        _ => numerical_coercion(op, lhs_type, rhs_type),
+    }
+}
+
+/// The common type of the results of the conditional functions, which is the type of the comparison,
+/// otherwise the string if the other type is a number, a decimal or a temporal type.
+pub fn conditional_coercion(
+    op: &str,
+    lhs_type: &DataType,
+    rhs_type: &DataType,
+) -> FuseQueryResult<DataType> {
+    if let Ok(data_type) = equal_coercion(op, lhs_type, rhs_type) {
+        return Ok(data_type);
+    }
+    let is_scalar = |dt: &DataType| is_numeric(dt) || is_decimal(dt) || is_temporal(dt);
+    match (lhs_type, rhs_type) {
+        (DataType::Utf8, dt) | (dt, DataType::Utf8) if is_scalar(dt) => Ok(DataType::Utf8),
+        _ => Err(FuseQueryError::Plan(format!(
+            "There is no common type of ({}) and ({}) for {}",
+            data_type_name(lhs_type),
+            data_type_name(rhs_type),
+            op
+        ))),
+    }
+}
+
+/// The result type of the negation, the unsigned integers are negated to the wider signed integers.
+pub fn negate_coercion(op: &str, data_type: &DataType) -> FuseQueryResult<DataType> {
+    use arrow::datatypes::DataType::*;
+
+    Ok(match data_type {
+        UInt8 => Int16,
+        UInt16 => Int32,
+        UInt32 | UInt64 => Int64,
+        Int8 | Int16 | Int32 | Int64 | Float32 | Float64 => data_type.clone(),
+        _ if is_decimal(data_type) => data_type.clone(),
+        _ => {
+            return Err(FuseQueryError::Internal(format!(
+                "Unsupported {} ({:?})",
+                op, data_type
+            )))
+        }
+    })
+}
+
+/// The result type of the date function, the argument is a date or a timestamp.
+/// The functions of the days keep the date, the others are timestamps.
+pub fn date_coercion(
+    op: &DataValueDateOperator,
+    data_type: &DataType,
+) -> FuseQueryResult<DataType> {
+    if !is_temporal(data_type) && data_type != &DataType::Null {
+        return Err(FuseQueryError::Internal(format!(
+            "Unsupported {} ({:?})",
+            op, data_type
+        )));
+    }
+
+    let by_days = |unit: &DataValueIntervalUnit| {
+        matches!(
+            unit,
+            DataValueIntervalUnit::Year | DataValueIntervalUnit::Month | DataValueIntervalUnit::Day
+        )
+    };
+    Ok(match op {
+        DataValueDateOperator::ToYear => DataType::UInt16,
+        DataValueDateOperator::ToMonth
+        | DataValueDateOperator::ToDayOfMonth
+        | DataValueDateOperator::ToDayOfWeek
+        | DataValueDateOperator::ToHour
+        | DataValueDateOperator::ToMinute
+        | DataValueDateOperator::ToSecond => DataType::UInt8,
+        DataValueDateOperator::ToStartOfMonth
+        | DataValueDateOperator::ToStartOfYear
+        | DataValueDateOperator::Today => DATE_TYPE,
+        DataValueDateOperator::ToStartOfDay | DataValueDateOperator::Now => TIMESTAMP_TYPE,
+        DataValueDateOperator::ToStartOfInterval(_, unit) if by_days(unit) => DATE_TYPE,
+        DataValueDateOperator::ToStartOfInterval(_, _) => TIMESTAMP_TYPE,
+        DataValueDateOperator::AddInterval(unit)
+        | DataValueDateOperator::SubtractInterval(unit)
+            if by_days(unit) && data_type == &DATE_TYPE =>
+        {
+            DATE_TYPE
+        }
+        DataValueDateOperator::AddInterval(_) | DataValueDateOperator::SubtractInterval(_) => {
+            TIMESTAMP_TYPE
+        }
+    })
+}
+
+/// The result type of the array and tuple functions, the arrays are of the common element type.
+pub fn nested_coercion(
+    op: &DataValueNestedOperator,
+    data_types: &[DataType],
+) -> FuseQueryResult<DataType> {
+    let unsupported = || {
+        FuseQueryError::Internal(format!(
+            "Unsupported {} ({})",
+            op,
+            data_types
+                .iter()
+                .map(|dt| format!("{:?}", dt))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    };
+    // The NULL is the array of NULLs.
+    let element_type = |dt: &DataType| match dt {
+        DataType::List(element) => Some(element.as_ref().clone()),
+        DataType::Null => Some(DataType::Null),
+        _ => None,
+    };
+
+    Ok(match op {
+        DataValueNestedOperator::Array => {
+            let mut element = DataType::Null;
+            for dt in data_types {
+                element = conditional_coercion(&op.to_string(), &element, dt)?;
+            }
+            DataType::List(Box::new(element))
+        }
+        DataValueNestedOperator::Tuple => tuple_type(data_types),
+        DataValueNestedOperator::Has => {
+            let element = element_type(&data_types[0]).ok_or_else(unsupported)?;
+            equal_coercion(&op.to_string(), &element, &data_types[1])?;
+            DataType::Boolean
+        }
+        DataValueNestedOperator::Length => {
+            element_type(&data_types[0]).ok_or_else(unsupported)?;
+            DataType::UInt64
+        }
+        DataValueNestedOperator::ArrayElement => match &data_types[1] {
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64 => element_type(&data_types[0]).ok_or_else(unsupported)?,
+            _ => return Err(unsupported()),
+        },
+        DataValueNestedOperator::TupleElement(n) => match &data_types[0] {
+            DataType::Struct(fields) if *n >= 1 && *n <= fields.len() => {
+                fields[*n - 1].data_type().clone()
+            }
+            _ => return Err(unsupported()),
+        },
+        DataValueNestedOperator::ArrayJoin => {
+            element_type(&data_types[0]).ok_or_else(unsupported)?
+        }
+        DataValueNestedOperator::GroupArray => DataType::List(Box::new(data_types[0].clone())),
+    })
+}
+
+/// The result type of the string functions, the arguments of concat and format are cast to
+/// the strings, the others are the strings and the integers.
+pub fn string_coercion(
+    op: &DataValueStringOperator,
+    data_types: &[DataType],
+) -> FuseQueryResult<DataType> {
+    let is_string = |dt: &DataType| matches!(dt, DataType::Utf8 | DataType::Null);
+    let is_integer = |dt: &DataType| {
+        matches!(
+            dt,
+            DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+                | DataType::Null
+        )
+    };
+    let valid = data_types.iter().enumerate().all(|(i, dt)| match op {
+        DataValueStringOperator::Concat => !matches!(dt, DataType::List(_) | DataType::Struct(_)),
+        DataValueStringOperator::Format => {
+            (i == 0 && is_string(dt))
+                || (i > 0 && !matches!(dt, DataType::List(_) | DataType::Struct(_)))
+        }
+        DataValueStringOperator::Substring | DataValueStringOperator::Repeat => {
+            (i == 0 && is_string(dt)) || (i > 0 && is_integer(dt))
+        }
+        DataValueStringOperator::LeftPad | DataValueStringOperator::RightPad => {
+            (i == 1 && is_integer(dt)) || (i != 1 && is_string(dt))
+        }
+        // The length of the arrays is the number of the elements.
+        DataValueStringOperator::Length => is_string(dt) || matches!(dt, DataType::List(_)),
+        _ => is_string(dt),
+    });
+    if !valid {
+        return Err(FuseQueryError::Internal(format!(
+            "Unsupported {} ({})",
+            op,
+            data_types
+                .iter()
+                .map(|dt| format!("{:?}", dt))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    Ok(match op {
+        DataValueStringOperator::Length | DataValueStringOperator::Position => DataType::UInt64,
+        DataValueStringOperator::StartsWith | DataValueStringOperator::EndsWith => {
+            DataType::Boolean
+        }
+        DataValueStringOperator::SplitByChar => DataType::List(Box::new(DataType::Utf8)),
+        _ => DataType::Utf8,
+    })
+}
+
+/// The result type of the math functions, the decimals are computed as the Float64.
+/// The abs of the signed integers is the unsigned integers of the same width,
+/// the round, floor and ceil keep the type, the intDiv is the integer division.
+pub fn math_coercion(
+    op: &DataValueMathOperator,
+    data_types: &[DataType],
+) -> FuseQueryResult<DataType> {
+    use arrow::datatypes::DataType::*;
+
+    let unsupported = || {
+        FuseQueryError::Internal(format!(
+            "Unsupported {} ({})",
+            op,
+            data_types
+                .iter()
+                .map(|dt| format!("{:?}", dt))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    };
+    let is_number = |dt: &DataType| dt == &Null || is_numeric(dt) || is_decimal(dt);
+    let is_integer = |dt: &DataType| {
+        matches!(
+            dt,
+            Null | Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | UInt64
+        )
+    };
+
+    match op {
+        DataValueMathOperator::Greatest | DataValueMathOperator::Least => {
+            let mut result = Null;
+            for dt in data_types {
+                if matches!(dt, List(_) | Struct(_)) {
+                    return Err(unsupported());
+                }
+                result = conditional_coercion(&op.to_string(), &result, dt)?;
+            }
+            Ok(result)
+        }
+        DataValueMathOperator::IntDiv => {
+            if !data_types.iter().all(|dt| dt == &Null || is_numeric(dt)) {
+                return Err(unsupported());
+            }
+            Ok(
+                match numerical_coercion(&op.to_string(), &data_types[0], &data_types[1])? {
+                    Null | Float16 | Float32 | Float64 => Int64,
+                    other => other,
+                },
+            )
+        }
+        DataValueMathOperator::Round
+        | DataValueMathOperator::Floor
+        | DataValueMathOperator::Ceil => {
+            if !is_number(&data_types[0]) || !data_types[1..].iter().all(is_integer) {
+                return Err(unsupported());
+            }
+            Ok(match &data_types[0] {
+                Null | Float16 => Float64,
+                dt if is_decimal(dt) => Float64,
+                dt => dt.clone(),
+            })
+        }
+        DataValueMathOperator::Abs => {
+            if !is_number(&data_types[0]) {
+                return Err(unsupported());
+            }
+            Ok(match &data_types[0] {
+                Int8 => UInt8,
+                Int16 => UInt16,
+                Int32 => UInt32,
+                Int64 => UInt64,
+                Null | Float16 => Float64,
+                dt if is_decimal(dt) => Float64,
+                dt => dt.clone(),
+            })
+        }
+        _ => {
+            if !data_types.iter().all(is_number) {
+                return Err(unsupported());
+            }
+            Ok(Float64)
+        }
+    }
+}
+
+/// The result type of the statistic aggregates, the numbers and the decimals are Float64,
+/// except the avg of the decimal, which is the decimal of the maximum precision like the sum.
+pub fn statistic_coercion(
+    op: &DataValueStatisticOperator,
+    data_types: &[DataType],
+) -> FuseQueryResult<DataType> {
+    let is_number = |dt: &DataType| dt == &DataType::Null || is_numeric(dt) || is_decimal(dt);
+    if !data_types.iter().all(is_number) {
+        return Err(FuseQueryError::Internal(format!(
+            "Unsupported {} ({})",
+            op,
+            data_types
+                .iter()
+                .map(|dt| format!("{:?}", dt))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    match (op, data_types) {
+        (DataValueStatisticOperator::Avg, [dt]) if is_decimal(dt) => {
+            let (_, scale) = decimal_precision_scale(dt).unwrap_or_default();
+            Ok(decimal_type(DECIMAL_MAX_PRECISION, scale))
+        }
+        _ => Ok(DataType::Float64),
+    }
+}
+
+/// The result type of the hash functions, the 64-bit hashes are the UInt64 of the arguments,
+/// md5 and sha256 are the lowercase hex digests of the string.
+pub fn hash_coercion(
+    op: &DataValueHashOperator,
+    data_types: &[DataType],
+) -> FuseQueryResult<DataType> {
+    let valid = match op {
+        DataValueHashOperator::Md5 | DataValueHashOperator::Sha256 => data_types
+            .iter()
+            .all(|dt| matches!(dt, DataType::Utf8 | DataType::Null)),
+        _ => data_types
+            .iter()
+            .all(|dt| !matches!(dt, DataType::List(_) | DataType::Struct(_))),
+    };
+    if !valid {
+        return Err(FuseQueryError::Internal(format!(
+            "Unsupported {} ({})",
+            op,
+            data_types
+                .iter()
+                .map(|dt| format!("{:?}", dt))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    Ok(match op {
+        DataValueHashOperator::Md5 | DataValueHashOperator::Sha256 => DataType::Utf8,
+        _ => DataType::UInt64,
+    })
 }
@@ -6,12 +6,18 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::sync::Arc;
 
+use arrow::array::{make_array, Array, ArrayData};
+use arrow::buffer::Buffer;
+use arrow::datatypes::{DateUnit, TimeUnit, ToByteSlice};
 use serde::{Deserialize, Serialize};
 
+use crate::datavalues::data_array_date::{date_to_string, timestamp_to_string};
+use crate::datavalues::data_array_decimal::decimal_to_string;
+use crate::datavalues::data_type::{decimal_precision_scale, decimal_type, is_decimal, tuple_type};
 use crate::datavalues::{
-    BooleanArray, DataArrayRef, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, NullArray, StringArray, UInt16Array, UInt32Array, UInt64Array,
-    UInt8Array,
+    BooleanArray, DataArrayRef, DataField, DataType, Date32Array, DecimalArray, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, ListArray, NullArray, StringArray,
+    StructArray, TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
 use crate::error::{FuseQueryError, FuseQueryResult};
 
@@ -31,6 +37,15 @@ pub enum DataValue {
     Float32(Option<f32>),
     Float64(Option<f64>),
     String(Option<String>),
+    /// The days since the UNIX epoch.
+    Date32(Option<i32>),
+    /// The seconds since the UNIX epoch in UTC.
+    Timestamp(Option<i64>),
+    /// The unscaled value, the precision and the scale.
+    Decimal(Option<i64>, usize, usize),
+    /// The elements of the array and the element type.
+    List(Option<Vec<DataValue>>, DataType),
+    /// The elements of the tuple, also the states of the aggregators.
     Struct(Vec<DataValue>),
 }
 
@@ -40,7 +55,8 @@ impl DataValue {
     pub fn is_null(&self) -> bool {
         matches!(
             self,
-            DataValue::Boolean(None)
+            DataValue::Null
+                | DataValue::Boolean(None)
                 | DataValue::Int8(None)
                 | DataValue::Int16(None)
                 | DataValue::Int32(None)
@@ -52,6 +68,10 @@ impl DataValue {
                 | DataValue::Float32(None)
                 | DataValue::Float64(None)
                 | DataValue::String(None)
+                | DataValue::Date32(None)
+                | DataValue::Timestamp(None)
+                | DataValue::Decimal(None, _, _)
+                | DataValue::List(None, _)
         )
     }
 
@@ -70,7 +90,13 @@ impl DataValue {
             DataValue::Float32(_) => DataType::Float32,
             DataValue::Float64(_) => DataType::Float64,
             DataValue::String(_) => DataType::Utf8,
-            DataValue::Struct(_) => unimplemented!(),
+            DataValue::Date32(_) => DataType::Date32(DateUnit::Day),
+            DataValue::Timestamp(_) => DataType::Timestamp(TimeUnit::Second, None),
+            DataValue::Decimal(_, precision, scale) => decimal_type(*precision, *scale),
+            DataValue::List(_, data_type) => DataType::List(Box::new(data_type.clone())),
+            DataValue::Struct(values) => {
+                tuple_type(&values.iter().map(|v| v.data_type()).collect::<Vec<_>>())
+            }
         }
     }
 
@@ -101,9 +127,85 @@ impl DataValue {
                 Arc::new(Float64Array::from(vec![*v; size])) as DataArrayRef
             }
             DataValue::String(v) => Arc::new(StringArray::from(vec![v.as_deref(); size])),
+            DataValue::Date32(Some(v)) => {
+                Arc::new(Date32Array::from(vec![*v; size])) as DataArrayRef
+            }
+            DataValue::Timestamp(Some(v)) => {
+                Arc::new(TimestampSecondArray::from_vec(vec![*v; size], None)) as DataArrayRef
+            }
+            // The decimals, the nested values and the NULLs are built from the values.
+            _ => Self::try_into_data_array(&vec![self.clone(); size], &self.data_type())?,
+        })
+    }
+
+    /// Builds an array of `data_type` from a list of values, Null is treated as a null slot.
+    pub fn try_into_data_array(
+        values: &[DataValue],
+        data_type: &DataType,
+    ) -> FuseQueryResult<DataArrayRef> {
+        Ok(match data_type {
+            DataType::Null => Arc::new(NullArray::new(values.len())),
+            DataType::Boolean => typed_data_values_to_array!(values, BooleanArray, Boolean),
+            DataType::Int8 => typed_data_values_to_array!(values, Int8Array, Int8),
+            DataType::Int16 => typed_data_values_to_array!(values, Int16Array, Int16),
+            DataType::Int32 => typed_data_values_to_array!(values, Int32Array, Int32),
+            DataType::Int64 => typed_data_values_to_array!(values, Int64Array, Int64),
+            DataType::UInt8 => typed_data_values_to_array!(values, UInt8Array, UInt8),
+            DataType::UInt16 => typed_data_values_to_array!(values, UInt16Array, UInt16),
+            DataType::UInt32 => typed_data_values_to_array!(values, UInt32Array, UInt32),
+            DataType::UInt64 => typed_data_values_to_array!(values, UInt64Array, UInt64),
+            DataType::Float32 => typed_data_values_to_array!(values, Float32Array, Float32),
+            DataType::Float64 => typed_data_values_to_array!(values, Float64Array, Float64),
+            DataType::Date32(DateUnit::Day) => {
+                typed_data_values_to_array!(values, Date32Array, Date32)
+            }
+            DataType::Timestamp(TimeUnit::Second, None) => {
+                let mut timestamps = Vec::with_capacity(values.len());
+                for value in values {
+                    timestamps.push(match value {
+                        DataValue::Timestamp(v) => Ok(*v),
+                        DataValue::Null => Ok(None),
+                        other => Err(FuseQueryError::Internal(format!(
+                            "Cannot build TimestampSecondArray from value {:?}",
+                            other
+                        ))),
+                    }?);
+                }
+                Arc::new(TimestampSecondArray::from_opt_vec(timestamps, None))
+            }
+            DataType::Timestamp(TimeUnit::Nanosecond, name) if is_decimal(data_type) => {
+                let mut decimals = Vec::with_capacity(values.len());
+                for value in values {
+                    decimals.push(match value {
+                        DataValue::Decimal(v, ..) if &value.data_type() == data_type => Ok(*v),
+                        DataValue::Null => Ok(None),
+                        other => Err(FuseQueryError::Internal(format!(
+                            "Cannot build DecimalArray of {:?} from value {:?}",
+                            data_type, other
+                        ))),
+                    }?);
+                }
+                Arc::new(DecimalArray::from_opt_vec(decimals, name.clone()))
+            }
+            DataType::Utf8 => {
+                let mut strings = Vec::with_capacity(values.len());
+                for value in values {
+                    strings.push(match value {
+                        DataValue::String(v) => Ok(v.as_deref()),
+                        DataValue::Null => Ok(None),
+                        other => Err(FuseQueryError::Internal(format!(
+                            "Cannot build StringArray from value {:?}",
+                            other
+                        ))),
+                    }?);
+                }
+                Arc::new(StringArray::from(strings))
+            }
+            DataType::List(data_type) => data_values_to_list_array(values, data_type)?,
+            DataType::Struct(fields) => data_values_to_struct_array(values, fields)?,
             other => {
                 return Err(FuseQueryError::Internal(format!(
-                    "DataValue to array cannot be NONE {:?}",
+                    "Unsupported try_into_data_array() for data type: {:?}",
                     other
                 )))
             }
@@ -147,6 +249,52 @@ impl DataValue {
             DataType::Utf8 => {
                 typed_cast_from_array_to_data_value!(array, index, StringArray, String)
             }
+            DataType::Date32(DateUnit::Day) => {
+                typed_cast_from_array_to_data_value!(array, index, Date32Array, Date32)
+            }
+            DataType::Timestamp(TimeUnit::Second, None) => {
+                typed_cast_from_array_to_data_value!(array, index, TimestampSecondArray, Timestamp)
+            }
+            other if is_decimal(other) => {
+                use arrow::array::PrimitiveArrayOps;
+                let (precision, scale) = decimal_precision_scale(other).unwrap_or_default();
+                let array = downcast_array!(array, DecimalArray)?;
+                DataValue::Decimal(
+                    match array.is_null(index) {
+                        true => None,
+                        false => Some(array.value(index)),
+                    },
+                    precision,
+                    scale,
+                )
+            }
+            DataType::List(data_type) => {
+                let array = downcast_array!(array, ListArray)?;
+                DataValue::List(
+                    match array.is_null(index) {
+                        true => None,
+                        false => {
+                            let values = array.value(index);
+                            Some(
+                                (0..values.len())
+                                    .map(|i| DataValue::try_from_array(&values, i))
+                                    .collect::<FuseQueryResult<Vec<_>>>()?,
+                            )
+                        }
+                    },
+                    data_type.as_ref().clone(),
+                )
+            }
+            DataType::Struct(_) => {
+                let array = downcast_array!(array, StructArray)?;
+                DataValue::Struct(
+                    array
+                        .columns()
+                        .iter()
+                        .map(|column| DataValue::try_from_array(column, index))
+                        .collect::<FuseQueryResult<Vec<_>>>()?,
+                )
+            }
             other => {
                 return Err(FuseQueryError::Internal(format!(
                     "Can't create a scalar of array of type \"{:?}\"",
@@ -186,6 +334,19 @@ impl TryFrom<&DataType> for DataValue {
             DataType::UInt64 => DataValue::UInt64(None),
             DataType::Float32 => DataValue::Float32(None),
             DataType::Float64 => DataValue::Float64(None),
+            DataType::Date32(DateUnit::Day) => DataValue::Date32(None),
+            DataType::Timestamp(TimeUnit::Second, None) => DataValue::Timestamp(None),
+            _ if is_decimal(data_type) => {
+                let (precision, scale) = decimal_precision_scale(data_type).unwrap_or_default();
+                DataValue::Decimal(None, precision, scale)
+            }
+            DataType::List(data_type) => DataValue::List(None, data_type.as_ref().clone()),
+            DataType::Struct(fields) => DataValue::Struct(
+                fields
+                    .iter()
+                    .map(|field| DataValue::try_from(field.data_type()))
+                    .collect::<FuseQueryResult<Vec<_>>>()?,
+            ),
             _ => {
                 return Err(FuseQueryError::Internal(format!(
                     "Unsupported try_from() for data type: {:?}",
@@ -212,7 +373,18 @@ impl fmt::Display for DataValue {
             DataValue::UInt32(v) => format_data_value_with_option!(f, v),
             DataValue::UInt64(v) => format_data_value_with_option!(f, v),
             DataValue::String(v) => format_data_value_with_option!(f, v),
-            DataValue::Struct(v) => write!(f, "{:?}", v),
+            DataValue::Date32(v) => {
+                format_data_value_with_option!(f, v.map(|v| date_to_string(v as i64)))
+            }
+            DataValue::Timestamp(v) => {
+                format_data_value_with_option!(f, v.map(timestamp_to_string))
+            }
+            DataValue::Decimal(v, _, scale) => {
+                format_data_value_with_option!(f, v.map(|v| decimal_to_string(v, *scale)))
+            }
+            DataValue::List(None, _) => write!(f, "NULL"),
+            DataValue::List(Some(v), _) => write!(f, "[{}]", format_nested_values(v)),
+            DataValue::Struct(v) => write!(f, "({})", format_nested_values(v)),
         }
     }
 }
@@ -233,7 +405,103 @@ impl fmt::Debug for DataValue {
             DataValue::Float32(v) => format_data_value_with_option!(f, v),
             DataValue::Float64(v) => format_data_value_with_option!(f, v),
             DataValue::String(v) => format_data_value_with_option!(f, v),
-            DataValue::Struct(v) => write!(f, "{:?}", v),
+            DataValue::Date32(v) => {
+                format_data_value_with_option!(f, v.map(|v| date_to_string(v as i64)))
+            }
+            DataValue::Timestamp(v) => {
+                format_data_value_with_option!(f, v.map(timestamp_to_string))
+            }
+            DataValue::Decimal(v, _, scale) => {
+                format_data_value_with_option!(f, v.map(|v| decimal_to_string(v, *scale)))
+            }
+            DataValue::List(None, _) => write!(f, "NULL"),
+            DataValue::List(Some(v), _) => write!(f, "[{}]", format_nested_values(v)),
+            DataValue::Struct(v) => write!(f, "({})", format_nested_values(v)),
+        }
+    }
+}
+
+// The elements of the arrays and the tuples, the strings are quoted.
+fn format_nested_values(values: &[DataValue]) -> String {
+    values
+        .iter()
+        .map(|v| match v {
+            DataValue::String(Some(v)) => format!("'{}'", v),
+            DataValue::Date32(Some(_)) | DataValue::Timestamp(Some(_)) => format!("'{}'", v),
+            v => v.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn data_values_to_list_array(
+    values: &[DataValue],
+    data_type: &DataType,
+) -> FuseQueryResult<DataArrayRef> {
+    let mut offsets = Vec::with_capacity(values.len() + 1);
+    let mut validity = Vec::with_capacity(values.len());
+    let mut elements = vec![];
+    offsets.push(0_i32);
+    for value in values {
+        match value {
+            DataValue::List(Some(v), _) => {
+                elements.extend(v.iter().cloned());
+                validity.push(true);
+            }
+            DataValue::List(None, _) | DataValue::Null => validity.push(false),
+            other => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Cannot build ListArray from value {:?}",
+                    other
+                )))
+            }
         }
+        offsets.push(elements.len() as i32);
+    }
+
+    let null_count = validity.iter().filter(|v| !**v).count();
+    // The validity bitmap is the values buffer of the boolean array.
+    let null_buffer = BooleanArray::from(validity).data().buffers()[0].clone();
+    let child = DataValue::try_into_data_array(&elements, data_type)?;
+    Ok(make_array(Arc::new(ArrayData::new(
+        DataType::List(Box::new(data_type.clone())),
+        values.len(),
+        Some(null_count),
+        Some(null_buffer),
+        0,
+        vec![Buffer::from(offsets.to_byte_slice())],
+        vec![child.data()],
+    ))))
+}
+
+fn data_values_to_struct_array(
+    values: &[DataValue],
+    fields: &[DataField],
+) -> FuseQueryResult<DataArrayRef> {
+    let mut columns = vec![Vec::with_capacity(values.len()); fields.len()];
+    for value in values {
+        match value {
+            DataValue::Struct(v) if v.len() == fields.len() => {
+                for (column, v) in columns.iter_mut().zip(v) {
+                    column.push(v.clone());
+                }
+            }
+            other => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Cannot build StructArray of {} fields from value {:?}",
+                    fields.len(),
+                    other
+                )))
+            }
+        }
+    }
+
+    let mut arrays = Vec::with_capacity(fields.len());
+    for (field, column) in fields.iter().zip(columns) {
+        arrays.push((
+            field.clone(),
+            DataValue::try_into_data_array(&column, field.data_type())?,
+        ));
     }
+    Ok(Arc::new(StructArray::from(arrays)))
 }
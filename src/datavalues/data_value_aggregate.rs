@@ -2,7 +2,9 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
-use crate::datavalues::{DataValue, DataValueAggregateOperator};
+use crate::datavalues::{
+    data_value_arithmetic_op, DataValue, DataValueAggregateOperator, DataValueArithmeticOperator,
+};
 use crate::error::{FuseQueryError, FuseQueryResult};
 
 pub fn data_value_aggregate_op(
@@ -73,6 +75,59 @@ pub fn data_value_aggregate_op(
             DataValueAggregateOperator::Sum => typed_data_value_add!(lhs, rhs, Float64, f64),
             DataValueAggregateOperator::Count => DataValue::UInt64(Some(1)),
         },
+        (DataValue::Date32(lhs), DataValue::Date32(rhs)) => match op {
+            DataValueAggregateOperator::Min => typed_data_value_min_max!(lhs, rhs, Date32, min),
+            DataValueAggregateOperator::Max => typed_data_value_min_max!(lhs, rhs, Date32, max),
+            DataValueAggregateOperator::Count => DataValue::UInt64(Some(1)),
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported data_value_{} for data type: left:{:?}, right:{:?}",
+                    op,
+                    left.data_type(),
+                    right.data_type()
+                )))
+            }
+        },
+        (DataValue::Timestamp(lhs), DataValue::Timestamp(rhs)) => match op {
+            DataValueAggregateOperator::Min => typed_data_value_min_max!(lhs, rhs, Timestamp, min),
+            DataValueAggregateOperator::Max => typed_data_value_min_max!(lhs, rhs, Timestamp, max),
+            DataValueAggregateOperator::Count => DataValue::UInt64(Some(1)),
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported data_value_{} for data type: left:{:?}, right:{:?}",
+                    op,
+                    left.data_type(),
+                    right.data_type()
+                )))
+            }
+        },
+        // The decimals of the same scale are compared by the unscaled values.
+        (DataValue::Decimal(lhs, precision, scale), DataValue::Decimal(rhs, _, rhs_scale))
+            if scale == rhs_scale =>
+        {
+            match op {
+                DataValueAggregateOperator::Min => DataValue::Decimal(
+                    lhs.iter().chain(rhs.iter()).min().cloned(),
+                    *precision,
+                    *scale,
+                ),
+                DataValueAggregateOperator::Max => DataValue::Decimal(
+                    lhs.iter().chain(rhs.iter()).max().cloned(),
+                    *precision,
+                    *scale,
+                ),
+                DataValueAggregateOperator::Sum => match (lhs, rhs) {
+                    (Some(_), Some(_)) => data_value_arithmetic_op(
+                        DataValueArithmeticOperator::Add,
+                        left.clone(),
+                        right.clone(),
+                    )?,
+                    (Some(_), None) => left.clone(),
+                    (None, _) => right.clone(),
+                },
+                DataValueAggregateOperator::Count => DataValue::UInt64(Some(1)),
+            }
+        }
         (DataValue::String(lhs), DataValue::String(rhs)) => match op {
             DataValueAggregateOperator::Min => {
                 typed_data_value_min_max_string!(lhs, rhs, String, min)
@@ -13,8 +13,8 @@ pub fn data_value_arithmetic_op(
     right: DataValue,
 ) -> FuseQueryResult<DataValue> {
     match (&left, &right) {
-        (DataValue::Null, _) => Ok(right),
-        (_, DataValue::Null) => Ok(left),
+        // NULL with any value is NULL.
+        (DataValue::Null, _) | (_, DataValue::Null) => Ok(DataValue::Null),
         _ => {
             let result = data_array_arithmetic_op(
                 op,
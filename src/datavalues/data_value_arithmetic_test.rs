@@ -15,40 +15,59 @@ fn test_data_value_arithmetic() {
         op: DataValueArithmeticOperator,
     }
 
-    let tests = vec![ScalarTest {
-        name: "add-passed",
-        args: vec![
-            vec![
+    let tests = vec![
+        ScalarTest {
+            name: "add-passed",
+            args: vec![
+                vec![
+                    DataValue::String(Some("xx".to_string())),
+                    DataValue::Int8(Some(2)),
+                ],
+                vec![DataValue::Int8(Some(1)), DataValue::Int8(Some(2))],
+                vec![DataValue::Int16(Some(1)), DataValue::Int16(Some(2))],
+                vec![DataValue::Int32(Some(1)), DataValue::Int32(Some(2))],
+                vec![DataValue::Int64(Some(1)), DataValue::Int64(Some(2))],
+                vec![DataValue::UInt8(Some(1)), DataValue::UInt8(Some(2))],
+                vec![DataValue::UInt16(Some(1)), DataValue::UInt16(Some(2))],
+                vec![DataValue::UInt32(Some(1)), DataValue::UInt32(Some(2))],
+                vec![DataValue::UInt64(Some(1)), DataValue::UInt64(Some(2))],
+                vec![DataValue::Float32(Some(1.0)), DataValue::Float32(Some(2.0))],
+                vec![DataValue::Float64(Some(1.0)), DataValue::Float64(Some(2.0))],
+            ],
+            op: DataValueArithmeticOperator::Add,
+            expect: vec![
                 DataValue::String(Some("xx".to_string())),
-                DataValue::Int8(Some(2)),
+                DataValue::Int8(Some(3)),
+                DataValue::Int16(Some(3)),
+                DataValue::Int32(Some(3)),
+                DataValue::Int64(Some(3)),
+                DataValue::UInt8(Some(3)),
+                DataValue::UInt16(Some(3)),
+                DataValue::UInt32(Some(3)),
+                DataValue::UInt64(Some(3)),
+                DataValue::Float32(Some(3.0)),
+                DataValue::Float64(Some(3.0)),
+            ],
+            error: vec!["Internal Error: Unsupported (Utf8) + (Int8)"],
+        },
+        ScalarTest {
+            name: "modulo-passed",
+            args: vec![
+                vec![DataValue::Int64(Some(7)), DataValue::Int64(Some(3))],
+                vec![DataValue::UInt8(Some(7)), DataValue::Int8(Some(-3))],
+                vec![DataValue::Float64(Some(7.5)), DataValue::Float64(Some(2.0))],
+                vec![DataValue::UInt64(Some(7)), DataValue::UInt64(Some(0))],
+            ],
+            op: DataValueArithmeticOperator::Modulo,
+            expect: vec![
+                DataValue::Int64(Some(1)),
+                DataValue::Int8(Some(1)),
+                DataValue::Float64(Some(1.5)),
+                DataValue::UInt64(None),
             ],
-            vec![DataValue::Int8(Some(1)), DataValue::Int8(Some(2))],
-            vec![DataValue::Int16(Some(1)), DataValue::Int16(Some(2))],
-            vec![DataValue::Int32(Some(1)), DataValue::Int32(Some(2))],
-            vec![DataValue::Int64(Some(1)), DataValue::Int64(Some(2))],
-            vec![DataValue::UInt8(Some(1)), DataValue::UInt8(Some(2))],
-            vec![DataValue::UInt16(Some(1)), DataValue::UInt16(Some(2))],
-            vec![DataValue::UInt32(Some(1)), DataValue::UInt32(Some(2))],
-            vec![DataValue::UInt64(Some(1)), DataValue::UInt64(Some(2))],
-            vec![DataValue::Float32(Some(1.0)), DataValue::Float32(Some(2.0))],
-            vec![DataValue::Float64(Some(1.0)), DataValue::Float64(Some(2.0))],
-        ],
-        op: DataValueArithmeticOperator::Add,
-        expect: vec![
-            DataValue::String(Some("xx".to_string())),
-            DataValue::Int8(Some(3)),
-            DataValue::Int16(Some(3)),
-            DataValue::Int32(Some(3)),
-            DataValue::Int64(Some(3)),
-            DataValue::UInt8(Some(3)),
-            DataValue::UInt16(Some(3)),
-            DataValue::UInt32(Some(3)),
-            DataValue::UInt64(Some(3)),
-            DataValue::Float32(Some(3.0)),
-            DataValue::Float64(Some(3.0)),
-        ],
-        error: vec!["Internal Error: Unsupported (Utf8) + (Int8)"],
-    }];
+            error: vec!["", "", "", "Internal Error: Divide by zero error"],
+        },
+    ];
 
     for t in tests {
         for (i, args) in t.args.iter().enumerate() {
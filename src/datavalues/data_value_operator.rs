@@ -25,6 +25,7 @@ impl std::fmt::Display for DataValueAggregateOperator {
 #[derive(Clone)]
 pub enum DataValueComparisonOperator {
     Eq,
+    NotEq,
     Lt,
     LtEq,
     Gt,
@@ -35,6 +36,7 @@ impl std::fmt::Display for DataValueComparisonOperator {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let display = match &self {
             DataValueComparisonOperator::Eq => "=",
+            DataValueComparisonOperator::NotEq => "!=",
             DataValueComparisonOperator::Lt => "<",
             DataValueComparisonOperator::LtEq => "<=",
             DataValueComparisonOperator::Gt => ">",
@@ -50,6 +52,7 @@ pub enum DataValueArithmeticOperator {
     Sub,
     Mul,
     Div,
+    Modulo,
 }
 
 impl std::fmt::Display for DataValueArithmeticOperator {
@@ -59,6 +62,7 @@ impl std::fmt::Display for DataValueArithmeticOperator {
             DataValueArithmeticOperator::Sub => "-",
             DataValueArithmeticOperator::Mul => "*",
             DataValueArithmeticOperator::Div => "/",
+            DataValueArithmeticOperator::Modulo => "%",
         };
         write!(f, "{}", display)
     }
@@ -79,3 +83,300 @@ impl std::fmt::Display for DataValueLogicOperator {
         write!(f, "{}", display)
     }
 }
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataValueUnaryOperator {
+    Negate,
+    Not,
+    IsNull,
+    IsNotNull,
+}
+
+impl std::fmt::Display for DataValueUnaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueUnaryOperator::Negate => "negate",
+            DataValueUnaryOperator::Not => "not",
+            DataValueUnaryOperator::IsNull => "isNull",
+            DataValueUnaryOperator::IsNotNull => "isNotNull",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataValueConditionalOperator {
+    If,
+    MultiIf,
+    Coalesce,
+    NullIf,
+    IfNull,
+}
+
+impl std::fmt::Display for DataValueConditionalOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueConditionalOperator::If => "if",
+            DataValueConditionalOperator::MultiIf => "multiIf",
+            DataValueConditionalOperator::Coalesce => "coalesce",
+            DataValueConditionalOperator::NullIf => "nullIf",
+            DataValueConditionalOperator::IfNull => "ifNull",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataValueIntervalUnit {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DataValueIntervalUnit {
+    /// The unit of the name such as day or days, case-insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        Some(match name.strip_suffix('s').unwrap_or(&name) {
+            "year" => DataValueIntervalUnit::Year,
+            "month" => DataValueIntervalUnit::Month,
+            "day" => DataValueIntervalUnit::Day,
+            "hour" => DataValueIntervalUnit::Hour,
+            "minute" => DataValueIntervalUnit::Minute,
+            "second" => DataValueIntervalUnit::Second,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for DataValueIntervalUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueIntervalUnit::Year => "Year",
+            DataValueIntervalUnit::Month => "Month",
+            DataValueIntervalUnit::Day => "Day",
+            DataValueIntervalUnit::Hour => "Hour",
+            DataValueIntervalUnit::Minute => "Minute",
+            DataValueIntervalUnit::Second => "Second",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataValueDateOperator {
+    ToYear,
+    ToMonth,
+    ToDayOfMonth,
+    ToDayOfWeek,
+    ToHour,
+    ToMinute,
+    ToSecond,
+    ToStartOfDay,
+    ToStartOfMonth,
+    ToStartOfYear,
+    ToStartOfInterval(i64, DataValueIntervalUnit),
+    AddInterval(DataValueIntervalUnit),
+    SubtractInterval(DataValueIntervalUnit),
+    Now,
+    Today,
+}
+
+impl std::fmt::Display for DataValueDateOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueDateOperator::ToYear => "toYear",
+            DataValueDateOperator::ToMonth => "toMonth",
+            DataValueDateOperator::ToDayOfMonth => "toDayOfMonth",
+            DataValueDateOperator::ToDayOfWeek => "toDayOfWeek",
+            DataValueDateOperator::ToHour => "toHour",
+            DataValueDateOperator::ToMinute => "toMinute",
+            DataValueDateOperator::ToSecond => "toSecond",
+            DataValueDateOperator::ToStartOfDay => "toStartOfDay",
+            DataValueDateOperator::ToStartOfMonth => "toStartOfMonth",
+            DataValueDateOperator::ToStartOfYear => "toStartOfYear",
+            DataValueDateOperator::ToStartOfInterval(_, _) => "toStartOfInterval",
+            DataValueDateOperator::AddInterval(unit) => return write!(f, "add{}s", unit),
+            DataValueDateOperator::SubtractInterval(unit) => return write!(f, "subtract{}s", unit),
+            DataValueDateOperator::Now => "now",
+            DataValueDateOperator::Today => "today",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DataValueNestedOperator {
+    Array,
+    Tuple,
+    Has,
+    Length,
+    ArrayElement,
+    /// The position of the element from 1.
+    TupleElement(usize),
+    ArrayJoin,
+    GroupArray,
+}
+
+impl std::fmt::Display for DataValueNestedOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueNestedOperator::Array => "array",
+            DataValueNestedOperator::Tuple => "tuple",
+            DataValueNestedOperator::Has => "has",
+            DataValueNestedOperator::Length => "length",
+            DataValueNestedOperator::ArrayElement => "arrayElement",
+            DataValueNestedOperator::TupleElement(_) => "tupleElement",
+            DataValueNestedOperator::ArrayJoin => "arrayJoin",
+            DataValueNestedOperator::GroupArray => "groupArray",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DataValueStringOperator {
+    Length,
+    Lower,
+    Upper,
+    Concat,
+    Substring,
+    Trim,
+    Replace,
+    Position,
+    StartsWith,
+    EndsWith,
+    SplitByChar,
+    Reverse,
+    Repeat,
+    Format,
+    LeftPad,
+    RightPad,
+}
+
+impl std::fmt::Display for DataValueStringOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueStringOperator::Length => "length",
+            DataValueStringOperator::Lower => "lower",
+            DataValueStringOperator::Upper => "upper",
+            DataValueStringOperator::Concat => "concat",
+            DataValueStringOperator::Substring => "substring",
+            DataValueStringOperator::Trim => "trim",
+            DataValueStringOperator::Replace => "replace",
+            DataValueStringOperator::Position => "position",
+            DataValueStringOperator::StartsWith => "startsWith",
+            DataValueStringOperator::EndsWith => "endsWith",
+            DataValueStringOperator::SplitByChar => "splitByChar",
+            DataValueStringOperator::Reverse => "reverse",
+            DataValueStringOperator::Repeat => "repeat",
+            DataValueStringOperator::Format => "format",
+            DataValueStringOperator::LeftPad => "lpad",
+            DataValueStringOperator::RightPad => "rpad",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DataValueMathOperator {
+    Abs,
+    Round,
+    Floor,
+    Ceil,
+    Sqrt,
+    Pow,
+    Exp,
+    Ln,
+    Log2,
+    Log10,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    IntDiv,
+    Greatest,
+    Least,
+}
+
+impl std::fmt::Display for DataValueMathOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueMathOperator::Abs => "abs",
+            DataValueMathOperator::Round => "round",
+            DataValueMathOperator::Floor => "floor",
+            DataValueMathOperator::Ceil => "ceil",
+            DataValueMathOperator::Sqrt => "sqrt",
+            DataValueMathOperator::Pow => "pow",
+            DataValueMathOperator::Exp => "exp",
+            DataValueMathOperator::Ln => "ln",
+            DataValueMathOperator::Log2 => "log2",
+            DataValueMathOperator::Log10 => "log10",
+            DataValueMathOperator::Sin => "sin",
+            DataValueMathOperator::Cos => "cos",
+            DataValueMathOperator::Tan => "tan",
+            DataValueMathOperator::Asin => "asin",
+            DataValueMathOperator::Acos => "acos",
+            DataValueMathOperator::Atan => "atan",
+            DataValueMathOperator::IntDiv => "intDiv",
+            DataValueMathOperator::Greatest => "greatest",
+            DataValueMathOperator::Least => "least",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DataValueHashOperator {
+    CityHash64,
+    XxHash64,
+    SipHash64,
+    Md5,
+    Sha256,
+}
+
+impl std::fmt::Display for DataValueHashOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueHashOperator::CityHash64 => "cityHash64",
+            DataValueHashOperator::XxHash64 => "xxHash64",
+            DataValueHashOperator::SipHash64 => "sipHash64",
+            DataValueHashOperator::Md5 => "md5",
+            DataValueHashOperator::Sha256 => "sha256",
+        };
+        write!(f, "{}", display)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DataValueStatisticOperator {
+    Avg,
+    VarPop,
+    VarSamp,
+    StddevPop,
+    StddevSamp,
+    CovarPop,
+    CovarSamp,
+    Corr,
+}
+
+impl std::fmt::Display for DataValueStatisticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display = match &self {
+            DataValueStatisticOperator::Avg => "avg",
+            DataValueStatisticOperator::VarPop => "varPop",
+            DataValueStatisticOperator::VarSamp => "varSamp",
+            DataValueStatisticOperator::StddevPop => "stddevPop",
+            DataValueStatisticOperator::StddevSamp => "stddevSamp",
+            DataValueStatisticOperator::CovarPop => "covarPop",
+            DataValueStatisticOperator::CovarSamp => "covarSamp",
+            DataValueStatisticOperator::Corr => "corr",
+        };
+        write!(f, "{}", display)
+    }
+}
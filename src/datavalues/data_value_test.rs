@@ -0,0 +1,64 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_data_value_to_array() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ArrayTest {
+        name: &'static str,
+        values: Vec<DataValue>,
+        data_type: DataType,
+        expect: DataArrayRef,
+    }
+
+    let tests = vec![
+        ArrayTest {
+            name: "uint64-passed",
+            values: vec![
+                DataValue::UInt64(Some(1)),
+                DataValue::Null,
+                DataValue::UInt64(Some(3)),
+            ],
+            data_type: DataType::UInt64,
+            expect: Arc::new(UInt64Array::from(vec![Some(1), None, Some(3)])),
+        },
+        ArrayTest {
+            name: "float64-passed",
+            values: vec![DataValue::Float64(Some(1.5)), DataValue::Float64(None)],
+            data_type: DataType::Float64,
+            expect: Arc::new(Float64Array::from(vec![Some(1.5), None])),
+        },
+        ArrayTest {
+            name: "string-passed",
+            values: vec![
+                DataValue::String(Some("x".to_string())),
+                DataValue::String(None),
+            ],
+            data_type: DataType::Utf8,
+            expect: Arc::new(StringArray::from(vec![Some("x"), None])),
+        },
+    ];
+
+    for t in tests {
+        let result = DataValue::try_into_data_array(&t.values, &t.data_type)?;
+        assert_eq!(
+            format!("{:?}", t.expect),
+            format!("{:?}", result),
+            "{}",
+            t.name
+        );
+    }
+
+    let result = DataValue::try_into_data_array(&[DataValue::Int8(Some(1))], &DataType::UInt8);
+    let actual = format!("{}", result.err().unwrap());
+    assert_eq!(
+        "Internal Error: Cannot build UInt8Array from value 1",
+        actual
+    );
+    Ok(())
+}
@@ -0,0 +1,28 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use crate::datavalues::{
+    data_array_unary_op, DataColumnarValue, DataValue, DataValueUnaryOperator,
+};
+use crate::error::FuseQueryResult;
+
+pub fn data_value_unary_op(
+    op: DataValueUnaryOperator,
+    value: DataValue,
+) -> FuseQueryResult<DataValue> {
+    match value {
+        DataValue::Null => match op {
+            DataValueUnaryOperator::IsNull => Ok(DataValue::Boolean(Some(true))),
+            DataValueUnaryOperator::IsNotNull => Ok(DataValue::Boolean(Some(false))),
+            _ => Ok(DataValue::Null),
+        },
+        _ => {
+            // The typed NULLs are arrays of one NULL.
+            let array =
+                DataValue::try_into_data_array(std::slice::from_ref(&value), &value.data_type())?;
+            let result = data_array_unary_op(op, &DataColumnarValue::Array(array))?;
+            DataValue::try_from_array(&result, 0)
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_data_value_unary() {
+    use super::*;
+
+    #[allow(dead_code)]
+    struct ScalarTest {
+        name: &'static str,
+        args: Vec<DataValue>,
+        expect: Vec<DataValue>,
+        error: Vec<&'static str>,
+        op: DataValueUnaryOperator,
+    }
+
+    let tests = vec![
+        ScalarTest {
+            name: "negate-passed",
+            args: vec![
+                DataValue::String(Some("xx".to_string())),
+                DataValue::Int32(Some(2)),
+                DataValue::UInt64(Some(2)),
+                DataValue::Float32(Some(2.5)),
+                DataValue::Int64(None),
+                DataValue::Null,
+            ],
+            op: DataValueUnaryOperator::Negate,
+            expect: vec![
+                DataValue::String(Some("xx".to_string())),
+                DataValue::Int32(Some(-2)),
+                DataValue::Int64(Some(-2)),
+                DataValue::Float32(Some(-2.5)),
+                DataValue::Int64(None),
+                DataValue::Null,
+            ],
+            error: vec!["Internal Error: Unsupported negate (Utf8)"],
+        },
+        ScalarTest {
+            name: "not-passed",
+            args: vec![
+                DataValue::Boolean(Some(true)),
+                DataValue::Boolean(None),
+                DataValue::Null,
+            ],
+            op: DataValueUnaryOperator::Not,
+            expect: vec![
+                DataValue::Boolean(Some(false)),
+                DataValue::Boolean(None),
+                DataValue::Null,
+            ],
+            error: vec![""],
+        },
+        ScalarTest {
+            name: "is-null-passed",
+            args: vec![
+                DataValue::Int64(Some(1)),
+                DataValue::Int64(None),
+                DataValue::Null,
+            ],
+            op: DataValueUnaryOperator::IsNull,
+            expect: vec![
+                DataValue::Boolean(Some(false)),
+                DataValue::Boolean(Some(true)),
+                DataValue::Boolean(Some(true)),
+            ],
+            error: vec![""],
+        },
+    ];
+
+    for t in tests {
+        for (i, arg) in t.args.iter().enumerate() {
+            let result = data_value_unary_op(t.op.clone(), arg.clone());
+            match result {
+                Ok(ref v) => {
+                    // Result check.
+                    if *v != t.expect[i] {
+                        println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect[i], v);
+                        assert!(false);
+                    }
+                }
+                Err(e) => assert_eq!(t.error[i], e.to_string()),
+            }
+        }
+    }
+}
@@ -77,6 +77,14 @@ macro_rules! arrow_array_op {
             DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
             DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
             DataType::Utf8 => compute_utf8_op!($LEFT, $RIGHT, $OP, StringArray),
+            DataType::Date32(_) => compute_op!($LEFT, $RIGHT, $OP, Date32Array),
+            DataType::Timestamp(arrow::datatypes::TimeUnit::Second, _) => {
+                compute_op!($LEFT, $RIGHT, $OP, TimestampSecondArray)
+            }
+            // The decimals of the same scale are compared by the unscaled values.
+            DataType::Timestamp(arrow::datatypes::TimeUnit::Nanosecond, _) => {
+                compute_op!($LEFT, $RIGHT, $OP, DecimalArray)
+            }
             _ => Err(FuseQueryError::Internal(format!(
                 "Unsupported arithmetic_compute::{} for data type: {:?}",
                 stringify!($OP),
@@ -208,15 +216,6 @@ macro_rules! format_data_value_with_option {
     }};
 }
 
-/// Invoke a boolean kernel on a pair of arrays
-macro_rules! array_boolean_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
-        let ll = downcast_array!($LEFT, $DT)?;
-        let rr = downcast_array!($RIGHT, $DT)?;
-        Ok(Arc::new(arrow::compute::$OP(&ll, &rr)?))
-    }};
-}
-
 macro_rules! typed_cast_from_array_to_data_value {
     ($array:expr, $index:expr, $ARRAYTYPE:ident, $SCALAR:ident) => {{
         use arrow::array::*;
@@ -228,6 +227,24 @@ macro_rules! typed_cast_from_array_to_data_value {
     }};
 }
 
+macro_rules! typed_data_values_to_array {
+    ($VALUES:expr, $ARRAYTYPE:ident, $SCALAR:ident) => {{
+        let mut values = Vec::with_capacity($VALUES.len());
+        for value in $VALUES {
+            values.push(match value {
+                DataValue::$SCALAR(v) => Ok(v.clone()),
+                DataValue::Null => Ok(None),
+                other => Err(FuseQueryError::Internal(format!(
+                    "Cannot build {} from value {:?}",
+                    stringify!($ARRAYTYPE),
+                    other
+                ))),
+            }?);
+        }
+        Arc::new($ARRAYTYPE::from(values)) as DataArrayRef
+    }};
+}
+
 macro_rules! typed_cast_from_data_value_to_std {
     ($SCALAR:ident, $NATIVE:ident) => {
         impl TryFrom<DataValue> for $NATIVE {
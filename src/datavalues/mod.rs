@@ -4,10 +4,24 @@
 
 mod data_array_aggregate_test;
 mod data_array_arithmetic_test;
+mod data_array_cast_test;
 mod data_array_comparison_test;
+mod data_array_conditional_test;
+mod data_array_date_test;
+mod data_array_decimal_test;
+mod data_array_hash_test;
+mod data_array_in_test;
+mod data_array_key_test;
+mod data_array_like_test;
 mod data_array_logic_test;
+mod data_array_math_test;
+mod data_array_nested_test;
+mod data_array_string_test;
+mod data_array_unary_test;
 mod data_value_aggregate_test;
 mod data_value_arithmetic_test;
+mod data_value_test;
+mod data_value_unary_test;
 
 #[macro_use]
 mod macros;
@@ -15,8 +29,21 @@ mod macros;
 mod data_array;
 mod data_array_aggregate;
 mod data_array_arithmetic;
+mod data_array_cast;
 mod data_array_comparison;
+mod data_array_compute;
+mod data_array_conditional;
+mod data_array_date;
+mod data_array_decimal;
+mod data_array_hash;
+mod data_array_in;
+mod data_array_key;
+mod data_array_like;
 mod data_array_logic;
+mod data_array_math;
+mod data_array_nested;
+mod data_array_string;
+mod data_array_unary;
 mod data_columnar_value;
 mod data_field;
 mod data_schema;
@@ -25,18 +52,38 @@ mod data_value;
 mod data_value_aggregate;
 mod data_value_arithmetic;
 mod data_value_operator;
+mod data_value_unary;
 
 pub use self::data_array_aggregate::data_array_aggregate_op;
 pub use self::data_array_arithmetic::data_array_arithmetic_op;
+pub use self::data_array_cast::{data_array_cast, data_array_try_cast};
 pub use self::data_array_comparison::data_array_comparison_op;
+pub use self::data_array_compute::{data_array_concat, data_array_take};
+pub use self::data_array_conditional::data_array_select;
+pub use self::data_array_date::data_array_date_op;
+pub use self::data_array_hash::data_array_hash_op;
+pub use self::data_array_in::{data_array_in, InListSet};
+pub use self::data_array_key::{data_array_row_key, data_array_row_keys, DataArrayRowKeys};
+pub use self::data_array_like::{data_array_like, LikePattern};
 pub use self::data_array_logic::data_array_logic_op;
-pub use self::data_type::numerical_coercion;
+pub use self::data_array_math::data_array_math_op;
+pub use self::data_array_nested::{data_array_nested_op, data_array_unnest};
+pub use self::data_array_string::data_array_string_op;
+pub use self::data_array_unary::data_array_unary_op;
+pub use self::data_type::{
+    conditional_coercion, data_type_name, date_coercion, decimal_coercion, decimal_precision_scale,
+    decimal_type, equal_coercion, hash_coercion, is_decimal, is_temporal, math_coercion,
+    negate_coercion, nested_coercion, numerical_coercion, statistic_coercion, string_coercion,
+    tuple_type, DATE_TYPE, DECIMAL_MAX_PRECISION, TIMESTAMP_TYPE,
+};
 pub use self::data_value_aggregate::data_value_aggregate_op;
 pub use self::data_value_arithmetic::data_value_arithmetic_op;
+pub use self::data_value_unary::data_value_unary_op;
 
 pub use self::data_array::{
-    BooleanArray, DataArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-    Int8Array, NullArray, StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+    BooleanArray, DataArrayRef, Date32Array, DecimalArray, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, ListArray, NullArray, StringArray, StructArray,
+    TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
 pub use self::data_columnar_value::DataColumnarValue;
 pub use self::data_field::DataField;
@@ -45,5 +92,7 @@ pub use self::data_type::DataType;
 pub use self::data_value::{DataValue, DataValueRef};
 pub use self::data_value_operator::{
     DataValueAggregateOperator, DataValueArithmeticOperator, DataValueComparisonOperator,
-    DataValueLogicOperator,
+    DataValueConditionalOperator, DataValueDateOperator, DataValueHashOperator,
+    DataValueIntervalUnit, DataValueLogicOperator, DataValueMathOperator, DataValueNestedOperator,
+    DataValueStatisticOperator, DataValueStringOperator, DataValueUnaryOperator,
 };
@@ -8,7 +8,7 @@ use std::sync::Arc;
 use crate::contexts::FuseQueryContext;
 use crate::datastreams::SendableDataBlockStream;
 use crate::error::FuseQueryResult;
-use crate::executors::IExecutor;
+use crate::executors::{IExecutor, SubqueryEvaluator};
 use crate::optimizers::Optimizer;
 use crate::planners::SelectPlan;
 use crate::processors::PipelineBuilder;
@@ -34,7 +34,8 @@ impl IExecutor for SelectExecutor {
     }
 
     async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
-        let plan = Optimizer::create().optimize(&self.select.plan)?;
+        let plan = SubqueryEvaluator::evaluate(self.ctx.clone(), &self.select.plan).await?;
+        let plan = Optimizer::create().optimize(&plan)?;
         PipelineBuilder::create(self.ctx.clone(), plan)
             .build()?
             .execute()
@@ -33,3 +33,1705 @@ async fn test_select_executor() -> crate::error::FuseQueryResult<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_select_executor_group_by() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number/10 as n, count(*) from system.numbers_mt(100) group by number/10",
+    )? {
+        let executor = SelectExecutor::try_create(ctx, plan)?;
+        let stream = executor.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+        assert_eq!(10, rows);
+        assert_eq!("n", blocks[0].schema().field(0).name());
+    } else {
+        assert!(false)
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_group_by_keys() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        (
+            "select nullIf(number % 3, 1) as k, count(*), sum(number) + 1 from system.numbers_mt(10) group by nullIf(number % 3, 1) order by k",
+            vec!["0, 4, 19", "2, 3, 16", "NULL, 3, 13"],
+        ),
+        (
+            "select toString(number % 2) as s, number % 3 as k, count(*) from system.numbers_mt(12) group by toString(number % 2), number % 3 order by s, k",
+            vec!["0, 0, 2", "0, 1, 2", "0, 2, 2", "1, 0, 2", "1, 1, 2", "1, 2, 2"],
+        ),
+        (
+            "select number % 2 as k, countDistinct(number % 4), avg(number), max(number) - min(number) from system.numbers_mt(10) group by number % 2 order by k",
+            vec!["0, 2, 4, 8", "1, 2, 5, 8"],
+        ),
+        (
+            "select 1 + 1, number from system.numbers_mt(3) order by number",
+            vec!["2, 0", "2, 1", "2, 2"],
+        ),
+        (
+            "select 1, count(*), 'a' as s from system.numbers_mt(10)",
+            vec!["1, 10, a"],
+        ),
+        (
+            "select number % 2 as k, 1 + 1, sum(number) from system.numbers_mt(10) group by number % 2 order by k",
+            vec!["0, 2, 20", "1, 2, 25"],
+        ),
+        (
+            "select number % 10 + 1 as k, count(*) from system.numbers_mt(20) group by number % 10 order by k limit 3",
+            vec!["1, 2", "2, 2", "3, 2"],
+        ),
+        (
+            "select number % 3 as k, count(*) from system.numbers_mt(10) group by k order by k",
+            vec!["0, 4", "1, 3", "2, 3"],
+        ),
+        (
+            "select number % 2 as number, count(*) from system.numbers_mt(10) group by number order by number",
+            vec!["0, 5", "1, 5"],
+        ),
+        (
+            "select number % 2 as k, (number % 2) * 10 + 1, sum(number) from system.numbers_mt(10) group by k order by k",
+            vec!["0, 1, 20", "1, 11, 25"],
+        ),
+        (
+            "select count(*) from system.numbers_mt(16) group by if(number < 8, -0.0, 0.0)",
+            vec!["16"],
+        ),
+        (
+            "select if(number < 4, cast('nan' as double), nullIf(1.0, 1.0)) as k, count(*) as c from system.numbers_mt(16) group by k order by c",
+            vec!["NaN, 4", "NULL, 12"],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                let mut values = vec![];
+                for column in block.columns() {
+                    values.push(format!("{:?}", DataValue::try_from_array(column, row)?));
+                }
+                actual.push(values.join(", "));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            panic!("Unexpected plan of {}", query)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_having() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number/10 as n, count(*) from system.numbers_mt(100) group by number/10 having n > 6 or sum(number) < 100",
+    )? {
+        let executor = SelectExecutor::try_create(ctx, plan)?;
+        let stream = executor.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+        // n in (0, 7, 8, 9).
+        assert_eq!(4, rows);
+    } else {
+        assert!(false)
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_order_by() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::DataBlock;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt(10000) order by number desc limit 3",
+    )? {
+        let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+        let stream = executor.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        let mut actual = vec![];
+        for block in &blocks {
+            let array = block
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap();
+            actual.extend(array.value_slice(0, array.len()).to_vec());
+        }
+        assert_eq!(vec![9999, 9998, 9997], actual);
+    } else {
+        assert!(false)
+    }
+
+    // The rows of the pipes are merged by the sort keys.
+    let tests = vec![
+        ("select nullIf(number % 4, 0) as k from system.numbers_mt(16) order by k nulls first limit 6", vec!["NULL", "NULL", "NULL", "NULL", "1", "1"]),
+        ("select if(number % 3 = 0, cast('nan' as double), number * -1.0) as k from system.numbers_mt(8) order by k desc limit 4", vec!["NaN", "NaN", "NaN", "-1"]),
+        ("select toString(number) as s from system.numbers_mt(12) order by s desc limit 3", vec!["9", "8", "7"]),
+        ("select number from system.numbers_mt(12) order by number % 3 desc, number limit 3", vec!["2", "5", "8"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            panic!("The query is not a select")
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_join() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![("inner", 5), ("left", 10), ("right", 10), ("full", 15)];
+    for (join_type, expect) in tests {
+        let query = format!(
+            "select a.number, b.number from system.numbers_mt(10) as a {} join system.numbers_mt(10) as b on a.number = b.number + 5",
+            join_type
+        );
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), &query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+            assert_eq!(expect, rows, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    // The keys of the different types are compared by the common type.
+    let tests = vec![
+        ("select a.number from system.numbers_mt(10) as a join (select number * 1.0 + 5 as x from system.numbers_mt(10)) as b on a.number = b.x", 5),
+        ("select a.number from system.numbers_mt(10) as a join (select toInt8(number) as x from system.numbers_mt(3)) as b on a.number = b.x", 3),
+        ("select a.number from system.numbers_mt(10) as a left join (select number * 1.0 as x from system.numbers_mt(5)) as b on a.number = b.x where b.x is null", 5),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+            assert_eq!(expect, rows, "{}", query);
+        } else {
+            panic!("The query is not a select");
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_union() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select number from system.numbers_mt(10) union all select number from system.numbers_mt(5)", 15),
+        ("select number from system.numbers_mt(10) union select number from system.numbers_mt(5)", 10),
+        ("select number from system.numbers_mt(10) union all select number from system.numbers_mt(5) union all select number + 1 from system.numbers_mt(3)", 18),
+        ("select number from system.numbers_mt(10) union all select number + 0.5 from system.numbers_mt(5)", 15),
+        ("select number from system.numbers_mt(10) union all select number from system.numbers_mt(5) limit 3", 3),
+        ("select number % 2 from system.numbers_mt(10) union select number % 3 from system.numbers_mt(10)", 3),
+        ("select a from (select number as a from system.numbers_mt(10) union all select number + 10 as b from system.numbers_mt(5)) where a >= 5", 10),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+            assert_eq!(expect, rows, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_distinct() -> crate::error::FuseQueryResult<()> {
+    use crate::datablocks::DataBlock;
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests =
+        vec![
+        ("select distinct number/10 from system.numbers_mt(100)", 10),
+        ("select distinct number/10 as n from system.numbers_mt(100) order by n desc limit 3", 3),
+        ("select distinct count(*) from system.numbers_mt(100) group by number/10", 1),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+            assert_eq!(expect, rows, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select count(distinct number/10), sum(distinct number/10) from system.numbers_mt(100)",
+    )? {
+        let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+        let stream = executor.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(1, blocks[0].num_rows());
+        assert_eq!(
+            DataValue::UInt64(Some(10)),
+            DataValue::try_from_array(blocks[0].column(0), 0)?
+        );
+        assert_eq!(
+            DataValue::UInt64(Some(45)),
+            DataValue::try_from_array(blocks[0].column(1), 0)?
+        );
+    } else {
+        assert!(false)
+    }
+
+    let tests = vec![
+        ("select count(distinct number % 3, number % 2) from system.numbers_mt(10)", vec!["6"]),
+        ("select count(distinct nullIf(number % 3, 0), number % 2) from system.numbers_mt(10)", vec!["4"]),
+        ("select count(distinct number % 3, number % 2) from system.numbers_mt(10) group by number % 2", vec!["3", "3"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            panic!("The query is not a select")
+        }
+    }
+
+    let result = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select sum(distinct number, number) from system.numbers_mt(10)",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: Invalid number of arguments for function sum: 2";
+    assert_eq!(expect, actual);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_subquery() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::DataBlock;
+    use crate::datavalues::DataValue;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select number from system.numbers_mt(10) where number > (select max(number) - 3 from system.numbers_mt(10))", 3),
+        ("select (select count(*) from system.numbers_mt(5)) as c, number from system.numbers_mt(3)", 3),
+        ("select number from system.numbers_mt(10) where number in (select number + 5 from system.numbers_mt(10))", 5),
+        ("select number from system.numbers_mt(10) where number not in (select number + 2 from system.numbers_mt(10))", 2),
+        ("select number from system.numbers_mt(10) where number not in (select nullIf(number, 1) from system.numbers_mt(10))", 0),
+        ("select number from system.numbers_mt(10) where nullIf(number, 0) not in (select number + 5 from system.numbers_mt(10))", 4),
+        ("select number from system.numbers_mt(10) where nullIf(number, 0) not in (select number from system.numbers_mt(10) where number > 100)", 10),
+        ("select number from system.numbers_mt(10) as a where number not in (select nullIf(b.number, 3) from system.numbers_mt(5) as b where b.number = a.number)", 5),
+        ("select number from system.numbers_mt(10) where exists (select number from system.numbers_mt(10) where number > 8)", 10),
+        ("select number from system.numbers_mt(10) where exists (select number from system.numbers_mt(10) where number > 100)", 0),
+        ("select number from system.numbers_mt(10) as a where exists (select * from system.numbers_mt(4) as b where b.number = a.number + 1)", 3),
+        ("select number from system.numbers_mt(10) as a where not exists (select * from system.numbers_mt(4) as b where b.number = a.number and b.number > 1)", 8),
+        ("select number from system.numbers_mt(10) as a where number > 1 and number in (select b.number from system.numbers_mt(5) as b where b.number = a.number)", 3),
+        ("select number from system.numbers_mt(10) where number in (select number * 1.0 from system.numbers_mt(3))", 3),
+        ("select number from system.numbers_mt(10) where number not in (select number * 1.0 from system.numbers_mt(3))", 7),
+        ("select number from system.numbers_mt(10) where number = 9 or number in (select number + 5 from system.numbers_mt(2))", 3),
+        ("select number from system.numbers_mt(10) where number = 0 or number not in (select nullIf(number, 1) from system.numbers_mt(3))", 1),
+        ("select number from system.numbers_mt(10) where number = 0 or nullIf(number, 1) not in (select number from system.numbers_mt(10) where number > 100)", 10),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+            assert_eq!(expect, rows, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt(10) where number = (select number from system.numbers_mt(10))",
+    )? {
+        let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+        let result = executor.execute().await;
+        let actual = format!("{}", result.err().unwrap());
+        let expect = "Error during plan: Scalar subquery returned more than one row";
+        assert_eq!(expect, actual);
+    } else {
+        assert!(false)
+    }
+
+    let tests = vec![
+        ("select number in (select number * 2 from system.numbers_mt(3)) from system.numbers_mt(5) order by number", vec!["true", "false", "true", "false", "true"]),
+        ("select number not in (select nullIf(number, 1) * 1.0 from system.numbers_mt(3)) from system.numbers_mt(4) order by number", vec!["false", "NULL", "false", "NULL"]),
+        ("select sum(number) in (select number * 45 from system.numbers_mt(2)) from system.numbers_mt(10)", vec!["true"]),
+        ("select count(*) in (select number * 5 from system.numbers_mt(2)) as c from system.numbers_mt(10) group by number % 2", vec!["true", "true"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            panic!("The query is not a select")
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_cte() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("with t as (select number from system.numbers_mt(10)) select number from t where number > 6", 3),
+        ("with t(n) as (select number from system.numbers_mt(10)) select n from t where n < 4", 4),
+        ("with t as (select number from system.numbers_mt(10)), s as (select number from t where number > 4) select number from s", 5),
+        ("with t as (select number from system.numbers_mt(10)) select a.number from t as a join t as b on a.number = b.number + 3", 7),
+        ("with t as (select number from system.numbers_mt(10)) select number from t union all select number from t", 20),
+    ];
+    for materialize in &[false, true] {
+        ctx.set_materialize_cte(*materialize)?;
+        for (query, expect) in &tests {
+            if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+                let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+                let stream = executor.execute().await?;
+                let blocks = stream.try_collect::<Vec<_>>().await?;
+                let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+                assert_eq!(*expect, rows, "{}", query);
+            } else {
+                assert!(false)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_window() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select row_number() over (order by number desc) from system.numbers_mt(6) order by number", vec![6, 5, 4, 3, 2, 1]),
+        ("select row_number() over (partition by number / 3 order by number) from system.numbers_mt(6) order by number", vec![1, 2, 3, 1, 2, 3]),
+        ("select rank() over (order by number / 2) from system.numbers_mt(6) order by number", vec![1, 1, 3, 3, 5, 5]),
+        ("select dense_rank() over (order by number / 2) from system.numbers_mt(6) order by number", vec![1, 1, 2, 2, 3, 3]),
+        ("select lag(number, 2, 100) over (order by number) from system.numbers_mt(6) order by number", vec![100, 100, 0, 1, 2, 3]),
+        ("select lead(number, 1, 0) over (partition by number / 3 order by number) + 1 from system.numbers_mt(6) where number < 2 or number > 2 order by number", vec![2, 1, 5, 6, 1]),
+        ("select sum(number) over (order by number) from system.numbers_mt(6) order by number", vec![0, 1, 3, 6, 10, 15]),
+        ("select sum(number) over (order by number / 2) from system.numbers_mt(6) order by number", vec![1, 1, 6, 6, 15, 15]),
+        ("select sum(number) over (partition by number / 3) from system.numbers_mt(6) order by number", vec![3, 3, 3, 12, 12, 12]),
+        ("select sum(number) over (order by number rows between 1 preceding and 1 following) from system.numbers_mt(6) order by number", vec![1, 3, 6, 9, 12, 9]),
+        ("select count(*) over (order by number rows between unbounded preceding and 2 preceding) from system.numbers_mt(6) order by number", vec![0, 0, 1, 2, 3, 4]),
+        ("select max(number) over (order by number rows between current row and unbounded following) from system.numbers_mt(6) order by number", vec![5, 5, 5, 5, 5, 5]),
+        ("select min(number) over (order by number desc rows 2 preceding) from system.numbers_mt(6) order by number", vec![0, 1, 2, 3, 4, 5]),
+        ("select number from system.numbers_mt(6) order by row_number() over (order by number desc) limit 2", vec![5, 4]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            let expect = expect.iter().map(|v| format!("{}", v)).collect::<Vec<_>>();
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    let tests = vec![
+        ("select number, row_number() over (order by number) from system.numbers_mt(6) group by number", "Error during plan: Window functions with GROUP BY or aggregate functions are not supported yet"),
+        ("select number from system.numbers_mt(6) where row_number() over (order by number) > 1", "Error during plan: Window functions are not allowed in WHERE"),
+        ("select sum(number) over (order by number range between 1 preceding and current row) from system.numbers_mt(6)", "Error during plan: RANGE frame with offset is not supported yet: RANGE BETWEEN 1 PRECEDING AND CURRENT ROW"),
+        ("select abs(number) over (order by number) from system.numbers_mt(6)", "Error during plan: Unsupported window function: abs"),
+    ];
+    for (query, expect) in tests {
+        let result = Planner::new().build_from_sql(ctx.clone(), query);
+        let actual = format!("{}", result.err().unwrap());
+        assert_eq!(expect, actual, "{}", query);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_conditional() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select case when number < 2 then 'a' when number < 4 then 'b' else 'c' end from system.numbers_mt(6) order by number", vec!["a", "a", "b", "b", "c", "c"]),
+        ("select case number when 1 then 10 when 3 then 30 end from system.numbers_mt(4) order by number", vec!["NULL", "10", "NULL", "30"]),
+        ("select if(number > 1, number, 100) from system.numbers_mt(4) order by number", vec!["100", "100", "2", "3"]),
+        ("select multiIf(number = 0, 'zero', number = 1, 'one', 'many') from system.numbers_mt(3) order by number", vec!["zero", "one", "many"]),
+        ("select coalesce(null, nullIf(number, 1), 100) from system.numbers_mt(3) order by number", vec!["0", "100", "2"]),
+        ("select ifNull(nullIf(number, 2), 7) from system.numbers_mt(3) order by number", vec!["0", "1", "7"]),
+        ("select sum(if(number > 2, 1, 0)) from system.numbers_mt(6)", vec!["3"]),
+        ("select if(number > 1, number, 'x') from system.numbers_mt(4) order by number", vec!["x", "x", "2", "3"]),
+        ("select coalesce(nullIf(number, 1), 'one') from system.numbers_mt(3) order by number", vec!["0", "one", "2"]),
+        ("select if(count(*) > 5, 'a', 'b') from system.numbers_mt(10)", vec!["a"]),
+        ("select multiIf(sum(number) > 100, 'big', sum(number) > 10, 'mid', 'small') from system.numbers_mt(10)", vec!["mid"]),
+        ("select coalesce(max(number), 0) from system.numbers_mt(10)", vec!["9"]),
+        ("select if(count(*) > 3, 'many', 'few') as c from system.numbers_mt(10) group by number % 3 order by c", vec!["few", "few", "many"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    let tests = vec![
+        (
+            "select if(number > 1, number) from system.numbers_mt(3)",
+            "Error during plan: Invalid number of arguments for function if: 2",
+        ),
+        (
+            "select if(number > 1, [number], 'a') from system.numbers_mt(3)",
+            "Error during plan: There is no common type of (List(UInt64)) and (Utf8) for if",
+        ),
+    ];
+    for (query, expect) in tests {
+        let result = Planner::new().build_from_sql(ctx.clone(), query);
+        let actual = format!("{}", result.err().unwrap());
+        assert_eq!(expect, actual, "{}", query);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_cast() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select cast(number as String) from system.numbers_mt(3) order by number", vec!["0", "1", "2"]),
+        ("select cast(cast(number as varchar) as Int8) + 1 from system.numbers_mt(3) order by number", vec!["1", "2", "3"]),
+        ("select cast(number as double) / 2 from system.numbers_mt(3) order by number", vec!["0", "0.5", "1"]),
+        ("select toFloat64(toString(number)) from system.numbers_mt(2) order by number", vec!["0", "1"]),
+        ("select toBoolean(number) from system.numbers_mt(2) order by number", vec!["false", "true"]),
+        ("select toUInt8OrNull(number * 100) from system.numbers_mt(4) order by number", vec!["0", "100", "200", "NULL"]),
+        ("select toInt32OrNull('x') from system.numbers_mt(1)", vec!["NULL"]),
+        ("select sum(cast(number as Int64)) from system.numbers_mt(4)", vec!["6"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    // The values which cannot be cast are errors when executing.
+    let query = "select toUInt8(number * 100) from system.numbers_mt(4)";
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+        let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+        let result = match executor.execute().await {
+            Ok(stream) => stream.try_collect::<Vec<_>>().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        let actual = format!("{}", result.err().unwrap());
+        assert_eq!(
+            "Internal Error: Cannot cast value 300 from UInt64 to UInt8",
+            actual
+        );
+    }
+
+    let tests = vec![
+        (
+            "select cast(number as time) from system.numbers_mt(3)",
+            "Error during plan: Unsupported data type: TIME",
+        ),
+        (
+            "select cast(number as Int128) from system.numbers_mt(3)",
+            "Error during plan: Unsupported data type: Int128",
+        ),
+    ];
+    for (query, expect) in tests {
+        let result = Planner::new().build_from_sql(ctx.clone(), query);
+        let actual = format!("{}", result.err().unwrap());
+        assert_eq!(expect, actual, "{}", query);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_operators() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        (
+            "select number from system.numbers_mt(4) where number != 2 order by number",
+            vec!["0", "1", "3"],
+        ),
+        (
+            "select number from system.numbers_mt(4) where number <> 0 order by number",
+            vec!["1", "2", "3"],
+        ),
+        (
+            "select number % 3 from system.numbers_mt(5) order by number",
+            vec!["0", "1", "2", "0", "1"],
+        ),
+        (
+            "select -number from system.numbers_mt(3) order by number",
+            vec!["0", "-1", "-2"],
+        ),
+        (
+            "select number + -1 from system.numbers_mt(3) order by number",
+            vec!["-1", "0", "1"],
+        ),
+        (
+            "select -(number * 2) from system.numbers_mt(2) order by number",
+            vec!["0", "-2"],
+        ),
+        (
+            "select number from system.numbers_mt(4) where not (number > 1) order by number",
+            vec!["0", "1"],
+        ),
+        (
+            "select not (number % 2 = 0) from system.numbers_mt(3) order by number",
+            vec!["false", "true", "false"],
+        ),
+        ("select -sum(number) from system.numbers_mt(4)", vec!["-6"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    // The zero divisor and the overflow are errors when executing.
+    let tests = vec![
+        (
+            "select number % 0 from system.numbers_mt(3)",
+            "Internal Error: Divide by zero error",
+        ),
+        (
+            "select -(toInt64(number) - 9223372036854775807 - 1) from system.numbers_mt(1)",
+            "Internal Error: The result of negate(-9223372036854775808) is overflow of Int64",
+        ),
+        (
+            "select -toInt8(-128) from system.numbers_mt(1)",
+            "Internal Error: The result of negate(-128) is overflow of Int8",
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let result = match executor.execute().await {
+                Ok(stream) => stream.try_collect::<Vec<_>>().await.map(|_| ()),
+                Err(e) => Err(e),
+            };
+            let actual = format!("{}", result.err().unwrap());
+            assert_eq!(expect, actual, "{}", query);
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_predicates() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select number from system.numbers_mt(6) where number in (1, 3, 10) order by number", vec!["1", "3"]),
+        ("select number from system.numbers_mt(4) where number not in (1, 3) order by number", vec!["0", "2"]),
+        ("select number from system.numbers_mt(4) where number not in (1, null) order by number", vec![]),
+        ("select number from system.numbers_mt(4) where number in (number + 1, 2) order by number", vec!["2"]),
+        ("select number in (1, 2) from system.numbers_mt(3) order by number", vec!["false", "true", "true"]),
+        ("select number from system.numbers_mt(6) where number between 2 and 4 order by number", vec!["2", "3", "4"]),
+        ("select number from system.numbers_mt(6) where number not between 1 and 4 order by number", vec!["0", "5"]),
+        ("select number from system.numbers_mt(20) where toString(number) like '1_' order by number limit 3", vec!["10", "11", "12"]),
+        ("select number from system.numbers_mt(12) where toString(number) not like '%1%' and number > 7 order by number", vec!["8", "9"]),
+        ("select number from system.numbers_mt(3) where ilike(if(number = 1, 'ABC', 'xyz'), 'a%') order by number", vec!["1"]),
+        ("select number from system.numbers_mt(3) where nullIf(number, 1) is null order by number", vec!["1"]),
+        ("select number from system.numbers_mt(3) where nullIf(number, 1) is not null order by number", vec!["0", "2"]),
+        ("select null is null from system.numbers_mt(1)", vec!["true"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_date() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select toYear(toDate('2021-03-04')) from system.numbers_mt(1)", vec!["2021"]),
+        ("select toDate('2021-03-04') + interval '1' day from system.numbers_mt(1)", vec!["2021-03-05"]),
+        ("select toDate('2021-03-31') - interval '1 month' from system.numbers_mt(1)", vec!["2021-02-28"]),
+        ("select toDateTime('2021-03-04 10:20:30') + interval '2' hour from system.numbers_mt(1)", vec!["2021-03-04 12:20:30"]),
+        ("select addDays(date '2021-03-04', number) from system.numbers_mt(3) order by number", vec!["2021-03-04", "2021-03-05", "2021-03-06"]),
+        ("select toStartOfInterval(toDateTime('2021-03-04 10:20:30'), interval '15' minute) from system.numbers_mt(1)", vec!["2021-03-04 10:15:00"]),
+        ("select toStartOfMonth(cast('2021-03-04 10:20:30' as timestamp)) from system.numbers_mt(1)", vec!["2021-03-01"]),
+        ("select number from system.numbers_mt(5) where addDays(toDate('2021-03-04'), number) > '2021-03-06' order by number", vec!["3", "4"]),
+        ("select max(addDays(toDate('2021-03-04'), number)) from system.numbers_mt(5)", vec!["2021-03-08"]),
+        ("select today() > toDate('2020-01-01') from system.numbers_mt(1)", vec!["true"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_decimal() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select cast('1.25' as decimal(10, 2)) + cast('2.5' as decimal(10, 1)) from system.numbers_mt(1)", vec!["3.75"]),
+        ("select cast('0.1' as decimal(10, 1)) * 3 - cast('0.3' as decimal(10, 2)) from system.numbers_mt(1)", vec!["0.00"]),
+        ("select cast('10' as decimal(10, 2)) / 3 from system.numbers_mt(1)", vec!["3.33"]),
+        ("select -cast(number as decimal(10, 2)) from system.numbers_mt(2) order by number", vec!["0.00", "-1.00"]),
+        ("select number from system.numbers_mt(5) where cast(number as decimal(10, 1)) / 2 > '1.2' order by number", vec!["3", "4"]),
+        ("select sum(cast(number as decimal(10, 2)) / 10) from system.numbers_mt(10)", vec!["4.50"]),
+        ("select max(cast(number as decimal(10, 2))) from system.numbers_mt(10)", vec!["9.00"]),
+        ("select avg(cast(number as decimal(10, 2))) from system.numbers_mt(10)", vec!["4.50"]),
+        ("select avg(cast(number as decimal(10, 3)) / 3) from system.numbers_mt(3)", vec!["0.333"]),
+        ("select avg(cast(number as decimal(10, 2))) as a from system.numbers_mt(10) group by number % 2 order by a", vec!["4.00", "5.00"]),
+        ("select avg(cast(number as decimal(10, 2))) from system.numbers_mt(10) where number > 100", vec!["NULL"]),
+        ("select toString(cast('-0.5' as decimal(4, 3))) from system.numbers_mt(1)", vec!["-0.500"]),
+        ("select cast(number as decimal(10, 2)) < 1.5 from system.numbers_mt(3) order by number", vec!["true", "true", "false"]),
+        ("select number from system.numbers_mt(3) where cast(number as decimal(10, 2)) > 0.5 order by number", vec!["1", "2"]),
+        ("select number from system.numbers_mt(3) where 1.5 >= cast(number as decimal(10, 2)) order by number", vec!["0", "1"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_null() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select number + null from system.numbers_mt(1)", vec!["NULL"]),
+        ("select nullIf(number, 1) * 2 from system.numbers_mt(3) order by number", vec!["0", "NULL", "4"]),
+        ("select nullIf(number, 1) > 0 from system.numbers_mt(3) order by number", vec!["false", "NULL", "true"]),
+        ("select nullIf(number, 1) = null from system.numbers_mt(2) order by number", vec!["NULL", "NULL"]),
+        ("select (nullIf(number, 1) > 0) and false from system.numbers_mt(3) order by number", vec!["false", "false", "false"]),
+        ("select (nullIf(number, 1) > 0) and true from system.numbers_mt(3) order by number", vec!["false", "NULL", "true"]),
+        ("select (nullIf(number, 1) > 0) or true from system.numbers_mt(3) order by number", vec!["true", "true", "true"]),
+        ("select (nullIf(number, 1) > 0) or false from system.numbers_mt(3) order by number", vec!["false", "NULL", "true"]),
+        ("select null and false from system.numbers_mt(1)", vec!["false"]),
+        ("select number from system.numbers_mt(3) where not (nullIf(number, 1) > 0) or number = 2 order by number", vec!["0", "2"]),
+        ("select count(nullIf(number, 1)) from system.numbers_mt(3)", vec!["2"]),
+        ("select count(*) from system.numbers_mt(3) where nullIf(number, 1) is null", vec!["1"]),
+        ("select count(null) from system.numbers_mt(3)", vec!["0"]),
+        ("select sum(nullIf(number, 0)) from system.numbers_mt(3)", vec!["3"]),
+        ("select min(nullIf(number, 0)) from system.numbers_mt(3)", vec!["1"]),
+        ("select sum(number) from system.numbers_mt(3) where number > 5", vec!["NULL"]),
+        ("select count(number) from system.numbers_mt(3) where number > 5", vec!["0"]),
+        ("select sum(number) + 1 from system.numbers_mt(3) where number > 5", vec!["NULL"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    // The nullable of the fields follows the arguments, count is never NULL.
+    let tests = vec![
+        (
+            "select count(number), sum(number) from system.numbers_mt(3)",
+            vec![false, true],
+        ),
+        (
+            "select nullIf(number, 1) + 1, number + 1 from system.numbers_mt(3)",
+            vec![true, false],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let actual = plan
+                .plan
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.is_nullable())
+                .collect::<Vec<_>>();
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_array() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select [1, 2, 3] from system.numbers_mt(1)", vec!["[1, 2, 3]"]),
+        ("select [number, null] from system.numbers_mt(2) order by number", vec!["[0, NULL]", "[1, NULL]"]),
+        ("select ['a', 'b'] from system.numbers_mt(1)", vec!["['a', 'b']"]),
+        ("select length([1, 2, 3]) from system.numbers_mt(1)", vec!["3"]),
+        ("select [], number from system.numbers_mt(2) order by number", vec!["[]", "[]"]),
+        ("select length([]) from system.numbers_mt(3)", vec!["0", "0", "0"]),
+        ("select length([]) + number, [1], [] from system.numbers_mt(2) order by number", vec!["0", "1"]),
+        ("select [[], [number]] from system.numbers_mt(2) order by number", vec!["[[], [0]]", "[[], [1]]"]),
+        ("select has([1, 2, 3], number) from system.numbers_mt(5) where number > 1 order by number", vec!["true", "true", "false"]),
+        ("select [10, 20, 30][2] from system.numbers_mt(1)", vec!["20"]),
+        ("select [10, 20, 30][number] from system.numbers_mt(5) order by number", vec!["NULL", "10", "20", "30", "NULL"]),
+        ("select [[1, 2], [3]][1][2] from system.numbers_mt(1)", vec!["2"]),
+        ("select [10, 20, 30][-1] from system.numbers_mt(1)", vec!["30"]),
+        ("select tuple(number, 'a') from system.numbers_mt(1)", vec!["(0, 'a')"]),
+        ("select tupleElement(tuple(number, 'a'), 2) from system.numbers_mt(1)", vec!["a"]),
+        ("select arrayJoin([1, 2, 3]) from system.numbers_mt(1)", vec!["1", "2", "3"]),
+        ("select arrayJoin([number, number * 10]) as x from system.numbers_mt(3) where number > 0 order by x", vec!["1", "2", "10", "20"]),
+        ("select groupArray(number) from system.numbers_mt(1)", vec!["[0]"]),
+        ("select groupArray(number % 2) from system.numbers_mt(8) where number = 3", vec!["[1]"]),
+        ("select groupArray(1) from system.numbers_mt(5)", vec!["[1, 1, 1, 1, 1]"]),
+        ("select groupArray(number) from system.numbers_mt(3) where number > 5", vec!["[]"]),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_string() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        ("select length('abc') from system.numbers_mt(1)", vec!["3"]),
+        (
+            "select upper(lower('aBc')) from system.numbers_mt(1)",
+            vec!["ABC"],
+        ),
+        (
+            "select concat('n', number) from system.numbers_mt(3) order by number",
+            vec!["n0", "n1", "n2"],
+        ),
+        (
+            "select substring('hello', 2, 3) from system.numbers_mt(1)",
+            vec!["ell"],
+        ),
+        ("select trim('  a  ') from system.numbers_mt(1)", vec!["a"]),
+        (
+            "select replace('a-b', '-', '+') from system.numbers_mt(1)",
+            vec!["a+b"],
+        ),
+        (
+            "select position('hello', 'l') from system.numbers_mt(1)",
+            vec!["3"],
+        ),
+        (
+            "select startsWith('hello', 'he'), endsWith('hello', 'he') from system.numbers_mt(1)",
+            vec!["true"],
+        ),
+        (
+            "select splitByChar(',', 'a,b') from system.numbers_mt(1)",
+            vec!["['a', 'b']"],
+        ),
+        (
+            "select reverse('abc') from system.numbers_mt(1)",
+            vec!["cba"],
+        ),
+        (
+            "select repeat('ab', number) from system.numbers_mt(3) order by number",
+            vec!["", "ab", "abab"],
+        ),
+        (
+            "select format('{}-{}', 'a', number) from system.numbers_mt(1)",
+            vec!["a-0"],
+        ),
+        (
+            "select lpad('7', 3, '0'), rpad('7', 3) from system.numbers_mt(1)",
+            vec!["007"],
+        ),
+        (
+            "select concat('a', null) from system.numbers_mt(1)",
+            vec!["NULL"],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_math() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        (
+            "select abs(-number) from system.numbers_mt(3) order by number",
+            vec!["0", "1", "2"],
+        ),
+        (
+            "select round(3.14159, 2), floor(2.7), ceil(2.1) from system.numbers_mt(1)",
+            vec!["3.14"],
+        ),
+        (
+            "select round(1234, -2) from system.numbers_mt(1)",
+            vec!["1200"],
+        ),
+        (
+            "select floor(-2.5), ceil(-2.5) from system.numbers_mt(1)",
+            vec!["-3"],
+        ),
+        (
+            "select sqrt(16) + pow(2, 3) from system.numbers_mt(1)",
+            vec!["12"],
+        ),
+        (
+            "select exp(0), ln(1), log2(8), log10(100) from system.numbers_mt(1)",
+            vec!["1"],
+        ),
+        ("select log2(8) from system.numbers_mt(1)", vec!["3"]),
+        (
+            "select sin(0) + cos(0) + atan(0) from system.numbers_mt(1)",
+            vec!["1"],
+        ),
+        (
+            "select intDiv(number, 2) from system.numbers_mt(5) order by number",
+            vec!["0", "0", "1", "1", "2"],
+        ),
+        (
+            "select greatest(number, 2, 1) from system.numbers_mt(4) order by number",
+            vec!["2", "2", "2", "3"],
+        ),
+        (
+            "select least(number, 2) from system.numbers_mt(4) order by number",
+            vec!["0", "1", "2", "2"],
+        ),
+        (
+            "select greatest(1, null) from system.numbers_mt(1)",
+            vec!["NULL"],
+        ),
+        (
+            "select cityHash64('abc') from system.numbers_mt(1)",
+            vec!["4220206313085259313"],
+        ),
+        (
+            "select cityHash64(number) = cityHash64(toString(number)) from system.numbers_mt(2) order by number",
+            vec!["false", "false"],
+        ),
+        (
+            "select xxHash64(''), sipHash64('') from system.numbers_mt(1)",
+            vec!["17241709254077376921"],
+        ),
+        (
+            "select md5('abc') from system.numbers_mt(1)",
+            vec!["900150983cd24fb0d6963f7d28e17f72"],
+        ),
+        (
+            "select sha256('') from system.numbers_mt(1)",
+            vec!["e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"],
+        ),
+        // The functions of the aggregates are computed over the aggregate output.
+        (
+            "select round(avg(number), 2) from system.numbers_mt(10)",
+            vec!["4.5"],
+        ),
+        (
+            "select abs(sum(number)) from system.numbers_mt(10)",
+            vec!["45"],
+        ),
+        (
+            "select toString(count(*)) from system.numbers_mt(10)",
+            vec!["10"],
+        ),
+        (
+            "select round(avg(number), 2) as a from system.numbers_mt(10) group by number % 3 order by a",
+            vec!["4", "4.5", "5"],
+        ),
+        (
+            "select abs(sum(number)) as s from system.numbers_mt(10) group by number % 2 order by s",
+            vec!["20", "25"],
+        ),
+        (
+            "select toString(count(*)) as c from system.numbers_mt(10) group by number % 3 order by c",
+            vec!["3", "3", "4"],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_statistic() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        (
+            "select avg(number) from system.numbers_mt(100)",
+            vec!["49.5"],
+        ),
+        (
+            "select varPop(number), stddevPop(number) from system.numbers_mt(100)",
+            vec!["833.25"],
+        ),
+        (
+            "select var_samp(number) from system.numbers_mt(5)",
+            vec!["2.5"],
+        ),
+        (
+            "select stddevSamp(number) from system.numbers_mt(3)",
+            vec!["1"],
+        ),
+        (
+            "select covarPop(number, number) from system.numbers_mt(100)",
+            vec!["833.25"],
+        ),
+        (
+            "select covarSamp(number, 4 - number) from system.numbers_mt(5)",
+            vec!["-2.5"],
+        ),
+        (
+            "select corr(number, number * 2 + 1) from system.numbers_mt(5)",
+            vec!["1"],
+        ),
+        (
+            "select avg(number) as a from system.numbers_mt(10) group by number % 2 order by a",
+            vec!["4", "5"],
+        ),
+        (
+            "select avg(number), varSamp(number) from system.numbers_mt(10) where number > 100",
+            vec!["NULL"],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_uniq() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let tests = vec![
+        (
+            "select uniq(number % 7), uniqExact(number % 7) from system.numbers_mt(1000)",
+            vec!["7"],
+        ),
+        (
+            "select uniqExact(number % 7, number % 3) from system.numbers_mt(1000)",
+            vec!["21"],
+        ),
+        (
+            "select approx_count_distinct(toString(number % 100)) from system.numbers_mt(1000)",
+            vec!["99"],
+        ),
+        (
+            "select uniq(number) as u from system.numbers_mt(100) group by number % 2 order by u",
+            vec!["49", "50"],
+        ),
+        (
+            "select uniq(number) from system.numbers_mt(10) where number > 100",
+            vec!["0"],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_executor_udf() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::executors::*;
+    use crate::functions::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    ctx.register_udf(ScalarUDF::create(
+        "udfTwice",
+        vec![DataType::UInt64],
+        DataType::UInt64,
+        Arc::new(|arrays| {
+            data_array_arithmetic_op(
+                DataValueArithmeticOperator::Mul,
+                &DataColumnarValue::Array(arrays[0].clone()),
+                &DataColumnarValue::Scalar(DataValue::UInt64(Some(2))),
+            )
+        }),
+    ))?;
+    // The count of the rows and the sum of the values in the state.
+    ctx.register_udaf(AggregateUDF::create(
+        "udafMean",
+        vec![DataType::Float64],
+        DataType::Float64,
+        DataValue::Struct(vec![
+            DataValue::UInt64(Some(0)),
+            DataValue::Float64(Some(0.0)),
+        ]),
+        Arc::new(|state, arrays| match state {
+            DataValue::Struct(values) => Ok(DataValue::Struct(vec![
+                data_value_arithmetic_op(
+                    DataValueArithmeticOperator::Add,
+                    values[0].clone(),
+                    DataValue::UInt64(Some(arrays[0].len() as u64)),
+                )?,
+                data_value_aggregate_op(
+                    DataValueAggregateOperator::Sum,
+                    values[1].clone(),
+                    data_array_aggregate_op(DataValueAggregateOperator::Sum, arrays[0].clone())?,
+                )?,
+            ])),
+            _ => Ok(state.clone()),
+        }),
+        Arc::new(|state, other| match (state, other) {
+            (DataValue::Struct(a), DataValue::Struct(b)) => Ok(DataValue::Struct(vec![
+                data_value_arithmetic_op(
+                    DataValueArithmeticOperator::Add,
+                    a[0].clone(),
+                    b[0].clone(),
+                )?,
+                data_value_arithmetic_op(
+                    DataValueArithmeticOperator::Add,
+                    a[1].clone(),
+                    b[1].clone(),
+                )?,
+            ])),
+            _ => Ok(state.clone()),
+        }),
+        Arc::new(|state| match state {
+            DataValue::Struct(values) => data_value_arithmetic_op(
+                DataValueArithmeticOperator::Div,
+                values[1].clone(),
+                values[0].clone(),
+            ),
+            _ => Ok(DataValue::Null),
+        }),
+    ))?;
+    let result = ctx.register_udf(ScalarUDF::create(
+        "sum",
+        vec![],
+        DataType::UInt64,
+        Arc::new(|arrays| Ok(arrays[0].clone())),
+    ));
+    assert_eq!(
+        "Internal Error: Function sum already exists",
+        format!("{}", result.err().unwrap())
+    );
+
+    let tests = vec![
+        (
+            "select udfTwice(number) from system.numbers_mt(3) order by number",
+            vec!["0", "2", "4"],
+        ),
+        (
+            "select udafMean(number) from system.numbers_mt(100)",
+            vec!["49.5"],
+        ),
+        (
+            "select udafMean(number) as m from system.numbers_mt(100) group by number % 2 order by m",
+            vec!["49", "50"],
+        ),
+        (
+            "select udfTwice(sum(number)) from system.numbers_mt(10)",
+            vec!["90"],
+        ),
+        (
+            "select udfTwice(count(*)) as c from system.numbers_mt(10) group by number % 2 order by c",
+            vec!["10", "10"],
+        ),
+        (
+            "select name from system.functions where name = 'sum' or name = 'udafMean'",
+            vec!["sum", "udafMean"],
+        ),
+        (
+            "select is_aggregate from system.functions where name = 'udafMean' or name = 'udfTwice'",
+            vec!["true", "false"],
+        ),
+        (
+            "select alias_to from system.functions where name = 'ucase'",
+            vec!["upper"],
+        ),
+    ];
+    for (query, expect) in tests {
+        if let PlanNode::Select(plan) = Planner::new().build_from_sql(ctx.clone(), query)? {
+            let executor = SelectExecutor::try_create(ctx.clone(), plan)?;
+            let stream = executor.execute().await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let mut actual: Vec<String> = vec![];
+            for row in 0..block.num_rows() {
+                actual.push(format!(
+                    "{:?}",
+                    DataValue::try_from_array(block.column(0), row)?
+                ));
+            }
+            assert_eq!(expect, actual, "{}", query);
+        } else {
+            assert!(false)
+        }
+    }
+
+    // The functions registered to the context are not visible to the other contexts.
+    let other_ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let result = Planner::new().build_from_sql(
+        other_ctx.clone(),
+        "select udfTwice(number) from system.numbers_mt(3)",
+    );
+    assert_eq!(
+        "Internal Error: Unsupported Function: udfTwice",
+        format!("{}", result.err().unwrap())
+    );
+    if let PlanNode::Select(plan) = Planner::new().build_from_sql(
+        other_ctx.clone(),
+        "select name from system.functions where name = 'udfTwice'",
+    )? {
+        let executor = SelectExecutor::try_create(other_ctx, plan)?;
+        let stream = executor.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(0, DataBlock::concat_blocks(&blocks)?.num_rows());
+    } else {
+        panic!("The query is not a select");
+    }
+    Ok(())
+}
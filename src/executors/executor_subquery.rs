@@ -0,0 +1,261 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+
+use crate::contexts::FuseQueryContext;
+use crate::datablocks::DataBlock;
+use crate::datavalues::DataValue;
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::executors::SelectExecutor;
+use crate::optimizers::Optimizer;
+use crate::planners::{
+    AggregatePlan, ExpressionPlan, FilterPlan, JoinPlan, PlanNode, PlanRewriter, PlanVisitor,
+    ProjectionPlan, SelectPlan, SortPlan, TopNPlan,
+};
+
+/// Evaluate the uncorrelated subqueries of the plan to constants,
+/// each subquery is executed as its own SelectPlan.
+/// The IN subquery is evaluated to the list of its values.
+pub struct SubqueryEvaluator;
+
+impl SubqueryEvaluator {
+    pub async fn evaluate(
+        ctx: Arc<FuseQueryContext>,
+        plan: &PlanNode,
+    ) -> FuseQueryResult<PlanNode> {
+        let mut visitor = SubqueryVisitor { subqueries: vec![] };
+        plan.accept(&mut visitor)?;
+        if visitor.subqueries.is_empty() {
+            return Ok(plan.clone());
+        }
+
+        // The subqueries are identified by their plan pointers.
+        let mut values = HashMap::new();
+        for subquery in visitor.subqueries {
+            let (plan, exists, list) = match subquery {
+                ExpressionPlan::ScalarSubquery(ref plan) => (plan.clone(), false, false),
+                ExpressionPlan::Exists(ref plan) => (plan.clone(), true, false),
+                ExpressionPlan::InSubquery { ref subquery, .. } => (subquery.clone(), false, true),
+                _ => continue,
+            };
+            let key = Arc::as_ptr(&plan) as usize;
+            if values.contains_key(&key) {
+                continue;
+            }
+
+            let select = match plan.as_ref() {
+                PlanNode::Select(v) => v.clone(),
+                other => SelectPlan {
+                    plan: Box::new(other.clone()),
+                },
+            };
+            let stream = SelectExecutor::try_create(ctx.clone(), select)?
+                .execute()
+                .await?;
+            let blocks = stream.try_collect::<Vec<_>>().await?;
+            let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+
+            let value = if exists {
+                DataValue::Boolean(Some(rows > 0))
+            } else if list {
+                let mut values = Vec::with_capacity(rows);
+                for block in &blocks {
+                    for row in 0..block.num_rows() {
+                        values.push(DataValue::try_from_array(block.column(0), row)?);
+                    }
+                }
+                let data_type = plan.schema().field(0).data_type().clone();
+                DataValue::List(Some(values), data_type)
+            } else {
+                match rows {
+                    0 => {
+                        let data_type = plan.schema().field(0).data_type().clone();
+                        let nulls = DataValue::try_into_data_array(&[DataValue::Null], &data_type)?;
+                        DataValue::try_from_array(&nulls, 0)?
+                    }
+                    1 => {
+                        let block = DataBlock::concat_blocks(&blocks)?;
+                        DataValue::try_from_array(block.column(0), 0)?
+                    }
+                    _ => {
+                        return Err(FuseQueryError::Plan(
+                            "Scalar subquery returned more than one row".to_string(),
+                        ))
+                    }
+                }
+            };
+            values.insert(key, value);
+        }
+
+        plan.rewrite(&mut SubqueryRewriter { values })
+    }
+}
+
+struct SubqueryVisitor {
+    subqueries: Vec<ExpressionPlan>,
+}
+
+impl SubqueryVisitor {
+    fn collect(&mut self, expr: &ExpressionPlan) -> FuseQueryResult<()> {
+        match expr {
+            ExpressionPlan::ScalarSubquery(_) | ExpressionPlan::Exists(_) => {
+                self.subqueries.push(expr.clone())
+            }
+            ExpressionPlan::InSubquery { expr: inner, .. } => {
+                self.subqueries.push(expr.clone());
+                self.collect(inner)?;
+            }
+            _ => {
+                for child in Optimizer::expression_plan_children(expr)? {
+                    if !matches!(child, ExpressionPlan::Wildcard) {
+                        self.collect(&child)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PlanVisitor for SubqueryVisitor {
+    fn pre_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool> {
+        let exprs = match plan {
+            PlanNode::Projection(v) => v.expr.clone(),
+            PlanNode::Filter(v) => vec![v.predicate.clone()],
+            PlanNode::Aggregate(v) => [&v.group_expr[..], &v.aggr_expr[..]].concat(),
+            PlanNode::Sort(v) => v.order_by.clone(),
+            PlanNode::TopN(v) => v.order_by.clone(),
+            PlanNode::Join(v) => {
+                v.on.iter()
+                    .flat_map(|(l, r)| vec![l.clone(), r.clone()])
+                    .collect()
+            }
+            _ => vec![],
+        };
+        for expr in &exprs {
+            self.collect(expr)?;
+        }
+        Ok(true)
+    }
+}
+
+struct SubqueryRewriter {
+    values: HashMap<usize, DataValue>,
+}
+
+impl SubqueryRewriter {
+    fn rewrite_expr(&self, expr: &ExpressionPlan) -> ExpressionPlan {
+        let rewrite = |e: &ExpressionPlan| Box::new(self.rewrite_expr(e));
+        match expr {
+            ExpressionPlan::ScalarSubquery(plan) | ExpressionPlan::Exists(plan) => {
+                match self.values.get(&(Arc::as_ptr(plan) as usize)) {
+                    Some(value) => ExpressionPlan::Constant(value.clone()),
+                    None => expr.clone(),
+                }
+            }
+            // x IN () is false and x NOT IN () is true, even if x is NULL.
+            ExpressionPlan::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => match self.values.get(&(Arc::as_ptr(subquery) as usize)) {
+                Some(DataValue::List(Some(values), _)) if values.is_empty() => {
+                    ExpressionPlan::Constant(DataValue::Boolean(Some(*negated)))
+                }
+                Some(DataValue::List(Some(values), _)) => {
+                    let mut args = vec![self.rewrite_expr(expr)];
+                    args.extend(values.iter().cloned().map(ExpressionPlan::Constant));
+                    ExpressionPlan::Function {
+                        op: if *negated { "notIn" } else { "in" }.to_string(),
+                        args,
+                        functions: None,
+                    }
+                }
+                _ => ExpressionPlan::InSubquery {
+                    expr: rewrite(expr),
+                    subquery: subquery.clone(),
+                    negated: *negated,
+                },
+            },
+            ExpressionPlan::Alias(alias, expr) => {
+                ExpressionPlan::Alias(alias.clone(), rewrite(expr))
+            }
+            ExpressionPlan::BinaryExpression { left, op, right } => {
+                ExpressionPlan::BinaryExpression {
+                    left: rewrite(left),
+                    op: op.clone(),
+                    right: rewrite(right),
+                }
+            }
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => ExpressionPlan::Function {
+                op: op.clone(),
+                args: self.rewrite_exprs(args),
+                functions: functions.clone(),
+            },
+            ExpressionPlan::Cast { expr, data_type } => ExpressionPlan::Cast {
+                expr: rewrite(expr),
+                data_type: data_type.clone(),
+            },
+            ExpressionPlan::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => ExpressionPlan::Sort {
+                expr: rewrite(expr),
+                asc: *asc,
+                nulls_first: *nulls_first,
+            },
+            other => other.clone(),
+        }
+    }
+
+    fn rewrite_exprs(&self, exprs: &[ExpressionPlan]) -> Vec<ExpressionPlan> {
+        exprs.iter().map(|e| self.rewrite_expr(e)).collect()
+    }
+}
+
+impl PlanRewriter for SubqueryRewriter {
+    fn mutate(&mut self, plan: PlanNode) -> FuseQueryResult<PlanNode> {
+        Ok(match plan {
+            PlanNode::Projection(v) => PlanNode::Projection(ProjectionPlan {
+                expr: self.rewrite_exprs(&v.expr),
+                ..v
+            }),
+            PlanNode::Filter(v) => PlanNode::Filter(FilterPlan {
+                predicate: self.rewrite_expr(&v.predicate),
+                ..v
+            }),
+            PlanNode::Aggregate(v) => PlanNode::Aggregate(AggregatePlan {
+                group_expr: self.rewrite_exprs(&v.group_expr),
+                aggr_expr: self.rewrite_exprs(&v.aggr_expr),
+                ..v
+            }),
+            PlanNode::Sort(v) => PlanNode::Sort(SortPlan {
+                order_by: self.rewrite_exprs(&v.order_by),
+                ..v
+            }),
+            PlanNode::TopN(v) => PlanNode::TopN(TopNPlan {
+                order_by: self.rewrite_exprs(&v.order_by),
+                ..v
+            }),
+            PlanNode::Join(v) => PlanNode::Join(JoinPlan {
+                on: v
+                    .on
+                    .iter()
+                    .map(|(l, r)| (self.rewrite_expr(l), self.rewrite_expr(r)))
+                    .collect(),
+                ..v
+            }),
+            other => other,
+        })
+    }
+}
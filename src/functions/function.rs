@@ -5,142 +5,167 @@
 use std::fmt;
 
 use crate::datablocks::DataBlock;
-use crate::datavalues::{DataColumnarValue, DataSchema, DataType, DataValue};
-use crate::error::FuseQueryResult;
-use crate::functions::function_logic::LogicFunction;
-use crate::functions::{
-    AggregatorFunction, AliasFunction, ArithmeticFunction, ComparisonFunction, ConstantFunction,
-    FieldFunction,
-};
-
-#[derive(Clone)]
-pub enum Function {
-    Alias(AliasFunction),
-    Constant(ConstantFunction),
-    Variable(FieldFunction),
-    Arithmetic(ArithmeticFunction),
-    Comparison(ComparisonFunction),
-    Logic(LogicFunction),
-    Aggregator(AggregatorFunction),
-}
+use crate::datavalues::{DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue};
+use crate::error::{FuseQueryError, FuseQueryResult};
 
-impl Function {
-    pub fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
-        match self {
-            Function::Alias(v) => v.return_type(input_schema),
-            Function::Constant(v) => v.return_type(input_schema),
-            Function::Variable(v) => v.return_type(input_schema),
-            Function::Arithmetic(v) => v.return_type(input_schema),
-            Function::Comparison(v) => v.return_type(input_schema),
-            Function::Logic(v) => v.return_type(input_schema),
-            Function::Aggregator(v) => v.return_type(input_schema),
-        }
-    }
-
-    pub fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
-        match self {
-            Function::Alias(v) => v.nullable(input_schema),
-            Function::Constant(v) => v.nullable(input_schema),
-            Function::Variable(v) => v.nullable(input_schema),
-            Function::Arithmetic(v) => v.nullable(input_schema),
-            Function::Comparison(v) => v.nullable(input_schema),
-            Function::Logic(v) => v.nullable(input_schema),
-            Function::Aggregator(v) => v.nullable(input_schema),
-        }
-    }
-
-    pub fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
-        match self {
-            Function::Alias(v) => v.eval(block),
-            Function::Constant(v) => v.eval(block),
-            Function::Variable(v) => v.eval(block),
-            Function::Arithmetic(v) => v.eval(block),
-            Function::Comparison(v) => v.eval(block),
-            Function::Logic(v) => v.eval(block),
-            Function::Aggregator(v) => v.eval(block),
-        }
-    }
-
-    pub fn set_depth(&mut self, depth: usize) {
-        match self {
-            Function::Alias(v) => v.set_depth(depth),
-            Function::Constant(v) => v.set_depth(depth),
-            Function::Variable(v) => v.set_depth(depth),
-            Function::Arithmetic(v) => v.set_depth(depth),
-            Function::Comparison(v) => v.set_depth(depth),
-            Function::Logic(v) => v.set_depth(depth),
-            Function::Aggregator(v) => v.set_depth(depth),
-        }
-    }
+pub type Function = Box<dyn IFunction>;
+pub type AggregateFunction = Box<dyn IAggregateFunction>;
+
+/// The function of the expression, created by the FunctionFactory.
+/// The aggregate operations are passed through the arguments to the aggregators in the tree,
+/// the aggregators are numbered by the depth which is the index of their states.
+pub trait IFunction: fmt::Display + Sync + Send + IFunctionClone {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType>;
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool>;
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue>;
+
+    fn set_depth(&mut self, _depth: usize) {}
 
     // Accumulator all the block to one state.
     // This is used in aggregation.
     // sum(state) = sum(block1) + sum(block2) ...
-    pub fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
-        match self {
-            Function::Alias(v) => v.accumulate(block),
-            Function::Constant(v) => v.accumulate(block),
-            Function::Variable(v) => v.accumulate(block),
-            Function::Arithmetic(v) => v.accumulate(block),
-            Function::Comparison(v) => v.accumulate(block),
-            Function::Logic(v) => v.accumulate(block),
-            Function::Aggregator(v) => v.accumulate(block),
-        }
+    fn accumulate(&mut self, _block: &DataBlock) -> FuseQueryResult<()> {
+        Ok(())
+    }
+
+    // Evaluate the arguments of the aggregators in the tree to the arrays of the block.
+    // This is used in GROUP BY, the arguments are evaluated once for all the groups of the block.
+    fn eval_aggregate_args(
+        &mut self,
+        _block: &DataBlock,
+        _args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        Ok(())
+    }
+
+    // Accumulate one row of the arguments from eval_aggregate_args, in the same order.
+    fn accumulate_row(
+        &mut self,
+        _args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        _row: usize,
+    ) -> FuseQueryResult<()> {
+        Ok(())
     }
 
     // Get the final state for all the accumulator.
-    pub fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
-        match self {
-            Function::Alias(v) => v.accumulate_result(),
-            Function::Constant(v) => v.accumulate_result(),
-            Function::Variable(v) => v.accumulate_result(),
-            Function::Arithmetic(v) => v.accumulate_result(),
-            Function::Comparison(v) => v.accumulate_result(),
-            Function::Logic(v) => v.accumulate_result(),
-            Function::Aggregator(v) => v.accumulate_result(),
-        }
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self
+        )))
     }
 
     // Merge partial accumulator results(state) to one.
     // merge(state) = sum(state1) + sum(state2)
     // This is used in aggregation.
-    pub fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
-        match self {
-            Function::Alias(v) => v.merge_state(states),
-            Function::Constant(v) => v.merge_state(states),
-            Function::Variable(v) => v.merge_state(states),
-            Function::Arithmetic(v) => v.merge_state(states),
-            Function::Comparison(v) => v.merge_state(states),
-            Function::Logic(v) => v.merge_state(states),
-            Function::Aggregator(v) => v.merge_state(states),
-        }
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self
+        )))
     }
 
     // Return the final result merge(state)
     // This is used in aggregation.
-    pub fn merge_result(&self) -> FuseQueryResult<DataValue> {
-        match self {
-            Function::Alias(v) => v.merge_result(),
-            Function::Constant(v) => v.merge_result(),
-            Function::Variable(v) => v.merge_result(),
-            Function::Arithmetic(v) => v.merge_result(),
-            Function::Comparison(v) => v.merge_result(),
-            Function::Logic(v) => v.merge_result(),
-            Function::Aggregator(v) => v.merge_result(),
-        }
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self
+        )))
+    }
+
+    /// The value of the constant function, None for the others.
+    fn constant_value(&self) -> Option<&DataValue> {
+        None
+    }
+
+    /// Whether the function is arrayJoin(x) which unnests the rows, maybe with an alias.
+    fn is_array_join(&self) -> bool {
+        false
+    }
+}
+
+/// The aggregate function such as sum(x), wrapped by the AggregatorFunction in the expression tree.
+/// The partial state is one value, the DataValue::Struct for the states of several values.
+pub trait IAggregateFunction: fmt::Display + Sync + Send + IAggregateFunctionClone {
+    fn return_type(&self, args: &[DataType]) -> FuseQueryResult<DataType>;
+
+    /// The result is NULL if there are no values by default.
+    fn nullable(&self, _args: &[DataType]) -> FuseQueryResult<bool> {
+        Ok(true)
+    }
+
+    /// Accumulate the columns of the arguments of the block.
+    fn accumulate(&mut self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<()>;
+
+    /// Accumulate one row of the arrays of the arguments, GROUP BY updates the states row by row.
+    fn accumulate_row(&mut self, arrays: &[DataArrayRef], row: usize) -> FuseQueryResult<()> {
+        let columns = arrays
+            .iter()
+            .map(|array| DataColumnarValue::Array(array.slice(row, 1)))
+            .collect::<Vec<_>>();
+        self.accumulate(&columns, 1)
+    }
+
+    /// The partial state of the accumulated blocks.
+    fn accumulate_result(&self) -> FuseQueryResult<DataValue>;
+
+    /// Merge the partial state from accumulate_result.
+    fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()>;
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue>;
+}
+
+/// The clone of the boxed functions, implemented for all the functions which are Clone.
+pub trait IFunctionClone {
+    fn clone_box(&self) -> Function;
+}
+
+impl<T> IFunctionClone for T
+where
+    T: 'static + IFunction + Clone,
+{
+    fn clone_box(&self) -> Function {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Function {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl fmt::Debug for dyn IFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The clone of the boxed aggregate functions, implemented for all the ones which are Clone.
+pub trait IAggregateFunctionClone {
+    fn clone_box(&self) -> AggregateFunction;
+}
+
+impl<T> IAggregateFunctionClone for T
+where
+    T: 'static + IAggregateFunction + Clone,
+{
+    fn clone_box(&self) -> AggregateFunction {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for AggregateFunction {
+    fn clone(&self) -> Self {
+        self.clone_box()
     }
 }
 
-impl fmt::Debug for Function {
+impl fmt::Debug for dyn IAggregateFunction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Function::Alias(v) => write!(f, "{}", v),
-            Function::Constant(v) => write!(f, "{}", v),
-            Function::Variable(v) => write!(f, "{}", v),
-            Function::Arithmetic(v) => write!(f, "{}", v),
-            Function::Comparison(v) => write!(f, "{}", v),
-            Function::Logic(v) => write!(f, "{}", v),
-            Function::Aggregator(v) => write!(f, "{}", v),
-        }
+        write!(f, "{}", self)
     }
 }
@@ -2,23 +2,27 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
 use crate::datablocks::DataBlock;
 use crate::datavalues;
 use crate::datavalues::{
-    DataColumnarValue, DataSchema, DataType, DataValue, DataValueAggregateOperator,
-    DataValueArithmeticOperator,
+    DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue, DataValueAggregateOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{
+    AggregateFunction, Function, FunctionFactory, IAggregateFunction, IFunction,
 };
-use crate::error::FuseQueryResult;
-use crate::functions::Function;
 
+/// The aggregator in the expression tree, which accumulates the arguments to the aggregate function.
+/// The partial state of the aggregate function is at the depth of the aggregator.
 #[derive(Clone, Debug)]
 pub struct AggregatorFunction {
     depth: usize,
-    op: DataValueAggregateOperator,
-    arg: Box<Function>,
-    state: DataValue,
+    func: AggregateFunction,
+    args: Vec<Function>,
 }
 
 impl AggregatorFunction {
@@ -26,125 +30,358 @@ impl AggregatorFunction {
         op: DataValueAggregateOperator,
         args: &[Function],
     ) -> FuseQueryResult<Function> {
-        let state = DataValue::Null;
-        Ok(Function::Aggregator(AggregatorFunction {
+        let func = OperatorAggregateFunction::try_create(op, false, args)?;
+        Self::try_create_by_aggregate(func, args)
+    }
+
+    /// The aggregator of the distinct values, such as count(DISTINCT x).
+    /// The partial state is the set of the distinct values.
+    pub fn try_create_distinct(
+        op: DataValueAggregateOperator,
+        args: &[Function],
+    ) -> FuseQueryResult<Function> {
+        let func = OperatorAggregateFunction::try_create(op, true, args)?;
+        Self::try_create_by_aggregate(func, args)
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let aggregators = vec![
+            ("count", DataValueAggregateOperator::Count, false),
+            ("min", DataValueAggregateOperator::Min, false),
+            ("max", DataValueAggregateOperator::Max, false),
+            ("sum", DataValueAggregateOperator::Sum, false),
+            ("countDistinct", DataValueAggregateOperator::Count, true),
+            ("sumDistinct", DataValueAggregateOperator::Sum, true),
+        ];
+        for (name, op, distinct) in aggregators {
+            factory.register_aggregate(
+                name,
+                Arc::new(move |_, args| {
+                    OperatorAggregateFunction::try_create(op.clone(), distinct, args)
+                }),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The aggregator of the aggregate function with the arguments.
+    pub fn try_create_by_aggregate(
+        func: AggregateFunction,
+        args: &[Function],
+    ) -> FuseQueryResult<Function> {
+        Ok(Box::new(AggregatorFunction {
             depth: 0,
-            op,
-            arg: Box::new(args[0].clone()),
-            state,
+            func,
+            args: args.to_vec(),
         }))
     }
 
-    pub fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
-        match self.op {
-            DataValueAggregateOperator::Count => Ok(DataType::UInt64),
-            _ => self.arg.return_type(input_schema),
-        }
+    fn arg_types(&self, input_schema: &DataSchema) -> FuseQueryResult<Vec<DataType>> {
+        self.args
+            .iter()
+            .map(|arg| arg.return_type(input_schema))
+            .collect()
+    }
+}
+
+impl IFunction for AggregatorFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        self.func.return_type(&self.arg_types(input_schema)?)
     }
 
-    pub fn nullable(&self, _input_schema: &DataSchema) -> FuseQueryResult<bool> {
-        Ok(false)
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        self.func.nullable(&self.arg_types(input_schema)?)
     }
 
-    pub fn set_depth(&mut self, depth: usize) {
+    fn set_depth(&mut self, depth: usize) {
         self.depth = depth;
     }
 
-    pub fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
-        self.arg.eval(block)
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        match self.args.first_mut() {
+            Some(arg) => arg.eval(block),
+            None => Ok(DataColumnarValue::Scalar(DataValue::Null)),
+        }
     }
 
-    pub fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
-        let rows = block.num_rows();
-        let val = self.arg.eval(&block)?;
-        match &self.op {
-            DataValueAggregateOperator::Count => {
-                self.state = datavalues::data_value_arithmetic_op(
-                    DataValueArithmeticOperator::Add,
-                    self.state.clone(),
-                    DataValue::UInt64(Some(rows as u64)),
-                )?;
-            }
-            DataValueAggregateOperator::Min => {
-                self.state = datavalues::data_value_aggregate_op(
-                    DataValueAggregateOperator::Min,
-                    self.state.clone(),
-                    datavalues::data_array_aggregate_op(
-                        DataValueAggregateOperator::Min,
-                        val.to_array(rows)?,
-                    )?,
-                )?;
-            }
-            DataValueAggregateOperator::Max => {
-                self.state = datavalues::data_value_aggregate_op(
-                    DataValueAggregateOperator::Max,
-                    self.state.clone(),
-                    datavalues::data_array_aggregate_op(
-                        DataValueAggregateOperator::Max,
-                        val.to_array(rows)?,
-                    )?,
-                )?;
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        let mut columns = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            columns.push(arg.eval(block)?);
+        }
+        self.func.accumulate(&columns, block.num_rows())
+    }
+
+    fn eval_aggregate_args(
+        &mut self,
+        block: &DataBlock,
+        args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            arrays.push(arg.eval(block)?.to_array(block.num_rows())?);
+        }
+        args.push(arrays);
+        Ok(())
+    }
+
+    fn accumulate_row(
+        &mut self,
+        args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        row: usize,
+    ) -> FuseQueryResult<()> {
+        let arrays = args.next().ok_or_else(|| {
+            FuseQueryError::Internal(format!("Missing the arguments of the function {}", self))
+        })?;
+        self.func.accumulate_row(arrays, row)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Ok(vec![self.func.accumulate_result()?])
+    }
+
+    fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
+        self.func.merge(&states[self.depth])
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        self.func.merge_result()
+    }
+}
+
+impl fmt::Display for AggregatorFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.func)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
             }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The count, min, max and sum of the values, maybe the distinct values.
+#[derive(Clone)]
+struct OperatorAggregateFunction {
+    op: DataValueAggregateOperator,
+    state: DataValue,
+    // The DISTINCT mode aggregates the distinct values only,
+    // the values are keyed by their serialized form, the values of several arguments are tuples.
+    distinct: bool,
+    distinct_values: HashMap<String, DataValue>,
+}
+
+impl OperatorAggregateFunction {
+    fn try_create(
+        op: DataValueAggregateOperator,
+        distinct: bool,
+        args: &[Function],
+    ) -> FuseQueryResult<AggregateFunction> {
+        if distinct
+            && !matches!(
+                op,
+                DataValueAggregateOperator::Count | DataValueAggregateOperator::Sum
+            )
+        {
+            return Err(FuseQueryError::Internal(format!(
+                "Unsupported DISTINCT aggregator: {:?}",
+                op
+            )));
+        }
+        // Only the count takes several arguments, count(DISTINCT ...) counts their tuples.
+        let max_args = match op {
+            DataValueAggregateOperator::Count => usize::MAX,
+            _ => 1,
+        };
+        if args.is_empty() || args.len() > max_args {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(OperatorAggregateFunction {
+            op,
+            state: DataValue::Null,
+            distinct,
+            distinct_values: HashMap::new(),
+        }))
+    }
+
+    /// The value of the arguments at the row, the tuple of the values if there are several arguments.
+    fn distinct_value(arrays: &[DataArrayRef], row: usize) -> FuseQueryResult<DataValue> {
+        if arrays.len() == 1 {
+            return DataValue::try_from_array(&arrays[0], row);
+        }
+        Ok(DataValue::Struct(
+            arrays
+                .iter()
+                .map(|array| DataValue::try_from_array(array, row))
+                .collect::<FuseQueryResult<Vec<_>>>()?,
+        ))
+    }
+
+    fn insert_distinct_value(&mut self, value: DataValue) -> FuseQueryResult<()> {
+        // NULLs are not aggregated, neither are the tuples with NULLs.
+        let has_null = match &value {
+            DataValue::Struct(values) => values.iter().any(|v| v.is_null()),
+            value => value.is_null(),
+        };
+        if !has_null {
+            let key = serde_json::to_string(&value)?;
+            self.distinct_values.entry(key).or_insert(value);
+        }
+        Ok(())
+    }
+}
+
+impl IAggregateFunction for OperatorAggregateFunction {
+    fn return_type(&self, args: &[DataType]) -> FuseQueryResult<DataType> {
+        let data_type = args[0].clone();
+        match self.op {
+            DataValueAggregateOperator::Count => Ok(DataType::UInt64),
+            // The decimal sum is in the decimal of the maximum precision.
             DataValueAggregateOperator::Sum => {
-                self.state = datavalues::data_value_arithmetic_op(
-                    DataValueArithmeticOperator::Add,
-                    self.state.clone(),
-                    datavalues::data_array_aggregate_op(
-                        DataValueAggregateOperator::Sum,
-                        val.to_array(rows)?,
-                    )?,
-                )?;
+                Ok(match datavalues::decimal_precision_scale(&data_type) {
+                    Some((_, scale)) => {
+                        datavalues::decimal_type(datavalues::DECIMAL_MAX_PRECISION, scale)
+                    }
+                    None => data_type,
+                })
             }
+            _ => Ok(data_type),
         }
-        Ok(())
     }
 
-    pub fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
-        Ok(vec![self.state.clone()])
+    /// The count is never NULL, the others are NULL if there are no values.
+    fn nullable(&self, _args: &[DataType]) -> FuseQueryResult<bool> {
+        Ok(!matches!(self.op, DataValueAggregateOperator::Count))
     }
 
-    pub fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
-        let val = states[self.depth].clone();
+    fn accumulate(&mut self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<()> {
+        if self.distinct {
+            let arrays = columns
+                .iter()
+                .map(|column| column.to_array(rows))
+                .collect::<FuseQueryResult<Vec<_>>>()?;
+            for row in 0..rows {
+                self.insert_distinct_value(Self::distinct_value(&arrays, row)?)?;
+            }
+            return Ok(());
+        }
+
+        let val = columns[0].clone();
+
         match &self.op {
+            // The count(x) counts the values which are not NULL, count(*) counts the rows.
             DataValueAggregateOperator::Count => {
-                self.state = datavalues::data_value_arithmetic_op(
-                    DataValueArithmeticOperator::Add,
-                    self.state.clone(),
-                    val,
-                )?;
-            }
-            DataValueAggregateOperator::Min => {
+                let count = match val {
+                    DataColumnarValue::Array(array) => array.len() - array.null_count(),
+                    DataColumnarValue::Scalar(value) if value.is_null() => 0,
+                    DataColumnarValue::Scalar(_) => rows,
+                };
                 self.state = datavalues::data_value_aggregate_op(
-                    DataValueAggregateOperator::Min,
+                    DataValueAggregateOperator::Sum,
                     self.state.clone(),
-                    val,
+                    DataValue::UInt64(Some(count as u64)),
                 )?;
             }
-            DataValueAggregateOperator::Max => {
+            op => {
                 self.state = datavalues::data_value_aggregate_op(
-                    DataValueAggregateOperator::Max,
-                    self.state.clone(),
-                    val,
-                )?;
-            }
-            DataValueAggregateOperator::Sum => {
-                self.state = datavalues::data_value_arithmetic_op(
-                    DataValueArithmeticOperator::Add,
+                    op.clone(),
                     self.state.clone(),
-                    val,
+                    datavalues::data_array_aggregate_op(op.clone(), val.to_array(rows)?)?,
                 )?;
             }
         }
         Ok(())
     }
 
-    pub fn merge_result(&self) -> FuseQueryResult<DataValue> {
+    fn accumulate_row(&mut self, arrays: &[DataArrayRef], row: usize) -> FuseQueryResult<()> {
+        if self.distinct {
+            return self.insert_distinct_value(Self::distinct_value(arrays, row)?);
+        }
+
+        let value = DataValue::try_from_array(&arrays[0], row)?;
+
+        let (op, value) = match (&self.op, value) {
+            (DataValueAggregateOperator::Count, value) => (
+                DataValueAggregateOperator::Sum,
+                DataValue::UInt64(Some(!value.is_null() as u64)),
+            ),
+            // The decimal sum is in the decimal of the maximum precision, as the sum of the arrays.
+            (DataValueAggregateOperator::Sum, DataValue::Decimal(value, _, scale)) => (
+                DataValueAggregateOperator::Sum,
+                DataValue::Decimal(value, datavalues::DECIMAL_MAX_PRECISION, scale),
+            ),
+            (op, value) => (op.clone(), value),
+        };
+        self.state = datavalues::data_value_aggregate_op(op, self.state.clone(), value)?;
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<DataValue> {
+        if self.distinct {
+            let values = self.distinct_values.values().cloned().collect();
+            return Ok(DataValue::Struct(values));
+        }
         Ok(self.state.clone())
     }
+
+    fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()> {
+        if self.distinct {
+            if let DataValue::Struct(values) = state {
+                for value in values {
+                    self.insert_distinct_value(value.clone())?;
+                }
+            }
+            return Ok(());
+        }
+
+        // The NULL states of the partitions without values are skipped.
+        let op = match self.op {
+            DataValueAggregateOperator::Count => DataValueAggregateOperator::Sum,
+            ref op => op.clone(),
+        };
+        self.state = datavalues::data_value_aggregate_op(op, self.state.clone(), state.clone())?;
+        Ok(())
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        if self.distinct {
+            return match self.op {
+                DataValueAggregateOperator::Count => {
+                    Ok(DataValue::UInt64(Some(self.distinct_values.len() as u64)))
+                }
+                _ => {
+                    let mut state = DataValue::Null;
+                    for value in self.distinct_values.values() {
+                        state = datavalues::data_value_aggregate_op(
+                            DataValueAggregateOperator::Sum,
+                            state,
+                            value.clone(),
+                        )?;
+                    }
+                    Ok(state)
+                }
+            };
+        }
+        // The count of no rows is 0, the other aggregates of no values are NULL.
+        match (&self.op, &self.state) {
+            (DataValueAggregateOperator::Count, DataValue::Null) => Ok(DataValue::UInt64(Some(0))),
+            _ => Ok(self.state.clone()),
+        }
+    }
 }
 
-impl fmt::Display for AggregatorFunction {
+impl fmt::Display for OperatorAggregateFunction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}({:?})", self.op, self.arg)
+        if self.distinct {
+            return write!(f, "{:?}Distinct", self.op);
+        }
+        write!(f, "{:?}", self.op)
     }
 }
@@ -35,6 +35,21 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
         ],
     );
 
+    let null_schema = Arc::new(DataSchema::new(vec![DataField::new(
+        "c",
+        DataType::Int64,
+        true,
+    )]));
+    let null_block = DataBlock::create(
+        null_schema,
+        vec![Arc::new(Int64Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            None,
+        ]))],
+    );
+
     let field_a = FieldFunction::try_create("a")?;
     let field_b = FieldFunction::try_create("b")?;
 
@@ -58,7 +73,7 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             evals: 2,
             args: vec![field_a.clone(), field_b.clone()],
             display: "Max(a)",
-            nullable: false,
+            nullable: true,
             func: AggregatorFunction::try_create(
                 DataValueAggregateOperator::Max,
                 &[FieldFunction::try_create("a")?],
@@ -72,7 +87,7 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             evals: 2,
             args: vec![field_a.clone(), field_b.clone()],
             display: "Min(a)",
-            nullable: false,
+            nullable: true,
             func: AggregatorFunction::try_create(
                 DataValueAggregateOperator::Min,
                 &[FieldFunction::try_create("a")?],
@@ -86,7 +101,7 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             evals: 1,
             args: vec![field_a.clone(), field_b.clone()],
             display: "Sum(a)",
-            nullable: false,
+            nullable: true,
             func: AggregatorFunction::try_create(
                 DataValueAggregateOperator::Sum,
                 &[FieldFunction::try_create("a")?],
@@ -95,12 +110,40 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             expect: DataValue::Int64(Some(10)),
             error: "",
         },
+        Test {
+            name: "count-distinct-passed",
+            evals: 2,
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "CountDistinct(a)",
+            nullable: false,
+            func: AggregatorFunction::try_create_distinct(
+                DataValueAggregateOperator::Count,
+                &[FieldFunction::try_create("a")?],
+            )?,
+            block: block.clone(),
+            expect: DataValue::UInt64(Some(4)),
+            error: "",
+        },
+        Test {
+            name: "sum-distinct-passed",
+            evals: 3,
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "SumDistinct(a)",
+            nullable: true,
+            func: AggregatorFunction::try_create_distinct(
+                DataValueAggregateOperator::Sum,
+                &[FieldFunction::try_create("a")?],
+            )?,
+            block: block.clone(),
+            expect: DataValue::Int64(Some(10)),
+            error: "",
+        },
         Test {
             name: "sum(a)+1-merge-passed",
             evals: 4,
             args: vec![field_a.clone(), field_b.clone()],
             display: "Sum(a) + 1",
-            nullable: false,
+            nullable: true,
             func: ArithmeticFunction::try_create(
                 DataValueArithmeticOperator::Add,
                 &[
@@ -120,7 +163,7 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             evals: 4,
             args: vec![field_a.clone(), field_b.clone()],
             display: "Sum(a)/Count(a)",
-            nullable: false,
+            nullable: true,
             func: ArithmeticFunction::try_create(
                 DataValueArithmeticOperator::Div,
                 &[
@@ -143,7 +186,7 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             evals: 4,
             args: vec![field_a.clone(), field_b.clone()],
             display: "Sum(a+1)+2",
-            nullable: false,
+            nullable: true,
             func: ArithmeticFunction::try_create(
                 DataValueArithmeticOperator::Add,
                 &[
@@ -164,6 +207,34 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
             expect: DataValue::Int64(Some(100)),
             error: "",
         },
+        Test {
+            name: "count-skip-nulls-passed",
+            evals: 1,
+            args: vec![],
+            display: "Count(c)",
+            nullable: false,
+            func: AggregatorFunction::try_create(
+                DataValueAggregateOperator::Count,
+                &[FieldFunction::try_create("c")?],
+            )?,
+            block: null_block.clone(),
+            expect: DataValue::UInt64(Some(2)),
+            error: "",
+        },
+        Test {
+            name: "sum-skip-nulls-passed",
+            evals: 1,
+            args: vec![],
+            display: "Sum(c)",
+            nullable: true,
+            func: AggregatorFunction::try_create(
+                DataValueAggregateOperator::Sum,
+                &[FieldFunction::try_create("c")?],
+            )?,
+            block: null_block,
+            expect: DataValue::Int64(Some(4)),
+            error: "",
+        },
     ];
 
     for t in tests {
@@ -186,7 +257,8 @@ fn test_aggregator_function() -> crate::error::FuseQueryResult<()> {
 
         let result = final_func.merge_result()?;
 
-        assert_eq!(&t.expect, &result);
+        assert_eq!(&t.expect, &result, "{}", t.name);
+        assert_eq!(t.nullable, t.func.nullable(t.block.schema())?, "{}", t.name);
     }
     Ok(())
 }
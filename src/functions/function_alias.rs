@@ -5,55 +5,77 @@
 use std::fmt;
 
 use crate::datablocks::DataBlock;
-use crate::datavalues::{DataColumnarValue, DataSchema, DataType, DataValue};
+use crate::datavalues::{DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue};
 use crate::error::FuseQueryResult;
-use crate::functions::Function;
+use crate::functions::{Function, IFunction};
 
 #[derive(Clone, Debug)]
 pub struct AliasFunction {
     depth: usize,
     alias: String,
-    func: Box<Function>,
+    func: Function,
 }
 
 impl AliasFunction {
     pub fn try_create(alias: String, func: Function) -> FuseQueryResult<Function> {
-        Ok(Function::Alias(AliasFunction {
+        Ok(Box::new(AliasFunction {
             depth: 0,
             alias,
-            func: Box::new(func),
+            func,
         }))
     }
+}
+
+impl IFunction for AliasFunction {
+    fn is_array_join(&self) -> bool {
+        self.func.is_array_join()
+    }
 
-    pub fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
         self.func.return_type(input_schema)
     }
 
-    pub fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
         self.func.nullable(input_schema)
     }
 
-    pub fn set_depth(&mut self, depth: usize) {
+    fn set_depth(&mut self, depth: usize) {
         self.depth = depth;
     }
 
-    pub fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
         self.func.eval(block)
     }
 
-    pub fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
         self.func.accumulate(block)
     }
 
-    pub fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+    fn eval_aggregate_args(
+        &mut self,
+        block: &DataBlock,
+        args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        self.func.eval_aggregate_args(block, args)
+    }
+
+    fn accumulate_row(
+        &mut self,
+        args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        row: usize,
+    ) -> FuseQueryResult<()> {
+        self.func.accumulate_row(args, row)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
         self.func.accumulate_result()
     }
 
-    pub fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
+    fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
         self.func.merge_state(states)
     }
 
-    pub fn merge_result(&self) -> FuseQueryResult<DataValue> {
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
         self.func.merge_result()
     }
 }
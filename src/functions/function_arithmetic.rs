@@ -3,21 +3,22 @@
 // Code is licensed under AGPL License, Version 3.0.
 
 use std::fmt;
+use std::sync::Arc;
 
 use crate::datablocks::DataBlock;
 use crate::datavalues;
 use crate::datavalues::{
-    DataColumnarValue, DataSchema, DataType, DataValue, DataValueArithmeticOperator,
+    DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue, DataValueArithmeticOperator,
 };
 use crate::error::FuseQueryResult;
-use crate::functions::Function;
+use crate::functions::{Function, FunctionFactory, IFunction};
 
 #[derive(Clone)]
 pub struct ArithmeticFunction {
     depth: usize,
     op: DataValueArithmeticOperator,
-    left: Box<Function>,
-    right: Box<Function>,
+    left: Function,
+    right: Function,
 }
 
 impl ArithmeticFunction {
@@ -25,15 +26,34 @@ impl ArithmeticFunction {
         op: DataValueArithmeticOperator,
         args: &[Function],
     ) -> FuseQueryResult<Function> {
-        Ok(Function::Arithmetic(ArithmeticFunction {
+        Ok(Box::new(ArithmeticFunction {
             depth: 0,
             op,
-            left: Box::from(args[0].clone()),
-            right: Box::from(args[1].clone()),
+            left: args[0].clone(),
+            right: args[1].clone(),
         }))
     }
 
-    pub fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("+", DataValueArithmeticOperator::Add),
+            ("-", DataValueArithmeticOperator::Sub),
+            ("*", DataValueArithmeticOperator::Mul),
+            ("/", DataValueArithmeticOperator::Div),
+            ("%", DataValueArithmeticOperator::Modulo),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl IFunction for ArithmeticFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
         datavalues::numerical_coercion(
             format!("{}", self.op).as_str(),
             &self.left.return_type(input_schema)?,
@@ -41,32 +61,53 @@ impl ArithmeticFunction {
         )
     }
 
-    pub fn nullable(&self, _input_schema: &DataSchema) -> FuseQueryResult<bool> {
-        Ok(false)
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        Ok(self.left.nullable(input_schema)? || self.right.nullable(input_schema)?)
     }
 
-    pub fn set_depth(&mut self, depth: usize) {
+    fn set_depth(&mut self, depth: usize) {
         self.left.set_depth(depth);
         self.right.set_depth(depth + 1);
         self.depth = depth;
     }
 
-    pub fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
-        Ok(DataColumnarValue::Array(
-            datavalues::data_array_arithmetic_op(
-                self.op.clone(),
-                &self.left.eval(block)?,
-                &self.right.eval(block)?,
-            )?,
-        ))
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let left = self.left.eval(block)?;
+        let right = self.right.eval(block)?;
+        let result = datavalues::data_array_arithmetic_op(self.op.clone(), &left, &right)?;
+        // The constants are computed once, the result is the constant of all the rows.
+        match (left, right) {
+            (DataColumnarValue::Scalar(_), DataColumnarValue::Scalar(_)) => Ok(
+                DataColumnarValue::Scalar(DataValue::try_from_array(&result, 0)?),
+            ),
+            _ => Ok(DataColumnarValue::Array(result)),
+        }
     }
 
-    pub fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
         self.left.accumulate(&block)?;
         self.right.accumulate(&block)
     }
 
-    pub fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+    fn eval_aggregate_args(
+        &mut self,
+        block: &DataBlock,
+        args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        self.left.eval_aggregate_args(block, args)?;
+        self.right.eval_aggregate_args(block, args)
+    }
+
+    fn accumulate_row(
+        &mut self,
+        args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        row: usize,
+    ) -> FuseQueryResult<()> {
+        self.left.accumulate_row(args, row)?;
+        self.right.accumulate_row(args, row)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
         Ok([
             &self.left.accumulate_result()?[..],
             &self.right.accumulate_result()?[..],
@@ -74,12 +115,12 @@ impl ArithmeticFunction {
         .concat())
     }
 
-    pub fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
+    fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
         self.left.merge_state(states)?;
         self.right.merge_state(states)
     }
 
-    pub fn merge_result(&self) -> FuseQueryResult<DataValue> {
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
         datavalues::data_value_arithmetic_op(
             self.op.clone(),
             self.left.merge_result()?,
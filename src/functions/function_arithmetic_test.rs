@@ -124,6 +124,24 @@ fn test_arithmetic_function() -> crate::error::FuseQueryResult<()> {
             expect: Arc::new(Int64Array::from(vec![4, 1, 0])),
             error: "",
         },
+        Test {
+            name: "modulo-int64-passed",
+            evals: 2,
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "a % b",
+            nullable: false,
+            op: DataValueArithmeticOperator::Modulo,
+            block: DataBlock::create(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from(vec![4, 3, 2])),
+                    Arc::new(Int64Array::from(vec![1, 2, 3])),
+                    Arc::new(Int16Array::from(vec![1, 2, 3])),
+                ],
+            ),
+            expect: Arc::new(Int64Array::from(vec![0, 1, 2])),
+            error: "",
+        },
     ];
 
     for t in tests {
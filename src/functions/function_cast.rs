@@ -0,0 +1,187 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// Cast the argument to the data type: CAST(x AS type), toX(x) and toXOrNull(x).
+/// The values which cannot be cast are errors, except the OrNull variants return NULLs.
+#[derive(Clone)]
+pub struct CastFunction {
+    depth: usize,
+    /// The function name, None is CAST(x AS type)
+    name: Option<String>,
+    func: Function,
+    data_type: DataType,
+    or_null: bool,
+}
+
+impl CastFunction {
+    pub fn try_create(func: Function, data_type: DataType) -> FuseQueryResult<Function> {
+        Ok(Box::new(CastFunction {
+            depth: 0,
+            name: None,
+            func,
+            data_type,
+            or_null: false,
+        }))
+    }
+
+    /// Register the toX(x) and toXOrNull(x) functions of the data types.
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let type_names = vec![
+            "Boolean",
+            "Int8",
+            "Int16",
+            "Int32",
+            "Int64",
+            "UInt8",
+            "UInt16",
+            "UInt32",
+            "UInt64",
+            "Float32",
+            "Float64",
+            "String",
+            "Date",
+            "DateTime",
+            "Timestamp",
+        ];
+        for type_name in type_names {
+            let data_type = Self::data_type_from_name(type_name).ok_or_else(|| {
+                FuseQueryError::Internal(format!("Unsupported data type: {}", type_name))
+            })?;
+            for (suffix, or_null) in [("", false), ("OrNull", true)] {
+                let data_type = data_type.clone();
+                factory.register(
+                    &format!("to{}{}", type_name, suffix),
+                    Arc::new(move |name, args| {
+                        Self::try_create_by_name(name, args, data_type.clone(), or_null)
+                    }),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // The toX(x) or toXOrNull(x) function, the name is the one which is called.
+    fn try_create_by_name(
+        name: &str,
+        args: &[Function],
+        data_type: DataType,
+        or_null: bool,
+    ) -> FuseQueryResult<Function> {
+        if args.len() != 1 {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                name,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(CastFunction {
+            depth: 0,
+            name: Some(name.to_string()),
+            func: args[0].clone(),
+            data_type,
+            or_null,
+        }))
+    }
+
+    /// The data type of the name, such as Int32, UInt64, Float64, String, Boolean and Date.
+    pub fn data_type_from_name(name: &str) -> Option<DataType> {
+        Some(match name.to_lowercase().as_str() {
+            "boolean" => DataType::Boolean,
+            "int8" => DataType::Int8,
+            "int16" => DataType::Int16,
+            "int32" => DataType::Int32,
+            "int64" => DataType::Int64,
+            "uint8" => DataType::UInt8,
+            "uint16" => DataType::UInt16,
+            "uint32" => DataType::UInt32,
+            "uint64" => DataType::UInt64,
+            "float32" => DataType::Float32,
+            "float64" => DataType::Float64,
+            "string" => DataType::Utf8,
+            "date" => datavalues::DATE_TYPE,
+            "datetime" | "timestamp" => datavalues::TIMESTAMP_TYPE,
+            _ => return None,
+        })
+    }
+}
+
+impl IFunction for CastFunction {
+    fn return_type(&self, _input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        Ok(self.data_type.clone())
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        Ok(self.or_null || self.func.nullable(input_schema)?)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let array = self.func.eval(block)?.to_array(block.num_rows())?;
+        let result = if self.or_null {
+            datavalues::data_array_cast(&array, &self.data_type)?
+        } else {
+            datavalues::data_array_try_cast(&array, &self.data_type)?
+        };
+        Ok(DataColumnarValue::Array(result))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        self.func.accumulate(block)
+    }
+
+    fn eval_aggregate_args(
+        &mut self,
+        block: &DataBlock,
+        args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        self.func.eval_aggregate_args(block, args)
+    }
+
+    fn accumulate_row(
+        &mut self,
+        args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        row: usize,
+    ) -> FuseQueryResult<()> {
+        self.func.accumulate_row(args, row)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        self.func.accumulate_result()
+    }
+
+    fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
+        self.func.merge_state(states)
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        self.func.merge_result()
+    }
+}
+
+impl fmt::Display for CastFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(ref name) => write!(f, "{}({:?})", name, self.func),
+            None => write!(
+                f,
+                "cast({:?} as {})",
+                self.func,
+                datavalues::data_type_name(&self.data_type)
+            ),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_cast_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 200, 300])),
+            Arc::new(StringArray::from(vec!["1", "2.5", "x"])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "cast-passed",
+            func: CastFunction::try_create(field_a.clone(), DataType::Utf8)?,
+            display: "cast(a as Utf8)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(StringArray::from(vec!["1", "200", "300"])),
+            error: "",
+        },
+        Test {
+            name: "cast-decimal-passed",
+            func: CastFunction::try_create(field_a.clone(), decimal_type(10, 2))?,
+            display: "cast(a as Decimal(10, 2))",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(DecimalArray::from_vec(
+                vec![100, 20000, 30000],
+                Some(Arc::new("Decimal(10, 2)".to_string())),
+            )),
+            error: "",
+        },
+        Test {
+            name: "to-int16-passed",
+            func: FunctionFactory::get("toInt16", std::slice::from_ref(&field_a))?,
+            display: "toInt16(a)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(Int16Array::from(vec![1, 200, 300])),
+            error: "",
+        },
+        Test {
+            name: "to-int8-or-null-passed",
+            func: FunctionFactory::get("toInt8OrNull", std::slice::from_ref(&field_a))?,
+            display: "toInt8OrNull(a)",
+            nullable: true,
+            block: block.clone(),
+            expect: Arc::new(Int8Array::from(vec![Some(1), None, None])),
+            error: "",
+        },
+        Test {
+            name: "to-float64-or-null-passed",
+            func: FunctionFactory::get("toFloat64OrNull", std::slice::from_ref(&field_s))?,
+            display: "toFloat64OrNull(s)",
+            nullable: true,
+            block: block.clone(),
+            expect: Arc::new(Float64Array::from(vec![Some(1.0), Some(2.5), None])),
+            error: "",
+        },
+        Test {
+            name: "to-uint8-overflow-error",
+            func: FunctionFactory::get("toUInt8", std::slice::from_ref(&field_a))?,
+            display: "toUInt8(a)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(UInt8Array::from(vec![1, 200, 0])),
+            error: "Internal Error: Cannot cast value 300 from Int64 to UInt8",
+        },
+        Test {
+            name: "to-float64-parse-error",
+            func: FunctionFactory::get("toFloat64", std::slice::from_ref(&field_s))?,
+            display: "toFloat64(s)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(Float64Array::from(vec![1.0, 2.5, 0.0])),
+            error: "Internal Error: Cannot cast value x from Utf8 to Float64",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let ref v = func.eval(&t.block)?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(expect_type, actual_type);
+
+        // Result check.
+        if !v.to_array(t.block.num_rows())?.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+
+    // Arguments check.
+    let result = FunctionFactory::get("toString", &[field_a.clone(), field_s.clone()]);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: Invalid number of arguments for function toString: 2",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
@@ -3,6 +3,7 @@
 // Code is licensed under AGPL License, Version 3.0.
 
 use std::fmt;
+use std::sync::Arc;
 
 use crate::datablocks::DataBlock;
 use crate::datavalues;
@@ -11,14 +12,14 @@ use crate::datavalues::{
 };
 use crate::error::{FuseQueryError, FuseQueryResult};
 
-use crate::functions::Function;
+use crate::functions::{Function, FunctionFactory, IFunction};
 
 #[derive(Clone)]
 pub struct ComparisonFunction {
     depth: usize,
     op: DataValueComparisonOperator,
-    left: Box<Function>,
-    right: Box<Function>,
+    left: Function,
+    right: Function,
     saved: Option<DataColumnarValue>,
 }
 
@@ -27,57 +28,79 @@ impl ComparisonFunction {
         op: DataValueComparisonOperator,
         args: &[Function],
     ) -> FuseQueryResult<Function> {
-        Ok(Function::Comparison(ComparisonFunction {
+        Ok(Box::new(ComparisonFunction {
             depth: 0,
             op,
-            left: Box::from(args[0].clone()),
-            right: Box::from(args[1].clone()),
+            left: args[0].clone(),
+            right: args[1].clone(),
             saved: None,
         }))
     }
 
-    pub fn return_type(&self, _input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("=", DataValueComparisonOperator::Eq),
+            ("!=", DataValueComparisonOperator::NotEq),
+            ("<", DataValueComparisonOperator::Lt),
+            (">", DataValueComparisonOperator::Gt),
+            ("<=", DataValueComparisonOperator::LtEq),
+            (">=", DataValueComparisonOperator::GtEq),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        factory.register_alias("<>", "!=")
+    }
+}
+
+impl IFunction for ComparisonFunction {
+    fn return_type(&self, _input_schema: &DataSchema) -> FuseQueryResult<DataType> {
         Ok(DataType::Boolean)
     }
 
-    pub fn nullable(&self, _input_schema: &DataSchema) -> FuseQueryResult<bool> {
-        Ok(false)
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        Ok(self.left.nullable(input_schema)? || self.right.nullable(input_schema)?)
     }
 
-    pub fn set_depth(&mut self, depth: usize) {
+    fn set_depth(&mut self, depth: usize) {
         self.depth = depth;
     }
 
-    pub fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
-        Ok(DataColumnarValue::Array(
-            datavalues::data_array_comparison_op(
-                self.op.clone(),
-                &self.left.eval(block)?,
-                &self.right.eval(block)?,
-            )?,
-        ))
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let left = self.left.eval(block)?;
+        let right = self.right.eval(block)?;
+        let result = datavalues::data_array_comparison_op(self.op.clone(), &left, &right)?;
+        match (left, right) {
+            (DataColumnarValue::Scalar(_), DataColumnarValue::Scalar(_)) => Ok(
+                DataColumnarValue::Scalar(DataValue::try_from_array(&result, 0)?),
+            ),
+            _ => Ok(DataColumnarValue::Array(result)),
+        }
     }
 
-    pub fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
         self.left.accumulate(block)?;
         self.right.accumulate(block)
     }
 
-    pub fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
         Err(FuseQueryError::Internal(format!(
             "Unsupported aggregate operation for function {}",
             self.op
         )))
     }
 
-    pub fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
         Err(FuseQueryError::Internal(format!(
             "Unsupported aggregate operation for function {}",
             self.op
         )))
     }
 
-    pub fn merge_result(&self) -> FuseQueryResult<DataValue> {
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
         Err(FuseQueryError::Internal(format!(
             "Unsupported aggregate operation for function {}",
             self.op
@@ -31,23 +31,42 @@ fn test_comparison_function() -> crate::error::FuseQueryResult<()> {
     let field_a = FieldFunction::try_create("a").unwrap();
     let field_b = FieldFunction::try_create("b").unwrap();
 
-    let tests = vec![Test {
-        name: "equal-passed",
-        func_name: "EqualFunction",
-        args: vec![field_a.clone(), field_b.clone()],
-        display: "a = b",
-        nullable: false,
-        op: DataValueComparisonOperator::Eq,
-        block: DataBlock::create(
-            schema.clone(),
-            vec![
-                Arc::new(Int64Array::from(vec![4, 3, 2, 4])),
-                Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
-            ],
-        ),
-        expect: Arc::new(BooleanArray::from(vec![false, false, false, true])),
-        error: "",
-    }];
+    let tests = vec![
+        Test {
+            name: "equal-passed",
+            func_name: "EqualFunction",
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "a = b",
+            nullable: false,
+            op: DataValueComparisonOperator::Eq,
+            block: DataBlock::create(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from(vec![4, 3, 2, 4])),
+                    Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+                ],
+            ),
+            expect: Arc::new(BooleanArray::from(vec![false, false, false, true])),
+            error: "",
+        },
+        Test {
+            name: "not-equal-passed",
+            func_name: "NotEqualFunction",
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "a != b",
+            nullable: false,
+            op: DataValueComparisonOperator::NotEq,
+            block: DataBlock::create(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from(vec![4, 3, 2, 4])),
+                    Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+                ],
+            ),
+            expect: Arc::new(BooleanArray::from(vec![true, true, true, false])),
+            error: "",
+        },
+    ];
 
     for t in tests {
         let mut func =
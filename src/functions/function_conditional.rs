@@ -0,0 +1,217 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    BooleanArray, DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue,
+    DataValueComparisonOperator, DataValueConditionalOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The conditional functions select the rows between the argument arrays by the boolean masks:
+/// if(cond, a, b), multiIf(cond1, a1, cond2, a2, ..., else), coalesce(a, b, ...),
+/// nullIf(a, b) and ifNull(a, b).
+#[derive(Clone)]
+pub struct ConditionalFunction {
+    depth: usize,
+    op: DataValueConditionalOperator,
+    args: Vec<Function>,
+}
+
+impl ConditionalFunction {
+    pub fn try_create(
+        op: DataValueConditionalOperator,
+        args: &[Function],
+    ) -> FuseQueryResult<Function> {
+        let valid = match op {
+            DataValueConditionalOperator::If => args.len() == 3,
+            DataValueConditionalOperator::MultiIf => args.len() >= 3 && args.len() % 2 == 1,
+            DataValueConditionalOperator::Coalesce => !args.is_empty(),
+            DataValueConditionalOperator::NullIf | DataValueConditionalOperator::IfNull => {
+                args.len() == 2
+            }
+        };
+        if !valid {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(ConditionalFunction {
+            depth: 0,
+            op,
+            args: args.to_vec(),
+        }))
+    }
+
+    /// The arguments which may be the result.
+    fn result_args(&self) -> Vec<&Function> {
+        let last = self.args.len() - 1;
+        match self.op {
+            DataValueConditionalOperator::If | DataValueConditionalOperator::MultiIf => self
+                .args
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i % 2 == 1 || *i == last)
+                .map(|(_, arg)| arg)
+                .collect(),
+            DataValueConditionalOperator::Coalesce | DataValueConditionalOperator::IfNull => {
+                self.args.iter().collect()
+            }
+            DataValueConditionalOperator::NullIf => vec![&self.args[0]],
+        }
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("if", DataValueConditionalOperator::If),
+            ("multiIf", DataValueConditionalOperator::MultiIf),
+            ("coalesce", DataValueConditionalOperator::Coalesce),
+            ("nullIf", DataValueConditionalOperator::NullIf),
+            ("ifNull", DataValueConditionalOperator::IfNull),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl IFunction for ConditionalFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        let mut data_type = DataType::Null;
+        for arg in self.result_args() {
+            data_type = datavalues::conditional_coercion(
+                format!("{}", self.op).as_str(),
+                &data_type,
+                &arg.return_type(input_schema)?,
+            )?;
+        }
+        Ok(data_type)
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        let mut nullables = Vec::with_capacity(self.args.len());
+        for arg in self.result_args() {
+            nullables.push(arg.nullable(input_schema)?);
+        }
+        Ok(match self.op {
+            DataValueConditionalOperator::NullIf => true,
+            DataValueConditionalOperator::Coalesce | DataValueConditionalOperator::IfNull => {
+                nullables.iter().all(|nullable| *nullable)
+            }
+            _ => nullables.iter().any(|nullable| *nullable),
+        })
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let rows = block.num_rows();
+        let data_type = self.return_type(block.schema())?;
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in self.args.iter_mut() {
+            arrays.push(arg.eval(block)?.to_array(rows)?);
+        }
+
+        let last = arrays.len() - 1;
+        let mut result = datavalues::data_array_cast(&arrays[last], &data_type)?;
+        match self.op {
+            // Select from the last condition to the first.
+            DataValueConditionalOperator::If | DataValueConditionalOperator::MultiIf => {
+                for i in (0..last).step_by(2).rev() {
+                    let mask = match arrays[i].data_type() {
+                        DataType::Boolean => arrays[i].clone(),
+                        DataType::Null => {
+                            Arc::new(BooleanArray::from(vec![false; rows])) as DataArrayRef
+                        }
+                        other => {
+                            return Err(FuseQueryError::Internal(format!(
+                                "The condition of function {} must be Boolean, but got {:?}",
+                                self.op, other
+                            )))
+                        }
+                    };
+                    result =
+                        datavalues::data_array_select(&mask, &arrays[i + 1], &result, &data_type)?;
+                }
+            }
+            // Select the first non-NULL value from the last argument to the first.
+            DataValueConditionalOperator::Coalesce | DataValueConditionalOperator::IfNull => {
+                for array in arrays[..last].iter().rev() {
+                    // The NullArray has no null bitmap, it never has a non-NULL value.
+                    if array.data_type() == &DataType::Null {
+                        continue;
+                    }
+                    let mask = Arc::new(arrow::compute::is_not_null(array)?) as DataArrayRef;
+                    result = datavalues::data_array_select(&mask, array, &result, &data_type)?;
+                }
+            }
+            DataValueConditionalOperator::NullIf => {
+                let mask = datavalues::data_array_comparison_op(
+                    DataValueComparisonOperator::Eq,
+                    &DataColumnarValue::Array(arrays[0].clone()),
+                    &DataColumnarValue::Array(arrays[1].clone()),
+                )?;
+                let nulls =
+                    DataValue::try_into_data_array(&vec![DataValue::Null; rows], &data_type)?;
+                result = datavalues::data_array_select(&mask, &nulls, &arrays[0], &data_type)?;
+            }
+        }
+        Ok(DataColumnarValue::Array(result))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in self.args.iter_mut() {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+}
+
+impl fmt::Display for ConditionalFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.op)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_conditional_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        args: Vec<Function>,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: DataArrayRef,
+        error: &'static str,
+        op: DataValueConditionalOperator,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("c", DataType::Boolean, false),
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Int64, false),
+    ]));
+
+    let field_c = FieldFunction::try_create("c")?;
+    let field_a = FieldFunction::try_create("a")?;
+    let field_b = FieldFunction::try_create("b")?;
+    let null = ConstantFunction::try_create(DataValue::Null)?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(BooleanArray::from(vec![true, false, true, false])),
+            Arc::new(Int64Array::from(vec![Some(1), None, Some(3), Some(4)])),
+            Arc::new(Int64Array::from(vec![1, 2, 5, 6])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "if-passed",
+            args: vec![field_c.clone(), field_a.clone(), field_b.clone()],
+            display: "if(c, a, b)",
+            nullable: true,
+            op: DataValueConditionalOperator::If,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![Some(1), Some(2), Some(3), Some(6)])),
+            error: "",
+        },
+        Test {
+            name: "multiif-passed",
+            args: vec![
+                field_c.clone(),
+                field_b.clone(),
+                field_c.clone(),
+                field_a.clone(),
+                null.clone(),
+            ],
+            display: "multiIf(c, b, c, a, Null)",
+            nullable: true,
+            op: DataValueConditionalOperator::MultiIf,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![Some(1), None, Some(5), None])),
+            error: "",
+        },
+        Test {
+            name: "coalesce-passed",
+            args: vec![null.clone(), field_a.clone(), field_b.clone()],
+            display: "coalesce(Null, a, b)",
+            nullable: false,
+            op: DataValueConditionalOperator::Coalesce,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            error: "",
+        },
+        Test {
+            name: "nullif-passed",
+            args: vec![field_b.clone(), field_a.clone()],
+            display: "nullIf(b, a)",
+            nullable: true,
+            op: DataValueConditionalOperator::NullIf,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![None, Some(2), Some(5), Some(6)])),
+            error: "",
+        },
+        Test {
+            name: "ifnull-passed",
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "ifNull(a, b)",
+            nullable: false,
+            op: DataValueConditionalOperator::IfNull,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            error: "",
+        },
+        Test {
+            name: "if-condition-error",
+            args: vec![field_a.clone(), field_a.clone(), field_b.clone()],
+            display: "if(a, a, b)",
+            nullable: true,
+            op: DataValueConditionalOperator::If,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            error: "Internal Error: The condition of function if must be Boolean, but got Int64",
+        },
+    ];
+
+    for t in tests {
+        let mut func = ConditionalFunction::try_create(t.op, &t.args)?;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string());
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let ref v = func.eval(&t.block)?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(expect_type, actual_type);
+
+        // Result check.
+        if !v.to_array(t.block.num_rows())?.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+
+    // Arguments check.
+    let result = ConditionalFunction::try_create(
+        DataValueConditionalOperator::If,
+        &[field_c.clone(), field_a.clone()],
+    );
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: Invalid number of arguments for function if: 2",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
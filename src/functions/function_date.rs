@@ -0,0 +1,229 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    DataColumnarValue, DataSchema, DataType, DataValue, DataValueDateOperator,
+    DataValueIntervalUnit,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The date functions: toYear(t), toStartOfDay(t), toStartOfInterval(t, INTERVAL n unit),
+/// addDays(t, n), subtractHours(t, n), now() and today().
+/// The now() and today() are the time when the function is created.
+#[derive(Clone)]
+pub struct DateFunction {
+    depth: usize,
+    op: DataValueDateOperator,
+    args: Vec<Function>,
+    now: i64,
+}
+
+impl DateFunction {
+    pub fn try_create(op: DataValueDateOperator, args: &[Function]) -> FuseQueryResult<Function> {
+        let expect = match op {
+            DataValueDateOperator::Now | DataValueDateOperator::Today => 0,
+            DataValueDateOperator::AddInterval(_) | DataValueDateOperator::SubtractInterval(_) => 2,
+            _ => 1,
+        };
+        if args.len() != expect {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(DateFunction {
+            depth: 0,
+            op,
+            args: args.to_vec(),
+            now: chrono::Utc::now().timestamp(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("toYear", DataValueDateOperator::ToYear),
+            ("toMonth", DataValueDateOperator::ToMonth),
+            ("toDayOfMonth", DataValueDateOperator::ToDayOfMonth),
+            ("toDayOfWeek", DataValueDateOperator::ToDayOfWeek),
+            ("toHour", DataValueDateOperator::ToHour),
+            ("toMinute", DataValueDateOperator::ToMinute),
+            ("toSecond", DataValueDateOperator::ToSecond),
+            ("toStartOfDay", DataValueDateOperator::ToStartOfDay),
+            ("toStartOfMonth", DataValueDateOperator::ToStartOfMonth),
+            ("toStartOfYear", DataValueDateOperator::ToStartOfYear),
+            ("now", DataValueDateOperator::Now),
+            ("today", DataValueDateOperator::Today),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        factory.register(
+            "toStartOfInterval",
+            Arc::new(|_, args| Self::try_create_start_of_interval(args)),
+        )?;
+
+        // The addDays(t, n) and subtractDays(t, n) of the units, the singular names are the aliases.
+        let units = vec![
+            DataValueIntervalUnit::Year,
+            DataValueIntervalUnit::Month,
+            DataValueIntervalUnit::Day,
+            DataValueIntervalUnit::Hour,
+            DataValueIntervalUnit::Minute,
+            DataValueIntervalUnit::Second,
+        ];
+        for unit in units {
+            for prefix in ["add", "subtract"] {
+                let name = format!("{}{}s", prefix, unit);
+                let op = match prefix {
+                    "add" => DataValueDateOperator::AddInterval(unit.clone()),
+                    _ => DataValueDateOperator::SubtractInterval(unit.clone()),
+                };
+                factory.register(
+                    &name,
+                    Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+                )?;
+                factory.register_alias(&format!("{}{}", prefix, unit), &name)?;
+            }
+        }
+        Ok(())
+    }
+
+    // The toStartOfInterval(t, n, unit), the n and the unit are constants.
+    fn try_create_start_of_interval(args: &[Function]) -> FuseQueryResult<Function> {
+        if args.len() != 3 {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function toStartOfInterval: {}",
+                args.len()
+            )));
+        }
+
+        let n = match args[1].constant_value() {
+            Some(DataValue::Int64(Some(n))) => Some(*n),
+            Some(DataValue::UInt64(Some(n))) => Some(*n as i64),
+            _ => None,
+        };
+        let unit = match args[2].constant_value() {
+            Some(DataValue::String(Some(unit))) => DataValueIntervalUnit::from_name(unit),
+            _ => None,
+        };
+        match (n, unit) {
+            (Some(n), Some(unit)) => Self::try_create(
+                DataValueDateOperator::ToStartOfInterval(n, unit),
+                std::slice::from_ref(&args[0]),
+            ),
+            _ => Err(FuseQueryError::Plan(format!(
+                "The interval of function toStartOfInterval must be a constant interval, but got {:?}, {:?}",
+                args[1], args[2]
+            ))),
+        }
+    }
+}
+
+impl IFunction for DateFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        match self.op {
+            DataValueDateOperator::Now | DataValueDateOperator::Today => {
+                datavalues::date_coercion(&self.op, &DataType::Null)
+            }
+            _ => datavalues::date_coercion(&self.op, &self.args[0].return_type(input_schema)?),
+        }
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        for arg in &self.args {
+            if arg.nullable(input_schema)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        match self.op {
+            DataValueDateOperator::Now => {
+                return Ok(DataColumnarValue::Scalar(DataValue::Timestamp(Some(
+                    self.now,
+                ))))
+            }
+            DataValueDateOperator::Today => {
+                return Ok(DataColumnarValue::Scalar(DataValue::Date32(Some(
+                    self.now.div_euclid(86400) as i32,
+                ))))
+            }
+            _ => {}
+        }
+
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            arrays.push(arg.eval(block)?.to_array(block.num_rows())?);
+        }
+        Ok(DataColumnarValue::Array(datavalues::data_array_date_op(
+            self.op.clone(),
+            &arrays,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+}
+
+impl fmt::Display for DateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.op {
+            DataValueDateOperator::ToStartOfInterval(n, ref unit) => {
+                write!(f, "{}({:?}, {} {})", self.op, self.args[0], n, unit)
+            }
+            _ => {
+                write!(f, "{}(", self.op)?;
+                for (i, arg) in self.args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_date_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("d", DATE_TYPE, false),
+        DataField::new("t", TIMESTAMP_TYPE, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_d = FieldFunction::try_create("d")?;
+    let field_t = FieldFunction::try_create("t")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(Date32Array::from(vec![18690, 18658])),
+            Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614853230), Some(0)],
+                None,
+            )),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "to-month-passed",
+            func: FunctionFactory::get("toMonth", &[field_d.clone()])?,
+            display: "toMonth(d)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(UInt8Array::from(vec![3, 1])),
+            error: "",
+        },
+        Test {
+            name: "to-start-of-interval-passed",
+            func: FunctionFactory::get(
+                "toStartOfInterval",
+                &[
+                    field_t.clone(),
+                    ConstantFunction::try_create(DataValue::Int64(Some(2)))?,
+                    ConstantFunction::try_create(DataValue::String(Some("hour".to_string())))?,
+                ],
+            )?,
+            display: "toStartOfInterval(t, 2 Hour)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614816000 + 7200 * 5), Some(0)],
+                None,
+            )),
+            error: "",
+        },
+        Test {
+            name: "add-days-passed",
+            func: FunctionFactory::get("addDays", &[field_d.clone(), field_a.clone()])?,
+            display: "addDays(d, a)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(Date32Array::from(vec![18691, 18660])),
+            error: "",
+        },
+        Test {
+            name: "subtract-seconds-passed",
+            func: FunctionFactory::get("subtractSeconds", &[field_t.clone(), field_a.clone()])?,
+            display: "subtractSeconds(t, a)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(TimestampSecondArray::from_opt_vec(
+                vec![Some(1614853229), Some(-2)],
+                None,
+            )),
+            error: "",
+        },
+        Test {
+            name: "to-year-int64-error",
+            func: FunctionFactory::get("toYear", &[field_a.clone()])?,
+            display: "toYear(a)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(UInt16Array::from(vec![0, 0])),
+            error: "Internal Error: Unsupported toYear (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let ref v = func.eval(&t.block)?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(expect_type, actual_type);
+
+        // Result check.
+        if !v.to_array(t.block.num_rows())?.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+
+    // The today() is the date of now().
+    let mut now = FunctionFactory::get("now", &[])?;
+    let mut today = FunctionFactory::get("today", &[])?;
+    match (now.eval(&block)?, today.eval(&block)?) {
+        (
+            DataColumnarValue::Scalar(DataValue::Timestamp(Some(now))),
+            DataColumnarValue::Scalar(DataValue::Date32(Some(today))),
+        ) => assert!(now.div_euclid(86400) - today as i64 <= 1),
+        _ => assert!(false),
+    }
+
+    let result = FunctionFactory::get("toYear", &[]);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: Invalid number of arguments for function toYear: 0",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
@@ -2,36 +2,209 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
-use crate::datavalues::{
-    DataValueAggregateOperator, DataValueArithmeticOperator, DataValueComparisonOperator,
-    DataValueLogicOperator,
-};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
 use crate::error::{FuseQueryError, FuseQueryResult};
 use crate::functions::{
-    AggregatorFunction, ArithmeticFunction, ComparisonFunction, Function, LogicFunction,
+    AggregateFunction, AggregatorFunction, ArithmeticFunction, CastFunction, ComparisonFunction,
+    ConditionalFunction, DateFunction, Function, HashFunction, InFunction, LikeFunction,
+    LogicFunction, MathFunction, NestedFunction, StatisticFunction, StringFunction, UnaryFunction,
+    UniqFunction,
 };
 
-pub struct ScalarFunctionFactory;
+/// The creator of the function by the name which is called and the arguments.
+pub type FactoryFunc = Arc<dyn Fn(&str, &[Function]) -> FuseQueryResult<Function> + Send + Sync>;
+
+/// The creator of the aggregate function by the name which is called and the arguments.
+pub type AggregateFactoryFunc =
+    Arc<dyn Fn(&str, &[Function]) -> FuseQueryResult<AggregateFunction> + Send + Sync>;
+
+#[derive(Clone)]
+enum FunctionCreator {
+    Scalar(FactoryFunc),
+    Aggregate(AggregateFactoryFunc),
+}
+
+/// The function in the registry, listed by system.functions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionDescription {
+    pub name: String,
+    pub is_aggregate: bool,
+    /// The name of the function if it is an alias.
+    pub alias_to: Option<String>,
+}
+
+#[derive(Clone)]
+struct FunctionEntry {
+    name: String,
+    creator: FunctionCreator,
+}
+
+lazy_static! {
+    static ref FACTORY: RwLock<FunctionFactory> =
+        RwLock::new(FunctionFactory::create().expect("The built-in functions are registered"));
+}
+
+/// The registry of the functions, the names and the aliases are case-insensitive.
+/// The global registry has the built-in functions, more functions can be registered to it:
+/// FunctionFactory::instance().write()?.register("myFunction", creator)
+/// The registry of the context has the user-defined functions of the context only.
+#[derive(Clone)]
+pub struct FunctionFactory {
+    // The functions and the aliases are keyed by the lowercase names,
+    // the aliases are to the lowercase names of the functions.
+    functions: HashMap<String, FunctionEntry>,
+    aliases: HashMap<String, String>,
+}
+
+impl FunctionFactory {
+    /// The registry without any function.
+    pub fn create_empty() -> Self {
+        FunctionFactory {
+            functions: HashMap::new(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// The registry of the built-in functions.
+    pub fn create() -> FuseQueryResult<Self> {
+        let mut factory = Self::create_empty();
+        ArithmeticFunction::register(&mut factory)?;
+        ComparisonFunction::register(&mut factory)?;
+        LogicFunction::register(&mut factory)?;
+        UnaryFunction::register(&mut factory)?;
+        InFunction::register(&mut factory)?;
+        LikeFunction::register(&mut factory)?;
+        ConditionalFunction::register(&mut factory)?;
+        CastFunction::register(&mut factory)?;
+        DateFunction::register(&mut factory)?;
+        NestedFunction::register(&mut factory)?;
+        StringFunction::register(&mut factory)?;
+        MathFunction::register(&mut factory)?;
+        HashFunction::register(&mut factory)?;
+        AggregatorFunction::register(&mut factory)?;
+        StatisticFunction::register(&mut factory)?;
+        UniqFunction::register(&mut factory)?;
+        Ok(factory)
+    }
+
+    /// The global registry which is used by the planner.
+    pub fn instance() -> &'static RwLock<FunctionFactory> {
+        &FACTORY
+    }
 
-impl ScalarFunctionFactory {
+    /// Create the function of the global registry.
     pub fn get(name: &str, args: &[Function]) -> FuseQueryResult<Function> {
-        match name.to_lowercase().as_str() {
-            "+" => ArithmeticFunction::try_create(DataValueArithmeticOperator::Add, args),
-            "-" => ArithmeticFunction::try_create(DataValueArithmeticOperator::Sub, args),
-            "*" => ArithmeticFunction::try_create(DataValueArithmeticOperator::Mul, args),
-            "/" => ArithmeticFunction::try_create(DataValueArithmeticOperator::Div, args),
-            "=" => ComparisonFunction::try_create(DataValueComparisonOperator::Eq, args),
-            "<" => ComparisonFunction::try_create(DataValueComparisonOperator::Lt, args),
-            ">" => ComparisonFunction::try_create(DataValueComparisonOperator::Gt, args),
-            "<=" => ComparisonFunction::try_create(DataValueComparisonOperator::LtEq, args),
-            ">=" => ComparisonFunction::try_create(DataValueComparisonOperator::GtEq, args),
-            "and" => LogicFunction::try_create(DataValueLogicOperator::And, args),
-            "or" => LogicFunction::try_create(DataValueLogicOperator::Or, args),
-            "count" => AggregatorFunction::try_create(DataValueAggregateOperator::Count, args),
-            "min" => AggregatorFunction::try_create(DataValueAggregateOperator::Min, args),
-            "max" => AggregatorFunction::try_create(DataValueAggregateOperator::Max, args),
-            "sum" => AggregatorFunction::try_create(DataValueAggregateOperator::Sum, args),
-            _ => Err(FuseQueryError::Internal(format!(
+        Self::instance().read()?.try_get(name, args)
+    }
+
+    /// Whether the function of the global registry is an aggregate function.
+    pub fn is_aggregate(name: &str) -> bool {
+        match Self::instance().read() {
+            Ok(factory) => factory.is_aggregate_function(name),
+            Err(_) => false,
+        }
+    }
+
+    pub fn register(&mut self, name: &str, creator: FactoryFunc) -> FuseQueryResult<()> {
+        self.insert(name, FunctionCreator::Scalar(creator))
+    }
+
+    pub fn register_aggregate(
+        &mut self,
+        name: &str,
+        creator: AggregateFactoryFunc,
+    ) -> FuseQueryResult<()> {
+        self.insert(name, FunctionCreator::Aggregate(creator))
+    }
+
+    /// Register the alias to the function which is registered.
+    pub fn register_alias(&mut self, alias: &str, name: &str) -> FuseQueryResult<()> {
+        let key = alias.to_lowercase();
+        if self.functions.contains_key(&key) || self.aliases.contains_key(&key) {
+            return Err(FuseQueryError::Internal(format!(
+                "Function {} already exists",
+                alias
+            )));
+        }
+        let target = self.resolve(name)?.to_string();
+        self.aliases.insert(key, target);
+        Ok(())
+    }
+
+    /// Create the function by the name or the alias.
+    pub fn try_get(&self, name: &str, args: &[Function]) -> FuseQueryResult<Function> {
+        let key = self.resolve(name)?;
+        match &self.functions[key].creator {
+            FunctionCreator::Scalar(creator) => creator(name, args),
+            FunctionCreator::Aggregate(creator) => {
+                AggregatorFunction::try_create_by_aggregate(creator(name, args)?, args)
+            }
+        }
+    }
+
+    /// Whether the function or the alias is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.resolve(name).is_ok()
+    }
+
+    pub fn is_aggregate_function(&self, name: &str) -> bool {
+        match self.resolve(name) {
+            Ok(key) => matches!(self.functions[key].creator, FunctionCreator::Aggregate(_)),
+            Err(_) => false,
+        }
+    }
+
+    /// The functions and the aliases sorted by the names, as they are registered.
+    pub fn descriptions(&self) -> Vec<FunctionDescription> {
+        let functions = self.functions.values().map(|entry| FunctionDescription {
+            name: entry.name.clone(),
+            is_aggregate: matches!(entry.creator, FunctionCreator::Aggregate(_)),
+            alias_to: None,
+        });
+        let aliases = self.aliases.iter().map(|(alias, key)| {
+            let entry = &self.functions[key];
+            FunctionDescription {
+                name: alias.clone(),
+                is_aggregate: matches!(entry.creator, FunctionCreator::Aggregate(_)),
+                alias_to: Some(entry.name.clone()),
+            }
+        });
+        let mut descriptions = functions.chain(aliases).collect::<Vec<_>>();
+        descriptions.sort_by(|a, b| a.name.cmp(&b.name));
+        descriptions
+    }
+
+    fn insert(&mut self, name: &str, creator: FunctionCreator) -> FuseQueryResult<()> {
+        let key = name.to_lowercase();
+        if self.functions.contains_key(&key) || self.aliases.contains_key(&key) {
+            return Err(FuseQueryError::Internal(format!(
+                "Function {} already exists",
+                name
+            )));
+        }
+        self.functions.insert(
+            key,
+            FunctionEntry {
+                name: name.to_string(),
+                creator,
+            },
+        );
+        Ok(())
+    }
+
+    // The lowercase name of the function of the name or the alias.
+    fn resolve(&self, name: &str) -> FuseQueryResult<&str> {
+        let key = name.to_lowercase();
+        if let Some((key, _)) = self.functions.get_key_value(&key) {
+            return Ok(key);
+        }
+        match self.aliases.get(&key) {
+            Some(key) => Ok(key),
+            None => Err(FuseQueryError::Internal(format!(
                 "Unsupported Function: {}",
                 name
             ))),
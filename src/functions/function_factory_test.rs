@@ -121,7 +121,7 @@ fn test_factory() -> crate::error::FuseQueryResult<()> {
         },
     ];
     for t in tests {
-        let result = ScalarFunctionFactory::get(t.fun, &*t.args);
+        let result = FunctionFactory::get(t.fun, &*t.args);
         match result {
             Ok(_) => {}
             Err(e) => assert_eq!(t.error, e.to_string()),
@@ -129,3 +129,211 @@ fn test_factory() -> crate::error::FuseQueryResult<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_factory_register() -> crate::error::FuseQueryResult<()> {
+    use std::fmt;
+    use std::sync::Arc;
+
+    use crate::datablocks::DataBlock;
+    use crate::datavalues::*;
+    use crate::error::FuseQueryResult;
+    use crate::functions::*;
+
+    // The twice(x) which is x + x, as the functions from outside.
+    #[derive(Clone)]
+    struct TwiceFunction {
+        arg: Function,
+    }
+
+    impl IFunction for TwiceFunction {
+        fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+            self.arg.return_type(input_schema)
+        }
+
+        fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+            self.arg.nullable(input_schema)
+        }
+
+        fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+            let value = self.arg.eval(block)?;
+            Ok(DataColumnarValue::Array(data_array_arithmetic_op(
+                DataValueArithmeticOperator::Add,
+                &value,
+                &value,
+            )?))
+        }
+    }
+
+    impl fmt::Display for TwiceFunction {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "twice({:?})", self.arg)
+        }
+    }
+
+    // The countRows(x) which counts the rows.
+    #[derive(Clone)]
+    struct CountRowsFunction {
+        rows: u64,
+    }
+
+    impl IAggregateFunction for CountRowsFunction {
+        fn return_type(&self, _args: &[DataType]) -> FuseQueryResult<DataType> {
+            Ok(DataType::UInt64)
+        }
+
+        fn accumulate(
+            &mut self,
+            _columns: &[DataColumnarValue],
+            rows: usize,
+        ) -> FuseQueryResult<()> {
+            self.rows += rows as u64;
+            Ok(())
+        }
+
+        fn accumulate_result(&self) -> FuseQueryResult<DataValue> {
+            Ok(DataValue::UInt64(Some(self.rows)))
+        }
+
+        fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()> {
+            if let DataValue::UInt64(Some(rows)) = state {
+                self.rows += rows;
+            }
+            Ok(())
+        }
+
+        fn merge_result(&self) -> FuseQueryResult<DataValue> {
+            Ok(DataValue::UInt64(Some(self.rows)))
+        }
+    }
+
+    impl fmt::Display for CountRowsFunction {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "countRows")
+        }
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![DataField::new(
+        "a",
+        DataType::Int64,
+        false,
+    )]));
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![Arc::new(Int64Array::from(vec![1, 2, 3]))],
+    );
+    let field_a = FieldFunction::try_create("a")?;
+
+    // The names and the aliases are case-insensitive.
+    let mut factory = FunctionFactory::create()?;
+    for (name, display) in vec![
+        ("CITYHASH64", "cityHash64(a)"),
+        ("Ceiling", "ceil(a)"),
+        ("toint8ornull", "toint8ornull(a)"),
+        ("SUM", "Sum(a)"),
+        ("GROUPARRAY", "groupArray(a)"),
+    ] {
+        let func = factory.try_get(name, &[field_a.clone()])?;
+        assert_eq!(display, format!("{:?}", func), "{}", name);
+    }
+    for name in vec!["<>", "NOT LIKE", "Ceiling", "addDay", "nothing"] {
+        assert!(!factory.is_aggregate_function(name), "{}", name);
+    }
+    for name in vec!["SUM", "countDistinct", "GROUPARRAY"] {
+        assert!(factory.is_aggregate_function(name), "{}", name);
+        assert!(FunctionFactory::is_aggregate(name), "{}", name);
+    }
+    let result = factory.try_get("nothing", &[]);
+    match result {
+        Err(e) => assert_eq!(
+            "Internal Error: Unsupported Function: nothing",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+
+    // The functions from outside.
+    factory.register(
+        "twice",
+        Arc::new(|_, args| {
+            Ok(Box::new(TwiceFunction {
+                arg: args[0].clone(),
+            }))
+        }),
+    )?;
+    factory.register_alias("double", "TWICE")?;
+    factory.register_aggregate(
+        "countRows",
+        Arc::new(|_, _| Ok(Box::new(CountRowsFunction { rows: 0 }))),
+    )?;
+    let descriptions = factory.descriptions();
+    assert!(descriptions.contains(&FunctionDescription {
+        name: "double".to_string(),
+        is_aggregate: false,
+        alias_to: Some("twice".to_string()),
+    }));
+    assert!(descriptions.contains(&FunctionDescription {
+        name: "countRows".to_string(),
+        is_aggregate: true,
+        alias_to: None,
+    }));
+    assert!(factory.is_aggregate_function("countrows"));
+    assert!(!factory.is_aggregate_function("double"));
+
+    let mut func = factory.try_get("Double", &[field_a.clone()])?;
+    assert_eq!("twice(a)", format!("{:?}", func));
+    assert_eq!(DataType::Int64, func.return_type(&schema)?);
+    let array = func.eval(&block)?.to_array(block.num_rows())?;
+    assert!(array.equals(&Int64Array::from(vec![2, 4, 6])));
+
+    let mut func = factory.try_get("countRows", &[field_a.clone()])?;
+    assert_eq!("countRows(a)", format!("{:?}", func));
+    func.accumulate(&block)?;
+    func.accumulate(&block)?;
+    let state = func.accumulate_result()?;
+    let mut func = factory.try_get("countRows", &[field_a.clone()])?;
+    func.merge_state(&state)?;
+    func.merge_state(&state)?;
+    assert_eq!(DataValue::UInt64(Some(12)), func.merge_result()?);
+
+    // The names and the aliases are unique.
+    let result = factory.register("SUM", Arc::new(|_, args| Ok(args[0].clone())));
+    match result {
+        Err(e) => assert_eq!("Internal Error: Function SUM already exists", e.to_string()),
+        Ok(_) => assert!(false),
+    }
+    let result = factory.register_alias("Double", "abs");
+    match result {
+        Err(e) => assert_eq!(
+            "Internal Error: Function Double already exists",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    let result = factory.register_alias("alias", "nothing");
+    match result {
+        Err(e) => assert_eq!(
+            "Internal Error: Unsupported Function: nothing",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+
+    // The functions registered to the global registry are visible to the planner.
+    FunctionFactory::instance().write()?.register(
+        "testTwice",
+        Arc::new(|_, args| {
+            Ok(Box::new(TwiceFunction {
+                arg: args[0].clone(),
+            }))
+        }),
+    )?;
+    let plan = crate::planners::ExpressionPlan::Function {
+        op: "TESTTWICE".to_string(),
+        args: vec![crate::planners::ExpressionPlan::Field("a".to_string())],
+        functions: None,
+    };
+    assert!(!plan.is_aggregate());
+    assert_eq!("twice(a)", format!("{:?}", plan.to_function()?));
+    Ok(())
+}
@@ -7,7 +7,7 @@ use std::fmt;
 use crate::datablocks::DataBlock;
 use crate::datavalues::{DataColumnarValue, DataSchema, DataType, DataValue};
 use crate::error::{FuseQueryError, FuseQueryResult};
-use crate::functions::Function;
+use crate::functions::{Function, IFunction};
 
 #[derive(Clone, Debug)]
 pub struct FieldFunction {
@@ -18,54 +18,56 @@ pub struct FieldFunction {
 
 impl FieldFunction {
     pub fn try_create(value: &str) -> FuseQueryResult<Function> {
-        Ok(Function::Variable(FieldFunction {
+        Ok(Box::new(FieldFunction {
             depth: 0,
             value: value.to_string(),
             saved: None,
         }))
     }
+}
 
-    pub fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+impl IFunction for FieldFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
         Ok(input_schema
             .field_with_name(&self.value)?
             .data_type()
             .clone())
     }
 
-    pub fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
         Ok(input_schema.field_with_name(&self.value)?.is_nullable())
     }
 
-    pub fn set_depth(&mut self, depth: usize) {
+    fn set_depth(&mut self, depth: usize) {
         self.depth = depth;
     }
 
-    pub fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
         Ok(DataColumnarValue::Array(
             block.column_by_name(self.value.as_str())?.clone(),
         ))
     }
 
-    pub fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
         self.saved = Some(DataColumnarValue::Array(
             block.column_by_name(self.value.as_str())?.clone(),
         ));
         Ok(())
     }
 
-    pub fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
         Err(FuseQueryError::Internal(
             "Unsupported aggregate operation for function field".to_string(),
         ))
     }
 
-    pub fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
         Err(FuseQueryError::Internal(
             "Unsupported aggregate operation for function field".to_string(),
         ))
     }
 
-    pub fn merge_result(&self) -> FuseQueryResult<DataValue> {
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
         Err(FuseQueryError::Internal(
             "Unsupported aggregate operation for function field".to_string(),
         ))
@@ -0,0 +1,137 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    DataColumnarValue, DataSchema, DataType, DataValue, DataValueHashOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The hash functions: cityHash64(x, ...), xxHash64(x, ...), sipHash64(x, ...), md5(s) and sha256(s).
+#[derive(Clone)]
+pub struct HashFunction {
+    depth: usize,
+    op: DataValueHashOperator,
+    args: Vec<Function>,
+}
+
+impl HashFunction {
+    pub fn try_create(op: DataValueHashOperator, args: &[Function]) -> FuseQueryResult<Function> {
+        let (min, max) = match op {
+            DataValueHashOperator::Md5 | DataValueHashOperator::Sha256 => (1, 1),
+            _ => (1, usize::MAX),
+        };
+        if args.len() < min || args.len() > max {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(HashFunction {
+            depth: 0,
+            op,
+            args: args.to_vec(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("cityHash64", DataValueHashOperator::CityHash64),
+            ("xxHash64", DataValueHashOperator::XxHash64),
+            ("sipHash64", DataValueHashOperator::SipHash64),
+            ("md5", DataValueHashOperator::Md5),
+            ("sha256", DataValueHashOperator::Sha256),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl IFunction for HashFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        let mut data_types = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            data_types.push(arg.return_type(input_schema)?);
+        }
+        datavalues::hash_coercion(&self.op, &data_types)
+    }
+
+    /// The result is NULL if any argument is NULL.
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        for arg in &self.args {
+            if arg.nullable(input_schema)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            arrays.push(arg.eval(block)?.to_array(block.num_rows())?);
+        }
+        Ok(DataColumnarValue::Array(datavalues::data_array_hash_op(
+            self.op.clone(),
+            &arrays,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+}
+
+impl fmt::Display for HashFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.op)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_hash_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["abc", ""])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "city-hash64-passed",
+            func: FunctionFactory::get("cityHash64", &[field_s.clone()])?,
+            display: "cityHash64(s)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["4220206313085259313", "11160318154034397263"],
+            error: "",
+        },
+        Test {
+            name: "md5-passed",
+            func: FunctionFactory::get("MD5", &[field_s.clone()])?,
+            display: "md5(s)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec![
+                "900150983cd24fb0d6963f7d28e17f72",
+                "d41d8cd98f00b204e9800998ecf8427e",
+            ],
+            error: "",
+        },
+        Test {
+            name: "sha256-int64-error",
+            func: FunctionFactory::get("sha256", &[field_a.clone()])?,
+            display: "sha256(a)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec![],
+            error: "Internal Error: Unsupported sha256 (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let v = func.eval(&t.block)?.to_array(t.block.num_rows())?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(&expect_type, actual_type);
+
+        // Result check.
+        let actual = (0..v.len())
+            .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+            .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+        assert_eq!(t.expect, actual, "{}", t.name);
+    }
+
+    let result = FunctionFactory::get("md5", &[field_s.clone(), field_s]);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: Invalid number of arguments for function md5: 2",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
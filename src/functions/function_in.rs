@@ -0,0 +1,150 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{DataColumnarValue, DataSchema, DataType, DataValue, InListSet};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The IN list: in(x, v1, v2, ...) is x IN (v1, v2, ...) and notIn is x NOT IN (...).
+/// The values are constants, compiled into a set of the common type on the first block.
+#[derive(Clone)]
+pub struct InFunction {
+    depth: usize,
+    negated: bool,
+    func: Function,
+    values: Vec<DataValue>,
+    set: Option<InListSet>,
+}
+
+impl InFunction {
+    pub fn try_create(args: &[Function], negated: bool) -> FuseQueryResult<Function> {
+        let name = if negated { "notIn" } else { "in" };
+        if args.len() < 2 {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                name,
+                args.len()
+            )));
+        }
+
+        let mut values = Vec::with_capacity(args.len() - 1);
+        for arg in &args[1..] {
+            match arg.constant_value() {
+                Some(value) => values.push(value.clone()),
+                None => {
+                    return Err(FuseQueryError::Plan(format!(
+                        "The values of function {} must be constants, but got {:?}",
+                        name, arg
+                    )))
+                }
+            }
+        }
+
+        Ok(Box::new(InFunction {
+            depth: 0,
+            negated,
+            func: args[0].clone(),
+            values,
+            set: None,
+        }))
+    }
+
+    fn name(&self) -> &'static str {
+        if self.negated {
+            "notIn"
+        } else {
+            "in"
+        }
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        factory.register("in", Arc::new(|_, args| Self::try_create(args, false)))?;
+        factory.register("notIn", Arc::new(|_, args| Self::try_create(args, true)))
+    }
+}
+
+impl IFunction for InFunction {
+    fn return_type(&self, _input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        let has_null = self.values.iter().any(|v| v.is_null());
+        Ok(has_null || self.func.nullable(input_schema)?)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let array = self.func.eval(block)?.to_array(block.num_rows())?;
+        let mut data_type = array.data_type().clone();
+        if data_type != DataType::Null {
+            for value in &self.values {
+                data_type =
+                    datavalues::equal_coercion(self.name(), &data_type, &value.data_type())?;
+            }
+        }
+        if data_type == DataType::Null {
+            return Ok(DataColumnarValue::Array(DataValue::try_into_data_array(
+                &vec![DataValue::Null; array.len()],
+                &DataType::Boolean,
+            )?));
+        }
+
+        let set = match self.set {
+            Some(ref set) if set.data_type() == &data_type => set,
+            _ => self
+                .set
+                .insert(InListSet::try_create(&self.values, &data_type)?),
+        };
+        Ok(DataColumnarValue::Array(datavalues::data_array_in(
+            &array,
+            set,
+            self.negated,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        self.func.accumulate(block)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.name()
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.name()
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.name()
+        )))
+    }
+}
+
+impl fmt::Display for InFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values = self
+            .values
+            .iter()
+            .map(|v| format!("{:?}", v))
+            .collect::<Vec<_>>();
+        write!(f, "{}({:?}, {})", self.name(), self.func, values.join(", "))
+    }
+}
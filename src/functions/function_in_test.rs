@@ -0,0 +1,121 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_in_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let one = ConstantFunction::try_create(DataValue::UInt64(Some(1)))?;
+    let three = ConstantFunction::try_create(DataValue::UInt64(Some(3)))?;
+    let null = ConstantFunction::try_create(DataValue::Null)?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["x", "y", "z"])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "in-passed",
+            func: InFunction::try_create(&[field_a.clone(), one.clone(), three.clone()], false)?,
+            display: "in(a, 1, 3)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![true, false, true])),
+            error: "",
+        },
+        Test {
+            name: "not-in-passed",
+            func: InFunction::try_create(&[field_a.clone(), one.clone(), three.clone()], true)?,
+            display: "notIn(a, 1, 3)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![false, true, false])),
+            error: "",
+        },
+        Test {
+            name: "not-in-null-passed",
+            func: InFunction::try_create(&[field_a.clone(), one.clone(), null.clone()], true)?,
+            display: "notIn(a, 1, Null)",
+            nullable: true,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![Some(false), None, None])),
+            error: "",
+        },
+        Test {
+            name: "in-string-error",
+            func: InFunction::try_create(&[field_s.clone(), one.clone()], false)?,
+            display: "in(s, 1)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![false, false, false])),
+            error: "Internal Error: Unsupported (Utf8) in (UInt64)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let ref v = func.eval(&t.block)?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(expect_type, actual_type);
+
+        // Result check.
+        if !v.to_array(t.block.num_rows())?.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+
+    // The values must be constants.
+    let result = InFunction::try_create(&[field_a.clone(), field_a.clone()], false);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: The values of function in must be constants, but got a",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
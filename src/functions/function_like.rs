@@ -0,0 +1,142 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{DataColumnarValue, DataSchema, DataType, DataValue, LikePattern};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The pattern matching: like(x, pattern) is x LIKE pattern, ilike is case-insensitive,
+/// notLike and notILike are negated. The pattern is a constant compiled on creation.
+#[derive(Clone)]
+pub struct LikeFunction {
+    depth: usize,
+    case_insensitive: bool,
+    negated: bool,
+    func: Function,
+    pattern: String,
+    compiled: LikePattern,
+}
+
+impl LikeFunction {
+    pub fn try_create(
+        args: &[Function],
+        case_insensitive: bool,
+        negated: bool,
+    ) -> FuseQueryResult<Function> {
+        let name = Self::function_name(case_insensitive, negated);
+        if args.len() != 2 {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                name,
+                args.len()
+            )));
+        }
+
+        let pattern = match args[1].constant_value() {
+            Some(DataValue::String(Some(pattern))) => Some(pattern.clone()),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            FuseQueryError::Plan(format!(
+                "The pattern of function {} must be a constant string, but got {:?}",
+                name, args[1]
+            ))
+        })?;
+
+        Ok(Box::new(LikeFunction {
+            depth: 0,
+            case_insensitive,
+            negated,
+            func: args[0].clone(),
+            compiled: LikePattern::compile(&pattern, case_insensitive),
+            pattern,
+        }))
+    }
+
+    fn function_name(case_insensitive: bool, negated: bool) -> &'static str {
+        match (case_insensitive, negated) {
+            (false, false) => "like",
+            (false, true) => "notLike",
+            (true, false) => "ilike",
+            (true, true) => "notILike",
+        }
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        for (case_insensitive, negated) in
+            [(false, false), (false, true), (true, false), (true, true)]
+        {
+            factory.register(
+                Self::function_name(case_insensitive, negated),
+                Arc::new(move |_, args| Self::try_create(args, case_insensitive, negated)),
+            )?;
+        }
+        factory.register_alias("not like", "notLike")
+    }
+}
+
+impl IFunction for LikeFunction {
+    fn return_type(&self, _input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        self.func.nullable(input_schema)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let array = self.func.eval(block)?.to_array(block.num_rows())?;
+        Ok(DataColumnarValue::Array(datavalues::data_array_like(
+            &array,
+            &self.compiled,
+            self.negated,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        self.func.accumulate(block)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            Self::function_name(self.case_insensitive, self.negated)
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            Self::function_name(self.case_insensitive, self.negated)
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            Self::function_name(self.case_insensitive, self.negated)
+        )))
+    }
+}
+
+impl fmt::Display for LikeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}({:?}, {})",
+            Self::function_name(self.case_insensitive, self.negated),
+            self.func,
+            self.pattern
+        )
+    }
+}
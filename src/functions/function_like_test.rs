@@ -0,0 +1,119 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_like_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: DataArrayRef,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let pattern = ConstantFunction::try_create(DataValue::String(Some("a%".to_string())))?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["abc", "Abc", "bc"])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "like-passed",
+            func: FunctionFactory::get("like", &[field_s.clone(), pattern.clone()])?,
+            display: "like(s, a%)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![true, false, false])),
+            error: "",
+        },
+        Test {
+            name: "not-like-passed",
+            func: FunctionFactory::get("NOT LIKE", &[field_s.clone(), pattern.clone()])?,
+            display: "notLike(s, a%)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![false, true, true])),
+            error: "",
+        },
+        Test {
+            name: "ilike-passed",
+            func: FunctionFactory::get("ilike", &[field_s.clone(), pattern.clone()])?,
+            display: "ilike(s, a%)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![true, true, false])),
+            error: "",
+        },
+        Test {
+            name: "like-int64-error",
+            func: FunctionFactory::get("like", &[field_a.clone(), pattern.clone()])?,
+            display: "like(a, a%)",
+            nullable: false,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![false, false, false])),
+            error: "Internal Error: Unsupported like (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let ref v = func.eval(&t.block)?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(expect_type, actual_type);
+
+        // Result check.
+        if !v.to_array(t.block.num_rows())?.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+
+    // The pattern must be a constant string.
+    let result = FunctionFactory::get("like", &[field_s.clone(), field_s.clone()]);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: The pattern of function like must be a constant string, but got s",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
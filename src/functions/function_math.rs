@@ -0,0 +1,159 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    DataColumnarValue, DataSchema, DataType, DataValue, DataValueMathOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The math functions: abs(x), round(x[, n]), floor(x[, n]), ceil(x[, n]), sqrt(x), pow(x, y),
+/// exp(x), ln(x), log2(x), log10(x), sin(x), cos(x), tan(x), asin(x), acos(x), atan(x),
+/// intDiv(x, y), greatest(x, ...) and least(x, ...).
+#[derive(Clone)]
+pub struct MathFunction {
+    depth: usize,
+    op: DataValueMathOperator,
+    args: Vec<Function>,
+}
+
+impl MathFunction {
+    pub fn try_create(op: DataValueMathOperator, args: &[Function]) -> FuseQueryResult<Function> {
+        let (min, max) = match op {
+            DataValueMathOperator::Round
+            | DataValueMathOperator::Floor
+            | DataValueMathOperator::Ceil => (1, 2),
+            DataValueMathOperator::Pow | DataValueMathOperator::IntDiv => (2, 2),
+            DataValueMathOperator::Greatest | DataValueMathOperator::Least => (2, usize::MAX),
+            _ => (1, 1),
+        };
+        if args.len() < min || args.len() > max {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(MathFunction {
+            depth: 0,
+            op,
+            args: args.to_vec(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("abs", DataValueMathOperator::Abs),
+            ("round", DataValueMathOperator::Round),
+            ("floor", DataValueMathOperator::Floor),
+            ("ceil", DataValueMathOperator::Ceil),
+            ("sqrt", DataValueMathOperator::Sqrt),
+            ("pow", DataValueMathOperator::Pow),
+            ("exp", DataValueMathOperator::Exp),
+            ("ln", DataValueMathOperator::Ln),
+            ("log2", DataValueMathOperator::Log2),
+            ("log10", DataValueMathOperator::Log10),
+            ("sin", DataValueMathOperator::Sin),
+            ("cos", DataValueMathOperator::Cos),
+            ("tan", DataValueMathOperator::Tan),
+            ("asin", DataValueMathOperator::Asin),
+            ("acos", DataValueMathOperator::Acos),
+            ("atan", DataValueMathOperator::Atan),
+            ("intDiv", DataValueMathOperator::IntDiv),
+            ("greatest", DataValueMathOperator::Greatest),
+            ("least", DataValueMathOperator::Least),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        factory.register_alias("ceiling", "ceil")?;
+        factory.register_alias("power", "pow")?;
+        factory.register_alias("log", "ln")
+    }
+}
+
+impl IFunction for MathFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        let mut data_types = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            data_types.push(arg.return_type(input_schema)?);
+        }
+        datavalues::math_coercion(&self.op, &data_types)
+    }
+
+    /// The result is NULL if any argument is NULL.
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        for arg in &self.args {
+            if arg.nullable(input_schema)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            arrays.push(arg.eval(block)?.to_array(block.num_rows())?);
+        }
+        Ok(DataColumnarValue::Array(datavalues::data_array_math_op(
+            self.op.clone(),
+            &arrays,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+}
+
+impl fmt::Display for MathFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.op)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
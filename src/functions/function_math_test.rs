@@ -0,0 +1,141 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_math_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["Abc", "x,y"])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "abs-passed",
+            func: FunctionFactory::get(
+                "abs",
+                &[FunctionFactory::get("negate", &[field_a.clone()])?],
+            )?,
+            display: "abs(negate(a))",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["1", "2"],
+            error: "",
+        },
+        Test {
+            name: "round-passed",
+            func: FunctionFactory::get(
+                "round",
+                &[
+                    FunctionFactory::get("sqrt", &[field_a.clone()])?,
+                    ConstantFunction::try_create(DataValue::Int64(Some(2)))?,
+                ],
+            )?,
+            display: "round(sqrt(a), 2)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["1", "1.41"],
+            error: "",
+        },
+        Test {
+            name: "int-div-passed",
+            func: FunctionFactory::get("intDiv", &[field_a.clone(), field_a.clone()])?,
+            display: "intDiv(a, a)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["1", "1"],
+            error: "",
+        },
+        Test {
+            name: "greatest-passed",
+            func: FunctionFactory::get(
+                "greatest",
+                &[
+                    field_a.clone(),
+                    ConstantFunction::try_create(DataValue::Float64(Some(1.5)))?,
+                ],
+            )?,
+            display: "greatest(a, 1.5)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["1.5", "2"],
+            error: "",
+        },
+        Test {
+            name: "sqrt-string-error",
+            func: FunctionFactory::get("sqrt", &[field_s.clone()])?,
+            display: "sqrt(s)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec![],
+            error: "Internal Error: Unsupported sqrt (Utf8)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let v = func.eval(&t.block)?.to_array(t.block.num_rows())?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(&expect_type, actual_type);
+
+        // Result check.
+        let actual = (0..v.len())
+            .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+            .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+        assert_eq!(t.expect, actual, "{}", t.name);
+    }
+
+    let result = FunctionFactory::get("pow", &[field_a]);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: Invalid number of arguments for function pow: 1",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
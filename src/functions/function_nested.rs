@@ -0,0 +1,265 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    DataColumnarValue, DataSchema, DataType, DataValue, DataValueNestedOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{
+    AggregateFunction, AggregatorFunction, Function, FunctionFactory, IAggregateFunction, IFunction,
+};
+
+/// The array and tuple functions: array(x, ...) which is [x, ...], tuple(x, ...), has(arr, x),
+/// arrayElement(arr, i) which is arr[i], tupleElement(t, i), arrayJoin(arr)
+/// which unnests the array to the rows, and the aggregator groupArray(x).
+#[derive(Clone)]
+pub struct NestedFunction {
+    depth: usize,
+    op: DataValueNestedOperator,
+    args: Vec<Function>,
+}
+
+impl NestedFunction {
+    pub fn try_create(op: DataValueNestedOperator, args: &[Function]) -> FuseQueryResult<Function> {
+        let valid = match op {
+            DataValueNestedOperator::Array => true,
+            DataValueNestedOperator::Tuple => !args.is_empty(),
+            DataValueNestedOperator::Has
+            | DataValueNestedOperator::ArrayElement
+            | DataValueNestedOperator::TupleElement(_) => args.len() == 2,
+            DataValueNestedOperator::Length
+            | DataValueNestedOperator::ArrayJoin
+            | DataValueNestedOperator::GroupArray => args.len() == 1,
+        };
+        if !valid {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        if let DataValueNestedOperator::GroupArray = op {
+            return AggregatorFunction::try_create_by_aggregate(
+                GroupArrayFunction::try_create(args)?,
+                args,
+            );
+        }
+        Ok(Box::new(NestedFunction {
+            depth: 0,
+            op,
+            args: args.to_vec(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("array", DataValueNestedOperator::Array),
+            ("tuple", DataValueNestedOperator::Tuple),
+            ("has", DataValueNestedOperator::Has),
+            ("arrayElement", DataValueNestedOperator::ArrayElement),
+            ("arrayJoin", DataValueNestedOperator::ArrayJoin),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        factory.register(
+            "tupleElement",
+            Arc::new(|_, args| Self::try_create_tuple_element(args)),
+        )?;
+        factory.register_aggregate(
+            "groupArray",
+            Arc::new(|_, args| GroupArrayFunction::try_create(args)),
+        )
+    }
+
+    // The tupleElement(t, i), the i is a constant for the type of the element.
+    fn try_create_tuple_element(args: &[Function]) -> FuseQueryResult<Function> {
+        let n = match args.get(1).and_then(|arg| arg.constant_value()) {
+            Some(DataValue::Int64(Some(n))) if *n > 0 => Some(*n as usize),
+            Some(DataValue::UInt64(Some(n))) if *n > 0 => Some(*n as usize),
+            _ => None,
+        };
+        match n {
+            Some(n) => Self::try_create(DataValueNestedOperator::TupleElement(n), args),
+            None => Err(FuseQueryError::Plan(format!(
+                "The index of function tupleElement must be a positive constant, but got {:?}",
+                args.get(1)
+            ))),
+        }
+    }
+}
+
+impl IFunction for NestedFunction {
+    fn is_array_join(&self) -> bool {
+        matches!(self.op, DataValueNestedOperator::ArrayJoin)
+    }
+
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        let mut data_types = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            data_types.push(arg.return_type(input_schema)?);
+        }
+        datavalues::nested_coercion(&self.op, &data_types)
+    }
+
+    /// The elements out of the range are NULLs, the arrays are NULL if the argument is NULL.
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        match self.op {
+            DataValueNestedOperator::Array
+            | DataValueNestedOperator::Tuple
+            | DataValueNestedOperator::GroupArray => Ok(false),
+            DataValueNestedOperator::ArrayElement
+            | DataValueNestedOperator::TupleElement(_)
+            | DataValueNestedOperator::ArrayJoin => Ok(true),
+            DataValueNestedOperator::Has | DataValueNestedOperator::Length => {
+                for arg in &self.args {
+                    if arg.nullable(input_schema)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        // The empty array [] has no argument to take the rows from, it is the constant.
+        if self.args.is_empty() {
+            return Ok(DataColumnarValue::Scalar(DataValue::List(
+                Some(vec![]),
+                DataType::Null,
+            )));
+        }
+
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            arrays.push(arg.eval(block)?.to_array(block.num_rows())?);
+        }
+        Ok(DataColumnarValue::Array(datavalues::data_array_nested_op(
+            self.op.clone(),
+            &arrays,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NestedFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.op {
+            DataValueNestedOperator::Array => write!(f, "[")?,
+            _ => write!(f, "{}(", self.op)?,
+        }
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        match self.op {
+            DataValueNestedOperator::Array => write!(f, "]"),
+            _ => write!(f, ")"),
+        }
+    }
+}
+
+/// The groupArray(x) which collects the values to the array.
+#[derive(Clone)]
+struct GroupArrayFunction {
+    // The values and their type collected by groupArray.
+    state: Vec<DataValue>,
+    state_type: DataType,
+}
+
+impl GroupArrayFunction {
+    fn try_create(args: &[Function]) -> FuseQueryResult<AggregateFunction> {
+        if args.len() != 1 {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function groupArray: {}",
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(GroupArrayFunction {
+            state: vec![],
+            state_type: DataType::Null,
+        }))
+    }
+}
+
+impl IAggregateFunction for GroupArrayFunction {
+    fn return_type(&self, args: &[DataType]) -> FuseQueryResult<DataType> {
+        datavalues::nested_coercion(&DataValueNestedOperator::GroupArray, args)
+    }
+
+    fn nullable(&self, _args: &[DataType]) -> FuseQueryResult<bool> {
+        Ok(false)
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<()> {
+        // The NULLs are not collected.
+        let array = columns[0].to_array(rows)?;
+        if array.data_type() != &DataType::Null {
+            self.state_type = array.data_type().clone();
+        }
+        for row in 0..array.len() {
+            let value = DataValue::try_from_array(&array, row)?;
+            if !value.is_null() {
+                self.state.push(value);
+            }
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<DataValue> {
+        self.merge_result()
+    }
+
+    fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()> {
+        match state {
+            DataValue::List(values, data_type) => {
+                if data_type != &DataType::Null {
+                    self.state_type = data_type.clone();
+                }
+                self.state.extend(values.iter().flatten().cloned());
+                Ok(())
+            }
+            state => Err(FuseQueryError::Internal(format!(
+                "Unsupported aggregate state {:?} for function {}",
+                state, self
+            ))),
+        }
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Ok(DataValue::List(
+            Some(self.state.clone()),
+            self.state_type.clone(),
+        ))
+    }
+}
+
+impl fmt::Display for GroupArrayFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", DataValueNestedOperator::GroupArray)
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_nested_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["x", "y"])),
+        ],
+    );
+    let array = FunctionFactory::get("array", &[field_a.clone(), field_a.clone()])?;
+    let tuple = FunctionFactory::get("tuple", &[field_a.clone(), field_s.clone()])?;
+
+    let tests = vec![
+        Test {
+            name: "array-passed",
+            func: array.clone(),
+            display: "[a, a]",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["[1, 1]", "[2, 2]"],
+            error: "",
+        },
+        Test {
+            name: "has-passed",
+            func: FunctionFactory::get(
+                "has",
+                &[
+                    array.clone(),
+                    ConstantFunction::try_create(DataValue::Int64(Some(2)))?,
+                ],
+            )?,
+            display: "has([a, a], 2)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["false", "true"],
+            error: "",
+        },
+        Test {
+            name: "array-element-passed",
+            func: FunctionFactory::get("arrayElement", &[array.clone(), field_a.clone()])?,
+            display: "arrayElement([a, a], a)",
+            nullable: true,
+            block: block.clone(),
+            expect: vec!["1", "2"],
+            error: "",
+        },
+        Test {
+            name: "tuple-element-passed",
+            func: FunctionFactory::get(
+                "tupleElement",
+                &[
+                    tuple.clone(),
+                    ConstantFunction::try_create(DataValue::Int64(Some(2)))?,
+                ],
+            )?,
+            display: "tupleElement(tuple(a, s), 2)",
+            nullable: true,
+            block: block.clone(),
+            expect: vec!["x", "y"],
+            error: "",
+        },
+        Test {
+            name: "has-int64-error",
+            func: FunctionFactory::get("has", &[field_a.clone(), field_a.clone()])?,
+            display: "has(a, a)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec![],
+            error: "Internal Error: Unsupported has (Int64, Int64)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let v = func.eval(&t.block)?.to_array(t.block.num_rows())?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(&expect_type, actual_type);
+
+        // Result check.
+        let actual = (0..v.len())
+            .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+            .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+        assert_eq!(t.expect, actual, "{}", t.name);
+    }
+
+    let result = FunctionFactory::get(
+        "tupleElement",
+        &[
+            tuple,
+            ConstantFunction::try_create(DataValue::Int64(Some(0)))?,
+        ],
+    );
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: The index of function tupleElement must be a positive constant, but got Some(0)",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
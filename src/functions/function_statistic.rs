@@ -0,0 +1,334 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::array::{Array, PrimitiveArrayOps};
+
+use crate::datavalues;
+use crate::datavalues::{
+    DataArrayRef, DataColumnarValue, DataType, DataValue, DataValueAggregateOperator,
+    DataValueStatisticOperator, Float64Array,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{AggregateFunction, Function, FunctionFactory, IAggregateFunction};
+
+/// The avg, variance, standard deviation, covariance and correlation of the numbers.
+/// The partial state is the count, the means and the sums of the squared differences,
+/// which are merged by the parallel algorithm of Chan et al.
+/// The avg of the decimal is exact, it is the exact sum of the decimals divided by the count.
+#[derive(Clone)]
+pub struct StatisticFunction {
+    op: DataValueStatisticOperator,
+    state: Moments,
+    decimal_sum: DataValue,
+}
+
+impl StatisticFunction {
+    pub fn try_create(
+        op: DataValueStatisticOperator,
+        args: &[Function],
+    ) -> FuseQueryResult<AggregateFunction> {
+        let expect = match op {
+            DataValueStatisticOperator::CovarPop
+            | DataValueStatisticOperator::CovarSamp
+            | DataValueStatisticOperator::Corr => 2,
+            _ => 1,
+        };
+        if args.len() != expect {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(StatisticFunction {
+            op,
+            state: Moments::default(),
+            decimal_sum: DataValue::Null,
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("avg", DataValueStatisticOperator::Avg),
+            ("varPop", DataValueStatisticOperator::VarPop),
+            ("varSamp", DataValueStatisticOperator::VarSamp),
+            ("stddevPop", DataValueStatisticOperator::StddevPop),
+            ("stddevSamp", DataValueStatisticOperator::StddevSamp),
+            ("covarPop", DataValueStatisticOperator::CovarPop),
+            ("covarSamp", DataValueStatisticOperator::CovarSamp),
+            ("corr", DataValueStatisticOperator::Corr),
+        ];
+        for (name, op) in functions {
+            factory.register_aggregate(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        factory.register_alias("var_pop", "varPop")?;
+        factory.register_alias("var_samp", "varSamp")?;
+        factory.register_alias("stddev_pop", "stddevPop")?;
+        factory.register_alias("stddev_samp", "stddevSamp")?;
+        factory.register_alias("covar_pop", "covarPop")?;
+        factory.register_alias("covar_samp", "covarSamp")
+    }
+
+    fn add_decimal_sum(&mut self, value: DataValue) -> FuseQueryResult<()> {
+        self.decimal_sum = datavalues::data_value_aggregate_op(
+            DataValueAggregateOperator::Sum,
+            self.decimal_sum.clone(),
+            value,
+        )?;
+        Ok(())
+    }
+}
+
+impl IAggregateFunction for StatisticFunction {
+    fn return_type(&self, args: &[DataType]) -> FuseQueryResult<DataType> {
+        datavalues::statistic_coercion(&self.op, args)
+    }
+
+    /// The results of no values are NULL, so are the sample ones of one value.
+    fn nullable(&self, _args: &[DataType]) -> FuseQueryResult<bool> {
+        Ok(true)
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<()> {
+        let mut arrays = Vec::with_capacity(columns.len());
+        for column in columns {
+            let array = column.to_array(rows)?;
+            if matches!(self.op, DataValueStatisticOperator::Avg)
+                && datavalues::is_decimal(array.data_type())
+            {
+                self.add_decimal_sum(datavalues::data_array_aggregate_op(
+                    DataValueAggregateOperator::Sum,
+                    array.clone(),
+                )?)?;
+            }
+            arrays.push(datavalues::data_array_cast(&array, &DataType::Float64)?);
+        }
+        let mut values = Vec::with_capacity(arrays.len());
+        for array in &arrays {
+            values.push(
+                array
+                    .as_any()
+                    .downcast_ref::<Float64Array>()
+                    .ok_or_else(|| {
+                        FuseQueryError::Internal(format!(
+                            "Cannot downcast_array from datatype:{:?} item to:Float64Array",
+                            array.data_type()
+                        ))
+                    })?,
+            );
+        }
+
+        // The rows with any NULL argument are skipped.
+        for row in 0..rows {
+            if values.iter().any(|v| v.is_null(row)) {
+                continue;
+            }
+            let x = values[0].value(row);
+            let y = values.get(1).map_or(x, |v| v.value(row));
+            self.state.push(x, y);
+        }
+        Ok(())
+    }
+
+    fn accumulate_row(&mut self, arrays: &[DataArrayRef], row: usize) -> FuseQueryResult<()> {
+        let mut values = Vec::with_capacity(arrays.len());
+        for array in arrays {
+            let value = DataValue::try_from_array(array, row)?;
+            if let (DataValueStatisticOperator::Avg, DataValue::Decimal(Some(_), ..)) =
+                (&self.op, &value)
+            {
+                self.add_decimal_sum(value.clone())?;
+            }
+            match value_as_f64(value)? {
+                Some(v) => values.push(v),
+                None => return Ok(()),
+            }
+        }
+        let x = values[0];
+        let y = values.get(1).map_or(x, |v| *v);
+        self.state.push(x, y);
+        Ok(())
+    }
+
+    /// The state is the moments and the exact sum of the decimals.
+    fn accumulate_result(&self) -> FuseQueryResult<DataValue> {
+        Ok(DataValue::Struct(vec![
+            self.state.to_data_value(),
+            self.decimal_sum.clone(),
+        ]))
+    }
+
+    fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()> {
+        match state {
+            DataValue::Struct(values) if values.len() == 2 => {
+                self.state.merge(&Moments::try_from_data_value(&values[0])?);
+                self.add_decimal_sum(values[1].clone())
+            }
+            _ => Err(FuseQueryError::Internal(format!(
+                "Invalid state of the statistic function: {:?}",
+                state
+            ))),
+        }
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        let s = &self.state;
+        // The avg of the decimals is truncated to the scale like the decimal division.
+        if let (DataValueStatisticOperator::Avg, DataValue::Decimal(sum, _, scale)) =
+            (&self.op, &self.decimal_sum)
+        {
+            return Ok(DataValue::Decimal(
+                sum.filter(|_| s.count > 0).map(|sum| sum / s.count as i64),
+                datavalues::DECIMAL_MAX_PRECISION,
+                *scale,
+            ));
+        }
+        let n = s.count as f64;
+        let result = match self.op {
+            DataValueStatisticOperator::Avg if s.count > 0 => Some(s.mean_x),
+            DataValueStatisticOperator::VarPop if s.count > 0 => Some(s.m2_x / n),
+            DataValueStatisticOperator::VarSamp if s.count > 1 => Some(s.m2_x / (n - 1.0)),
+            DataValueStatisticOperator::StddevPop if s.count > 0 => Some((s.m2_x / n).sqrt()),
+            DataValueStatisticOperator::StddevSamp if s.count > 1 => {
+                Some((s.m2_x / (n - 1.0)).sqrt())
+            }
+            DataValueStatisticOperator::CovarPop if s.count > 0 => Some(s.c_xy / n),
+            DataValueStatisticOperator::CovarSamp if s.count > 1 => Some(s.c_xy / (n - 1.0)),
+            // The correlation of the constant values is undefined.
+            DataValueStatisticOperator::Corr if s.m2_x > 0.0 && s.m2_y > 0.0 => {
+                Some(s.c_xy / (s.m2_x * s.m2_y).sqrt())
+            }
+            _ => None,
+        };
+        Ok(DataValue::Float64(result))
+    }
+}
+
+impl fmt::Display for StatisticFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.op)
+    }
+}
+
+// The number as f64 as data_array_cast to Float64, None for NULL.
+fn value_as_f64(value: DataValue) -> FuseQueryResult<Option<f64>> {
+    Ok(match value {
+        DataValue::Null => None,
+        DataValue::Int8(v) => v.map(|v| v as f64),
+        DataValue::Int16(v) => v.map(|v| v as f64),
+        DataValue::Int32(v) => v.map(|v| v as f64),
+        DataValue::Int64(v) => v.map(|v| v as f64),
+        DataValue::UInt8(v) => v.map(|v| v as f64),
+        DataValue::UInt16(v) => v.map(|v| v as f64),
+        DataValue::UInt32(v) => v.map(|v| v as f64),
+        DataValue::UInt64(v) => v.map(|v| v as f64),
+        DataValue::Float32(v) => v.map(|v| v as f64),
+        DataValue::Float64(v) => v,
+        DataValue::Decimal(v, _, scale) => v.map(|v| v as f64 / 10f64.powi(scale as i32)),
+        value => {
+            return Err(FuseQueryError::Internal(format!(
+                "Invalid argument of the statistic function: {:?}",
+                value
+            )))
+        }
+    })
+}
+
+// The count and the means of x and y, the sums of the squared differences from the means
+// of x and y, and the sum of the products of the differences of x and y.
+#[derive(Clone, Default)]
+struct Moments {
+    count: u64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl Moments {
+    // Welford's online update.
+    fn push(&mut self, x: f64, y: f64) {
+        self.count += 1;
+        let n = self.count as f64;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / n;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    fn merge(&mut self, other: &Moments) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        self.count += other.count;
+        self.mean_x += dx * nb / n;
+        self.mean_y += dy * nb / n;
+        self.m2_x += other.m2_x + dx * dx * na * nb / n;
+        self.m2_y += other.m2_y + dy * dy * na * nb / n;
+        self.c_xy += other.c_xy + dx * dy * na * nb / n;
+    }
+
+    fn to_data_value(&self) -> DataValue {
+        DataValue::Struct(vec![
+            DataValue::UInt64(Some(self.count)),
+            DataValue::Float64(Some(self.mean_x)),
+            DataValue::Float64(Some(self.mean_y)),
+            DataValue::Float64(Some(self.m2_x)),
+            DataValue::Float64(Some(self.m2_y)),
+            DataValue::Float64(Some(self.c_xy)),
+        ])
+    }
+
+    fn try_from_data_value(value: &DataValue) -> FuseQueryResult<Self> {
+        let float = |v: &DataValue| match v {
+            DataValue::Float64(Some(v)) => Some(*v),
+            _ => None,
+        };
+        if let DataValue::Struct(values) = value {
+            if let [DataValue::UInt64(Some(count)), mean_x, mean_y, m2_x, m2_y, c_xy] =
+                values.as_slice()
+            {
+                if let (Some(mean_x), Some(mean_y), Some(m2_x), Some(m2_y), Some(c_xy)) = (
+                    float(mean_x),
+                    float(mean_y),
+                    float(m2_x),
+                    float(m2_y),
+                    float(c_xy),
+                ) {
+                    return Ok(Moments {
+                        count: *count,
+                        mean_x,
+                        mean_y,
+                        m2_x,
+                        m2_y,
+                        c_xy,
+                    });
+                }
+            }
+        }
+        Err(FuseQueryError::Internal(format!(
+            "Invalid state of the statistic function: {:?}",
+            value
+        )))
+    }
+}
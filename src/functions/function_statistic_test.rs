@@ -0,0 +1,181 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_statistic_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::datablocks::DataBlock;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: &'static str,
+        args: Vec<Function>,
+        display: &'static str,
+        expect: Option<f64>,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("b", DataType::Int64, false),
+        DataField::new("c", DataType::Float64, true),
+    ]));
+    // The values are split to the blocks of the two partial aggregations.
+    let blocks = vec![
+        DataBlock::create(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![4, 3])),
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(Float64Array::from(vec![Some(1.5), None])),
+            ],
+        ),
+        DataBlock::create(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![2, 1])),
+                Arc::new(Int64Array::from(vec![3, 4])),
+                Arc::new(Float64Array::from(vec![None, None])),
+            ],
+        ),
+    ];
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_b = FieldFunction::try_create("b")?;
+    let field_c = FieldFunction::try_create("c")?;
+
+    let tests = vec![
+        Test {
+            name: "avg-passed",
+            func: "avg",
+            args: vec![field_a.clone()],
+            display: "avg(a)",
+            expect: Some(2.5),
+        },
+        Test {
+            name: "var-pop-passed",
+            func: "varPop",
+            args: vec![field_a.clone()],
+            display: "varPop(a)",
+            expect: Some(1.25),
+        },
+        Test {
+            name: "var-samp-alias-passed",
+            func: "VAR_SAMP",
+            args: vec![field_a.clone()],
+            display: "varSamp(a)",
+            expect: Some(5.0 / 3.0),
+        },
+        Test {
+            name: "stddev-pop-passed",
+            func: "stddevPop",
+            args: vec![field_a.clone()],
+            display: "stddevPop(a)",
+            expect: Some(1.25f64.sqrt()),
+        },
+        Test {
+            name: "stddev-samp-passed",
+            func: "stddevSamp",
+            args: vec![field_a.clone()],
+            display: "stddevSamp(a)",
+            expect: Some((5.0f64 / 3.0).sqrt()),
+        },
+        Test {
+            name: "covar-pop-passed",
+            func: "covarPop",
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "covarPop(a, b)",
+            expect: Some(-1.25),
+        },
+        Test {
+            name: "covar-samp-passed",
+            func: "covarSamp",
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "covarSamp(a, b)",
+            expect: Some(-5.0 / 3.0),
+        },
+        Test {
+            name: "corr-passed",
+            func: "corr",
+            args: vec![field_a.clone(), field_b.clone()],
+            display: "corr(a, b)",
+            expect: Some(-1.0),
+        },
+        Test {
+            name: "avg-nulls-passed",
+            func: "avg",
+            args: vec![field_c.clone()],
+            display: "avg(c)",
+            expect: Some(1.5),
+        },
+        Test {
+            name: "var-samp-one-value-passed",
+            func: "varSamp",
+            args: vec![field_c.clone()],
+            display: "varSamp(c)",
+            expect: None,
+        },
+        Test {
+            name: "corr-constant-passed",
+            func: "corr",
+            args: vec![field_a.clone(), field_c.clone()],
+            display: "corr(a, c)",
+            expect: None,
+        },
+    ];
+
+    let approx = |expect: Option<f64>, actual: DataValue, name: &str| match (expect, actual) {
+        (Some(expect), DataValue::Float64(Some(actual))) => {
+            assert!(
+                (expect - actual).abs() < 1e-9,
+                "{}: {} != {}",
+                name,
+                expect,
+                actual
+            )
+        }
+        (None, actual) => assert_eq!(DataValue::Float64(None), actual, "{}", name),
+        (expect, actual) => panic!("{}: {:?} != {:?}", name, expect, actual),
+    };
+
+    for t in tests {
+        let mut final_func = FunctionFactory::get(t.func, &t.args)?;
+        for block in &blocks {
+            let mut func = FunctionFactory::get(t.func, &t.args)?;
+            func.accumulate(block)?;
+            final_func.merge_state(&func.accumulate_result()?)?;
+        }
+        assert_eq!(t.display, format!("{}", final_func), "{}", t.name);
+        assert_eq!(
+            DataType::Float64,
+            final_func.return_type(&schema)?,
+            "{}",
+            t.name
+        );
+        approx(t.expect, final_func.merge_result()?, t.name);
+    }
+
+    // The results of no values are NULL.
+    let func = FunctionFactory::get("avg", &[field_c])?;
+    assert_eq!(DataValue::Float64(None), func.merge_result()?);
+
+    let result = FunctionFactory::get("corr", &[field_a]);
+    assert_eq!(
+        "Error during plan: Invalid number of arguments for function corr: 1",
+        format!("{}", result.err().unwrap())
+    );
+
+    let string_schema = DataSchema::new(vec![DataField::new("s", DataType::Utf8, false)]);
+    let func = FunctionFactory::get("avg", &[FieldFunction::try_create("s")?])?;
+    assert_eq!(
+        "Internal Error: Unsupported avg (Utf8)",
+        format!("{}", func.return_type(&string_schema).err().unwrap())
+    );
+    Ok(())
+}
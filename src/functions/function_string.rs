@@ -0,0 +1,169 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    DataColumnarValue, DataSchema, DataType, DataValue, DataValueStringOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The string functions: length(s), lower(s), upper(s), concat(s, ...), substring(s, offset[, n]),
+/// trim(s), replace(s, from, to), position(s, needle), startsWith(s, prefix), endsWith(s, suffix),
+/// splitByChar(separator, s), reverse(s), repeat(s, n), format(pattern, x, ...),
+/// lpad(s, n[, pad]) and rpad(s, n[, pad]).
+#[derive(Clone)]
+pub struct StringFunction {
+    depth: usize,
+    op: DataValueStringOperator,
+    args: Vec<Function>,
+}
+
+impl StringFunction {
+    pub fn try_create(op: DataValueStringOperator, args: &[Function]) -> FuseQueryResult<Function> {
+        let (min, max) = match op {
+            DataValueStringOperator::Length
+            | DataValueStringOperator::Lower
+            | DataValueStringOperator::Upper
+            | DataValueStringOperator::Trim
+            | DataValueStringOperator::Reverse => (1, 1),
+            DataValueStringOperator::Position
+            | DataValueStringOperator::StartsWith
+            | DataValueStringOperator::EndsWith
+            | DataValueStringOperator::SplitByChar
+            | DataValueStringOperator::Repeat => (2, 2),
+            DataValueStringOperator::Substring
+            | DataValueStringOperator::LeftPad
+            | DataValueStringOperator::RightPad => (2, 3),
+            DataValueStringOperator::Replace => (3, 3),
+            DataValueStringOperator::Concat | DataValueStringOperator::Format => (1, usize::MAX),
+        };
+        if args.len() < min || args.len() > max {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(StringFunction {
+            depth: 0,
+            op,
+            args: args.to_vec(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("length", DataValueStringOperator::Length),
+            ("lower", DataValueStringOperator::Lower),
+            ("upper", DataValueStringOperator::Upper),
+            ("concat", DataValueStringOperator::Concat),
+            ("substring", DataValueStringOperator::Substring),
+            ("trim", DataValueStringOperator::Trim),
+            ("replace", DataValueStringOperator::Replace),
+            ("position", DataValueStringOperator::Position),
+            ("startsWith", DataValueStringOperator::StartsWith),
+            ("endsWith", DataValueStringOperator::EndsWith),
+            ("splitByChar", DataValueStringOperator::SplitByChar),
+            ("reverse", DataValueStringOperator::Reverse),
+            ("repeat", DataValueStringOperator::Repeat),
+            ("format", DataValueStringOperator::Format),
+            ("lpad", DataValueStringOperator::LeftPad),
+            ("rpad", DataValueStringOperator::RightPad),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        factory.register_alias("lcase", "lower")?;
+        factory.register_alias("ucase", "upper")?;
+        factory.register_alias("substr", "substring")
+    }
+}
+
+impl IFunction for StringFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        let mut data_types = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            data_types.push(arg.return_type(input_schema)?);
+        }
+        datavalues::string_coercion(&self.op, &data_types)
+    }
+
+    /// The result is NULL if any argument is NULL, lpad and rpad are NULL for the negative length.
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        if let DataValueStringOperator::LeftPad | DataValueStringOperator::RightPad = self.op {
+            return Ok(true);
+        }
+        for arg in &self.args {
+            if arg.nullable(input_schema)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let mut arrays = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            arrays.push(arg.eval(block)?.to_array(block.num_rows())?);
+        }
+        Ok(DataColumnarValue::Array(datavalues::data_array_string_op(
+            self.op.clone(),
+            &arrays,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_state(&mut self, _states: &[DataValue]) -> FuseQueryResult<()> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        Err(FuseQueryError::Internal(format!(
+            "Unsupported aggregate operation for function {}",
+            self.op
+        )))
+    }
+}
+
+impl fmt::Display for StringFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.op)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
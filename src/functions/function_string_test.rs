@@ -0,0 +1,141 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_string_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        func: Function,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: Vec<&'static str>,
+        error: &'static str,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("s", DataType::Utf8, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_s = FieldFunction::try_create("s")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["Abc", "x,y"])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "upper-passed",
+            func: FunctionFactory::get("upper", &[field_s.clone()])?,
+            display: "upper(s)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["ABC", "X,Y"],
+            error: "",
+        },
+        Test {
+            name: "concat-passed",
+            func: FunctionFactory::get("concat", &[field_s.clone(), field_a.clone()])?,
+            display: "concat(s, a)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["Abc1", "x,y2"],
+            error: "",
+        },
+        Test {
+            name: "substring-passed",
+            func: FunctionFactory::get("substr", &[field_s.clone(), field_a.clone()])?,
+            display: "substring(s, a)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["Abc", ",y"],
+            error: "",
+        },
+        Test {
+            name: "split-by-char-passed",
+            func: FunctionFactory::get(
+                "splitByChar",
+                &[
+                    ConstantFunction::try_create(DataValue::String(Some(",".to_string())))?,
+                    field_s.clone(),
+                ],
+            )?,
+            display: "splitByChar(,, s)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec!["['Abc']", "['x', 'y']"],
+            error: "",
+        },
+        Test {
+            name: "lpad-passed",
+            func: FunctionFactory::get("lpad", &[field_s.clone(), field_a.clone()])?,
+            display: "lpad(s, a)",
+            nullable: true,
+            block: block.clone(),
+            expect: vec!["A", "x,"],
+            error: "",
+        },
+        Test {
+            name: "lower-int64-error",
+            func: FunctionFactory::get("lower", &[field_a.clone()])?,
+            display: "lower(a)",
+            nullable: false,
+            block: block.clone(),
+            expect: vec![],
+            error: "Internal Error: Unsupported lower (Int64)",
+        },
+    ];
+
+    for t in tests {
+        let mut func = t.func;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string(), "{}", t.name);
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let v = func.eval(&t.block)?.to_array(t.block.num_rows())?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(&expect_type, actual_type);
+
+        // Result check.
+        let actual = (0..v.len())
+            .map(|i| DataValue::try_from_array(&v, i).map(|v| format!("{:?}", v)))
+            .collect::<crate::error::FuseQueryResult<Vec<_>>>()?;
+        assert_eq!(t.expect, actual, "{}", t.name);
+    }
+
+    let result = FunctionFactory::get("replace", &[field_s]);
+    match result {
+        Err(e) => assert_eq!(
+            "Error during plan: Invalid number of arguments for function replace: 1",
+            e.to_string()
+        ),
+        Ok(_) => assert!(false),
+    }
+    Ok(())
+}
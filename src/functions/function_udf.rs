@@ -0,0 +1,315 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{
+    AggregateFunction, Function, FunctionFactory, IAggregateFunction, IFunction,
+};
+
+/// The closure of the scalar UDF, the arrays of the arguments are in the declared types.
+/// The result is NULL only if one of the arguments is NULL.
+pub type ScalarUDFFunc =
+    Arc<dyn Fn(&[DataArrayRef]) -> FuseQueryResult<DataArrayRef> + Send + Sync>;
+/// The closure which accumulates the arrays of the arguments to the state.
+pub type AccumulateUDAFFunc =
+    Arc<dyn Fn(&DataValue, &[DataArrayRef]) -> FuseQueryResult<DataValue> + Send + Sync>;
+/// The closure which merges the second state to the first one.
+pub type MergeUDAFFunc =
+    Arc<dyn Fn(&DataValue, &DataValue) -> FuseQueryResult<DataValue> + Send + Sync>;
+/// The closure which finalizes the state to the result.
+pub type FinalizeUDAFFunc = Arc<dyn Fn(&DataValue) -> FuseQueryResult<DataValue> + Send + Sync>;
+
+/// The scalar user-defined function with the declared argument and return types.
+#[derive(Clone)]
+pub struct ScalarUDF {
+    pub name: String,
+    pub arg_types: Vec<DataType>,
+    pub return_type: DataType,
+    pub func: ScalarUDFFunc,
+}
+
+impl ScalarUDF {
+    pub fn create(
+        name: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+        func: ScalarUDFFunc,
+    ) -> Self {
+        ScalarUDF {
+            name: name.to_string(),
+            arg_types,
+            return_type,
+            func,
+        }
+    }
+}
+
+/// The aggregate user-defined function, the state is a DataValue starting from the init state,
+/// the partial states are merged in the final aggregation.
+#[derive(Clone)]
+pub struct AggregateUDF {
+    pub name: String,
+    pub arg_types: Vec<DataType>,
+    pub return_type: DataType,
+    pub init_state: DataValue,
+    pub accumulate: AccumulateUDAFFunc,
+    pub merge: MergeUDAFFunc,
+    pub finalize: FinalizeUDAFFunc,
+}
+
+impl AggregateUDF {
+    pub fn create(
+        name: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+        init_state: DataValue,
+        accumulate: AccumulateUDAFFunc,
+        merge: MergeUDAFFunc,
+        finalize: FinalizeUDAFFunc,
+    ) -> Self {
+        AggregateUDF {
+            name: name.to_string(),
+            arg_types,
+            return_type,
+            init_state,
+            accumulate,
+            merge,
+            finalize,
+        }
+    }
+}
+
+// The arguments are cast to the declared types.
+fn udf_arrays(
+    name: &str,
+    arg_types: &[DataType],
+    columns: &[DataColumnarValue],
+    rows: usize,
+) -> FuseQueryResult<Vec<DataArrayRef>> {
+    if columns.len() != arg_types.len() {
+        return Err(FuseQueryError::Plan(format!(
+            "Invalid number of arguments for function {}: {}",
+            name,
+            columns.len()
+        )));
+    }
+    columns
+        .iter()
+        .zip(arg_types)
+        .map(|(column, data_type)| datavalues::data_array_cast(&column.to_array(rows)?, data_type))
+        .collect()
+}
+
+/// The scalar UDF in the expression tree.
+#[derive(Clone)]
+pub struct UDFFunction {
+    depth: usize,
+    udf: ScalarUDF,
+    args: Vec<Function>,
+}
+
+impl UDFFunction {
+    pub fn try_create(udf: ScalarUDF, args: &[Function]) -> FuseQueryResult<Function> {
+        if args.len() != udf.arg_types.len() {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                udf.name,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(UDFFunction {
+            depth: 0,
+            udf,
+            args: args.to_vec(),
+        }))
+    }
+
+    // Call the UDF with the arguments, the result must be the rows of the declared type.
+    fn call(&self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<DataArrayRef> {
+        let arrays = udf_arrays(&self.udf.name, &self.udf.arg_types, columns, rows)?;
+        let array = (self.udf.func)(&arrays)?;
+        if array.len() != rows || array.data_type() != &self.udf.return_type {
+            return Err(FuseQueryError::Internal(format!(
+                "The result of function {} must be {} rows of {:?}, but got {} rows of {:?}",
+                self.udf.name,
+                rows,
+                self.udf.return_type,
+                array.len(),
+                array.data_type()
+            )));
+        }
+        Ok(array)
+    }
+
+    pub fn register(factory: &mut FunctionFactory, udf: ScalarUDF) -> FuseQueryResult<()> {
+        let name = udf.name.clone();
+        factory.register(
+            &name,
+            Arc::new(move |_, args| Self::try_create(udf.clone(), args)),
+        )
+    }
+}
+
+impl IFunction for UDFFunction {
+    fn return_type(&self, _input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        Ok(self.udf.return_type.clone())
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        for arg in &self.args {
+            if arg.nullable(input_schema)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let mut columns = Vec::with_capacity(self.args.len());
+        for arg in &mut self.args {
+            columns.push(arg.eval(block)?);
+        }
+        Ok(DataColumnarValue::Array(
+            self.call(&columns, block.num_rows())?,
+        ))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate(block)?;
+        }
+        Ok(())
+    }
+
+    fn eval_aggregate_args(
+        &mut self,
+        block: &DataBlock,
+        args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.eval_aggregate_args(block, args)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_row(
+        &mut self,
+        args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        row: usize,
+    ) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.accumulate_row(args, row)?;
+        }
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        let mut states = vec![];
+        for arg in &self.args {
+            states.extend(arg.accumulate_result()?);
+        }
+        Ok(states)
+    }
+
+    fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
+        for arg in &mut self.args {
+            arg.merge_state(states)?;
+        }
+        Ok(())
+    }
+
+    // The UDF of the results of the arguments.
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        let mut columns = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            columns.push(DataColumnarValue::Scalar(arg.merge_result()?));
+        }
+        DataValue::try_from_array(&self.call(&columns, 1)?, 0)
+    }
+}
+
+impl fmt::Display for UDFFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.udf.name)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The aggregate UDF, wrapped by the AggregatorFunction in the expression tree.
+#[derive(Clone)]
+pub struct UDAFFunction {
+    udaf: AggregateUDF,
+    state: DataValue,
+}
+
+impl UDAFFunction {
+    pub fn try_create(udaf: AggregateUDF, args: &[Function]) -> FuseQueryResult<AggregateFunction> {
+        if args.len() != udaf.arg_types.len() {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                udaf.name,
+                args.len()
+            )));
+        }
+
+        let state = udaf.init_state.clone();
+        Ok(Box::new(UDAFFunction { udaf, state }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory, udaf: AggregateUDF) -> FuseQueryResult<()> {
+        let name = udaf.name.clone();
+        factory.register_aggregate(
+            &name,
+            Arc::new(move |_, args| Self::try_create(udaf.clone(), args)),
+        )
+    }
+}
+
+impl IAggregateFunction for UDAFFunction {
+    fn return_type(&self, _args: &[DataType]) -> FuseQueryResult<DataType> {
+        Ok(self.udaf.return_type.clone())
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<()> {
+        let arrays = udf_arrays(&self.udaf.name, &self.udaf.arg_types, columns, rows)?;
+        self.state = (self.udaf.accumulate)(&self.state, &arrays)?;
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<DataValue> {
+        Ok(self.state.clone())
+    }
+
+    fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()> {
+        self.state = (self.udaf.merge)(&self.state, state)?;
+        Ok(())
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        (self.udaf.finalize)(&self.state)
+    }
+}
+
+impl fmt::Display for UDAFFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.udaf.name)
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_udf_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::datablocks::DataBlock;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    let schema = Arc::new(DataSchema::new(vec![DataField::new(
+        "a",
+        DataType::Int32,
+        false,
+    )]));
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![Arc::new(Int32Array::from(vec![4, 3, 2, 1]))],
+    );
+    let field_a = FieldFunction::try_create("a")?;
+    let args = vec![field_a.clone()];
+
+    // The argument is cast to the declared Int64.
+    let udf = ScalarUDF::create(
+        "twice",
+        vec![DataType::Int64],
+        DataType::Int64,
+        Arc::new(|arrays| {
+            data_array_arithmetic_op(
+                DataValueArithmeticOperator::Mul,
+                &DataColumnarValue::Array(arrays[0].clone()),
+                &DataColumnarValue::Scalar(DataValue::Int64(Some(2))),
+            )
+        }),
+    );
+    let mut func = UDFFunction::try_create(udf.clone(), &args)?;
+    assert_eq!("twice(a)", format!("{}", func));
+    assert_eq!(DataType::Int64, func.return_type(&schema)?);
+    assert_eq!(false, func.nullable(&schema)?);
+    let expect: DataArrayRef = Arc::new(Int64Array::from(vec![8, 6, 4, 2]));
+    assert!(func.eval(&block)?.to_array(0)?.equals(&*expect));
+
+    let result = UDFFunction::try_create(udf, &[field_a.clone(), field_a.clone()]);
+    let actual = format!("{}", result.err().unwrap());
+    assert_eq!(
+        "Error during plan: Invalid number of arguments for function twice: 2",
+        actual
+    );
+
+    // The result type must be the declared one.
+    let udf = ScalarUDF::create(
+        "bad",
+        vec![DataType::Int32],
+        DataType::Int64,
+        Arc::new(|arrays| Ok(arrays[0].clone())),
+    );
+    let mut func = UDFFunction::try_create(udf, &args)?;
+    let actual = format!("{}", func.eval(&block).err().unwrap());
+    assert_eq!(
+        "Internal Error: The result of function bad must be 4 rows of Int64, but got 4 rows of Int32",
+        actual
+    );
+
+    // The sum of the partial states.
+    let udaf = AggregateUDF::create(
+        "mySum",
+        vec![DataType::Int64],
+        DataType::Int64,
+        DataValue::Int64(Some(0)),
+        Arc::new(|state, arrays| {
+            data_value_aggregate_op(
+                DataValueAggregateOperator::Sum,
+                state.clone(),
+                data_array_aggregate_op(DataValueAggregateOperator::Sum, arrays[0].clone())?,
+            )
+        }),
+        Arc::new(|state, other| {
+            data_value_aggregate_op(
+                DataValueAggregateOperator::Sum,
+                state.clone(),
+                other.clone(),
+            )
+        }),
+        Arc::new(|state| Ok(state.clone())),
+    );
+    let mut partial = AggregatorFunction::try_create_by_aggregate(
+        UDAFFunction::try_create(udaf.clone(), &args)?,
+        &args,
+    )?;
+    partial.accumulate(&block)?;
+    partial.accumulate(&block)?;
+    let states = partial.accumulate_result()?;
+    assert_eq!(vec![DataValue::Int64(Some(20))], states);
+
+    let mut last = AggregatorFunction::try_create_by_aggregate(
+        UDAFFunction::try_create(udaf.clone(), &args)?,
+        &args,
+    )?;
+    assert_eq!("mySum(a)", format!("{}", last));
+    last.merge_state(&states)?;
+    last.merge_state(&states)?;
+    assert_eq!(DataValue::Int64(Some(40)), last.merge_result()?);
+
+    // The UDF of the aggregate is computed over the merged result.
+    let udf = ScalarUDF::create(
+        "twice",
+        vec![DataType::Int64],
+        DataType::Int64,
+        Arc::new(|arrays| {
+            data_array_arithmetic_op(
+                DataValueArithmeticOperator::Mul,
+                &DataColumnarValue::Array(arrays[0].clone()),
+                &DataColumnarValue::Scalar(DataValue::Int64(Some(2))),
+            )
+        }),
+    );
+    let aggregator = |args: &[Function]| -> crate::error::FuseQueryResult<Function> {
+        AggregatorFunction::try_create_by_aggregate(
+            UDAFFunction::try_create(udaf.clone(), args)?,
+            args,
+        )
+    };
+    let mut partial = UDFFunction::try_create(udf.clone(), &[aggregator(&args)?])?;
+    partial.accumulate(&block)?;
+    let mut group_args = vec![];
+    partial.eval_aggregate_args(&block, &mut group_args)?;
+    partial.accumulate_row(&mut group_args.iter(), 0)?;
+    let states = partial.accumulate_result()?;
+    assert_eq!(vec![DataValue::Int64(Some(14))], states);
+
+    let mut last = UDFFunction::try_create(udf, &[aggregator(&args)?])?;
+    assert_eq!("twice(mySum(a))", format!("{}", last));
+    last.merge_state(&states)?;
+    last.merge_state(&states)?;
+    assert_eq!(DataValue::Int64(Some(56)), last.merge_result()?);
+
+    let result = UDAFFunction::try_create(udaf, &[]);
+    let actual = format!("{}", result.err().unwrap());
+    assert_eq!(
+        "Error during plan: Invalid number of arguments for function mySum: 0",
+        actual
+    );
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::{
+    DataArrayRef, DataColumnarValue, DataSchema, DataType, DataValue, DataValueUnaryOperator,
+};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{Function, FunctionFactory, IFunction};
+
+/// The unary operators: negate(x) is -x, not(x) is NOT x,
+/// isNull(x) is x IS NULL and isNotNull(x) is x IS NOT NULL.
+#[derive(Clone)]
+pub struct UnaryFunction {
+    depth: usize,
+    op: DataValueUnaryOperator,
+    func: Function,
+}
+
+impl UnaryFunction {
+    pub fn try_create(op: DataValueUnaryOperator, args: &[Function]) -> FuseQueryResult<Function> {
+        if args.len() != 1 {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: {}",
+                op,
+                args.len()
+            )));
+        }
+
+        Ok(Box::new(UnaryFunction {
+            depth: 0,
+            op,
+            func: args[0].clone(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        let functions = vec![
+            ("negate", DataValueUnaryOperator::Negate),
+            ("not", DataValueUnaryOperator::Not),
+            ("isNull", DataValueUnaryOperator::IsNull),
+            ("isNotNull", DataValueUnaryOperator::IsNotNull),
+        ];
+        for (name, op) in functions {
+            factory.register(
+                name,
+                Arc::new(move |_, args| Self::try_create(op.clone(), args)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl IFunction for UnaryFunction {
+    fn return_type(&self, input_schema: &DataSchema) -> FuseQueryResult<DataType> {
+        match self.op {
+            DataValueUnaryOperator::Negate => datavalues::negate_coercion(
+                format!("{}", self.op).as_str(),
+                &self.func.return_type(input_schema)?,
+            ),
+            DataValueUnaryOperator::Not
+            | DataValueUnaryOperator::IsNull
+            | DataValueUnaryOperator::IsNotNull => Ok(DataType::Boolean),
+        }
+    }
+
+    fn nullable(&self, input_schema: &DataSchema) -> FuseQueryResult<bool> {
+        match self.op {
+            DataValueUnaryOperator::IsNull | DataValueUnaryOperator::IsNotNull => Ok(false),
+            _ => self.func.nullable(input_schema),
+        }
+    }
+
+    fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+    }
+
+    fn eval(&mut self, block: &DataBlock) -> FuseQueryResult<DataColumnarValue> {
+        let value = DataColumnarValue::Array(self.func.eval(block)?.to_array(block.num_rows())?);
+        Ok(DataColumnarValue::Array(datavalues::data_array_unary_op(
+            self.op.clone(),
+            &value,
+        )?))
+    }
+
+    fn accumulate(&mut self, block: &DataBlock) -> FuseQueryResult<()> {
+        self.func.accumulate(block)
+    }
+
+    fn eval_aggregate_args(
+        &mut self,
+        block: &DataBlock,
+        args: &mut Vec<Vec<DataArrayRef>>,
+    ) -> FuseQueryResult<()> {
+        self.func.eval_aggregate_args(block, args)
+    }
+
+    fn accumulate_row(
+        &mut self,
+        args: &mut std::slice::Iter<Vec<DataArrayRef>>,
+        row: usize,
+    ) -> FuseQueryResult<()> {
+        self.func.accumulate_row(args, row)
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<Vec<DataValue>> {
+        self.func.accumulate_result()
+    }
+
+    fn merge_state(&mut self, states: &[DataValue]) -> FuseQueryResult<()> {
+        self.func.merge_state(states)
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        datavalues::data_value_unary_op(self.op.clone(), self.func.merge_result()?)
+    }
+}
+
+impl fmt::Display for UnaryFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({:?})", self.op, self.func)
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_unary_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use crate::datablocks::*;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        args: Vec<Function>,
+        display: &'static str,
+        nullable: bool,
+        block: DataBlock,
+        expect: DataArrayRef,
+        error: &'static str,
+        op: DataValueUnaryOperator,
+    }
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::UInt32, false),
+        DataField::new("b", DataType::Boolean, false),
+    ]));
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_b = FieldFunction::try_create("b")?;
+    let block = DataBlock::create(
+        schema.clone(),
+        vec![
+            Arc::new(UInt32Array::from(vec![1, 2, 3])),
+            Arc::new(BooleanArray::from(vec![true, false, true])),
+        ],
+    );
+
+    let tests = vec![
+        Test {
+            name: "negate-passed",
+            args: vec![field_a.clone()],
+            display: "negate(a)",
+            nullable: false,
+            op: DataValueUnaryOperator::Negate,
+            block: block.clone(),
+            expect: Arc::new(Int64Array::from(vec![-1, -2, -3])),
+            error: "",
+        },
+        Test {
+            name: "not-passed",
+            args: vec![field_b.clone()],
+            display: "not(b)",
+            nullable: false,
+            op: DataValueUnaryOperator::Not,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![false, true, false])),
+            error: "",
+        },
+        Test {
+            name: "not-error",
+            args: vec![field_a.clone()],
+            display: "not(a)",
+            nullable: false,
+            op: DataValueUnaryOperator::Not,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![false, true, false])),
+            error: "Internal Error: Unsupported not (UInt32)",
+        },
+        Test {
+            name: "is-not-null-passed",
+            args: vec![field_a.clone()],
+            display: "isNotNull(a)",
+            nullable: false,
+            op: DataValueUnaryOperator::IsNotNull,
+            block: block.clone(),
+            expect: Arc::new(BooleanArray::from(vec![true, true, true])),
+            error: "",
+        },
+    ];
+
+    for t in tests {
+        let mut func = UnaryFunction::try_create(t.op, &t.args)?;
+        if let Err(e) = func.eval(&t.block) {
+            assert_eq!(t.error, e.to_string());
+            continue;
+        }
+
+        // Display check.
+        let expect_display = t.display.to_string();
+        let actual_display = format!("{:?}", func);
+        assert_eq!(expect_display, actual_display);
+
+        // Nullable check.
+        let expect_null = t.nullable;
+        let actual_null = func.nullable(t.block.schema())?;
+        assert_eq!(expect_null, actual_null);
+
+        let ref v = func.eval(&t.block)?;
+        // Type check.
+        let expect_type = func.return_type(t.block.schema())?;
+        let actual_type = v.data_type();
+        assert_eq!(expect_type, actual_type);
+
+        // Result check.
+        if !v.to_array(t.block.num_rows())?.equals(&*t.expect) {
+            println!("{}, expect:\n{:?} \nactual:\n{:?}", t.name, t.expect, v);
+            assert!(false);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,221 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use twox_hash::XxHash64;
+
+use crate::datavalues;
+use crate::datavalues::{DataArrayRef, DataColumnarValue, DataType, DataValue};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::{AggregateFunction, Function, FunctionFactory, IAggregateFunction};
+
+// The HyperLogLog has 2^12 registers, the standard error is 1.04 / sqrt(4096) = 1.6%.
+const HLL_PRECISION: u32 = 12;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// The count of the distinct rows of the arguments, the rows with NULLs are not counted.
+/// The uniq is approximated by the HyperLogLog of the xxHash64 of the row keys in bounded memory,
+/// the partial state is the non-zero registers as the Struct of UInt64 (index << 8 | rank).
+/// The uniqExact keeps the rows, the partial state is the Struct of the serialized rows.
+#[derive(Clone)]
+pub struct UniqFunction {
+    exact: bool,
+    registers: Vec<u8>,
+    values: HashSet<String>,
+}
+
+impl UniqFunction {
+    pub fn try_create(exact: bool, args: &[Function]) -> FuseQueryResult<AggregateFunction> {
+        if args.is_empty() {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid number of arguments for function {}: 0",
+                if exact { "uniqExact" } else { "uniq" }
+            )));
+        }
+
+        Ok(Box::new(UniqFunction {
+            exact,
+            registers: if exact {
+                vec![]
+            } else {
+                vec![0; HLL_REGISTERS]
+            },
+            values: HashSet::new(),
+        }))
+    }
+
+    pub fn register(factory: &mut FunctionFactory) -> FuseQueryResult<()> {
+        factory.register_aggregate("uniq", Arc::new(|_, args| Self::try_create(false, args)))?;
+        factory.register_aggregate(
+            "uniqExact",
+            Arc::new(|_, args| Self::try_create(true, args)),
+        )?;
+        factory.register_alias("approx_count_distinct", "uniq")
+    }
+
+    fn insert_row_key(&mut self, key: &[u8]) {
+        let mut hasher = XxHash64::default();
+        hasher.write(key);
+        self.insert_hash(hasher.finish());
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // The rank is the position of the first 1 bit of the rest bits.
+        let rank = ((hash << HLL_PRECISION).leading_zeros() + 1).min(64 - HLL_PRECISION + 1) as u8;
+        if self.registers[index] < rank {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let mut sum = 0.0;
+        let mut zeros = 0;
+        for rank in &self.registers {
+            sum += 1.0 / (1u64 << rank) as f64;
+            if *rank == 0 {
+                zeros += 1;
+            }
+        }
+        let raw = alpha * m * m / sum;
+        // The linear counting is more accurate for the small cardinalities.
+        if raw <= 2.5 * m && zeros > 0 {
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+        raw.round() as u64
+    }
+}
+
+impl IAggregateFunction for UniqFunction {
+    fn return_type(&self, _args: &[DataType]) -> FuseQueryResult<DataType> {
+        Ok(DataType::UInt64)
+    }
+
+    /// The count of no rows is 0.
+    fn nullable(&self, _args: &[DataType]) -> FuseQueryResult<bool> {
+        Ok(false)
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], rows: usize) -> FuseQueryResult<()> {
+        let mut arrays = Vec::with_capacity(columns.len());
+        for column in columns {
+            arrays.push(column.to_array(rows)?);
+        }
+
+        if self.exact {
+            for row in 0..rows {
+                let mut values = Vec::with_capacity(arrays.len());
+                for array in &arrays {
+                    values.push(DataValue::try_from_array(array, row)?);
+                }
+                if values.iter().all(|v| !v.is_null()) {
+                    self.values.insert(serde_json::to_string(&values)?);
+                }
+            }
+            return Ok(());
+        }
+
+        let keys = datavalues::data_array_row_keys(&arrays)?;
+        for row in 0..keys.len() {
+            if !arrays.iter().any(|array| array.is_null(row)) {
+                self.insert_row_key(keys.key(row));
+            }
+        }
+        Ok(())
+    }
+
+    fn accumulate_row(&mut self, arrays: &[DataArrayRef], row: usize) -> FuseQueryResult<()> {
+        if self.exact {
+            let mut values = Vec::with_capacity(arrays.len());
+            for array in arrays {
+                values.push(DataValue::try_from_array(array, row)?);
+            }
+            if values.iter().all(|v| !v.is_null()) {
+                self.values.insert(serde_json::to_string(&values)?);
+            }
+            return Ok(());
+        }
+
+        if arrays.iter().any(|array| array.is_null(row)) {
+            return Ok(());
+        }
+        let mut key = vec![];
+        datavalues::data_array_row_key(arrays, row, &mut key)?;
+        self.insert_row_key(&key);
+        Ok(())
+    }
+
+    fn accumulate_result(&self) -> FuseQueryResult<DataValue> {
+        if self.exact {
+            let values = self
+                .values
+                .iter()
+                .map(|v| DataValue::String(Some(v.clone())))
+                .collect();
+            return Ok(DataValue::Struct(values));
+        }
+        let registers = self
+            .registers
+            .iter()
+            .enumerate()
+            .filter(|(_, rank)| **rank > 0)
+            .map(|(index, rank)| DataValue::UInt64(Some((index as u64) << 8 | *rank as u64)))
+            .collect();
+        Ok(DataValue::Struct(registers))
+    }
+
+    fn merge(&mut self, state: &DataValue) -> FuseQueryResult<()> {
+        let values = match state {
+            DataValue::Struct(values) => values,
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Invalid state of the function {}: {:?}",
+                    self, state
+                )))
+            }
+        };
+        for value in values {
+            match value {
+                DataValue::String(Some(v)) if self.exact => {
+                    self.values.insert(v.clone());
+                }
+                DataValue::UInt64(Some(v))
+                    if !self.exact && ((v >> 8) as usize) < HLL_REGISTERS =>
+                {
+                    let (index, rank) = ((v >> 8) as usize, (v & 0xff) as u8);
+                    self.registers[index] = self.registers[index].max(rank);
+                }
+                _ => {
+                    return Err(FuseQueryError::Internal(format!(
+                        "Invalid state of the function {}: {:?}",
+                        self, value
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_result(&self) -> FuseQueryResult<DataValue> {
+        if self.exact {
+            return Ok(DataValue::UInt64(Some(self.values.len() as u64)));
+        }
+        Ok(DataValue::UInt64(Some(self.estimate())))
+    }
+}
+
+impl fmt::Display for UniqFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.exact {
+            return write!(f, "uniqExact");
+        }
+        write!(f, "uniq")
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_uniq_function() -> crate::error::FuseQueryResult<()> {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::datablocks::DataBlock;
+    use crate::datavalues::*;
+    use crate::functions::*;
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("a", DataType::UInt64, false),
+        DataField::new("b", DataType::Utf8, true),
+    ]));
+    // The blocks of the two partial aggregations have the values 0..30000 and 20000..50000.
+    let blocks = [(0u64, 30000u64), (20000, 50000)]
+        .iter()
+        .map(|(start, end)| {
+            DataBlock::create(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt64Array::from((*start..*end).collect::<Vec<_>>())),
+                    Arc::new(StringArray::from(
+                        (*start..*end)
+                            .map(|v| match v % 10 {
+                                0 => None,
+                                _ => Some(if v % 2 == 0 { "x" } else { "y" }),
+                            })
+                            .collect::<Vec<_>>(),
+                    )),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let field_a = FieldFunction::try_create("a")?;
+    let field_b = FieldFunction::try_create("b")?;
+
+    let tests = vec![
+        ("uniq", vec![field_a.clone()], "uniq(a)", 50000, 0.05),
+        (
+            "approx_count_distinct",
+            vec![field_a.clone()],
+            "uniq(a)",
+            50000,
+            0.05,
+        ),
+        (
+            "uniqExact",
+            vec![field_a.clone()],
+            "uniqExact(a)",
+            50000,
+            0.0,
+        ),
+        ("uniq", vec![field_b.clone()], "uniq(b)", 2, 0.0),
+        ("uniqExact", vec![field_b.clone()], "uniqExact(b)", 2, 0.0),
+        // The rows with NULLs are not counted.
+        (
+            "uniqExact",
+            vec![field_a.clone(), field_b.clone()],
+            "uniqExact(a, b)",
+            45000,
+            0.0,
+        ),
+    ];
+
+    for (name, args, display, expect, error) in tests {
+        let mut final_func = FunctionFactory::get(name, &args)?;
+        for block in &blocks {
+            let mut func = FunctionFactory::get(name, &args)?;
+            func.accumulate(block)?;
+            final_func.merge_state(&func.accumulate_result()?)?;
+        }
+        assert_eq!(display, format!("{}", final_func));
+        assert_eq!(DataType::UInt64, final_func.return_type(&schema)?);
+        assert_eq!(false, final_func.nullable(&schema)?);
+        match final_func.merge_result()? {
+            DataValue::UInt64(Some(actual)) => {
+                let actual_error = (actual as f64 - expect as f64).abs() / expect as f64;
+                assert!(
+                    actual_error <= error,
+                    "{}: {} != {}",
+                    display,
+                    actual,
+                    expect
+                );
+            }
+            actual => panic!("{}: {:?} != {}", display, actual, expect),
+        }
+    }
+
+    // The count of no rows is 0.
+    let func = FunctionFactory::get("uniq", &[field_a])?;
+    assert_eq!(DataValue::UInt64(Some(0)), func.merge_result()?);
+
+    let result = FunctionFactory::get("uniqExact", &[]);
+    assert_eq!(
+        "Error during plan: Invalid number of arguments for function uniqExact: 0",
+        format!("{}", result.err().unwrap())
+    );
+    Ok(())
+}
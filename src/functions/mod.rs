@@ -4,26 +4,73 @@
 
 mod function_aggregator_test;
 mod function_arithmetic_test;
+mod function_cast_test;
 mod function_comparison_test;
+mod function_conditional_test;
+mod function_date_test;
 mod function_factory_test;
+mod function_hash_test;
+mod function_in_test;
+mod function_like_test;
 mod function_logic_test;
+mod function_math_test;
+mod function_nested_test;
+mod function_statistic_test;
+mod function_string_test;
+mod function_udf_test;
+mod function_unary_test;
+mod function_uniq_test;
 
 mod function;
 mod function_aggregator;
 mod function_alias;
 mod function_arithmetic;
+mod function_cast;
 mod function_comparison;
+mod function_conditional;
 mod function_constant;
+mod function_date;
 mod function_factory;
 mod function_field;
+mod function_hash;
+mod function_in;
+mod function_like;
 mod function_logic;
+mod function_math;
+mod function_nested;
+mod function_statistic;
+mod function_string;
+mod function_udf;
+mod function_unary;
+mod function_uniq;
 
-pub use self::function::Function;
+pub use self::function::{
+    AggregateFunction, Function, IAggregateFunction, IAggregateFunctionClone, IFunction,
+    IFunctionClone,
+};
 pub use self::function_aggregator::AggregatorFunction;
 pub use self::function_alias::AliasFunction;
 pub use self::function_arithmetic::ArithmeticFunction;
+pub use self::function_cast::CastFunction;
 pub use self::function_comparison::ComparisonFunction;
+pub use self::function_conditional::ConditionalFunction;
 pub use self::function_constant::ConstantFunction;
-pub use self::function_factory::ScalarFunctionFactory;
+pub use self::function_date::DateFunction;
+pub use self::function_factory::{
+    AggregateFactoryFunc, FactoryFunc, FunctionDescription, FunctionFactory,
+};
 pub use self::function_field::FieldFunction;
+pub use self::function_hash::HashFunction;
+pub use self::function_in::InFunction;
+pub use self::function_like::LikeFunction;
 pub use self::function_logic::LogicFunction;
+pub use self::function_math::MathFunction;
+pub use self::function_nested::NestedFunction;
+pub use self::function_statistic::StatisticFunction;
+pub use self::function_string::StringFunction;
+pub use self::function_udf::{
+    AccumulateUDAFFunc, AggregateUDF, FinalizeUDAFFunc, MergeUDAFFunc, ScalarUDF, ScalarUDFFunc,
+    UDAFFunction, UDFFunction,
+};
+pub use self::function_unary::UnaryFunction;
+pub use self::function_uniq::UniqFunction;
@@ -3,9 +3,12 @@
 // Code is licensed under AGPL License, Version 3.0.
 
 mod optimizer_filter_push_down_test;
+mod optimizer_top_n_test;
 
 mod optimizer;
 mod optimizer_filter_push_down;
+mod optimizer_top_n;
 
 pub use self::optimizer::{IOptimizer, Optimizer};
 pub use self::optimizer_filter_push_down::FilterPushDownOptimizer;
+pub use self::optimizer_top_n::TopNOptimizer;
@@ -3,7 +3,7 @@
 // Code is licensed under AGPL License, Version 3.0.
 
 use crate::error::FuseQueryResult;
-use crate::optimizers::FilterPushDownOptimizer;
+use crate::optimizers::{FilterPushDownOptimizer, TopNOptimizer};
 use crate::planners::{ExpressionPlan, PlanNode};
 use std::collections::HashMap;
 
@@ -20,6 +20,7 @@ impl Optimizer {
     pub fn create() -> Self {
         let mut optimizers: Vec<Box<dyn IOptimizer>> = vec![];
         optimizers.push(Box::new(FilterPushDownOptimizer::create()));
+        optimizers.push(Box::new(TopNOptimizer::create()));
         Optimizer { optimizers }
     }
 
@@ -45,12 +46,13 @@ impl Optimizer {
                     map.insert(field.name().clone(), expr);
                 })
             }
-            PlanNode::Aggregate(v) => Self::projections_to_map(v.input.as_ref(), map)?,
-            PlanNode::Filter(v) => Self::projections_to_map(v.input.as_ref(), map)?,
-            PlanNode::Limit(v) => Self::projections_to_map(v.input.as_ref(), map)?,
-            PlanNode::Explain(v) => Self::projections_to_map(v.plan.as_ref(), map)?,
-            PlanNode::Select(v) => Self::projections_to_map(v.plan.as_ref(), map)?,
-            _ => {}
+            // The inputs of the join/union/CTE have their own projections.
+            PlanNode::Join(_) | PlanNode::Union(_) | PlanNode::Materialize(_) => {}
+            other => {
+                for input in other.inputs() {
+                    Self::projections_to_map(input.as_ref(), map)?;
+                }
+            }
         }
         Ok(())
     }
@@ -70,7 +72,13 @@ impl Optimizer {
                 vec![left.as_ref().clone(), right.as_ref().clone()]
             }
             ExpressionPlan::Function { args, .. } => args.clone(),
+            ExpressionPlan::Cast { expr, .. } => vec![expr.as_ref().clone()],
+            ExpressionPlan::Sort { expr, .. } => vec![expr.as_ref().clone()],
             ExpressionPlan::Wildcard => vec![expr.clone()],
+            ExpressionPlan::ScalarSubquery(_) | ExpressionPlan::Exists(_) => vec![],
+            ExpressionPlan::InSubquery { expr, .. } => vec![expr.as_ref().clone()],
+            // The window function is computed by its own plan.
+            ExpressionPlan::Window { .. } => vec![],
         })
     }
 }
@@ -2,9 +2,10 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use crate::datavalues::DataSchemaRef;
 use crate::error::FuseQueryResult;
 use crate::optimizers::{IOptimizer, Optimizer};
-use crate::planners::{ExpressionPlan, FilterPlan, PlanNode};
+use crate::planners::{ExpressionPlan, FilterPlan, PlanNode, PlanRewriter, RewriteRecursion};
 use std::collections::HashMap;
 
 pub struct FilterPushDownOptimizer {}
@@ -16,20 +17,24 @@ impl FilterPushDownOptimizer {
 }
 
 /// replaces columns by its name on the projection.
+/// The columns of the input schema(the filter is above the projection) are kept.
 fn rewrite_alias_expr(
     expr: &ExpressionPlan,
     projection: &HashMap<String, ExpressionPlan>,
+    input_schema: &DataSchemaRef,
 ) -> FuseQueryResult<ExpressionPlan> {
     let expressions = Optimizer::expression_plan_children(expr)?;
 
     let expressions = expressions
         .iter()
-        .map(|e| rewrite_alias_expr(e, &projection))
+        .map(|e| rewrite_alias_expr(e, &projection, input_schema))
         .collect::<FuseQueryResult<Vec<_>>>()?;
 
     if let ExpressionPlan::Field(name) = expr {
-        if let Some(expr) = projection.get(name) {
-            return Ok(expr.clone());
+        if input_schema.field_with_name(name).is_err() {
+            if let Some(expr) = projection.get(name) {
+                return Ok(expr.clone());
+            }
         }
     }
     Ok(rebuild_alias_from_exprs(&expr, &expressions))
@@ -50,33 +55,80 @@ fn rebuild_alias_from_exprs(
             op: op.clone(),
             right: Box::new(expressions[1].clone()),
         },
-        ExpressionPlan::Function { op, .. } => ExpressionPlan::Function {
+        ExpressionPlan::Function { op, functions, .. } => ExpressionPlan::Function {
             op: op.clone(),
             args: expressions.to_vec(),
+            functions: functions.clone(),
+        },
+        ExpressionPlan::Cast { data_type, .. } => ExpressionPlan::Cast {
+            expr: Box::new(expressions[0].clone()),
+            data_type: data_type.clone(),
+        },
+        ExpressionPlan::InSubquery {
+            subquery, negated, ..
+        } => ExpressionPlan::InSubquery {
+            expr: Box::new(expressions[0].clone()),
+            subquery: subquery.clone(),
+            negated: *negated,
+        },
+        ExpressionPlan::Sort {
+            asc, nulls_first, ..
+        } => ExpressionPlan::Sort {
+            expr: Box::new(expressions[0].clone()),
+            asc: *asc,
+            nulls_first: *nulls_first,
         },
         other => other.clone(),
     }
 }
 
+struct FilterPushDownRewriter {
+    projection_map: HashMap<String, ExpressionPlan>,
+}
+
+impl PlanRewriter for FilterPushDownRewriter {
+    fn pre_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<RewriteRecursion> {
+        Ok(match plan {
+            PlanNode::Join(_) | PlanNode::Union(_) | PlanNode::Materialize(_) => {
+                RewriteRecursion::Mutate
+            }
+            _ => RewriteRecursion::Continue,
+        })
+    }
+
+    fn mutate(&mut self, plan: PlanNode) -> FuseQueryResult<PlanNode> {
+        match plan {
+            PlanNode::Filter(filter) => Ok(PlanNode::Filter(FilterPlan {
+                predicate: rewrite_alias_expr(
+                    &filter.predicate,
+                    &self.projection_map,
+                    &filter.input.schema(),
+                )?,
+                input: filter.input,
+            })),
+            // The inputs of the join/union/CTE are optimized with their own projections.
+            PlanNode::Join(_) | PlanNode::Union(_) | PlanNode::Materialize(_) => {
+                let children = plan
+                    .inputs()
+                    .iter()
+                    .map(|input| FilterPushDownOptimizer::create().optimize(input))
+                    .collect::<FuseQueryResult<Vec<_>>>()?;
+                plan.with_new_children(&children)
+            }
+            other => Ok(other),
+        }
+    }
+}
+
 impl IOptimizer for FilterPushDownOptimizer {
     fn name(&self) -> &str {
         "FilterPushDown"
     }
 
     fn optimize(&mut self, plan: &PlanNode) -> FuseQueryResult<PlanNode> {
-        let mut plans = plan.plan_to_list()?;
-        let projection_map = Optimizer::projection_to_map(plan)?;
-
-        for plan in plans.iter_mut() {
-            if let PlanNode::Filter(filter) = plan {
-                let rewritten = rewrite_alias_expr(&filter.predicate, &projection_map)?;
-                let new_filter = FilterPlan {
-                    predicate: rewritten,
-                    input: filter.input.clone(),
-                };
-                *filter = new_filter;
-            }
-        }
-        PlanNode::plan_list_to_node(&plans)
+        let mut rewriter = FilterPushDownRewriter {
+            projection_map: Optimizer::projection_to_map(plan)?,
+        };
+        plan.rewrite(&mut rewriter)
     }
 }
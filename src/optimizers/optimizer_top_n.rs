@@ -0,0 +1,62 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use crate::error::FuseQueryResult;
+use crate::optimizers::IOptimizer;
+use crate::planners::{PlanNode, PlanRewriter, ProjectionPlan, TopNPlan};
+
+/// Fuse the Sort and the Limit above it into a TopN,
+/// the projections between them don't change the rows.
+pub struct TopNOptimizer {}
+
+impl TopNOptimizer {
+    pub fn create() -> Self {
+        TopNOptimizer {}
+    }
+}
+
+struct TopNRewriter {}
+
+impl TopNRewriter {
+    /// Replace the Sort under the projections by the TopN.
+    fn fuse(plan: &PlanNode, n: usize) -> Option<PlanNode> {
+        match plan {
+            PlanNode::Sort(sort) => Some(PlanNode::TopN(TopNPlan {
+                order_by: sort.order_by.clone(),
+                n,
+                input: sort.input.clone(),
+            })),
+            PlanNode::Projection(projection) => Self::fuse(&projection.input, n).map(|input| {
+                PlanNode::Projection(ProjectionPlan {
+                    input: Arc::new(input),
+                    ..projection.clone()
+                })
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl PlanRewriter for TopNRewriter {
+    fn mutate(&mut self, plan: PlanNode) -> FuseQueryResult<PlanNode> {
+        if let PlanNode::Limit(ref limit) = plan {
+            if let Some(fused) = Self::fuse(&limit.input, limit.n) {
+                return Ok(fused);
+            }
+        }
+        Ok(plan)
+    }
+}
+
+impl IOptimizer for TopNOptimizer {
+    fn name(&self) -> &str {
+        "TopN"
+    }
+
+    fn optimize(&mut self, plan: &PlanNode) -> FuseQueryResult<PlanNode> {
+        plan.rewrite(&mut TopNRewriter {})
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_top_n_optimizer() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::optimizers::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number+1 as c from system.numbers_mt order by number desc limit 10",
+    )?;
+
+    let mut top_n = TopNOptimizer::create();
+    let optimized = top_n.optimize(&plan)?;
+    let expect = "\
+    └─ Projection: (number + 1) as c\
+    \n  └─ TopN: 10 order by: number DESC\
+    \n    └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    // The limit is not above the sort.
+    let plan =
+        Planner::new().build_from_sql(ctx, "select number from system.numbers_mt limit 10")?;
+    let optimized = top_n.optimize(&plan)?;
+    let expect = "\
+    └─ Limit: 10\
+    \n  └─ Projection: number\
+    \n    └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 // Code is licensed under AGPL License, Version 3.0.
 
 mod plan_filter_test;
+mod plan_rewriter_test;
 mod plan_select_test;
 
 mod parser;
@@ -18,13 +19,21 @@ mod plan_expression_field;
 mod plan_expression_function;
 mod plan_filter;
 mod plan_fragment;
+mod plan_join;
 mod plan_limit;
+mod plan_materialize;
 mod plan_node;
 mod plan_parser;
 mod plan_projection;
 mod plan_read_datasource;
+mod plan_rewriter;
 mod plan_scan;
 mod plan_select;
+mod plan_sort;
+mod plan_top_n;
+mod plan_union;
+mod plan_visitor;
+mod plan_window;
 mod planner;
 
 pub use self::parser::{DFExplainPlan, DFParser, DFStatement};
@@ -38,10 +47,18 @@ pub use self::plan_expression_field::field;
 pub use self::plan_expression_function::add;
 pub use self::plan_filter::FilterPlan;
 pub use self::plan_fragment::PlanFragment;
+pub use self::plan_join::{JoinPlan, JoinType};
 pub use self::plan_limit::LimitPlan;
+pub use self::plan_materialize::MaterializePlan;
 pub use self::plan_node::PlanNode;
 pub use self::plan_projection::ProjectionPlan;
 pub use self::plan_read_datasource::ReadDataSourcePlan;
+pub use self::plan_rewriter::{PlanRewriter, RewriteRecursion};
 pub use self::plan_scan::ScanPlan;
 pub use self::plan_select::SelectPlan;
+pub use self::plan_sort::SortPlan;
+pub use self::plan_top_n::TopNPlan;
+pub use self::plan_union::UnionPlan;
+pub use self::plan_visitor::PlanVisitor;
+pub use self::plan_window::{WindowFrame, WindowFrameBound, WindowFrameUnits, WindowPlan};
 pub use self::planner::Planner;
@@ -84,10 +84,51 @@ impl DFParser {
         let mut tokenizer = Tokenizer::new(dialect, sql);
         let tokens = tokenizer.tokenize()?;
         Ok(DFParser {
-            parser: Parser::new(tokens),
+            parser: Parser::new(Self::rewrite_brackets(tokens)?),
         })
     }
 
+    /// Rewrite the array literal `[x, ...]` to `array(x, ...)` and the subscript `x[i]` to
+    /// `arrayElement(x, i)`, sqlparser has no syntax for them.
+    fn rewrite_brackets(tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+        let mut rewritten: Vec<Token> = Vec::with_capacity(tokens.len());
+        let mut depth = 0;
+        for token in tokens {
+            match token {
+                Token::LBracket => {
+                    let start = match rewritten.iter().rposition(|t| !is_whitespace(t)) {
+                        Some(end) if ends_operand(&rewritten[end]) => {
+                            operand_start(&rewritten, end)
+                        }
+                        _ => None,
+                    };
+                    match start {
+                        Some(start) => {
+                            rewritten.insert(start, Token::LParen);
+                            rewritten.insert(start, Token::make_word("arrayElement", None));
+                            rewritten.push(Token::Comma);
+                        }
+                        None => {
+                            rewritten.push(Token::make_word("array", None));
+                            rewritten.push(Token::LParen);
+                        }
+                    }
+                    depth += 1;
+                }
+                Token::RBracket if depth > 0 => {
+                    rewritten.push(Token::RParen);
+                    depth -= 1;
+                }
+                Token::RBracket => return parser_err!("Unexpected ']'"),
+                token => rewritten.push(token),
+            }
+        }
+        if depth > 0 {
+            return parser_err!("Expected ']', found: EOF");
+        }
+        Ok(rewritten)
+    }
+
     /// Parse a SQL statement and produce a set of statements with dialect
     pub fn parse_sql(sql: &str) -> Result<Vec<DFStatement>, ParserError> {
         let dialect = &GenericDialect {};
@@ -294,6 +335,77 @@ impl DFParser {
     }
 }
 
+fn is_whitespace(token: &Token) -> bool {
+    matches!(token, Token::Whitespace(_))
+}
+
+// The keywords which are followed by an expression, `[` after them is an array literal.
+const EXPRESSION_KEYWORDS: &[Keyword] = &[
+    Keyword::SELECT,
+    Keyword::WHERE,
+    Keyword::HAVING,
+    Keyword::BY,
+    Keyword::ON,
+    Keyword::AND,
+    Keyword::OR,
+    Keyword::NOT,
+    Keyword::CASE,
+    Keyword::WHEN,
+    Keyword::THEN,
+    Keyword::ELSE,
+    Keyword::DISTINCT,
+    Keyword::ALL,
+    Keyword::LIKE,
+    Keyword::BETWEEN,
+    Keyword::IN,
+    Keyword::IS,
+    Keyword::LIMIT,
+    Keyword::OFFSET,
+];
+
+// Whether the token ends an operand, then `[` after it is a subscript.
+fn ends_operand(token: &Token) -> bool {
+    match token {
+        Token::Word(w) => w.quote_style.is_some() || !EXPRESSION_KEYWORDS.contains(&w.keyword),
+        Token::Number(_) | Token::SingleQuotedString(_) | Token::RParen => true,
+        _ => false,
+    }
+}
+
+// The start of the operand which ends at `end`: an identifier such as a.b,
+// a function call or a parenthesized expression, or a literal.
+fn operand_start(tokens: &[Token], end: usize) -> Option<usize> {
+    let previous = |i: usize| tokens[..i].iter().rposition(|t| !is_whitespace(t));
+    let mut start = end;
+    if tokens[end] == Token::RParen {
+        let mut depth = 0;
+        loop {
+            match tokens[start] {
+                Token::RParen => depth += 1,
+                Token::LParen => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+            start = start.checked_sub(1)?;
+        }
+        match previous(start) {
+            Some(i) if matches!(tokens[i], Token::Word(_)) && ends_operand(&tokens[i]) => start = i,
+            _ => return Some(start),
+        }
+    }
+    if matches!(tokens[start], Token::Word(_)) {
+        while let Some(i) = previous(start).filter(|i| tokens[*i] == Token::Period) {
+            match previous(i) {
+                Some(j) if matches!(tokens[j], Token::Word(_)) => start = j,
+                _ => break,
+            }
+        }
+    }
+    Some(start)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,4 +490,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn array_brackets() -> Result<(), ParserError> {
+        let tests = vec![
+            (
+                "SELECT [1, 2][1], a.b[2] FROM t WHERE has([a], 1)",
+                "SELECT arrayElement(array(1, 2), 1), arrayElement(a.b, 2) FROM t WHERE has(array(a), 1)",
+            ),
+            (
+                "SELECT f(x)[1][2], [[1]]",
+                "SELECT arrayElement(arrayElement(f(x), 1), 2), array(array(1))",
+            ),
+        ];
+        for (sql, expected) in tests {
+            let expected = DFParser::parse_sql(expected)?;
+            assert_eq!(DFParser::parse_sql(sql)?, expected, "{}", sql);
+        }
+
+        expect_parse_error("SELECT [1, 2", "Expected ']', found: EOF")?;
+        Ok(())
+    }
 }
@@ -7,8 +7,9 @@ use std::sync::Arc;
 use crate::datavalues::{DataField, DataSchema, DataSchemaRef};
 use crate::error::FuseQueryResult;
 use crate::planners::{
-    field, AggregatePlan, EmptyPlan, ExplainPlan, ExpressionPlan, FilterPlan, LimitPlan, PlanNode,
-    ProjectionPlan, ScanPlan, SelectPlan,
+    field, AggregatePlan, EmptyPlan, ExplainPlan, ExpressionPlan, FilterPlan, JoinPlan, JoinType,
+    LimitPlan, PlanNode, ProjectionPlan, ScanPlan, SelectPlan, SortPlan, TopNPlan, UnionPlan,
+    WindowPlan,
 };
 
 pub struct PlanBuilder {
@@ -108,6 +109,35 @@ impl PlanBuilder {
         })))
     }
 
+    /// Apply a hash join with the right plan, the right plan is the build side
+    pub fn join(
+        &self,
+        right: &PlanNode,
+        join_type: JoinType,
+        on: Vec<(ExpressionPlan, ExpressionPlan)>,
+    ) -> FuseQueryResult<Self> {
+        let schema = JoinPlan::join_schema(&self.plan.schema(), &right.schema(), join_type);
+        let on = JoinPlan::coerce_keys(&self.plan.schema(), &right.schema(), on)?;
+        Ok(Self::from(&PlanNode::Join(JoinPlan {
+            left: Arc::new(self.plan.clone()),
+            right: Arc::new(right.clone()),
+            on,
+            join_type,
+            schema,
+        })))
+    }
+
+    /// Apply a UNION ALL with the other inputs
+    pub fn union(&self, others: Vec<PlanNode>) -> FuseQueryResult<Self> {
+        let mut inputs = vec![self.plan.clone()];
+        inputs.extend(others);
+        let schema = UnionPlan::union_schema(&inputs)?;
+        Ok(Self::from(&PlanNode::Union(UnionPlan {
+            inputs: inputs.into_iter().map(Arc::new).collect(),
+            schema,
+        })))
+    }
+
     /// Apply a filter
     pub fn filter(&self, expr: ExpressionPlan) -> FuseQueryResult<Self> {
         Ok(Self::from(&PlanNode::Filter(FilterPlan {
@@ -124,6 +154,33 @@ impl PlanBuilder {
         })))
     }
 
+    /// Apply a sort
+    pub fn sort(&self, order_by: Vec<ExpressionPlan>) -> FuseQueryResult<Self> {
+        Ok(Self::from(&PlanNode::Sort(SortPlan {
+            order_by,
+            input: Arc::new(self.plan.clone()),
+        })))
+    }
+
+    /// Apply the window functions
+    pub fn window(&self, window_expr: Vec<ExpressionPlan>) -> FuseQueryResult<Self> {
+        let schema = WindowPlan::window_schema(&window_expr, &self.plan.schema())?;
+        Ok(Self::from(&PlanNode::Window(WindowPlan {
+            window_expr,
+            input: Arc::new(self.plan.clone()),
+            schema,
+        })))
+    }
+
+    /// Apply a sort with limit
+    pub fn top_n(&self, order_by: Vec<ExpressionPlan>, n: usize) -> FuseQueryResult<Self> {
+        Ok(Self::from(&PlanNode::TopN(TopNPlan {
+            order_by,
+            n,
+            input: Arc::new(self.plan.clone()),
+        })))
+    }
+
     pub fn select(&self) -> FuseQueryResult<Self> {
         Ok(Self::from(&PlanNode::Select(SelectPlan {
             plan: Box::new(self.plan.clone()),
@@ -4,7 +4,8 @@
 
 use std::fmt;
 
-use crate::planners::PlanNode;
+use crate::error::FuseQueryResult;
+use crate::planners::{PlanNode, PlanVisitor};
 
 /// Formatter settings for PlanStep debug.
 struct FormatterSettings {
@@ -41,7 +42,12 @@ impl PlanNode {
                     write!(f, "{:?}", v.aggr_expr[i])?;
                 }
                 for i in 0..v.group_expr.len() {
-                    if i > 0 {
+                    if i == 0 {
+                        if !v.aggr_expr.is_empty() {
+                            write!(f, " ")?;
+                        }
+                        write!(f, "group by: ")?;
+                    } else {
                         write!(f, ", ")?;
                     }
                     write!(f, "{:?}", v.group_expr[i])?;
@@ -50,6 +56,48 @@ impl PlanNode {
             }
             PlanNode::Filter(v) => write!(f, "{} Filter: {:?}", setting.prefix, v.predicate),
             PlanNode::Limit(v) => write!(f, "{} Limit: {}", setting.prefix, v.n),
+            PlanNode::Sort(v) => {
+                write!(f, "{} Sort: ", setting.prefix)?;
+                for i in 0..v.order_by.len() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", v.order_by[i])?;
+                }
+                write!(f, "")
+            }
+            PlanNode::TopN(v) => {
+                write!(f, "{} TopN: {} order by: ", setting.prefix, v.n)?;
+                for i in 0..v.order_by.len() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", v.order_by[i])?;
+                }
+                write!(f, "")
+            }
+            PlanNode::Window(v) => {
+                write!(f, "{} Window: ", setting.prefix)?;
+                for i in 0..v.window_expr.len() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", v.window_expr[i])?;
+                }
+                write!(f, "")
+            }
+            PlanNode::Join(v) => {
+                write!(f, "{} Join: {} ON ", setting.prefix, v.join_type)?;
+                for i in 0..v.on.len() {
+                    if i > 0 {
+                        write!(f, " AND ")?;
+                    }
+                    write!(f, "{:?} = {:?}", v.on[i].0, v.on[i].1)?;
+                }
+                write!(f, "")
+            }
+            PlanNode::Union(v) => write!(f, "{} Union: {} inputs", setting.prefix, v.inputs.len()),
+            PlanNode::Materialize(v) => write!(f, "{} Materialize: {}", setting.prefix, v.name),
             PlanNode::ReadSource(v) => write!(
                 f,
                 "{} ReadDataSource: scan parts [{}]{}",
@@ -69,20 +117,49 @@ impl PlanNode {
     }
 }
 
+/// Write the plan tree, the inputs are indented under the node.
+struct IndentVisitor<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    setting: FormatterSettings,
+}
+
+impl<'a, 'b> IndentVisitor<'a, 'b> {
+    fn is_displayed(plan: &PlanNode) -> bool {
+        !matches!(
+            plan,
+            PlanNode::Empty(_) | PlanNode::Scan(_) | PlanNode::Select(_) | PlanNode::Explain(_)
+        )
+    }
+}
+
+impl<'a, 'b> PlanVisitor for IndentVisitor<'a, 'b> {
+    fn pre_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool> {
+        if Self::is_displayed(plan) {
+            plan.format(self.f, &mut self.setting)?;
+            self.setting.indent += 1;
+        }
+        Ok(true)
+    }
+
+    fn post_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool> {
+        if Self::is_displayed(plan) {
+            self.setting.indent -= 1;
+        }
+        Ok(true)
+    }
+}
+
 impl fmt::Debug for PlanNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let setting = &mut FormatterSettings {
-            indent: 0,
-            indent_char: "  ",
-            prefix: "└─",
+        let mut visitor = IndentVisitor {
+            f,
+            setting: FormatterSettings {
+                indent: 0,
+                indent_char: "  ",
+                prefix: "└─",
+            },
         };
-
-        let mut plans = self.subplan_to_list().map_err(|_| std::fmt::Error)?;
-        plans.reverse();
-        for node in plans.iter() {
-            node.format(f, setting)?;
-            setting.indent += 1;
-        }
-        write!(f, "")
+        self.accept(&mut visitor).map_err(|_| fmt::Error)?;
+        Ok(())
     }
 }
@@ -3,12 +3,14 @@
 // Code is licensed under AGPL License, Version 3.0.
 
 use std::fmt;
+use std::sync::Arc;
 
-use crate::datavalues::{DataField, DataSchemaRef, DataValue};
+use crate::datavalues::{data_type_name, DataField, DataSchemaRef, DataType, DataValue};
 use crate::error::{FuseQueryError, FuseQueryResult};
 use crate::functions::{
-    AliasFunction, ConstantFunction, FieldFunction, Function, ScalarFunctionFactory,
+    AliasFunction, CastFunction, ConstantFunction, FieldFunction, Function, FunctionFactory,
 };
+use crate::planners::{PlanNode, WindowFrame, WindowPlan};
 
 #[derive(Clone)]
 pub enum ExpressionPlan {
@@ -23,15 +25,59 @@ pub enum ExpressionPlan {
     Function {
         op: String,
         args: Vec<ExpressionPlan>,
+        /// The registry of the context if the function is a UDF of the context,
+        /// None is the global registry.
+        functions: Option<Arc<FunctionFactory>>,
+    },
+    /// CAST(expr AS data_type)
+    Cast {
+        expr: Box<ExpressionPlan>,
+        data_type: DataType,
+    },
+    /// The sort key of ORDER BY.
+    Sort {
+        expr: Box<ExpressionPlan>,
+        asc: bool,
+        nulls_first: bool,
     },
     Wildcard,
+    /// The uncorrelated scalar subquery, the SelectExecutor evaluates it to a constant before the execution.
+    ScalarSubquery(Arc<PlanNode>),
+    /// The uncorrelated EXISTS subquery, evaluated like the scalar subquery.
+    Exists(Arc<PlanNode>),
+    /// The uncorrelated `expr [NOT] IN (subquery)` out of the conjunctions of WHERE,
+    /// the SelectExecutor evaluates the subquery to the values of in(expr, ...) or notIn(expr, ...).
+    InSubquery {
+        expr: Box<ExpressionPlan>,
+        subquery: Arc<PlanNode>,
+        negated: bool,
+    },
+    /// The window function `func OVER (PARTITION BY ... ORDER BY ... frame)`, computed by the WindowPlan.
+    Window {
+        func: Box<ExpressionPlan>,
+        partition_by: Vec<ExpressionPlan>,
+        /// The sort expressions, each one is an ExpressionPlan::Sort
+        order_by: Vec<ExpressionPlan>,
+        /// The frame of the aggregates, None is the default frame
+        frame: Option<WindowFrame>,
+    },
 }
 
 impl ExpressionPlan {
     pub fn to_field(&self, input_schema: &DataSchemaRef) -> FuseQueryResult<DataField> {
+        if let ExpressionPlan::Window { .. } = self {
+            return WindowPlan::window_field(self, input_schema);
+        }
+
         let func = self.to_function()?;
+        let name = match self {
+            ExpressionPlan::ScalarSubquery(_)
+            | ExpressionPlan::Exists(_)
+            | ExpressionPlan::InSubquery { .. } => format!("{:?}", self),
+            _ => format!("{:?}", func),
+        };
         Ok(DataField::new(
-            format!("{:?}", func).as_str(),
+            name.as_str(),
             func.return_type(&input_schema)?,
             func.nullable(&input_schema)?,
         ))
@@ -44,18 +90,37 @@ impl ExpressionPlan {
             ExpressionPlan::BinaryExpression { left, op, right } => {
                 let l = left.plan_to_function(depth)?;
                 let r = right.plan_to_function(depth + 1)?;
-                let mut func = ScalarFunctionFactory::get(op, &[l, r])?;
+                let mut func = FunctionFactory::get(op, &[l, r])?;
                 func.set_depth(depth);
                 Ok(func)
             }
-            ExpressionPlan::Function { op, args } => {
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => {
                 let mut funcs = Vec::with_capacity(args.len());
                 for arg in args {
-                    let mut func = arg.plan_to_function(depth + 1)?;
+                    let mut func = match arg {
+                        // count(*) counts the rows.
+                        ExpressionPlan::Wildcard => {
+                            ConstantFunction::try_create(DataValue::UInt64(Some(1)))?
+                        }
+                        _ => arg.plan_to_function(depth + 1)?,
+                    };
                     func.set_depth(depth);
                     funcs.push(func);
                 }
-                let mut func = ScalarFunctionFactory::get(op, &funcs)?;
+                let mut func = match functions {
+                    Some(functions) => functions.try_get(op, &funcs)?,
+                    None => FunctionFactory::get(op, &funcs)?,
+                };
+                func.set_depth(depth);
+                Ok(func)
+            }
+            ExpressionPlan::Cast { expr, data_type } => {
+                let mut func =
+                    CastFunction::try_create(expr.plan_to_function(depth + 1)?, data_type.clone())?;
                 func.set_depth(depth);
                 Ok(func)
             }
@@ -64,9 +129,23 @@ impl ExpressionPlan {
                 func.set_depth(depth);
                 AliasFunction::try_create(alias.clone(), func)
             }
+            ExpressionPlan::Sort { expr, .. } => expr.plan_to_function(depth),
+            // The subqueries are typed NULLs until they are evaluated.
+            ExpressionPlan::ScalarSubquery(plan) => {
+                let data_type = plan.schema().field(0).data_type().clone();
+                let nulls = DataValue::try_into_data_array(&[DataValue::Null], &data_type)?;
+                ConstantFunction::try_create(DataValue::try_from_array(&nulls, 0)?)
+            }
+            ExpressionPlan::Exists(_) | ExpressionPlan::InSubquery { .. } => {
+                ConstantFunction::try_create(DataValue::Boolean(None))
+            }
             ExpressionPlan::Wildcard => Err(FuseQueryError::Internal(
                 "Cannot transform wildcard to function".to_string(),
             )),
+            ExpressionPlan::Window { .. } => Err(FuseQueryError::Internal(format!(
+                "Window function {:?} must be computed by the window plan",
+                self
+            ))),
         }
     }
 
@@ -74,16 +153,129 @@ impl ExpressionPlan {
         self.plan_to_function(0)
     }
 
+    /// Whether the expression has any aggregate function.
     pub fn is_aggregate(&self) -> bool {
         match self {
             ExpressionPlan::Alias(_, expr) => expr.is_aggregate(),
+            ExpressionPlan::Sort { expr, .. } => expr.is_aggregate(),
+            ExpressionPlan::Cast { expr, .. } => expr.is_aggregate(),
+            ExpressionPlan::InSubquery { expr, .. } => expr.is_aggregate(),
             ExpressionPlan::BinaryExpression { left, right, .. } => {
                 left.is_aggregate() || right.is_aggregate()
             }
-            ExpressionPlan::Function { op, .. } => matches!(
-                op.to_lowercase().as_str(),
-                "max" | "min" | "avg" | "count" | "sum"
-            ),
+            ExpressionPlan::Function { args, .. } => {
+                self.is_aggregate_function() || args.iter().any(|arg| arg.is_aggregate())
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the expression is the call of an aggregate function.
+    pub fn is_aggregate_function(&self) -> bool {
+        match self {
+            ExpressionPlan::Function {
+                op,
+                functions: Some(functions),
+                ..
+            } => functions.is_aggregate_function(op),
+            ExpressionPlan::Function { op, .. } => FunctionFactory::is_aggregate(op),
+            _ => false,
+        }
+    }
+}
+
+/// The expressions are equal if they are the same tree, the subqueries are equal if they are the same plan.
+impl PartialEq for ExpressionPlan {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ExpressionPlan::Alias(a, x), ExpressionPlan::Alias(b, y)) => a == b && x == y,
+            (ExpressionPlan::Field(a), ExpressionPlan::Field(b)) => a == b,
+            (ExpressionPlan::Constant(a), ExpressionPlan::Constant(b)) => a == b,
+            (
+                ExpressionPlan::BinaryExpression { left, op, right },
+                ExpressionPlan::BinaryExpression {
+                    left: other_left,
+                    op: other_op,
+                    right: other_right,
+                },
+            ) => op == other_op && left == other_left && right == other_right,
+            (
+                ExpressionPlan::Function {
+                    op,
+                    args,
+                    functions,
+                },
+                ExpressionPlan::Function {
+                    op: other_op,
+                    args: other_args,
+                    functions: other_functions,
+                },
+            ) => {
+                let same_functions = match (functions, other_functions) {
+                    (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                    (a, b) => a.is_none() && b.is_none(),
+                };
+                op == other_op && args == other_args && same_functions
+            }
+            (
+                ExpressionPlan::Cast { expr, data_type },
+                ExpressionPlan::Cast {
+                    expr: other_expr,
+                    data_type: other_data_type,
+                },
+            ) => data_type == other_data_type && expr == other_expr,
+            (
+                ExpressionPlan::Sort {
+                    expr,
+                    asc,
+                    nulls_first,
+                },
+                ExpressionPlan::Sort {
+                    expr: other_expr,
+                    asc: other_asc,
+                    nulls_first: other_nulls_first,
+                },
+            ) => asc == other_asc && nulls_first == other_nulls_first && expr == other_expr,
+            (ExpressionPlan::Wildcard, ExpressionPlan::Wildcard) => true,
+            (ExpressionPlan::ScalarSubquery(a), ExpressionPlan::ScalarSubquery(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (ExpressionPlan::Exists(a), ExpressionPlan::Exists(b)) => Arc::ptr_eq(a, b),
+            (
+                ExpressionPlan::InSubquery {
+                    expr,
+                    subquery,
+                    negated,
+                },
+                ExpressionPlan::InSubquery {
+                    expr: other_expr,
+                    subquery: other_subquery,
+                    negated: other_negated,
+                },
+            ) => {
+                negated == other_negated
+                    && expr == other_expr
+                    && Arc::ptr_eq(subquery, other_subquery)
+            }
+            (
+                ExpressionPlan::Window {
+                    func,
+                    partition_by,
+                    order_by,
+                    frame,
+                },
+                ExpressionPlan::Window {
+                    func: other_func,
+                    partition_by: other_partition_by,
+                    order_by: other_order_by,
+                    frame: other_frame,
+                },
+            ) => {
+                func == other_func
+                    && partition_by == other_partition_by
+                    && order_by == other_order_by
+                    && frame == other_frame
+            }
             _ => false,
         }
     }
@@ -98,8 +290,56 @@ impl fmt::Debug for ExpressionPlan {
             ExpressionPlan::BinaryExpression { left, op, right } => {
                 write!(f, "({:?} {} {:?})", left, op, right,)
             }
-            ExpressionPlan::Function { op, args } => write!(f, "{}({:?})", op, args),
+            ExpressionPlan::Function { op, args, .. } => write!(f, "{}({:?})", op, args),
+            ExpressionPlan::Cast { expr, data_type } => {
+                write!(f, "cast({:?} as {})", expr, data_type_name(data_type))
+            }
+            ExpressionPlan::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => {
+                write!(f, "{:?} {}", expr, if *asc { "ASC" } else { "DESC" })?;
+                if *nulls_first {
+                    write!(f, " NULLS FIRST")?;
+                }
+                Ok(())
+            }
             ExpressionPlan::Wildcard => write!(f, "*"),
+            ExpressionPlan::ScalarSubquery(_) => write!(f, "(subquery)"),
+            ExpressionPlan::Exists(_) => write!(f, "exists(subquery)"),
+            ExpressionPlan::InSubquery { expr, negated, .. } => {
+                let op = if *negated { "notIn" } else { "in" };
+                write!(f, "{}({:?}, subquery)", op, expr)
+            }
+            ExpressionPlan::Window {
+                func,
+                partition_by,
+                order_by,
+                frame,
+            } => {
+                let join = |exprs: &[ExpressionPlan]| {
+                    exprs
+                        .iter()
+                        .map(|e| format!("{:?}", e))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                write!(f, "{:?} OVER (", func)?;
+                let mut delim = "";
+                if !partition_by.is_empty() {
+                    write!(f, "PARTITION BY {}", join(partition_by))?;
+                    delim = " ";
+                }
+                if !order_by.is_empty() {
+                    write!(f, "{}ORDER BY {}", delim, join(order_by))?;
+                    delim = " ";
+                }
+                if let Some(frame) = frame {
+                    write!(f, "{}{}", delim, frame)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
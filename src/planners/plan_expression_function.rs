@@ -47,4 +47,9 @@ impl ExpressionPlan {
     pub fn lt_eq(&self, other: ExpressionPlan) -> ExpressionPlan {
         binary_expr(self.clone(), "<=", other)
     }
+
+    /// And
+    pub fn and(&self, other: ExpressionPlan) -> ExpressionPlan {
+        binary_expr(self.clone(), "AND", other)
+    }
 }
@@ -0,0 +1,121 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datavalues;
+use crate::datavalues::{DataField, DataSchema, DataSchemaRef, DataType};
+use crate::error::FuseQueryResult;
+use crate::planners::{ExpressionPlan, PlanNode};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    /// The left rows which have at least one match.
+    Semi,
+    /// The left rows which have no match.
+    Anti,
+    /// The left rows which have no match by the NULL-aware last key, as `x NOT IN (subquery)`.
+    /// The row is not output if x is NULL or the subquery has NULL, unless the subquery is empty.
+    NullAwareAnti,
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JoinType::Inner => write!(f, "INNER"),
+            JoinType::Left => write!(f, "LEFT"),
+            JoinType::Right => write!(f, "RIGHT"),
+            JoinType::Full => write!(f, "FULL"),
+            JoinType::Semi => write!(f, "SEMI"),
+            JoinType::Anti => write!(f, "ANTI"),
+            JoinType::NullAwareAnti => write!(f, "NULL AWARE ANTI"),
+        }
+    }
+}
+
+/// Hash join on the equi-join keys, the right side is the build side.
+#[derive(Clone)]
+pub struct JoinPlan {
+    /// The probe side
+    pub left: Arc<PlanNode>,
+    /// The build side
+    pub right: Arc<PlanNode>,
+    /// The equi-join keys, (left key, right key)
+    pub on: Vec<(ExpressionPlan, ExpressionPlan)>,
+    pub join_type: JoinType,
+    pub schema: DataSchemaRef,
+}
+
+impl JoinPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    /// The left fields then the right fields, the outer side fields are nullable.
+    /// The SEMI/ANTI join outputs the left fields only.
+    pub fn join_schema(
+        left: &DataSchemaRef,
+        right: &DataSchemaRef,
+        join_type: JoinType,
+    ) -> DataSchemaRef {
+        if matches!(
+            join_type,
+            JoinType::Semi | JoinType::Anti | JoinType::NullAwareAnti
+        ) {
+            return left.clone();
+        }
+
+        let left_nullable = matches!(join_type, JoinType::Right | JoinType::Full);
+        let right_nullable = matches!(join_type, JoinType::Left | JoinType::Full);
+
+        let mut fields = Vec::with_capacity(left.fields().len() + right.fields().len());
+        for field in left.fields() {
+            fields.push(DataField::new(
+                field.name(),
+                field.data_type().clone(),
+                field.is_nullable() || left_nullable,
+            ));
+        }
+        for field in right.fields() {
+            fields.push(DataField::new(
+                field.name(),
+                field.data_type().clone(),
+                field.is_nullable() || right_nullable,
+            ));
+        }
+        Arc::new(DataSchema::new(fields))
+    }
+
+    /// Cast the keys of the different types to their common type,
+    /// so the equal values such as 1 and 1.0 have the same join key.
+    pub fn coerce_keys(
+        left: &DataSchemaRef,
+        right: &DataSchemaRef,
+        on: Vec<(ExpressionPlan, ExpressionPlan)>,
+    ) -> FuseQueryResult<Vec<(ExpressionPlan, ExpressionPlan)>> {
+        let cast = |expr: ExpressionPlan, from: &DataType, to: &DataType| match from == to {
+            true => expr,
+            false => ExpressionPlan::Cast {
+                expr: Box::new(expr),
+                data_type: to.clone(),
+            },
+        };
+        on.into_iter()
+            .map(|(left_key, right_key)| {
+                let left_type = left_key.to_field(left)?.data_type().clone();
+                let right_type = right_key.to_field(right)?.data_type().clone();
+                let data_type = datavalues::equal_coercion("=", &left_type, &right_type)?;
+                Ok((
+                    cast(left_key, &left_type, &data_type),
+                    cast(right_key, &right_type, &data_type),
+                ))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use crate::datavalues::DataSchemaRef;
+use crate::planners::PlanNode;
+
+/// The CTE which is executed once and shared by all its references.
+#[derive(Clone)]
+pub struct MaterializePlan {
+    /// The unique id of the CTE, the references have the same id
+    pub id: usize,
+    /// The CTE name
+    pub name: String,
+    /// The logical plan
+    pub input: Arc<PlanNode>,
+}
+
+impl MaterializePlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.input.schema()
+    }
+}
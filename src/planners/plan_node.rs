@@ -2,11 +2,14 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::sync::Arc;
+
 use crate::datavalues::DataSchemaRef;
 use crate::error::{FuseQueryError, FuseQueryResult};
 use crate::planners::{
-    AggregatePlan, EmptyPlan, ExplainPlan, FilterPlan, LimitPlan, PlanBuilder, ProjectionPlan,
-    ReadDataSourcePlan, ScanPlan, SelectPlan,
+    AggregatePlan, EmptyPlan, ExplainPlan, FilterPlan, JoinPlan, LimitPlan, MaterializePlan,
+    PlanRewriter, PlanVisitor, ProjectionPlan, ReadDataSourcePlan, RewriteRecursion, ScanPlan,
+    SelectPlan, SortPlan, TopNPlan, UnionPlan, WindowPlan,
 };
 
 #[derive(Clone)]
@@ -16,6 +19,12 @@ pub enum PlanNode {
     Aggregate(AggregatePlan),
     Filter(FilterPlan),
     Limit(LimitPlan),
+    Sort(SortPlan),
+    TopN(TopNPlan),
+    Window(WindowPlan),
+    Join(JoinPlan),
+    Union(UnionPlan),
+    Materialize(MaterializePlan),
     Scan(ScanPlan),
     ReadSource(ReadDataSourcePlan),
     Explain(ExplainPlan),
@@ -32,6 +41,12 @@ impl PlanNode {
             PlanNode::Aggregate(v) => v.schema(),
             PlanNode::Filter(v) => v.schema(),
             PlanNode::Limit(v) => v.schema(),
+            PlanNode::Sort(v) => v.schema(),
+            PlanNode::TopN(v) => v.schema(),
+            PlanNode::Window(v) => v.schema(),
+            PlanNode::Join(v) => v.schema(),
+            PlanNode::Union(v) => v.schema(),
+            PlanNode::Materialize(v) => v.schema(),
             PlanNode::ReadSource(v) => v.schema(),
             PlanNode::Select(v) => v.plan.schema(),
             PlanNode::Explain(_) => unimplemented!(),
@@ -46,119 +61,129 @@ impl PlanNode {
             PlanNode::Aggregate(_) => "AggregatePlan",
             PlanNode::Filter(_) => "FilterPlan",
             PlanNode::Limit(_) => "LimitPlan",
+            PlanNode::Sort(_) => "SortPlan",
+            PlanNode::TopN(_) => "TopNPlan",
+            PlanNode::Window(_) => "WindowPlan",
+            PlanNode::Join(_) => "JoinPlan",
+            PlanNode::Union(_) => "UnionPlan",
+            PlanNode::Materialize(_) => "MaterializePlan",
             PlanNode::ReadSource(_) => "ReadSourcePlan",
             PlanNode::Explain(_) => "ExplainPlan",
             PlanNode::Select(_) => "SelectPlan",
         }
     }
 
-    /// build plan node to list
-    /// with_parent only affected select/explain
-    fn build_plan_list(&self, with_parent: bool) -> FuseQueryResult<Vec<PlanNode>> {
-        let max_depth = 128;
-        let mut depth = 0;
-        let mut list = vec![];
-        let mut plan = self.clone();
-
-        loop {
-            if depth > max_depth {
-                return Err(FuseQueryError::Plan(format!(
-                    "PlanNode depth more than {}",
-                    max_depth
-                )));
-            }
-
-            match plan {
-                PlanNode::Aggregate(v) => {
-                    list.push(PlanNode::Aggregate(v.clone()));
-                    plan = v.input.as_ref().clone();
-                    depth += 1;
-                }
-                PlanNode::Projection(v) => {
-                    list.push(PlanNode::Projection(v.clone()));
-                    plan = v.input.as_ref().clone();
-                    depth += 1;
-                }
-                PlanNode::Filter(v) => {
-                    list.push(PlanNode::Filter(v.clone()));
-                    plan = v.input.as_ref().clone();
-                    depth += 1;
-                }
-                PlanNode::Limit(v) => {
-                    list.push(PlanNode::Limit(v.clone()));
-                    plan = v.input.as_ref().clone();
-                    depth += 1;
-                }
-                PlanNode::Select(v) => {
-                    if with_parent {
-                        list.push(PlanNode::Select(v.clone()));
-                    }
-                    plan = v.plan.as_ref().clone();
-                    depth += 1;
-                }
-                PlanNode::Explain(v) => {
-                    if with_parent {
-                        list.push(PlanNode::Explain(v.clone()));
-                    }
-                    plan = v.plan.as_ref().clone();
-                    depth += 1;
-                }
-
-                // Return.
-                PlanNode::Empty(_) => {
-                    break;
-                }
-                PlanNode::Scan(v) => {
-                    list.push(PlanNode::Scan(v));
-                    break;
-                }
-                PlanNode::ReadSource(v) => {
-                    list.push(PlanNode::ReadSource(v));
-                    break;
-                }
-            }
+    /// The input plans of the node, the join has two inputs.
+    pub fn inputs(&self) -> Vec<Arc<PlanNode>> {
+        match self {
+            PlanNode::Projection(v) => vec![v.input.clone()],
+            PlanNode::Aggregate(v) => vec![v.input.clone()],
+            PlanNode::Filter(v) => vec![v.input.clone()],
+            PlanNode::Limit(v) => vec![v.input.clone()],
+            PlanNode::Sort(v) => vec![v.input.clone()],
+            PlanNode::TopN(v) => vec![v.input.clone()],
+            PlanNode::Window(v) => vec![v.input.clone()],
+            PlanNode::Join(v) => vec![v.left.clone(), v.right.clone()],
+            PlanNode::Union(v) => v.inputs.clone(),
+            PlanNode::Materialize(v) => vec![v.input.clone()],
+            PlanNode::Select(v) => vec![Arc::new(v.plan.as_ref().clone())],
+            PlanNode::Explain(v) => vec![Arc::new(v.plan.as_ref().clone())],
+            PlanNode::Empty(_) | PlanNode::Scan(_) | PlanNode::ReadSource(_) => vec![],
         }
-        list.reverse();
-        Ok(list)
     }
 
-    pub fn subplan_to_list(&self) -> FuseQueryResult<Vec<PlanNode>> {
-        self.build_plan_list(false)
+    /// Create a new node of the same kind with the new inputs.
+    pub fn with_new_children(&self, children: &[PlanNode]) -> FuseQueryResult<PlanNode> {
+        let inputs = self.inputs();
+        if inputs.len() != children.len() {
+            return Err(FuseQueryError::Plan(format!(
+                "{} expects {} children, but got {}",
+                self.name(),
+                inputs.len(),
+                children.len()
+            )));
+        }
+
+        let child = |i: usize| Arc::new(children[i].clone());
+        Ok(match self {
+            PlanNode::Projection(v) => PlanNode::Projection(ProjectionPlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Aggregate(v) => PlanNode::Aggregate(AggregatePlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Filter(v) => PlanNode::Filter(FilterPlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Limit(v) => PlanNode::Limit(LimitPlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Sort(v) => PlanNode::Sort(SortPlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::TopN(v) => PlanNode::TopN(TopNPlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Window(v) => PlanNode::Window(WindowPlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Join(v) => PlanNode::Join(JoinPlan {
+                left: child(0),
+                right: child(1),
+                ..v.clone()
+            }),
+            PlanNode::Union(v) => PlanNode::Union(UnionPlan {
+                inputs: children.iter().cloned().map(Arc::new).collect(),
+                ..v.clone()
+            }),
+            PlanNode::Materialize(v) => PlanNode::Materialize(MaterializePlan {
+                input: child(0),
+                ..v.clone()
+            }),
+            PlanNode::Select(_) => PlanNode::Select(SelectPlan {
+                plan: Box::new(children[0].clone()),
+            }),
+            PlanNode::Explain(_) => PlanNode::Explain(ExplainPlan {
+                plan: Box::new(children[0].clone()),
+            }),
+            other => other.clone(),
+        })
     }
 
-    pub fn plan_to_list(&self) -> FuseQueryResult<Vec<PlanNode>> {
-        self.build_plan_list(true)
+    /// Visit the plan tree in depth-first order, see PlanVisitor.
+    pub fn accept<V: PlanVisitor>(&self, visitor: &mut V) -> FuseQueryResult<bool> {
+        if !visitor.pre_visit(self)? {
+            return Ok(false);
+        }
+
+        for input in self.inputs() {
+            if !input.accept(visitor)? {
+                return Ok(false);
+            }
+        }
+        visitor.post_visit(self)
     }
 
-    pub fn plan_list_to_node(list: &[PlanNode]) -> FuseQueryResult<PlanNode> {
-        let mut builder = PlanBuilder::empty(false);
-        for plan in list {
-            match plan {
-                PlanNode::Projection(v) => {
-                    builder = builder.project(v.expr.clone())?;
-                }
-                PlanNode::Aggregate(v) => {
-                    builder = builder.aggregate(v.group_expr.clone(), v.aggr_expr.clone())?;
-                }
-                PlanNode::Filter(v) => {
-                    builder = builder.filter(v.predicate.clone())?;
-                }
-                PlanNode::Limit(v) => {
-                    builder = builder.limit(v.n)?;
-                }
-                PlanNode::ReadSource(v) => {
-                    builder = PlanBuilder::from(&PlanNode::ReadSource(v.clone()))
-                }
-                PlanNode::Explain(_v) => {
-                    builder = builder.explain()?;
-                }
-                PlanNode::Select(_v) => {
-                    builder = builder.select()?;
-                }
-                PlanNode::Empty(_) => {}
-                PlanNode::Scan(_) => {}
+    /// Rewrite the plan tree from the bottom up, see PlanRewriter.
+    pub fn rewrite<R: PlanRewriter>(&self, rewriter: &mut R) -> FuseQueryResult<PlanNode> {
+        match rewriter.pre_visit(self)? {
+            RewriteRecursion::Stop => Ok(self.clone()),
+            RewriteRecursion::Mutate => rewriter.mutate(self.clone()),
+            RewriteRecursion::Continue => {
+                let children = self
+                    .inputs()
+                    .iter()
+                    .map(|input| input.rewrite(rewriter))
+                    .collect::<FuseQueryResult<Vec<_>>>()?;
+                rewriter.mutate(self.with_new_children(&children)?)
             }
         }
-        builder.build()
     }
 }
@@ -2,16 +2,25 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::contexts::FuseQueryContext;
-use crate::datavalues::{DataSchema, DataValue};
+use crate::datavalues::{
+    decimal_type, DataSchema, DataSchemaRef, DataType, DataValue, DataValueIntervalUnit, DATE_TYPE,
+    DECIMAL_MAX_PRECISION, TIMESTAMP_TYPE,
+};
 use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::CastFunction;
 use crate::planners::{
-    DFExplainPlan, DFParser, DFStatement, ExplainPlan, ExpressionPlan, PlanBuilder, PlanNode,
-    Planner, SelectPlan,
+    field, DFExplainPlan, DFParser, DFStatement, ExplainPlan, ExpressionPlan, JoinPlan, JoinType,
+    MaterializePlan, PlanBuilder, PlanNode, PlanRewriter, PlanVisitor, Planner, SelectPlan,
+    WindowFrame, WindowFrameBound, WindowFrameUnits,
 };
 
+/// The semi/anti join of a subquery: (right, join type, on).
+type SubqueryJoin = (PlanNode, JoinType, Vec<(ExpressionPlan, ExpressionPlan)>);
+
 impl Planner {
     pub fn build_from_sql(
         &self,
@@ -74,58 +83,284 @@ impl Planner {
         &self,
         ctx: Arc<FuseQueryContext>,
         query: &sqlparser::ast::Query,
+    ) -> FuseQueryResult<PlanNode> {
+        if query.ctes.is_empty() {
+            return self.query_body_to_plan(ctx, query);
+        }
+
+        // The CTEs are only visible in this query.
+        let scope = self.ctes.borrow().len();
+        let plan = self.with_ctes_to_plan(ctx, query);
+        self.ctes.borrow_mut().truncate(scope);
+        plan
+    }
+
+    /// Plan the CTEs as the named sub-plans, then plan the query with them.
+    ///
+    /// If the CTEs are materialized, the references share one MaterializePlan by id,
+    /// the CTE referenced only once is inlined again.
+    fn with_ctes_to_plan(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        query: &sqlparser::ast::Query,
+    ) -> FuseQueryResult<PlanNode> {
+        let materialize = ctx.get_materialize_cte()?;
+        let scope = self.ctes.borrow().len();
+        let first_id = self.next_cte_id.get();
+        for cte in &query.ctes {
+            let name = cte.alias.name.value.clone();
+            if self.ctes.borrow()[scope..].iter().any(|(n, _)| n == &name) {
+                return Err(FuseQueryError::Plan(format!(
+                    "WITH query name {} specified more than once",
+                    name
+                )));
+            }
+
+            let mut plan = self.query_to_plan(ctx.clone(), &cte.query)?;
+            if !cte.alias.columns.is_empty() {
+                let schema = plan.schema();
+                if schema.fields().len() != cte.alias.columns.len() {
+                    return Err(FuseQueryError::Plan(format!(
+                        "WITH query {} has {} columns available but {} columns specified",
+                        name,
+                        schema.fields().len(),
+                        cte.alias.columns.len()
+                    )));
+                }
+                let expr = schema
+                    .fields()
+                    .iter()
+                    .zip(&cte.alias.columns)
+                    .map(|(f, column)| {
+                        ExpressionPlan::Alias(column.value.clone(), Box::new(field(f.name())))
+                    })
+                    .collect();
+                plan = self.project(&plan, expr)?;
+            }
+            if materialize {
+                let id = self.next_cte_id.get();
+                self.next_cte_id.set(id + 1);
+                plan = PlanNode::Materialize(MaterializePlan {
+                    id,
+                    name: name.clone(),
+                    input: Arc::new(plan),
+                });
+            }
+            self.ctes.borrow_mut().push((name, plan));
+        }
+
+        let plan = self.query_body_to_plan(ctx, query)?;
+        if !materialize {
+            return Ok(plan);
+        }
+
+        let mut visitor = MaterializeVisitor {
+            references: HashMap::new(),
+        };
+        plan.accept(&mut visitor)?;
+        plan.rewrite(&mut MaterializeInliner {
+            references: visitor.references,
+            first_id,
+        })
+    }
+
+    /// Find the CTE in scope by name.
+    fn cte_to_plan(&self, name: &str) -> Option<PlanNode> {
+        self.ctes
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, plan)| plan.clone())
+    }
+
+    fn query_body_to_plan(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        query: &sqlparser::ast::Query,
     ) -> FuseQueryResult<PlanNode> {
         match &query.body {
             sqlparser::ast::SetExpr::Select(s) => {
-                self.select_to_plan(ctx, s.as_ref(), &query.limit)
+                self.select_to_plan(ctx, s.as_ref(), &query.order_by, &query.limit)
             }
+            _ => {
+                let plan = self.set_expr_to_plan(ctx.clone(), &query.body)?;
+
+                // The ORDER BY of a set operation can only reference the output columns.
+                let order_by_expr = query
+                    .order_by
+                    .iter()
+                    .map(|e| self.sql_order_by_to_rex(ctx.clone(), e, &plan.schema()))
+                    .collect::<FuseQueryResult<Vec<ExpressionPlan>>>()?;
+                let order_by_expr = Self::rebase_sort_exprs(&order_by_expr, &[], &plan)?;
+                let plan = self.sort(&plan, order_by_expr)?;
+                let plan = self.limit(ctx.clone(), &plan, &query.limit)?;
+
+                Ok(PlanNode::Select(SelectPlan {
+                    plan: Box::new(plan),
+                }))
+            }
+        }
+    }
+
+    /// Generate a logic plan from the body of a query
+    fn set_expr_to_plan(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        set_expr: &sqlparser::ast::SetExpr,
+    ) -> FuseQueryResult<PlanNode> {
+        match set_expr {
+            sqlparser::ast::SetExpr::Select(s) => self.select_to_plan(ctx, s.as_ref(), &[], &None),
+            sqlparser::ast::SetExpr::Query(q) => self.query_to_plan(ctx, q),
+            sqlparser::ast::SetExpr::SetOperation {
+                op: sqlparser::ast::SetOperator::Union,
+                all,
+                left,
+                right,
+            } => {
+                let mut inputs = vec![];
+                self.union_inputs(ctx.clone(), left, *all, &mut inputs)?;
+                self.union_inputs(ctx, right, *all, &mut inputs)?;
+
+                let others = inputs.split_off(1);
+                let plan = PlanBuilder::from(&inputs[0]).union(others)?.build()?;
+                if *all {
+                    Ok(plan)
+                } else {
+                    self.distinct(&plan)
+                }
+            }
+            sqlparser::ast::SetExpr::SetOperation { op, .. } => Err(FuseQueryError::Plan(format!(
+                "Set operation {} is not supported yet",
+                op
+            ))),
             _ => Err(FuseQueryError::Internal(format!(
                 "Query {} not implemented yet",
-                query.body
+                set_expr
             ))),
         }
     }
 
+    /// Flatten the nested UNIONs into the inputs of one union.
+    /// A UNION ALL child can always be flattened, and any UNION child can be flattened into a UNION DISTINCT.
+    fn union_inputs(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        set_expr: &sqlparser::ast::SetExpr,
+        parent_all: bool,
+        inputs: &mut Vec<PlanNode>,
+    ) -> FuseQueryResult<()> {
+        match set_expr {
+            sqlparser::ast::SetExpr::SetOperation {
+                op: sqlparser::ast::SetOperator::Union,
+                all,
+                left,
+                right,
+            } if *all || !parent_all => {
+                self.union_inputs(ctx.clone(), left, parent_all, inputs)?;
+                self.union_inputs(ctx, right, parent_all, inputs)
+            }
+            _ => {
+                inputs.push(self.set_expr_to_plan(ctx, set_expr)?);
+                Ok(())
+            }
+        }
+    }
+
     /// Generate a logic plan from an SQL select
     fn select_to_plan(
         &self,
         ctx: Arc<FuseQueryContext>,
         select: &sqlparser::ast::Select,
+        order_by: &[sqlparser::ast::OrderByExpr],
         limit: &Option<sqlparser::ast::Expr>,
     ) -> FuseQueryResult<PlanNode> {
-        if select.having.is_some() {
-            return Err(FuseQueryError::Internal(
-                "HAVING is not implemented yet".to_string(),
-            ));
-        }
-
         // from.
-        let plan = self.plan_tables_with_joins(ctx, &select.from)?;
+        let plan = self.plan_tables_with_joins(ctx.clone(), &select.from)?;
 
         // filter (also known as selection) first
-        let plan = self.filter(&plan, &select.selection)?;
+        let plan = self.filter(ctx.clone(), &plan, &select.selection)?;
 
         // projection.
         let projection_expr: Vec<ExpressionPlan> = select
             .projection
             .iter()
-            .map(|e| self.sql_select_to_rex(&e, &plan.schema()))
+            .map(|e| self.sql_select_to_rex(ctx.clone(), &e, &plan.schema()))
             .collect::<FuseQueryResult<Vec<ExpressionPlan>>>()?;
 
-        let aggr_expr: Vec<ExpressionPlan> = projection_expr
+        let has_aggregate = projection_expr.iter().any(|x| x.is_aggregate());
+
+        let having_expr = match select.having {
+            Some(ref having) => Some(self.sql_to_rex(ctx.clone(), having, &plan.schema())?),
+            None => None,
+        };
+
+        let having_aggregate = matches!(having_expr, Some(ref e) if e.is_aggregate());
+        let order_by_expr = order_by
             .iter()
-            .filter(|x| x.is_aggregate())
-            .cloned()
-            .collect();
+            .map(|e| self.sql_order_by_to_rex(ctx.clone(), e, &plan.schema()))
+            .collect::<FuseQueryResult<Vec<ExpressionPlan>>>()?;
 
-        let plan = if !select.group_by.is_empty() || !aggr_expr.is_empty() {
-            self.aggregate(&plan, projection_expr, aggr_expr, &select.group_by)?
+        let plan = if !select.group_by.is_empty() || has_aggregate || having_aggregate {
+            let mut window_expr = vec![];
+            for expr in projection_expr.iter().chain(&order_by_expr) {
+                Self::find_window_exprs(expr, &mut window_expr);
+            }
+            if !window_expr.is_empty() {
+                return Err(FuseQueryError::Plan(
+                    "Window functions with GROUP BY or aggregate functions are not supported yet"
+                        .to_string(),
+                ));
+            }
+
+            let plan = self.aggregate(
+                ctx.clone(),
+                &plan,
+                projection_expr.clone(),
+                &select.group_by,
+                having_expr,
+            )?;
+            let plan = if select.distinct {
+                self.distinct(&plan)?
+            } else {
+                plan
+            };
+            let order_by_expr = Self::rebase_sort_exprs(&order_by_expr, &projection_expr, &plan)?;
+            self.sort(&plan, order_by_expr)?
+        } else if having_expr.is_some() {
+            return Err(FuseQueryError::Plan(
+                "HAVING requires GROUP BY or aggregate functions".to_string(),
+            ));
         } else {
-            self.project(&plan, projection_expr)?
+            // The window functions are computed before the projection.
+            let (plan, projection_expr, order_by_expr) =
+                self.window(&plan, &projection_expr, &order_by_expr)?;
+            if select.distinct {
+                // SELECT DISTINCT is a grouping by all the projection columns.
+                let plan = self.project(&plan, projection_expr.clone())?;
+                let plan = self.distinct(&plan)?;
+                let order_by_expr =
+                    Self::rebase_sort_exprs(&order_by_expr, &projection_expr, &plan)?;
+                self.sort(&plan, order_by_expr)?
+            } else {
+                let output = self.project(&plan, projection_expr.clone())?;
+                match Self::rebase_sort_exprs(&order_by_expr, &projection_expr, &output) {
+                    Ok(order_by_expr) => self.sort(&output, order_by_expr)?,
+                    // Sort by the columns which are not in the projection, sort the input first.
+                    Err(_) => {
+                        let order_by_expr = order_by_expr
+                            .iter()
+                            .map(|e| Self::resolve_alias_expr(e, &projection_expr))
+                            .collect();
+                        let plan = self.sort(&plan, order_by_expr)?;
+                        self.project(&plan, projection_expr)?
+                    }
+                }
+            }
         };
 
         // limit.
-        let plan = self.limit(&plan, limit)?;
+        let plan = self.limit(ctx.clone(), &plan, limit)?;
 
         Ok(PlanNode::Select(SelectPlan {
             plan: Box::new(plan),
@@ -135,14 +370,17 @@ impl Planner {
     /// Generate a relational expression from a select SQL expression
     fn sql_select_to_rex(
         &self,
+        ctx: Arc<FuseQueryContext>,
         sql: &sqlparser::ast::SelectItem,
         schema: &DataSchema,
     ) -> FuseQueryResult<ExpressionPlan> {
         match sql {
-            sqlparser::ast::SelectItem::UnnamedExpr(expr) => self.sql_to_rex(expr, schema),
+            sqlparser::ast::SelectItem::UnnamedExpr(expr) => {
+                self.sql_to_rex(ctx.clone(), expr, schema)
+            }
             sqlparser::ast::SelectItem::ExprWithAlias { expr, alias } => Ok(ExpressionPlan::Alias(
                 alias.value.clone(),
-                Box::new(self.sql_to_rex(&expr, schema)?),
+                Box::new(self.sql_to_rex(ctx.clone(), &expr, schema)?),
             )),
             sqlparser::ast::SelectItem::Wildcard => Ok(ExpressionPlan::Wildcard),
             _ => Err(FuseQueryError::Internal(format!(
@@ -152,6 +390,20 @@ impl Planner {
         }
     }
 
+    /// Generate a sort expression from an ORDER BY item, the NULLs are last by default.
+    fn sql_order_by_to_rex(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        sql: &sqlparser::ast::OrderByExpr,
+        schema: &DataSchema,
+    ) -> FuseQueryResult<ExpressionPlan> {
+        Ok(ExpressionPlan::Sort {
+            expr: Box::new(self.sql_to_rex(ctx.clone(), &sql.expr, schema)?),
+            asc: sql.asc.unwrap_or(true),
+            nulls_first: sql.nulls_first.unwrap_or(false),
+        })
+    }
+
     fn plan_tables_with_joins(
         &self,
         ctx: Arc<FuseQueryContext>,
@@ -174,7 +426,283 @@ impl Planner {
         ctx: Arc<FuseQueryContext>,
         t: &sqlparser::ast::TableWithJoins,
     ) -> FuseQueryResult<PlanNode> {
-        self.create_relation(ctx, &t.relation)
+        if t.joins.is_empty() {
+            return self.create_relation(ctx, &t.relation);
+        }
+
+        let mut left = self.create_qualified_relation(ctx.clone(), &t.relation)?;
+        for join in &t.joins {
+            let right = self.create_qualified_relation(ctx.clone(), &join.relation)?;
+            left = self.join(ctx.clone(), &left, &right, &join.join_operator)?;
+        }
+        Ok(left)
+    }
+
+    /// Create the relation of JOIN, the columns are qualified by the table alias(or name).
+    fn create_qualified_relation(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        relation: &sqlparser::ast::TableFactor,
+    ) -> FuseQueryResult<PlanNode> {
+        let qualifier = match relation {
+            sqlparser::ast::TableFactor::Table { name, alias, .. } => match alias {
+                Some(alias) => alias.name.value.clone(),
+                None => name.0[name.0.len() - 1].value.clone(),
+            },
+            sqlparser::ast::TableFactor::Derived { alias, .. } => match alias {
+                Some(alias) => alias.name.value.clone(),
+                None => {
+                    return Err(FuseQueryError::Plan(
+                        "Subquery in JOIN must have an alias".to_string(),
+                    ))
+                }
+            },
+            // The nested join is already qualified.
+            sqlparser::ast::TableFactor::NestedJoin(_) => {
+                return self.create_relation(ctx, relation);
+            }
+        };
+
+        let plan = self.create_relation(ctx, relation)?;
+        let expr = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| {
+                ExpressionPlan::Alias(
+                    format!("{}.{}", qualifier, f.name()),
+                    Box::new(field(f.name())),
+                )
+            })
+            .collect();
+        self.project(&plan, expr)
+    }
+
+    /// Wrap the plans in a hash join, the ON must have the equi-join conditions.
+    fn join(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        left: &PlanNode,
+        right: &PlanNode,
+        operator: &sqlparser::ast::JoinOperator,
+    ) -> FuseQueryResult<PlanNode> {
+        let (join_type, constraint) = match operator {
+            sqlparser::ast::JoinOperator::Inner(c) => (JoinType::Inner, c),
+            sqlparser::ast::JoinOperator::LeftOuter(c) => (JoinType::Left, c),
+            sqlparser::ast::JoinOperator::RightOuter(c) => (JoinType::Right, c),
+            sqlparser::ast::JoinOperator::FullOuter(c) => (JoinType::Full, c),
+            other => {
+                return Err(FuseQueryError::Plan(format!(
+                    "Unsupported JOIN: {:?}",
+                    other
+                )))
+            }
+        };
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let mut on = vec![];
+        let mut filters = vec![];
+        match constraint {
+            sqlparser::ast::JoinConstraint::On(expr) => {
+                let schema = JoinPlan::join_schema(&left_schema, &right_schema, join_type);
+                let mut conditions = vec![];
+                Self::split_conjunction(expr, &mut conditions);
+                for condition in conditions {
+                    let mut key = None;
+                    if let sqlparser::ast::Expr::BinaryOp {
+                        left: l,
+                        op: sqlparser::ast::BinaryOperator::Eq,
+                        right: r,
+                    } = condition
+                    {
+                        let l = self.sql_to_rex(ctx.clone(), l, &schema)?;
+                        let r = self.sql_to_rex(ctx.clone(), r, &schema)?;
+                        if Self::expr_in_schema(&l, &left_schema)
+                            && Self::expr_in_schema(&r, &right_schema)
+                        {
+                            key = Some((l, r));
+                        } else if Self::expr_in_schema(&r, &left_schema)
+                            && Self::expr_in_schema(&l, &right_schema)
+                        {
+                            key = Some((r, l));
+                        }
+                    }
+
+                    match key {
+                        Some(key) => on.push(key),
+                        None => filters.push(self.sql_to_rex(ctx.clone(), condition, &schema)?),
+                    }
+                }
+            }
+            sqlparser::ast::JoinConstraint::Using(idents) => {
+                for ident in idents {
+                    on.push((
+                        field(&Self::resolve_column(&ident.value, &left_schema)?),
+                        field(&Self::resolve_column(&ident.value, &right_schema)?),
+                    ));
+                }
+            }
+            sqlparser::ast::JoinConstraint::Natural => {
+                return Err(FuseQueryError::Plan(
+                    "NATURAL JOIN is not supported".to_string(),
+                ))
+            }
+        }
+
+        if on.is_empty() {
+            return Err(FuseQueryError::Plan(
+                "JOIN requires at least one equi-join condition".to_string(),
+            ));
+        }
+        if !filters.is_empty() && join_type != JoinType::Inner {
+            return Err(FuseQueryError::Plan(format!(
+                "{} JOIN only supports the equi-join conditions",
+                join_type
+            )));
+        }
+
+        // The other conditions of INNER JOIN are filters over the join.
+        let mut builder = PlanBuilder::from(left).join(right, join_type, on)?;
+        for filter in filters {
+            builder = builder.filter(filter)?;
+        }
+        builder.build()
+    }
+
+    fn split_conjunction<'a>(
+        expr: &'a sqlparser::ast::Expr,
+        conditions: &mut Vec<&'a sqlparser::ast::Expr>,
+    ) {
+        match expr {
+            sqlparser::ast::Expr::BinaryOp {
+                left,
+                op: sqlparser::ast::BinaryOperator::And,
+                right,
+            } => {
+                Self::split_conjunction(left, conditions);
+                Self::split_conjunction(right, conditions);
+            }
+            sqlparser::ast::Expr::Nested(e) => Self::split_conjunction(e, conditions),
+            other => conditions.push(other),
+        }
+    }
+
+    /// The outer column of the correlated subquery may be qualified by the outer table alias,
+    /// which is not in the schema of a single table.
+    fn sql_outer_to_rex(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        sql: &sqlparser::ast::Expr,
+        schema: &DataSchema,
+    ) -> FuseQueryResult<ExpressionPlan> {
+        let expr = self.sql_to_rex(ctx, sql, schema)?;
+        Self::unqualify_expr(&expr, schema)
+    }
+
+    fn unqualify_expr(
+        expr: &ExpressionPlan,
+        schema: &DataSchema,
+    ) -> FuseQueryResult<ExpressionPlan> {
+        let unqualify = |e: &ExpressionPlan| Self::unqualify_expr(e, schema).map(Box::new);
+        Ok(match expr {
+            ExpressionPlan::Field(name) if schema.field_with_name(name).is_err() => {
+                match name.rfind('.') {
+                    Some(i) => field(&Self::resolve_column(&name[i + 1..], schema)?),
+                    None => expr.clone(),
+                }
+            }
+            ExpressionPlan::Alias(alias, expr) => {
+                ExpressionPlan::Alias(alias.clone(), unqualify(expr)?)
+            }
+            ExpressionPlan::BinaryExpression { left, op, right } => {
+                ExpressionPlan::BinaryExpression {
+                    left: unqualify(left)?,
+                    op: op.clone(),
+                    right: unqualify(right)?,
+                }
+            }
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => ExpressionPlan::Function {
+                op: op.clone(),
+                args: args
+                    .iter()
+                    .map(|arg| Self::unqualify_expr(arg, schema))
+                    .collect::<FuseQueryResult<Vec<_>>>()?,
+                functions: functions.clone(),
+            },
+            ExpressionPlan::Cast { expr, data_type } => ExpressionPlan::Cast {
+                expr: unqualify(expr)?,
+                data_type: data_type.clone(),
+            },
+            ExpressionPlan::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => ExpressionPlan::InSubquery {
+                expr: unqualify(expr)?,
+                subquery: subquery.clone(),
+                negated: *negated,
+            },
+            other => other.clone(),
+        })
+    }
+
+    /// Check the expression references at least one column.
+    fn expr_has_column(expr: &ExpressionPlan) -> bool {
+        match expr {
+            ExpressionPlan::Field(_) => true,
+            ExpressionPlan::Alias(_, expr) => Self::expr_has_column(expr),
+            ExpressionPlan::BinaryExpression { left, right, .. } => {
+                Self::expr_has_column(left) || Self::expr_has_column(right)
+            }
+            ExpressionPlan::Function { args, .. } => args.iter().any(Self::expr_has_column),
+            ExpressionPlan::Cast { expr, .. } => Self::expr_has_column(expr),
+            ExpressionPlan::InSubquery { expr, .. } => Self::expr_has_column(expr),
+            _ => false,
+        }
+    }
+
+    /// Check all the columns of the expression are in the schema.
+    fn expr_in_schema(expr: &ExpressionPlan, schema: &DataSchema) -> bool {
+        match expr {
+            ExpressionPlan::Field(name) => schema.field_with_name(name).is_ok(),
+            ExpressionPlan::Alias(_, expr) => Self::expr_in_schema(expr, schema),
+            ExpressionPlan::BinaryExpression { left, right, .. } => {
+                Self::expr_in_schema(left, schema) && Self::expr_in_schema(right, schema)
+            }
+            ExpressionPlan::Function { args, .. } => {
+                args.iter().all(|arg| Self::expr_in_schema(arg, schema))
+            }
+            ExpressionPlan::Cast { expr, .. } => Self::expr_in_schema(expr, schema),
+            ExpressionPlan::InSubquery { expr, .. } => Self::expr_in_schema(expr, schema),
+            _ => true,
+        }
+    }
+
+    /// Resolve the column name, a name without qualifier matches the qualified column.
+    fn resolve_column(name: &str, schema: &DataSchema) -> FuseQueryResult<String> {
+        if schema.field_with_name(name).is_ok() {
+            return Ok(name.to_string());
+        }
+
+        let suffix = format!(".{}", name);
+        let matched = schema
+            .fields()
+            .iter()
+            .filter(|f| f.name().ends_with(&suffix))
+            .collect::<Vec<_>>();
+        match matched.len() {
+            0 => Ok(name.to_string()),
+            1 => Ok(matched[0].name().clone()),
+            _ => Err(FuseQueryError::Plan(format!(
+                "Column reference '{}' is ambiguous",
+                name
+            ))),
+        }
     }
 
     fn create_relation(
@@ -184,6 +712,12 @@ impl Planner {
     ) -> FuseQueryResult<PlanNode> {
         match relation {
             sqlparser::ast::TableFactor::Table { name, args, .. } => {
+                if name.0.len() == 1 && args.is_empty() {
+                    if let Some(plan) = self.cte_to_plan(&name.0[0].value) {
+                        return Ok(plan);
+                    }
+                }
+
                 let mut db_name = ctx.get_current_database()?;
                 let mut table_name = name.to_string();
                 if name.0.len() == 2 {
@@ -195,7 +729,7 @@ impl Planner {
 
                 let mut table_args = None;
                 if !args.is_empty() {
-                    table_args = Some(self.sql_to_rex(&args[0], &schema)?);
+                    table_args = Some(self.sql_to_rex(ctx.clone(), &args[0], &schema)?);
                 }
 
                 let scan =
@@ -215,11 +749,20 @@ impl Planner {
     /// Generate a relational expression from a SQL expression
     pub fn sql_to_rex(
         &self,
+        ctx: Arc<FuseQueryContext>,
         sql: &sqlparser::ast::Expr,
         schema: &DataSchema,
     ) -> FuseQueryResult<ExpressionPlan> {
         match sql {
-            sqlparser::ast::Expr::Identifier(ref v) => Ok(ExpressionPlan::Field(v.clone().value)),
+            sqlparser::ast::Expr::Identifier(ref v) => Ok(ExpressionPlan::Field(
+                Self::resolve_column(&v.value, schema)?,
+            )),
+            sqlparser::ast::Expr::CompoundIdentifier(ref v) => Ok(ExpressionPlan::Field(
+                v.iter()
+                    .map(|ident| ident.value.clone())
+                    .collect::<Vec<_>>()
+                    .join("."),
+            )),
             sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(n)) => match n.parse::<i64>()
             {
                 Ok(n) => {
@@ -236,22 +779,275 @@ impl Planner {
             sqlparser::ast::Expr::Value(sqlparser::ast::Value::SingleQuotedString(s)) => {
                 Ok(ExpressionPlan::Constant(DataValue::String(Some(s.clone()))))
             }
+            sqlparser::ast::Expr::Value(sqlparser::ast::Value::Boolean(b)) => {
+                Ok(ExpressionPlan::Constant(DataValue::Boolean(Some(*b))))
+            }
+            sqlparser::ast::Expr::Value(sqlparser::ast::Value::Null) => {
+                Ok(ExpressionPlan::Constant(DataValue::Null))
+            }
+            // CASE is multiIf, the simple CASE compares the operand with each condition.
+            sqlparser::ast::Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => {
+                let mut args = Vec::with_capacity(conditions.len() * 2 + 1);
+                for (condition, result) in conditions.iter().zip(results.iter()) {
+                    let condition = self.sql_to_rex(ctx.clone(), condition, schema)?;
+                    let condition = match operand {
+                        Some(operand) => ExpressionPlan::BinaryExpression {
+                            op: "=".to_string(),
+                            left: Box::new(self.sql_to_rex(ctx.clone(), operand, schema)?),
+                            right: Box::new(condition),
+                        },
+                        None => condition,
+                    };
+                    args.push(condition);
+                    args.push(self.sql_to_rex(ctx.clone(), result, schema)?);
+                }
+                args.push(match else_result {
+                    Some(else_result) => self.sql_to_rex(ctx, else_result, schema)?,
+                    None => ExpressionPlan::Constant(DataValue::Null),
+                });
+                Ok(ExpressionPlan::Function {
+                    op: "multiIf".to_string(),
+                    args,
+                    functions: None,
+                })
+            }
+            sqlparser::ast::Expr::Value(sqlparser::ast::Value::Interval { .. }) => {
+                Err(FuseQueryError::Plan(format!(
+                    "The interval is only supported in the date arithmetic and toStartOfInterval: {}",
+                    sql
+                )))
+            }
+            sqlparser::ast::Expr::TypedString { data_type, value } => Ok(ExpressionPlan::Cast {
+                expr: Box::new(ExpressionPlan::Constant(DataValue::String(Some(
+                    value.clone(),
+                )))),
+                data_type: Self::sql_data_type_to_data_type(data_type)?,
+            }),
+            // The date +/- INTERVAL n unit is addUnits/subtractUnits.
             sqlparser::ast::Expr::BinaryOp { left, op, right } => {
+                match (op, left.as_ref(), right.as_ref()) {
+                    (
+                        sqlparser::ast::BinaryOperator::Plus,
+                        expr,
+                        sqlparser::ast::Expr::Value(interval @ sqlparser::ast::Value::Interval { .. }),
+                    )
+                    | (
+                        sqlparser::ast::BinaryOperator::Plus,
+                        sqlparser::ast::Expr::Value(interval @ sqlparser::ast::Value::Interval { .. }),
+                        expr,
+                    ) => {
+                        let (n, unit) = Self::sql_interval(interval)?;
+                        Ok(ExpressionPlan::Function {
+                            op: format!("add{}s", unit),
+                            args: vec![
+                                self.sql_to_rex(ctx, expr, schema)?,
+                                ExpressionPlan::Constant(DataValue::Int64(Some(n))),
+                            ],
+                            functions: None,
+                        })
+                    }
+                    (
+                        sqlparser::ast::BinaryOperator::Minus,
+                        expr,
+                        sqlparser::ast::Expr::Value(interval @ sqlparser::ast::Value::Interval { .. }),
+                    ) => {
+                        let (n, unit) = Self::sql_interval(interval)?;
+                        Ok(ExpressionPlan::Function {
+                            op: format!("subtract{}s", unit),
+                            args: vec![
+                                self.sql_to_rex(ctx, expr, schema)?,
+                                ExpressionPlan::Constant(DataValue::Int64(Some(n))),
+                            ],
+                            functions: None,
+                        })
+                    }
+                    _ => Ok(ExpressionPlan::BinaryExpression {
+                        op: format!("{}", op),
+                        left: Box::new(self.sql_to_rex(ctx.clone(), left, schema)?),
+                        right: Box::new(self.sql_to_rex(ctx.clone(), right, schema)?),
+                    }),
+                }
+            }
+            sqlparser::ast::Expr::UnaryOp { op, expr } => match (op, expr.as_ref()) {
+                (sqlparser::ast::UnaryOperator::Plus, _) => self.sql_to_rex(ctx, expr, schema),
+                // The negative number is a constant.
+                (
+                    sqlparser::ast::UnaryOperator::Minus,
+                    sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(n)),
+                ) => self.sql_to_rex(
+                    ctx,
+                    &sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(format!("-{}", n))),
+                    schema,
+                ),
+                (sqlparser::ast::UnaryOperator::Minus, _) => Ok(ExpressionPlan::Function {
+                    op: "negate".to_string(),
+                    args: vec![self.sql_to_rex(ctx, expr, schema)?],
+                    functions: None,
+                }),
+                (sqlparser::ast::UnaryOperator::Not, _) => Ok(ExpressionPlan::Function {
+                    op: "not".to_string(),
+                    args: vec![self.sql_to_rex(ctx, expr, schema)?],
+                    functions: None,
+                }),
+            },
+            sqlparser::ast::Expr::IsNull(expr) => Ok(ExpressionPlan::Function {
+                op: "isNull".to_string(),
+                args: vec![self.sql_to_rex(ctx, expr, schema)?],
+                functions: None,
+            }),
+            sqlparser::ast::Expr::IsNotNull(expr) => Ok(ExpressionPlan::Function {
+                op: "isNotNull".to_string(),
+                args: vec![self.sql_to_rex(ctx, expr, schema)?],
+                functions: None,
+            }),
+            // BETWEEN is low <= x AND x <= high, NOT BETWEEN is x < low OR x > high.
+            sqlparser::ast::Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let expr = self.sql_to_rex(ctx.clone(), expr, schema)?;
+                let (low_op, high_op, op) = if *negated {
+                    ("<", ">", "OR")
+                } else {
+                    (">=", "<=", "AND")
+                };
                 Ok(ExpressionPlan::BinaryExpression {
-                    op: format!("{}", op),
-                    left: Box::new(self.sql_to_rex(left, schema)?),
-                    right: Box::new(self.sql_to_rex(right, schema)?),
+                    op: op.to_string(),
+                    left: Box::new(ExpressionPlan::BinaryExpression {
+                        op: low_op.to_string(),
+                        left: Box::new(expr.clone()),
+                        right: Box::new(self.sql_to_rex(ctx.clone(), low, schema)?),
+                    }),
+                    right: Box::new(ExpressionPlan::BinaryExpression {
+                        op: high_op.to_string(),
+                        left: Box::new(expr),
+                        right: Box::new(self.sql_to_rex(ctx, high, schema)?),
+                    }),
+                })
+            }
+            // The IN list of constants is the in function compiled into a set,
+            // otherwise it is compared with each value.
+            sqlparser::ast::Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let expr = self.sql_to_rex(ctx.clone(), expr, schema)?;
+                let list = list
+                    .iter()
+                    .map(|e| self.sql_to_rex(ctx.clone(), e, schema))
+                    .collect::<FuseQueryResult<Vec<_>>>()?;
+                if list
+                    .iter()
+                    .all(|e| matches!(e, ExpressionPlan::Constant(_)))
+                {
+                    let mut args = vec![expr];
+                    args.extend(list);
+                    return Ok(ExpressionPlan::Function {
+                        op: if *negated { "notIn" } else { "in" }.to_string(),
+                        args,
+                        functions: None,
+                    });
+                }
+
+                let (cmp_op, op) = if *negated { ("!=", "AND") } else { ("=", "OR") };
+                let mut exprs = list
+                    .into_iter()
+                    .map(|value| ExpressionPlan::BinaryExpression {
+                        op: cmp_op.to_string(),
+                        left: Box::new(expr.clone()),
+                        right: Box::new(value),
+                    });
+                let first = exprs.next().ok_or_else(|| {
+                    FuseQueryError::Plan("The IN list cannot be empty".to_string())
+                })?;
+                Ok(
+                    exprs.fold(first, |acc, e| ExpressionPlan::BinaryExpression {
+                        op: op.to_string(),
+                        left: Box::new(acc),
+                        right: Box::new(e),
+                    }),
+                )
+            }
+            sqlparser::ast::Expr::Nested(e) => self.sql_to_rex(ctx.clone(), e, schema),
+            sqlparser::ast::Expr::Cast { expr, data_type } => Ok(ExpressionPlan::Cast {
+                expr: Box::new(self.sql_to_rex(ctx, expr, schema)?),
+                data_type: Self::sql_data_type_to_data_type(data_type)?,
+            }),
+            sqlparser::ast::Expr::Wildcard => Ok(ExpressionPlan::Wildcard),
+            sqlparser::ast::Expr::Subquery(q) => {
+                let plan = self.query_to_plan(ctx, q)?;
+                if plan.schema().fields().len() != 1 {
+                    return Err(FuseQueryError::Plan(
+                        "Scalar subquery must return exactly one column".to_string(),
+                    ));
+                }
+                Ok(ExpressionPlan::ScalarSubquery(Arc::new(plan)))
+            }
+            sqlparser::ast::Expr::Exists(q) => Ok(ExpressionPlan::Exists(Arc::new(
+                self.query_to_plan(ctx, q)?,
+            ))),
+            // The IN subquery out of the conjunctions of WHERE must be uncorrelated.
+            sqlparser::ast::Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                let plan = self.query_to_plan(ctx.clone(), subquery)?;
+                if plan.schema().fields().len() != 1 {
+                    return Err(FuseQueryError::Plan(
+                        "IN subquery must return exactly one column".to_string(),
+                    ));
+                }
+                Ok(ExpressionPlan::InSubquery {
+                    expr: Box::new(self.sql_to_rex(ctx, expr, schema)?),
+                    subquery: Arc::new(plan),
+                    negated: *negated,
                 })
             }
-            sqlparser::ast::Expr::Nested(e) => self.sql_to_rex(e, schema),
             sqlparser::ast::Expr::Function(e) => {
                 let mut args = Vec::with_capacity(e.args.len());
                 for arg in &e.args {
-                    args.push(self.sql_to_rex(arg, schema)?);
+                    // The interval argument of toStartOfInterval is the number and the unit.
+                    if let sqlparser::ast::Expr::Value(
+                        interval @ sqlparser::ast::Value::Interval { .. },
+                    ) = arg
+                    {
+                        let (n, unit) = Self::sql_interval(interval)?;
+                        args.push(ExpressionPlan::Constant(DataValue::Int64(Some(n))));
+                        args.push(ExpressionPlan::Constant(DataValue::String(Some(
+                            unit.to_string(),
+                        ))));
+                        continue;
+                    }
+                    args.push(self.sql_to_rex(ctx.clone(), arg, schema)?);
+                }
+                if let Some(ref over) = e.over {
+                    if e.distinct {
+                        return Err(FuseQueryError::Plan(
+                            "DISTINCT is not supported in window functions".to_string(),
+                        ));
+                    }
+                    return self.sql_window_to_rex(ctx, e.name.to_string(), args, over, schema);
                 }
+                // The DISTINCT aggregate is the -Distinct combinator, count(DISTINCT x) is countDistinct(x).
+                let op = if e.distinct {
+                    format!("{}Distinct", e.name)
+                } else {
+                    e.name.to_string()
+                };
+                let functions = ctx.get_function_factory(&op)?;
                 Ok(ExpressionPlan::Function {
-                    op: e.name.to_string(),
+                    op,
                     args,
+                    functions,
                 })
             }
             _ => Err(FuseQueryError::Plan(format!(
@@ -261,18 +1057,406 @@ impl Planner {
         }
     }
 
-    /// Apply a filter to the plan
+    /// The SQL types and the type names such as UInt64 and String.
+    fn sql_data_type_to_data_type(
+        data_type: &sqlparser::ast::DataType,
+    ) -> FuseQueryResult<DataType> {
+        match data_type {
+            sqlparser::ast::DataType::Boolean => Ok(DataType::Boolean),
+            sqlparser::ast::DataType::SmallInt => Ok(DataType::Int16),
+            sqlparser::ast::DataType::Int => Ok(DataType::Int32),
+            sqlparser::ast::DataType::BigInt => Ok(DataType::Int64),
+            sqlparser::ast::DataType::Float(_) | sqlparser::ast::DataType::Real => {
+                Ok(DataType::Float32)
+            }
+            sqlparser::ast::DataType::Double => Ok(DataType::Float64),
+            sqlparser::ast::DataType::Date => Ok(DATE_TYPE),
+            sqlparser::ast::DataType::Timestamp => Ok(TIMESTAMP_TYPE),
+            // The DECIMAL is DECIMAL(10, 0), the precision is at most 18.
+            sqlparser::ast::DataType::Decimal(precision, scale) => {
+                let precision = precision.unwrap_or(10) as usize;
+                let scale = scale.unwrap_or(0) as usize;
+                if precision == 0 || precision > DECIMAL_MAX_PRECISION || scale > precision {
+                    return Err(FuseQueryError::Plan(format!(
+                        "Unsupported data type: {}",
+                        data_type
+                    )));
+                }
+                Ok(decimal_type(precision, scale))
+            }
+            sqlparser::ast::DataType::Char(_)
+            | sqlparser::ast::DataType::Varchar(_)
+            | sqlparser::ast::DataType::Text => Ok(DataType::Utf8),
+            sqlparser::ast::DataType::Custom(name) => {
+                CastFunction::data_type_from_name(&name.to_string()).ok_or_else(|| {
+                    FuseQueryError::Plan(format!("Unsupported data type: {}", data_type))
+                })
+            }
+            _ => Err(FuseQueryError::Plan(format!(
+                "Unsupported data type: {}",
+                data_type
+            ))),
+        }
+    }
+
+    /// The number and the unit of INTERVAL '1' DAY or INTERVAL '1 day'.
+    fn sql_interval(
+        value: &sqlparser::ast::Value,
+    ) -> FuseQueryResult<(i64, DataValueIntervalUnit)> {
+        let interval = match value {
+            sqlparser::ast::Value::Interval {
+                value,
+                leading_field: Some(field),
+                last_field: None,
+                ..
+            } => Some((value.trim().to_string(), field.to_string())),
+            sqlparser::ast::Value::Interval {
+                value,
+                leading_field: None,
+                ..
+            } => {
+                let parts = value.split_whitespace().collect::<Vec<_>>();
+                match parts.as_slice() {
+                    [n, unit] => Some((n.to_string(), unit.to_string())),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        interval
+            .and_then(|(n, unit)| {
+                let n = n.parse::<i64>().ok()?;
+                DataValueIntervalUnit::from_name(&unit).map(|unit| (n, unit))
+            })
+            .ok_or_else(|| FuseQueryError::Plan(format!("Unsupported interval: {}", value)))
+    }
+
+    /// Generate a window function, the frame only applies to the aggregates.
+    fn sql_window_to_rex(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        op: String,
+        args: Vec<ExpressionPlan>,
+        over: &sqlparser::ast::WindowSpec,
+        schema: &DataSchema,
+    ) -> FuseQueryResult<ExpressionPlan> {
+        let func = ExpressionPlan::Function {
+            op: op.clone(),
+            args: args.clone(),
+            functions: ctx.get_function_factory(&op)?,
+        };
+        match op.to_lowercase().as_str() {
+            "row_number" | "rank" | "dense_rank" if !args.is_empty() => {
+                return Err(FuseQueryError::Plan(format!(
+                    "Window function {} takes no arguments",
+                    op
+                )))
+            }
+            "lag" | "lead" if args.is_empty() || args.len() > 3 => {
+                return Err(FuseQueryError::Plan(format!(
+                    "Window function {} requires 1 to 3 arguments",
+                    op
+                )))
+            }
+            "row_number" | "rank" | "dense_rank" | "lag" | "lead" => {}
+            _ if func.is_aggregate_function() => {}
+            _ => {
+                return Err(FuseQueryError::Plan(format!(
+                    "Unsupported window function: {}",
+                    op
+                )))
+            }
+        }
+
+        let partition_by = over
+            .partition_by
+            .iter()
+            .map(|e| self.sql_to_rex(ctx.clone(), e, schema))
+            .collect::<FuseQueryResult<Vec<_>>>()?;
+        let order_by = over
+            .order_by
+            .iter()
+            .map(|e| self.sql_order_by_to_rex(ctx.clone(), e, schema))
+            .collect::<FuseQueryResult<Vec<_>>>()?;
+        let frame = match over.window_frame {
+            Some(ref frame) => Some(Self::window_frame(frame)?),
+            None => None,
+        };
+        Ok(ExpressionPlan::Window {
+            func: Box::new(func),
+            partition_by,
+            order_by,
+            frame,
+        })
+    }
+
+    /// The frame without the end bound ends at the current row.
+    fn window_frame(frame: &sqlparser::ast::WindowFrame) -> FuseQueryResult<WindowFrame> {
+        let units = match frame.units {
+            sqlparser::ast::WindowFrameUnits::Rows => WindowFrameUnits::Rows,
+            sqlparser::ast::WindowFrameUnits::Range => WindowFrameUnits::Range,
+            sqlparser::ast::WindowFrameUnits::Groups => {
+                return Err(FuseQueryError::Plan(
+                    "GROUPS frame is not supported yet".to_string(),
+                ))
+            }
+        };
+        let bound = |bound: &sqlparser::ast::WindowFrameBound| match bound {
+            sqlparser::ast::WindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+            sqlparser::ast::WindowFrameBound::Preceding(n) => WindowFrameBound::Preceding(*n),
+            sqlparser::ast::WindowFrameBound::Following(n) => WindowFrameBound::Following(*n),
+        };
+        let frame = WindowFrame {
+            units,
+            start: bound(&frame.start_bound),
+            end: frame
+                .end_bound
+                .as_ref()
+                .map(bound)
+                .unwrap_or(WindowFrameBound::CurrentRow),
+        };
+
+        if matches!(frame.start, WindowFrameBound::Following(None))
+            || matches!(frame.end, WindowFrameBound::Preceding(None))
+        {
+            return Err(FuseQueryError::Plan(format!(
+                "Invalid window frame: {}",
+                frame
+            )));
+        }
+        let has_offset = |bound: WindowFrameBound| {
+            matches!(
+                bound,
+                WindowFrameBound::Preceding(Some(_)) | WindowFrameBound::Following(Some(_))
+            )
+        };
+        if frame.units == WindowFrameUnits::Range
+            && (has_offset(frame.start) || has_offset(frame.end))
+        {
+            return Err(FuseQueryError::Plan(format!(
+                "RANGE frame with offset is not supported yet: {}",
+                frame
+            )));
+        }
+        Ok(frame)
+    }
+
+    /// Apply a filter to the plan, the IN/EXISTS subqueries of the conjunctions are semi/anti joins.
     fn filter(
         &self,
+        ctx: Arc<FuseQueryContext>,
         plan: &PlanNode,
         predicate: &Option<sqlparser::ast::Expr>,
     ) -> FuseQueryResult<PlanNode> {
-        match *predicate {
-            Some(ref predicate_expr) => PlanBuilder::from(&plan)
-                .filter(self.sql_to_rex(predicate_expr, &plan.schema())?)?
-                .build(),
-            _ => Ok(plan.clone()),
+        let predicate_expr = match *predicate {
+            Some(ref predicate_expr) => predicate_expr,
+            _ => return Ok(plan.clone()),
+        };
+
+        let mut conditions = vec![];
+        Self::split_conjunction(predicate_expr, &mut conditions);
+        let mut joins = vec![];
+        let mut predicates = vec![];
+        for condition in conditions {
+            match self.subquery_join(ctx.clone(), plan, condition)? {
+                Some(join) => joins.push(join),
+                None => predicates.push(condition),
+            }
         }
+
+        let mut plan = if joins.is_empty() {
+            let predicate = self.sql_to_rex(ctx, predicate_expr, &plan.schema())?;
+            let mut window_expr = vec![];
+            Self::find_window_exprs(&predicate, &mut window_expr);
+            if !window_expr.is_empty() {
+                return Err(FuseQueryError::Plan(
+                    "Window functions are not allowed in WHERE".to_string(),
+                ));
+            }
+            return PlanBuilder::from(plan).filter(predicate)?.build();
+        } else if predicates.is_empty() {
+            plan.clone()
+        } else {
+            let mut predicate = self.sql_to_rex(ctx.clone(), predicates[0], &plan.schema())?;
+            for expr in &predicates[1..] {
+                predicate = predicate.and(self.sql_to_rex(ctx.clone(), expr, &plan.schema())?);
+            }
+            PlanBuilder::from(plan).filter(predicate)?.build()?
+        };
+
+        for (right, join_type, on) in joins {
+            plan = PlanBuilder::from(&plan)
+                .join(&right, join_type, on)?
+                .build()?;
+        }
+        Ok(plan)
+    }
+
+    /// Decorrelate the IN/EXISTS subquery to a semi/anti join.
+    /// The correlated subquery can only be a simple select whose correlated predicates are
+    /// equi-conditions, the uncorrelated EXISTS is evaluated as a constant instead.
+    fn subquery_join(
+        &self,
+        ctx: Arc<FuseQueryContext>,
+        outer: &PlanNode,
+        condition: &sqlparser::ast::Expr,
+    ) -> FuseQueryResult<Option<SubqueryJoin>> {
+        let (expr, query, negated) = match condition {
+            sqlparser::ast::Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => (Some(expr.as_ref()), subquery.as_ref(), *negated),
+            sqlparser::ast::Expr::Exists(subquery) => (None, subquery.as_ref(), false),
+            sqlparser::ast::Expr::UnaryOp {
+                op: sqlparser::ast::UnaryOperator::Not,
+                expr,
+            } => match expr.as_ref() {
+                sqlparser::ast::Expr::Exists(subquery) => (None, subquery.as_ref(), true),
+                sqlparser::ast::Expr::Nested(e) => match e.as_ref() {
+                    sqlparser::ast::Expr::Exists(subquery) => (None, subquery.as_ref(), true),
+                    _ => return Ok(None),
+                },
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        // NOT EXISTS is the plain anti join, NOT IN is NULL-aware.
+        let join_type = if negated && expr.is_some() {
+            JoinType::NullAwareAnti
+        } else if negated {
+            JoinType::Anti
+        } else {
+            JoinType::Semi
+        };
+
+        let outer_schema = outer.schema();
+        let select = match query.body {
+            sqlparser::ast::SetExpr::Select(ref select) => Some(select.as_ref()),
+            _ => None,
+        };
+        // The inner columns are qualified to be distinguished from the outer columns.
+        let inner = match select {
+            Some(select)
+                if select.from.len() == 1
+                    && select.from[0].joins.is_empty()
+                    && !matches!(
+                        select.from[0].relation,
+                        sqlparser::ast::TableFactor::Derived { alias: None, .. }
+                    ) =>
+            {
+                Some(self.create_qualified_relation(ctx.clone(), &select.from[0].relation)?)
+            }
+            Some(select) => Some(self.plan_tables_with_joins(ctx.clone(), &select.from)?),
+            None => None,
+        };
+
+        // Split the predicates of the subquery to the correlated keys and the inner predicates.
+        let mut on = vec![];
+        let mut predicates = vec![];
+        if let (Some(select), Some(inner)) = (select, &inner) {
+            let inner_schema = inner.schema();
+            let mut conditions = vec![];
+            if let Some(ref selection) = select.selection {
+                Self::split_conjunction(selection, &mut conditions);
+            }
+            for condition in conditions {
+                let mut key = None;
+                if let sqlparser::ast::Expr::BinaryOp {
+                    left: l,
+                    op: sqlparser::ast::BinaryOperator::Eq,
+                    right: r,
+                } = condition
+                {
+                    let inner_l = self.sql_to_rex(ctx.clone(), l, &inner_schema)?;
+                    let inner_r = self.sql_to_rex(ctx.clone(), r, &inner_schema)?;
+                    let outer_l = self.sql_outer_to_rex(ctx.clone(), l, &outer_schema)?;
+                    let outer_r = self.sql_outer_to_rex(ctx.clone(), r, &outer_schema)?;
+                    let is_inner = |e: &ExpressionPlan| {
+                        Self::expr_has_column(e) && Self::expr_in_schema(e, &inner_schema)
+                    };
+                    let is_outer = |inner_e: &ExpressionPlan, outer_e: &ExpressionPlan| {
+                        !Self::expr_in_schema(inner_e, &inner_schema)
+                            && Self::expr_in_schema(outer_e, &outer_schema)
+                    };
+                    if is_inner(&inner_l) && is_outer(&inner_r, &outer_r) {
+                        key = Some((outer_r, inner_l));
+                    } else if is_inner(&inner_r) && is_outer(&inner_l, &outer_l) {
+                        key = Some((outer_l, inner_r));
+                    }
+                }
+
+                match key {
+                    Some(key) => on.push(key),
+                    None => {
+                        let predicate = self.sql_to_rex(ctx.clone(), condition, &inner_schema)?;
+                        if !Self::expr_in_schema(&predicate, &inner_schema) {
+                            return Err(FuseQueryError::Plan(format!(
+                                "Unsupported correlated predicate in subquery: {}",
+                                condition
+                            )));
+                        }
+                        predicates.push(predicate);
+                    }
+                }
+            }
+        }
+
+        // Uncorrelated.
+        if on.is_empty() {
+            return match expr {
+                Some(expr) => {
+                    let right = self.query_to_plan(ctx.clone(), query)?;
+                    let right_schema = right.schema();
+                    if right_schema.fields().len() != 1 {
+                        return Err(FuseQueryError::Plan(
+                            "IN subquery must return exactly one column".to_string(),
+                        ));
+                    }
+                    let left_key = self.sql_to_rex(ctx, expr, &outer_schema)?;
+                    let right_key = field(right_schema.field(0).name());
+                    Ok(Some((right, join_type, vec![(left_key, right_key)])))
+                }
+                None => Ok(None),
+            };
+        }
+
+        // Correlated, the keys are only found in a select.
+        let (select, inner) = match (select, inner) {
+            (Some(select), Some(inner)) => (select, inner),
+            _ => unreachable!(),
+        };
+        let projection_expr = select
+            .projection
+            .iter()
+            .map(|e| self.sql_select_to_rex(ctx.clone(), e, &inner.schema()))
+            .collect::<FuseQueryResult<Vec<ExpressionPlan>>>()?;
+        if !select.group_by.is_empty()
+            || select.having.is_some()
+            || query.limit.is_some()
+            || projection_expr.iter().any(|e| e.is_aggregate())
+        {
+            return Err(FuseQueryError::Plan(format!(
+                "Unsupported correlated subquery: {}",
+                query
+            )));
+        }
+
+        let mut right = inner;
+        for predicate in predicates {
+            right = PlanBuilder::from(&right).filter(predicate)?.build()?;
+        }
+
+        if let Some(expr) = expr {
+            if projection_expr.len() != 1 {
+                return Err(FuseQueryError::Plan(
+                    "IN subquery must return exactly one column".to_string(),
+                ));
+            }
+            let left_key = self.sql_to_rex(ctx, expr, &outer_schema)?;
+            on.push((left_key, Self::strip_alias(&projection_expr[0]).clone()));
+        }
+        Ok(Some((right, join_type, on)))
     }
 
     /// Wrap a plan in a projection
@@ -280,42 +1464,507 @@ impl Planner {
         PlanBuilder::from(input).project(expr)?.build()
     }
 
-    /// Wrap a plan in an aggregate
+    /// Wrap a plan in an aggregate grouping by all the columns, without any aggregate functions.
+    fn distinct(&self, input: &PlanNode) -> FuseQueryResult<PlanNode> {
+        let group_expr = input
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| field(f.name()))
+            .collect();
+        PlanBuilder::from(input)
+            .aggregate(group_expr, vec![])?
+            .build()
+    }
+
+    /// Wrap a plan in a window plan for the window functions of the projection and ORDER BY,
+    /// the window functions are rewritten to the columns of the window plan output.
+    fn window(
+        &self,
+        input: &PlanNode,
+        projection_expr: &[ExpressionPlan],
+        order_by_expr: &[ExpressionPlan],
+    ) -> FuseQueryResult<(PlanNode, Vec<ExpressionPlan>, Vec<ExpressionPlan>)> {
+        let mut window_expr = vec![];
+        for expr in projection_expr.iter().chain(order_by_expr) {
+            Self::find_window_exprs(expr, &mut window_expr);
+        }
+        if window_expr.is_empty() {
+            return Ok((
+                input.clone(),
+                projection_expr.to_vec(),
+                order_by_expr.to_vec(),
+            ));
+        }
+
+        let plan = PlanBuilder::from(input).window(window_expr)?.build()?;
+        Ok((
+            plan,
+            projection_expr
+                .iter()
+                .map(Self::rebase_window_expr)
+                .collect(),
+            order_by_expr.iter().map(Self::rebase_window_expr).collect(),
+        ))
+    }
+
+    /// Collect the distinct window functions in the expression.
+    fn find_window_exprs(expr: &ExpressionPlan, found: &mut Vec<ExpressionPlan>) {
+        match expr {
+            ExpressionPlan::Window { .. } => {
+                if !found.contains(expr) {
+                    found.push(expr.clone());
+                }
+            }
+            ExpressionPlan::Function { args, .. } => args
+                .iter()
+                .for_each(|arg| Self::find_window_exprs(arg, found)),
+            ExpressionPlan::BinaryExpression { left, right, .. } => {
+                Self::find_window_exprs(left, found);
+                Self::find_window_exprs(right, found);
+            }
+            ExpressionPlan::Alias(_, inner) => Self::find_window_exprs(inner, found),
+            ExpressionPlan::Sort { expr, .. } => Self::find_window_exprs(expr, found),
+            ExpressionPlan::Cast { expr, .. } => Self::find_window_exprs(expr, found),
+            _ => {}
+        }
+    }
+
+    /// Rewrite the window functions to the columns of the window plan output.
+    fn rebase_window_expr(expr: &ExpressionPlan) -> ExpressionPlan {
+        match expr {
+            ExpressionPlan::Window { .. } => field(&format!("{:?}", expr)),
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => ExpressionPlan::Function {
+                op: op.clone(),
+                args: args.iter().map(Self::rebase_window_expr).collect(),
+                functions: functions.clone(),
+            },
+            ExpressionPlan::Cast { expr, data_type } => ExpressionPlan::Cast {
+                expr: Box::new(Self::rebase_window_expr(expr)),
+                data_type: data_type.clone(),
+            },
+            ExpressionPlan::BinaryExpression { left, op, right } => {
+                ExpressionPlan::BinaryExpression {
+                    left: Box::new(Self::rebase_window_expr(left)),
+                    op: op.clone(),
+                    right: Box::new(Self::rebase_window_expr(right)),
+                }
+            }
+            ExpressionPlan::Alias(alias, inner) => {
+                ExpressionPlan::Alias(alias.clone(), Box::new(Self::rebase_window_expr(inner)))
+            }
+            ExpressionPlan::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => ExpressionPlan::Sort {
+                expr: Box::new(Self::rebase_window_expr(expr)),
+                asc: *asc,
+                nulls_first: *nulls_first,
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Wrap a plan in a sort
+    fn sort(&self, input: &PlanNode, order_by: Vec<ExpressionPlan>) -> FuseQueryResult<PlanNode> {
+        if order_by.is_empty() {
+            return Ok(input.clone());
+        }
+        PlanBuilder::from(input).sort(order_by)?.build()
+    }
+
+    /// Wrap a plan in an aggregate of the aggregate functions in the projection and HAVING,
+    /// the HAVING and the projection are computed over the aggregate output.
     fn aggregate(
         &self,
+        ctx: Arc<FuseQueryContext>,
         input: &PlanNode,
         projection_expr: Vec<ExpressionPlan>,
-        aggr_expr: Vec<ExpressionPlan>,
         group_by: &[sqlparser::ast::Expr],
+        having_expr: Option<ExpressionPlan>,
     ) -> FuseQueryResult<PlanNode> {
+        // The GROUP BY may reference the aliases of the non-aggregate projection,
+        // which are resolved before the columns of the input.
+        let group_aliases = projection_expr
+            .iter()
+            .filter(|e| !e.is_aggregate())
+            .cloned()
+            .collect::<Vec<_>>();
         let group_expr: Vec<ExpressionPlan> = group_by
             .iter()
-            .map(|e| self.sql_to_rex(&e, &input.schema()))
+            .map(|e| {
+                let expr = self.sql_to_rex(ctx.clone(), &e, &input.schema())?;
+                Ok(Self::resolve_alias_expr(&expr, &group_aliases))
+            })
             .collect::<FuseQueryResult<Vec<ExpressionPlan>>>()?;
 
-        let group_by_count = group_expr.len();
-        let aggr_count = aggr_expr.len();
+        // The HAVING may reference the aliases of the projection.
+        let having_expr = having_expr.map(|expr| Self::resolve_alias_expr(&expr, &projection_expr));
 
-        if group_by_count + aggr_count != projection_expr.len() {
-            return Err(FuseQueryError::Plan(
-                "Projection references non-aggregate values".to_owned(),
-            ));
+        // The aggregate functions of the projection and HAVING are computed once.
+        let mut found = vec![];
+        for expr in projection_expr.iter().chain(having_expr.iter()) {
+            Self::find_aggregate_exprs(expr, &mut found);
+        }
+        let mut aggr_expr: Vec<ExpressionPlan> = vec![];
+        for expr in found {
+            if !aggr_expr.contains(&expr) {
+                aggr_expr.push(expr);
+            }
         }
 
-        PlanBuilder::from(&input)
-            .aggregate(group_expr, aggr_expr)?
-            .build()
+        let mut plan = PlanBuilder::from(&input)
+            .aggregate(group_expr.clone(), aggr_expr.clone())?
+            .build()?;
+        let aggregate_schema = plan.schema();
+
+        if let Some(ref expr) = having_expr {
+            let predicate = Self::rebase_aggregate_expr(
+                expr,
+                &group_expr,
+                &aggr_expr,
+                &aggregate_schema,
+                "HAVING",
+            )?;
+            plan = PlanBuilder::from(&plan).filter(predicate)?.build()?;
+        }
+
+        // Rewrite the projection to the columns of the aggregate output.
+        let mut output_expr = Vec::with_capacity(projection_expr.len());
+        for expr in &projection_expr {
+            output_expr.push(match expr {
+                ExpressionPlan::Alias(alias, inner) => ExpressionPlan::Alias(
+                    alias.clone(),
+                    Box::new(Self::rebase_aggregate_expr(
+                        inner,
+                        &group_expr,
+                        &aggr_expr,
+                        &aggregate_schema,
+                        "Projection",
+                    )?),
+                ),
+                _ => Self::rebase_aggregate_expr(
+                    expr,
+                    &group_expr,
+                    &aggr_expr,
+                    &aggregate_schema,
+                    "Projection",
+                )?,
+            });
+        }
+
+        // The aggregate output is already the projection.
+        let output_names = output_expr
+            .iter()
+            .map(|e| format!("{:?}", e))
+            .collect::<Vec<_>>();
+        let aggregate_names = aggregate_schema
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        if output_names == aggregate_names {
+            return Ok(plan);
+        }
+        self.project(&plan, output_expr)
+    }
+
+    /// Rewrite the sort expressions to the columns of the projection output.
+    fn rebase_sort_exprs(
+        order_by_expr: &[ExpressionPlan],
+        projection_expr: &[ExpressionPlan],
+        output: &PlanNode,
+    ) -> FuseQueryResult<Vec<ExpressionPlan>> {
+        let schema = output.schema();
+        // The wildcard is expanded, the projection can be only matched by the names.
+        let projection_expr = if projection_expr
+            .iter()
+            .any(|e| matches!(e, ExpressionPlan::Wildcard))
+        {
+            &[]
+        } else {
+            projection_expr
+        };
+
+        order_by_expr
+            .iter()
+            .map(|expr| Self::rebase_projection_expr(expr, projection_expr, &schema))
+            .collect()
+    }
+
+    fn rebase_projection_expr(
+        expr: &ExpressionPlan,
+        projection_expr: &[ExpressionPlan],
+        schema: &DataSchemaRef,
+    ) -> FuseQueryResult<ExpressionPlan> {
+        if let Some(i) = projection_expr
+            .iter()
+            .position(|e| Self::strip_alias(e) == expr)
+        {
+            return Ok(field(schema.field(i).name()));
+        }
+
+        let rebase = |e: &ExpressionPlan| Self::rebase_projection_expr(e, projection_expr, schema);
+        Ok(match expr {
+            ExpressionPlan::Field(name) => {
+                schema.field_with_name(name).map_err(|_| {
+                    FuseQueryError::Plan(format!(
+                        "ORDER BY references values not in the projection: {:?}",
+                        expr
+                    ))
+                })?;
+                expr.clone()
+            }
+            ExpressionPlan::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => ExpressionPlan::Sort {
+                expr: Box::new(rebase(expr)?),
+                asc: *asc,
+                nulls_first: *nulls_first,
+            },
+            ExpressionPlan::BinaryExpression { left, op, right } => {
+                ExpressionPlan::BinaryExpression {
+                    left: Box::new(rebase(left)?),
+                    op: op.clone(),
+                    right: Box::new(rebase(right)?),
+                }
+            }
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => {
+                if expr.is_aggregate() {
+                    return Err(FuseQueryError::Plan(format!(
+                        "ORDER BY references values not in the projection: {:?}",
+                        expr
+                    )));
+                }
+                ExpressionPlan::Function {
+                    op: op.clone(),
+                    args: args.iter().map(rebase).collect::<FuseQueryResult<_>>()?,
+                    functions: functions.clone(),
+                }
+            }
+            ExpressionPlan::Cast { expr, data_type } => ExpressionPlan::Cast {
+                expr: Box::new(rebase(expr)?),
+                data_type: data_type.clone(),
+            },
+            ExpressionPlan::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => ExpressionPlan::InSubquery {
+                expr: Box::new(rebase(expr)?),
+                subquery: subquery.clone(),
+                negated: *negated,
+            },
+            other => other.clone(),
+        })
+    }
+
+    fn strip_alias(expr: &ExpressionPlan) -> &ExpressionPlan {
+        match expr {
+            ExpressionPlan::Alias(_, inner) => inner.as_ref(),
+            other => other,
+        }
+    }
+
+    /// Replace the fields which are the aliases of the projection by the aliased expressions.
+    fn resolve_alias_expr(
+        expr: &ExpressionPlan,
+        projection_expr: &[ExpressionPlan],
+    ) -> ExpressionPlan {
+        match expr {
+            ExpressionPlan::Field(name) => projection_expr
+                .iter()
+                .find_map(|e| match e {
+                    ExpressionPlan::Alias(alias, inner) if alias == name => {
+                        Some(inner.as_ref().clone())
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| expr.clone()),
+            ExpressionPlan::Alias(alias, inner) => ExpressionPlan::Alias(
+                alias.clone(),
+                Box::new(Self::resolve_alias_expr(inner, projection_expr)),
+            ),
+            ExpressionPlan::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => ExpressionPlan::Sort {
+                expr: Box::new(Self::resolve_alias_expr(expr, projection_expr)),
+                asc: *asc,
+                nulls_first: *nulls_first,
+            },
+            ExpressionPlan::BinaryExpression { left, op, right } => {
+                ExpressionPlan::BinaryExpression {
+                    left: Box::new(Self::resolve_alias_expr(left, projection_expr)),
+                    op: op.clone(),
+                    right: Box::new(Self::resolve_alias_expr(right, projection_expr)),
+                }
+            }
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => ExpressionPlan::Function {
+                op: op.clone(),
+                args: args
+                    .iter()
+                    .map(|arg| Self::resolve_alias_expr(arg, projection_expr))
+                    .collect(),
+                functions: functions.clone(),
+            },
+            ExpressionPlan::Cast { expr, data_type } => ExpressionPlan::Cast {
+                expr: Box::new(Self::resolve_alias_expr(expr, projection_expr)),
+                data_type: data_type.clone(),
+            },
+            ExpressionPlan::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => ExpressionPlan::InSubquery {
+                expr: Box::new(Self::resolve_alias_expr(expr, projection_expr)),
+                subquery: subquery.clone(),
+                negated: *negated,
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// Collect the aggregate functions in the expression.
+    fn find_aggregate_exprs(expr: &ExpressionPlan, found: &mut Vec<ExpressionPlan>) {
+        match expr {
+            ExpressionPlan::Function { args, .. } => {
+                if expr.is_aggregate_function() {
+                    found.push(expr.clone());
+                } else {
+                    args.iter()
+                        .for_each(|arg| Self::find_aggregate_exprs(arg, found));
+                }
+            }
+            ExpressionPlan::BinaryExpression { left, right, .. } => {
+                Self::find_aggregate_exprs(left, found);
+                Self::find_aggregate_exprs(right, found);
+            }
+            ExpressionPlan::Alias(_, inner) => Self::find_aggregate_exprs(inner, found),
+            ExpressionPlan::Cast { expr, .. } => Self::find_aggregate_exprs(expr, found),
+            ExpressionPlan::InSubquery { expr, .. } => Self::find_aggregate_exprs(expr, found),
+            ExpressionPlan::Sort { expr, .. } => Self::find_aggregate_exprs(expr, found),
+            _ => {}
+        }
+    }
+
+    /// Rewrite the expression to the columns of the aggregate output, the columns of the input
+    /// are only referenced by the GROUP BY expressions.
+    fn rebase_aggregate_expr(
+        expr: &ExpressionPlan,
+        group_expr: &[ExpressionPlan],
+        aggr_expr: &[ExpressionPlan],
+        aggregate_schema: &DataSchemaRef,
+        clause: &str,
+    ) -> FuseQueryResult<ExpressionPlan> {
+        if let Some(i) = group_expr.iter().position(|e| e == expr) {
+            return Ok(field(aggregate_schema.field(i).name()));
+        }
+        if let Some(i) = aggr_expr.iter().position(|e| Self::strip_alias(e) == expr) {
+            return Ok(field(aggregate_schema.field(group_expr.len() + i).name()));
+        }
+
+        Ok(match expr {
+            ExpressionPlan::BinaryExpression { left, op, right } => {
+                ExpressionPlan::BinaryExpression {
+                    left: Box::new(Self::rebase_aggregate_expr(
+                        left,
+                        group_expr,
+                        aggr_expr,
+                        aggregate_schema,
+                        clause,
+                    )?),
+                    op: op.clone(),
+                    right: Box::new(Self::rebase_aggregate_expr(
+                        right,
+                        group_expr,
+                        aggr_expr,
+                        aggregate_schema,
+                        clause,
+                    )?),
+                }
+            }
+            ExpressionPlan::Function {
+                op,
+                args,
+                functions,
+            } => ExpressionPlan::Function {
+                op: op.clone(),
+                args: args
+                    .iter()
+                    .map(|arg| {
+                        Self::rebase_aggregate_expr(
+                            arg,
+                            group_expr,
+                            aggr_expr,
+                            aggregate_schema,
+                            clause,
+                        )
+                    })
+                    .collect::<FuseQueryResult<Vec<_>>>()?,
+                functions: functions.clone(),
+            },
+            ExpressionPlan::Cast { expr, data_type } => ExpressionPlan::Cast {
+                expr: Box::new(Self::rebase_aggregate_expr(
+                    expr,
+                    group_expr,
+                    aggr_expr,
+                    aggregate_schema,
+                    clause,
+                )?),
+                data_type: data_type.clone(),
+            },
+            ExpressionPlan::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => ExpressionPlan::InSubquery {
+                expr: Box::new(Self::rebase_aggregate_expr(
+                    expr,
+                    group_expr,
+                    aggr_expr,
+                    aggregate_schema,
+                    clause,
+                )?),
+                subquery: subquery.clone(),
+                negated: *negated,
+            },
+            ExpressionPlan::Field(_) => {
+                return Err(FuseQueryError::Plan(format!(
+                    "{} references non-aggregate values: {:?}",
+                    clause, expr
+                )))
+            }
+            other => other.clone(),
+        })
     }
 
     /// Wrap a plan in a limit
     fn limit(
         &self,
+        ctx: Arc<FuseQueryContext>,
         input: &PlanNode,
         limit: &Option<sqlparser::ast::Expr>,
     ) -> FuseQueryResult<PlanNode> {
         match *limit {
             Some(ref limit_expr) => {
-                let n = match self.sql_to_rex(&limit_expr, &input.schema())? {
+                let n = match self.sql_to_rex(ctx.clone(), &limit_expr, &input.schema())? {
                     ExpressionPlan::Constant(DataValue::UInt64(Some(n))) => Ok(n as usize),
                     _ => Err(FuseQueryError::Plan(
                         "Unexpected expression for LIMIT clause".to_string(),
@@ -327,3 +1976,36 @@ impl Planner {
         }
     }
 }
+
+/// Count the references of the materialized CTEs.
+struct MaterializeVisitor {
+    references: HashMap<usize, usize>,
+}
+
+impl PlanVisitor for MaterializeVisitor {
+    fn pre_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool> {
+        if let PlanNode::Materialize(v) = plan {
+            *self.references.entry(v.id).or_default() += 1;
+        }
+        Ok(true)
+    }
+}
+
+/// Inline the materialized CTEs of the current scope(id >= first_id) which are referenced only once.
+struct MaterializeInliner {
+    references: HashMap<usize, usize>,
+    first_id: usize,
+}
+
+impl PlanRewriter for MaterializeInliner {
+    fn mutate(&mut self, plan: PlanNode) -> FuseQueryResult<PlanNode> {
+        match plan {
+            PlanNode::Materialize(v)
+                if v.id >= self.first_id && self.references.get(&v.id) == Some(&1) =>
+            {
+                Ok(v.input.as_ref().clone())
+            }
+            other => Ok(other),
+        }
+    }
+}
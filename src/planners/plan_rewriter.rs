@@ -0,0 +1,25 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use crate::error::FuseQueryResult;
+use crate::planners::PlanNode;
+
+pub enum RewriteRecursion {
+    /// Rewrite the inputs, then mutate the node.
+    Continue,
+    /// Mutate the node without rewriting the inputs.
+    Mutate,
+    /// Keep the node and its inputs as they are.
+    Stop,
+}
+
+/// Rewrite the plan tree by PlanNode::rewrite from the bottom up.
+pub trait PlanRewriter {
+    fn pre_visit(&mut self, _plan: &PlanNode) -> FuseQueryResult<RewriteRecursion> {
+        Ok(RewriteRecursion::Continue)
+    }
+
+    /// Invoked with the node whose inputs are rewritten, returns the new node.
+    fn mutate(&mut self, plan: PlanNode) -> FuseQueryResult<PlanNode>;
+}
@@ -0,0 +1,74 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[test]
+fn test_plan_visitor_and_rewriter() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::error::FuseQueryResult;
+    use crate::planners::*;
+    use crate::testdata;
+
+    struct NameVisitor {
+        names: Vec<String>,
+    }
+
+    impl PlanVisitor for NameVisitor {
+        fn pre_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool> {
+            self.names.push(plan.name().to_string());
+            Ok(true)
+        }
+    }
+
+    // Remove all the projections.
+    struct ProjectionRewriter {}
+
+    impl PlanRewriter for ProjectionRewriter {
+        fn mutate(&mut self, plan: PlanNode) -> FuseQueryResult<PlanNode> {
+            match plan {
+                PlanNode::Projection(v) => Ok(v.input.as_ref().clone()),
+                other => Ok(other),
+            }
+        }
+    }
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx,
+        "select a.number from system.numbers_mt as a join system.numbers_mt as b on a.number = b.number",
+    )?;
+
+    let mut visitor = NameVisitor { names: vec![] };
+    plan.accept(&mut visitor)?;
+    let expect = vec![
+        "SelectPlan",
+        "ProjectionPlan",
+        "JoinPlan",
+        "ProjectionPlan",
+        "ReadSourcePlan",
+        "ProjectionPlan",
+        "ReadSourcePlan",
+    ];
+    assert_eq!(expect, visitor.names);
+
+    let rewritten = plan.rewrite(&mut ProjectionRewriter {})?;
+    let expect = "\
+    └─ Join: INNER ON a.number = b.number\
+    \n  └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n  └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", rewritten);
+    assert_eq!(expect, actual);
+
+    let result = rewritten.with_new_children(&[]);
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: SelectPlan expects 1 children, but got 0";
+    assert_eq!(expect, actual);
+    Ok(())
+}
@@ -28,3 +28,413 @@ fn test_select_wildcard_plan() -> crate::error::FuseQueryResult<()> {
     assert_eq!(expect, actual);
     Ok(())
 }
+
+#[test]
+fn test_select_group_by_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select count(*), number/10 as n from system.numbers_mt group by number/10",
+    )?;
+    let expect = "\
+    └─ Projection: Count(1), number / 10 as n\
+    \n  └─ Aggregate: count([*]) group by: (number / 10)\
+    \n    └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx,
+        "select number, count(*) from system.numbers_mt group by number/10",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: Projection references non-aggregate values: number";
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_having_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number/10 as n, count(*) from system.numbers_mt group by number/10 having n > 1 and sum(number) > 100",
+    )?;
+    let expect = "\
+    └─ Projection: number / 10 as n, Count(1)\
+    \n  └─ Filter: ((number / 10 > 1) AND (Sum(number) > 100))\
+    \n    └─ Aggregate: count([*]), sum([number]) group by: (number / 10)\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx,
+        "select count(*) from system.numbers_mt group by number/10 having number > 1",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: HAVING references non-aggregate values: number";
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_order_by_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number/10 as n, count(*) from system.numbers_mt group by number/10 order by n desc, count(*) limit 3",
+    )?;
+    let expect = "\
+    └─ Limit: 3\
+    \n  └─ Sort: n DESC, Count(1) ASC\
+    \n    └─ Projection: number / 10 as n, Count(1)\
+    \n      └─ Aggregate: count([*]) group by: (number / 10)\
+    \n        └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    // Sort by the column which is not in the projection.
+    let plan = Planner::new().build_from_sql(
+        ctx,
+        "select number+1 as c from system.numbers_mt order by number desc nulls first",
+    )?;
+    let expect = "\
+    └─ Projection: (number + 1) as c\
+    \n  └─ Sort: number DESC NULLS FIRST\
+    \n    └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_join_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select a.number, b.number from system.numbers_mt as a left join system.numbers_mt as b on a.number = b.number + 1",
+    )?;
+    let expect = "\
+    └─ Projection: a.number, b.number\
+    \n  └─ Join: LEFT ON a.number = (b.number + 1)\
+    \n    └─ Projection: number as a.number\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n    └─ Projection: number as b.number\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt as a join system.numbers_mt as b using(number)",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: Column reference 'number' is ambiguous";
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx,
+        "select a.number from system.numbers_mt as a left join system.numbers_mt as b on a.number > b.number",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: JOIN requires at least one equi-join condition";
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_union_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt(10) union select number + 1 from system.numbers_mt(5) order by number desc limit 3",
+    )?;
+    let expect = "\
+    └─ Limit: 3\
+    \n  └─ Sort: number DESC\
+    \n    └─ Aggregate: group by: number\
+    \n      └─ Union: 2 inputs\
+    \n        └─ Projection: number\
+    \n          └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n        └─ Projection: (number + 1)\
+    \n          └─ ReadDataSource: scan parts [1](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt union all select number, number from system.numbers_mt",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: UNION inputs have different number of columns: 1 and 2";
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx,
+        "select number from system.numbers_mt except select number from system.numbers_mt",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: Set operation EXCEPT is not supported yet";
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_distinct_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new()
+        .build_from_sql(ctx.clone(), "select distinct number from system.numbers_mt")?;
+    let expect = "\
+    └─ Aggregate: group by: number\
+    \n  └─ Projection: number\
+    \n    └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let plan = Planner::new()
+        .build_from_sql(ctx, "select count(distinct number) from system.numbers_mt")?;
+    let expect = "\
+    └─ Aggregate: countDistinct([number])\
+    \n  └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_subquery_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt as a where number > (select 1) and exists (select * from system.numbers_mt as b where b.number = a.number + 1)",
+    )?;
+    let expect = "\
+    └─ Projection: number\
+    \n  └─ Join: SEMI ON (number + 1) = b.number\
+    \n    └─ Filter: (number > (subquery))\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n    └─ Projection: number as b.number\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "select number from system.numbers_mt where number not in (select number from system.numbers_mt)",
+    )?;
+    let expect = "\
+    └─ Projection: number\
+    \n  └─ Join: NULL AWARE ANTI ON number = number\
+    \n    └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n    └─ Projection: number\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let result = Planner::new().build_from_sql(
+        ctx,
+        "select number from system.numbers_mt as a where exists (select * from system.numbers_mt as b where b.number > a.number)",
+    );
+    let actual = format!("{}", result.err().unwrap());
+    let expect =
+        "Error during plan: Unsupported correlated predicate in subquery: b.number > a.number";
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_cte_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "with t(n) as (select number from system.numbers_mt) select n from t where n > 1",
+    )?;
+    let expect = "\
+    └─ Projection: n\
+    \n  └─ Filter: (n > 1)\
+    \n    └─ Projection: number as n\
+    \n      └─ Projection: number\
+    \n        └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    ctx.set_materialize_cte(true)?;
+    let plan = Planner::new().build_from_sql(
+        ctx.clone(),
+        "with t as (select number from system.numbers_mt), s as (select number from t) select a.number from t as a join t as b on a.number = b.number join s on a.number = s.number",
+    )?;
+    let expect = "\
+    └─ Projection: a.number\
+    \n  └─ Join: INNER ON a.number = s.number\
+    \n    └─ Join: INNER ON a.number = b.number\
+    \n      └─ Projection: number as a.number\
+    \n        └─ Materialize: t\
+    \n          └─ Projection: number\
+    \n            └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n      └─ Projection: number as b.number\
+    \n        └─ Materialize: t\
+    \n          └─ Projection: number\
+    \n            └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)\
+    \n    └─ Projection: number as s.number\
+    \n      └─ Projection: number\
+    \n        └─ Materialize: t\
+    \n          └─ Projection: number\
+    \n            └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+
+    let result =
+        Planner::new().build_from_sql(ctx, "with t as (select 1), t as (select 2) select * from t");
+    let actual = format!("{}", result.err().unwrap());
+    let expect = "Error during plan: WITH query name t specified more than once";
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_window_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx,
+        "select number, rank() over (partition by number / 2 order by number desc) as r, sum(number) over (order by number rows between 1 preceding and current row) from system.numbers_mt order by r",
+    )?;
+    let expect = "\
+    └─ Sort: r ASC\
+    \n  └─ Projection: number, rank([]) OVER (PARTITION BY (number / 2) ORDER BY number DESC) as r, sum([number]) OVER (ORDER BY number ASC ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)\
+    \n    └─ Window: rank([]) OVER (PARTITION BY (number / 2) ORDER BY number DESC), sum([number]) OVER (ORDER BY number ASC ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)\
+    \n      └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[test]
+fn test_select_cast_decimal_plan() -> crate::error::FuseQueryResult<()> {
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    use crate::contexts::*;
+    use crate::planners::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let ctx = Arc::new(FuseQueryContext::create_ctx(
+        0,
+        test_source.number_source_for_test()?,
+    ));
+    let plan = Planner::new().build_from_sql(
+        ctx,
+        "select cast(number as decimal(10, 2)) from system.numbers_mt",
+    )?;
+    let expect = "\
+    └─ Projection: cast(number as Decimal(10, 2))\
+    \n  └─ ReadDataSource: scan parts [8](Read from system.numbers_mt table)";
+    let actual = format!("{:?}", plan);
+    assert_eq!(expect, actual);
+    assert_eq!(
+        "cast(number as Decimal(10, 2))",
+        plan.schema().field(0).name()
+    );
+    Ok(())
+}
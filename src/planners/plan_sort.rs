@@ -0,0 +1,22 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use crate::datavalues::DataSchemaRef;
+use crate::planners::{ExpressionPlan, PlanNode};
+
+#[derive(Clone)]
+pub struct SortPlan {
+    /// The sort expressions, each one is an ExpressionPlan::Sort
+    pub order_by: Vec<ExpressionPlan>,
+    /// The logical plan
+    pub input: Arc<PlanNode>,
+}
+
+impl SortPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.input.schema()
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use crate::datavalues::DataSchemaRef;
+use crate::planners::{ExpressionPlan, PlanNode};
+
+/// The first n rows of the sort, fused from Sort and Limit by TopNOptimizer.
+#[derive(Clone)]
+pub struct TopNPlan {
+    /// The sort expressions, each one is an ExpressionPlan::Sort
+    pub order_by: Vec<ExpressionPlan>,
+    /// The limit
+    pub n: usize,
+    /// The logical plan
+    pub input: Arc<PlanNode>,
+}
+
+impl TopNPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.input.schema()
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use crate::datavalues::{numerical_coercion, DataField, DataSchema, DataSchemaRef};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::planners::PlanNode;
+
+/// UNION ALL of the inputs, the rows of the inputs are not deduplicated.
+#[derive(Clone)]
+pub struct UnionPlan {
+    pub inputs: Vec<Arc<PlanNode>>,
+    pub schema: DataSchemaRef,
+}
+
+impl UnionPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    /// The union schema has the names of the first input,
+    /// the numeric columns of the different types are coerced to the common type.
+    pub fn union_schema(inputs: &[PlanNode]) -> FuseQueryResult<DataSchemaRef> {
+        let first = inputs
+            .first()
+            .ok_or_else(|| FuseQueryError::Plan("UNION requires inputs".to_string()))?
+            .schema();
+
+        let mut fields = first.fields().clone();
+        for input in &inputs[1..] {
+            let schema = input.schema();
+            if schema.fields().len() != fields.len() {
+                return Err(FuseQueryError::Plan(format!(
+                    "UNION inputs have different number of columns: {} and {}",
+                    fields.len(),
+                    schema.fields().len()
+                )));
+            }
+
+            for (i, field) in schema.fields().iter().enumerate() {
+                let data_type = if fields[i].data_type() == field.data_type() {
+                    field.data_type().clone()
+                } else {
+                    numerical_coercion("UNION", fields[i].data_type(), field.data_type()).map_err(
+                        |_| {
+                            FuseQueryError::Plan(format!(
+                                "UNION column {} types are not compatible: {:?} and {:?}",
+                                fields[i].name(),
+                                fields[i].data_type(),
+                                field.data_type()
+                            ))
+                        },
+                    )?
+                };
+                fields[i] = DataField::new(
+                    fields[i].name(),
+                    data_type,
+                    fields[i].is_nullable() || field.is_nullable(),
+                );
+            }
+        }
+        Ok(Arc::new(DataSchema::new(fields)))
+    }
+}
@@ -0,0 +1,18 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use crate::error::FuseQueryResult;
+use crate::planners::PlanNode;
+
+/// Visit the plan tree by PlanNode::accept in depth-first order.
+///
+/// pre_visit is invoked before the inputs of the node, post_visit is invoked after them,
+/// the traversal stops if any of them returns false.
+pub trait PlanVisitor {
+    fn pre_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool>;
+
+    fn post_visit(&mut self, _plan: &PlanNode) -> FuseQueryResult<bool> {
+        Ok(true)
+    }
+}
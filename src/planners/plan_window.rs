@@ -0,0 +1,140 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::datavalues::{DataField, DataSchema, DataSchemaRef, DataType};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::planners::{ExpressionPlan, PlanNode};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowFrameUnits {
+    Rows,
+    /// The frame of the peer rows, the rows with the same ORDER BY values.
+    Range,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowFrameBound {
+    /// `<N> PRECEDING` or `UNBOUNDED PRECEDING`
+    Preceding(Option<u64>),
+    CurrentRow,
+    /// `<N> FOLLOWING` or `UNBOUNDED FOLLOWING`
+    Following(Option<u64>),
+}
+
+/// The frame of the window aggregates, such as `ROWS BETWEEN 1 PRECEDING AND CURRENT ROW`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start: WindowFrameBound,
+    pub end: WindowFrameBound,
+}
+
+impl WindowFrame {
+    /// The frame from the partition start to the last peer of the current row.
+    pub fn default_frame() -> Self {
+        WindowFrame {
+            units: WindowFrameUnits::Range,
+            start: WindowFrameBound::Preceding(None),
+            end: WindowFrameBound::CurrentRow,
+        }
+    }
+}
+
+impl fmt::Display for WindowFrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WindowFrameBound::Preceding(None) => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(Some(n)) => write!(f, "{} PRECEDING", n),
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Following(None) => write!(f, "UNBOUNDED FOLLOWING"),
+            WindowFrameBound::Following(Some(n)) => write!(f, "{} FOLLOWING", n),
+        }
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let units = match self.units {
+            WindowFrameUnits::Rows => "ROWS",
+            WindowFrameUnits::Range => "RANGE",
+        };
+        write!(f, "{} BETWEEN {} AND {}", units, self.start, self.end)
+    }
+}
+
+/// Compute the window functions over the input,
+/// the output is the input columns followed by one column per window function.
+#[derive(Clone)]
+pub struct WindowPlan {
+    /// The window functions, each one is an ExpressionPlan::Window
+    pub window_expr: Vec<ExpressionPlan>,
+    /// The logical plan
+    pub input: Arc<PlanNode>,
+    pub schema: DataSchemaRef,
+}
+
+impl WindowPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    /// The ranking functions are numbered from 1, the offset functions return the type of
+    /// their argument and the framed aggregates return the type of the aggregate.
+    pub fn window_field(
+        expr: &ExpressionPlan,
+        input_schema: &DataSchemaRef,
+    ) -> FuseQueryResult<DataField> {
+        let name = format!("{:?}", expr);
+        let func = match expr {
+            ExpressionPlan::Window { func, .. } => func.as_ref(),
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Expression {:?} is not a window function",
+                    expr
+                )))
+            }
+        };
+
+        match func {
+            ExpressionPlan::Function { op, args, .. } => match op.to_lowercase().as_str() {
+                "row_number" | "rank" | "dense_rank" => {
+                    Ok(DataField::new(&name, DataType::UInt64, false))
+                }
+                "lag" | "lead" => {
+                    let arg = args.first().ok_or_else(|| {
+                        FuseQueryError::Plan(format!("Window function {} requires arguments", op))
+                    })?;
+                    let field = arg.to_field(input_schema)?;
+                    Ok(DataField::new(&name, field.data_type().clone(), true))
+                }
+                _ if func.is_aggregate_function() => {
+                    let field = func.to_field(input_schema)?;
+                    Ok(DataField::new(&name, field.data_type().clone(), true))
+                }
+                _ => Err(FuseQueryError::Plan(format!(
+                    "Unsupported window function: {}",
+                    op
+                ))),
+            },
+            _ => Err(FuseQueryError::Plan(format!(
+                "Unsupported window function: {:?}",
+                func
+            ))),
+        }
+    }
+
+    pub fn window_schema(
+        window_expr: &[ExpressionPlan],
+        input_schema: &DataSchemaRef,
+    ) -> FuseQueryResult<DataSchemaRef> {
+        let mut fields = input_schema.fields().clone();
+        for expr in window_expr {
+            fields.push(Self::window_field(expr, input_schema)?);
+        }
+        Ok(Arc::new(DataSchema::new(fields)))
+    }
+}
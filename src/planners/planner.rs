@@ -2,12 +2,24 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
-pub struct Planner;
+use std::cell::{Cell, RefCell};
+
+use crate::planners::PlanNode;
+
+pub struct Planner {
+    /// The CTEs in scope by name, the inner ones are at the end.
+    pub(crate) ctes: RefCell<Vec<(String, PlanNode)>>,
+    /// The id generator of the materialized CTEs.
+    pub(crate) next_cte_id: Cell<usize>,
+}
 
 impl Planner {
     /// Creates a new planner.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            ctes: RefCell::new(vec![]),
+            next_cte_id: Cell::new(0),
+        }
     }
 }
 
@@ -4,6 +4,7 @@
 
 mod pipeline_builder_test;
 mod processor_empty_test;
+mod processor_merge_sort_test;
 mod processor_merge_test;
 mod processor_through_test;
 
@@ -12,6 +13,7 @@ mod pipeline_builder;
 mod processor;
 mod processor_empty;
 mod processor_merge;
+mod processor_merge_sort;
 mod processor_through;
 
 pub use self::pipeline::Pipeline;
@@ -19,4 +21,5 @@ pub use self::pipeline_builder::PipelineBuilder;
 pub use self::processor::{FormatterSettings, IProcessor};
 pub use self::processor_empty::EmptyProcessor;
 pub use self::processor_merge::MergeProcessor;
+pub use self::processor_merge_sort::MergeSortProcessor;
 pub use self::processor_through::ThroughProcessor;
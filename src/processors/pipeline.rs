@@ -37,6 +37,12 @@ impl Pipeline {
         }
     }
 
+    pub fn last_pipe(&self) -> FuseQueryResult<&Pipe> {
+        self.processors.last().ok_or_else(|| {
+            FuseQueryError::Internal("Pipeline last pipe can not be none".to_string())
+        })
+    }
+
     pub fn add_source(&mut self, source: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
         if self.processors.first().is_none() {
             let mut first = vec![];
@@ -98,6 +104,28 @@ impl Pipeline {
         Ok(())
     }
 
+    /// Merge many(or one)-ways processors into one-way by the processor created by `f`,
+    /// such as MergeSortProcessor which keeps the order of the inputs.
+    pub fn merge_processor_with(
+        &mut self,
+        f: impl Fn() -> FuseQueryResult<Box<dyn IProcessor>>,
+    ) -> FuseQueryResult<()> {
+        let last = self.processors.last().ok_or_else(|| {
+            FuseQueryError::Internal(
+                "Can't merge processor when the last pipe is empty".to_string(),
+            )
+        })?;
+
+        if last.len() > 1 {
+            let mut p = f()?;
+            for x in last {
+                p.connect_to(x.clone())?;
+            }
+            self.processors.push(vec![Arc::from(p)]);
+        }
+        Ok(())
+    }
+
     /// Expand one(many) processors into size-ways.
     /// Assume the expand size is 3:
     ///
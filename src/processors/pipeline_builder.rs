@@ -2,15 +2,18 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::contexts::FuseQueryContext;
 use crate::error::{FuseQueryError, FuseQueryResult};
-use crate::planners::PlanNode;
-use crate::processors::Pipeline;
+use crate::planners::{JoinPlan, JoinType, MaterializePlan, PlanNode, PlanVisitor, UnionPlan};
+use crate::processors::{IProcessor, MergeSortProcessor, Pipeline};
 use crate::transforms::{
-    AggregateFinalTransform, AggregatePartialTransform, FilterTransform, LimitTransform,
-    ProjectionTransform, SourceTransform,
+    AggregateFinalTransform, AggregatePartialTransform, CastTransform, FilterTransform,
+    HashJoinBuildTransform, HashJoinProbeTransform, LimitTransform, MaterializeTransform,
+    ProjectionTransform, SortPartialTransform, SourceTransform, TopNPartialTransform,
+    WindowTransform,
 };
 
 pub struct PipelineBuilder {
@@ -24,84 +27,267 @@ impl PipelineBuilder {
     }
 
     pub fn build(&self) -> FuseQueryResult<Pipeline> {
+        let mut visitor = PipelineVisitor {
+            ctx: self.ctx.clone(),
+            pipelines: vec![],
+            materialized: HashMap::new(),
+        };
+        self.plan.accept(&mut visitor)?;
+
+        let mut pipeline = visitor.pipelines.pop().ok_or_else(|| {
+            FuseQueryError::Internal("Build pipeline from the empty plan".to_string())
+        })?;
+        pipeline.merge_processor()?;
+        Ok(pipeline)
+    }
+}
+
+/// Build the pipelines from the bottom up of the plan tree,
+/// the stack holds the pipelines of the visited inputs.
+struct PipelineVisitor {
+    ctx: Arc<FuseQueryContext>,
+    pipelines: Vec<Pipeline>,
+    /// The materialized CTEs by id.
+    materialized: HashMap<usize, Arc<MaterializeTransform>>,
+}
+
+impl PipelineVisitor {
+    fn pop_pipeline(&mut self) -> FuseQueryResult<Pipeline> {
+        self.pipelines
+            .pop()
+            .ok_or_else(|| FuseQueryError::Internal("Pipeline input can not be none".to_string()))
+    }
+
+    /// All the references of the CTE read the blocks of one MaterializeTransform,
+    /// the input pipelines of the other references are never executed.
+    fn visit_materialize(
+        &mut self,
+        plan: &MaterializePlan,
+        mut input: Pipeline,
+    ) -> FuseQueryResult<Pipeline> {
+        let materialize = match self.materialized.get(&plan.id) {
+            Some(materialize) => materialize.clone(),
+            None => {
+                input.merge_processor()?;
+                let mut materialize = MaterializeTransform::try_create(plan.schema())?;
+                materialize.connect_to(input.last_pipe()?[0].clone())?;
+                let materialize = Arc::new(materialize);
+                self.materialized.insert(plan.id, materialize.clone());
+                materialize
+            }
+        };
+
         let mut pipeline = Pipeline::create();
-        let plans = self.plan.subplan_to_list()?;
-        for plan in &plans {
-            match plan {
-                PlanNode::Limit(plan) => {
-                    pipeline.add_simple_transform(|| {
-                        Ok(Box::new(LimitTransform::try_create(plan.n)?))
-                    })?;
-                    if pipeline.pipe_num() > 1 {
-                        pipeline.merge_processor()?;
-                        pipeline.add_simple_transform(|| {
-                            Ok(Box::new(LimitTransform::try_create(plan.n)?))
-                        })?;
-                    }
-                }
-                PlanNode::Projection(plan) => {
-                    pipeline.add_simple_transform(|| {
-                        Ok(Box::new(ProjectionTransform::try_create(
-                            plan.schema.clone(),
-                            plan.expr.clone(),
-                        )?))
-                    })?;
-                }
-                PlanNode::Aggregate(plan) => {
-                    pipeline.add_simple_transform(|| {
-                        Ok(Box::new(AggregatePartialTransform::try_create(
-                            plan.schema.clone(),
-                            plan.aggr_expr.clone(),
-                        )?))
+        pipeline.add_source(materialize)?;
+        Ok(pipeline)
+    }
+
+    /// The pipes of all the inputs are the parallel pipes of the union.
+    fn visit_union(&self, plan: &UnionPlan, inputs: Vec<Pipeline>) -> FuseQueryResult<Pipeline> {
+        let mut pipeline = Pipeline::create();
+        for (input_plan, mut input) in plan.inputs.iter().zip(inputs) {
+            // The columns are renamed to the union schema too, whose names are of the first input.
+            let need_cast = input_plan.schema().fields() != plan.schema.fields();
+            if need_cast {
+                input.add_simple_transform(|| {
+                    Ok(Box::new(CastTransform::try_create(plan.schema.clone())?))
+                })?;
+            }
+
+            for processor in input.last_pipe()? {
+                pipeline.add_source(processor.clone())?;
+            }
+        }
+        Ok(pipeline)
+    }
+
+    /// The build side(right) is merged into one hash table,
+    /// the probe side(left) pipes probe it in parallel.
+    fn visit_join(
+        &self,
+        plan: &JoinPlan,
+        mut left: Pipeline,
+        mut right: Pipeline,
+    ) -> FuseQueryResult<Pipeline> {
+        let (left_keys, right_keys): (Vec<_>, Vec<_>) = plan.on.iter().cloned().unzip();
+
+        right.merge_processor()?;
+        let mut build = HashJoinBuildTransform::try_create(
+            plan.right.schema(),
+            right_keys,
+            plan.join_type == JoinType::NullAwareAnti,
+        )?;
+        build.connect_to(right.last_pipe()?[0].clone())?;
+        let build = Arc::new(build);
+
+        // The unmatched build rows are output by only one probe.
+        if matches!(plan.join_type, JoinType::Right | JoinType::Full) {
+            left.merge_processor()?;
+        }
+        left.add_simple_transform(|| {
+            Ok(Box::new(HashJoinProbeTransform::try_create(
+                plan.schema.clone(),
+                plan.join_type,
+                left_keys.clone(),
+                build.clone(),
+            )?))
+        })?;
+        Ok(left)
+    }
+}
+
+impl PlanVisitor for PipelineVisitor {
+    fn pre_visit(&mut self, _plan: &PlanNode) -> FuseQueryResult<bool> {
+        Ok(true)
+    }
+
+    fn post_visit(&mut self, plan: &PlanNode) -> FuseQueryResult<bool> {
+        let mut pipeline = match plan {
+            // The leaves.
+            PlanNode::ReadSource(_) | PlanNode::Empty(_) | PlanNode::Scan(_) => Pipeline::create(),
+            PlanNode::Join(plan) => {
+                let right = self.pop_pipeline()?;
+                let left = self.pop_pipeline()?;
+                self.pipelines.push(self.visit_join(plan, left, right)?);
+                return Ok(true);
+            }
+            PlanNode::Union(plan) => {
+                let inputs = self
+                    .pipelines
+                    .len()
+                    .checked_sub(plan.inputs.len())
+                    .ok_or_else(|| {
+                        FuseQueryError::Internal(
+                            "Pipeline inputs of union are not enough".to_string(),
+                        )
                     })?;
+                let inputs = self.pipelines.split_off(inputs);
+                self.pipelines.push(self.visit_union(plan, inputs)?);
+                return Ok(true);
+            }
+            PlanNode::Materialize(plan) => {
+                let input = self.pop_pipeline()?;
+                let pipeline = self.visit_materialize(plan, input)?;
+                self.pipelines.push(pipeline);
+                return Ok(true);
+            }
+            // The parent plans don't change the pipeline.
+            PlanNode::Select(_) | PlanNode::Explain(_) => return Ok(true),
+            _ => self.pop_pipeline()?,
+        };
 
+        match plan {
+            PlanNode::Limit(plan) => {
+                pipeline
+                    .add_simple_transform(|| Ok(Box::new(LimitTransform::try_create(plan.n)?)))?;
+                if pipeline.pipe_num() > 1 {
                     pipeline.merge_processor()?;
                     pipeline.add_simple_transform(|| {
-                        Ok(Box::new(AggregateFinalTransform::try_create(
-                            plan.schema.clone(),
-                            plan.aggr_expr.clone(),
-                        )?))
-                    })?;
-                }
-                PlanNode::Filter(plan) => {
-                    pipeline.add_simple_transform(|| {
-                        Ok(Box::new(FilterTransform::try_create(
-                            plan.predicate.clone(),
-                        )?))
+                        Ok(Box::new(LimitTransform::try_create(plan.n)?))
                     })?;
                 }
-                PlanNode::ReadSource(plan) => {
-                    let mut shuffle = vec![];
-                    let workers = self.ctx.worker_threads;
-                    let workers = if workers == 0 || workers >= plan.partitions.len() {
-                        1
-                    } else {
-                        plan.partitions.len() / workers
-                    };
-
-                    for chunk in plan.partitions.chunks(workers) {
-                        shuffle.push(chunk);
-                    }
-
-                    for partition in shuffle {
-                        let source = SourceTransform::try_create(
-                            self.ctx.clone(),
-                            plan.db.as_str(),
-                            plan.table.as_str(),
-                            partition.to_vec(),
-                        )?;
-                        pipeline.add_source(Arc::new(source))?;
-                    }
+            }
+            PlanNode::Projection(plan) => {
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(ProjectionTransform::try_create(
+                        plan.schema.clone(),
+                        plan.expr.clone(),
+                    )?))
+                })?;
+            }
+            PlanNode::Aggregate(plan) => {
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(AggregatePartialTransform::try_create(
+                        plan.schema.clone(),
+                        plan.group_expr.clone(),
+                        plan.aggr_expr.clone(),
+                    )?))
+                })?;
+
+                pipeline.merge_processor()?;
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(AggregateFinalTransform::try_create(
+                        plan.schema.clone(),
+                        plan.group_expr.clone(),
+                        plan.aggr_expr.clone(),
+                    )?))
+                })?;
+            }
+            PlanNode::Sort(plan) => {
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(SortPartialTransform::try_create(
+                        plan.order_by.clone(),
+                    )?))
+                })?;
+                pipeline.merge_processor_with(|| {
+                    Ok(Box::new(MergeSortProcessor::try_create(
+                        plan.order_by.clone(),
+                        None,
+                    )?))
+                })?;
+            }
+            PlanNode::TopN(plan) => {
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(TopNPartialTransform::try_create(
+                        plan.order_by.clone(),
+                        plan.n,
+                    )?))
+                })?;
+                pipeline.merge_processor_with(|| {
+                    Ok(Box::new(MergeSortProcessor::try_create(
+                        plan.order_by.clone(),
+                        Some(plan.n),
+                    )?))
+                })?;
+            }
+            PlanNode::Window(plan) => {
+                // The partitions may span all the pipes.
+                pipeline.merge_processor()?;
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(WindowTransform::try_create(
+                        plan.schema.clone(),
+                        plan.window_expr.clone(),
+                    )?))
+                })?;
+            }
+            PlanNode::Filter(plan) => {
+                pipeline.add_simple_transform(|| {
+                    Ok(Box::new(FilterTransform::try_create(
+                        plan.predicate.clone(),
+                    )?))
+                })?;
+            }
+            PlanNode::ReadSource(plan) => {
+                let mut shuffle = vec![];
+                let workers = self.ctx.worker_threads;
+                let workers = if workers == 0 || workers >= plan.partitions.len() {
+                    1
+                } else {
+                    plan.partitions.len() / workers
+                };
+
+                for chunk in plan.partitions.chunks(workers) {
+                    shuffle.push(chunk);
                 }
-                other => {
-                    return Err(FuseQueryError::Internal(format!(
-                        "Build pipeline from the plan node unsupported:{:?}",
-                        other.name()
-                    )))
+
+                for partition in shuffle {
+                    let source = SourceTransform::try_create(
+                        self.ctx.clone(),
+                        plan.db.as_str(),
+                        plan.table.as_str(),
+                        partition.to_vec(),
+                    )?;
+                    pipeline.add_source(Arc::new(source))?;
                 }
             }
+            other => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Build pipeline from the plan node unsupported:{:?}",
+                    other.name()
+                )))
+            }
         }
-        pipeline.merge_processor()?;
-        Ok(pipeline)
+        self.pipelines.push(pipeline);
+        Ok(true)
     }
 }
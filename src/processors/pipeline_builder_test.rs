@@ -24,11 +24,12 @@ async fn test_pipeline_builder() -> crate::error::FuseQueryResult<()> {
     let pipeline = PipelineBuilder::create(ctx, plan).build()?;
     let expect = "\
     \n  └─ LimitTransform × 1 processor\
-    \n    └─ AggregateFinalTransform × 1 processor\
-    \n      └─ Merge (AggregatePartialTransform × 8 processors) to (MergeProcessor × 1)\
-    \n        └─ AggregatePartialTransform × 8 processors\
-    \n          └─ FilterTransform × 8 processors\
-    \n            └─ SourceTransform × 8 processors";
+    \n    └─ ProjectionTransform × 1 processor\
+    \n      └─ AggregateFinalTransform × 1 processor\
+    \n        └─ Merge (AggregatePartialTransform × 8 processors) to (MergeProcessor × 1)\
+    \n          └─ AggregatePartialTransform × 8 processors\
+    \n            └─ FilterTransform × 8 processors\
+    \n              └─ SourceTransform × 8 processors";
     let actual = format!("{:?}", pipeline);
     assert_eq!(expect, actual);
     Ok(())
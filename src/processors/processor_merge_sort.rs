@@ -0,0 +1,461 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+use arrow::array::as_ordarray;
+use arrow::compute::{SortColumn, SortOptions};
+use async_trait::async_trait;
+use tokio::stream::StreamExt;
+use tokio::sync::mpsc;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{ChannelStream, SendableDataBlockStream};
+use crate::datavalues::DataValue;
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::functions::Function;
+use crate::planners::ExpressionPlan;
+use crate::processors::{FormatterSettings, IProcessor};
+
+/// The rows of the blocks of the merged stream.
+const MERGE_SORT_BLOCK_SIZE: usize = 10000;
+
+/// Merge the sorted streams of many processors into one sorted stream(k-way merge).
+/// It replaces the MergeProcessor when the order of the inputs must be preserved.
+/// The merged blocks are streamed as they fill, the merge stops at the limit if it's set.
+pub struct MergeSortProcessor {
+    funcs: Vec<Function>,
+    options: Vec<SortOptions>,
+    limit: Option<usize>,
+    list: Vec<Arc<dyn IProcessor>>,
+}
+
+impl MergeSortProcessor {
+    pub fn try_create(
+        order_by: Vec<ExpressionPlan>,
+        limit: Option<usize>,
+    ) -> FuseQueryResult<Self> {
+        let (funcs, options) = Self::sort_descriptions(&order_by)?;
+        Ok(MergeSortProcessor {
+            funcs,
+            options,
+            limit,
+            list: vec![],
+        })
+    }
+
+    /// Get the key functions and the options from the sort expressions.
+    pub fn sort_descriptions(
+        order_by: &[ExpressionPlan],
+    ) -> FuseQueryResult<(Vec<Function>, Vec<SortOptions>)> {
+        let mut funcs = Vec::with_capacity(order_by.len());
+        let mut options = Vec::with_capacity(order_by.len());
+        for expr in order_by {
+            match expr {
+                ExpressionPlan::Sort {
+                    asc, nulls_first, ..
+                } => {
+                    funcs.push(expr.to_function()?);
+                    options.push(SortOptions {
+                        descending: !*asc,
+                        nulls_first: *nulls_first,
+                    });
+                }
+                _ => {
+                    return Err(FuseQueryError::Internal(format!(
+                        "Expression {:?} is not a sort expression",
+                        expr
+                    )))
+                }
+            }
+        }
+        Ok((funcs, options))
+    }
+
+    /// Evaluate the sort key columns of the block.
+    pub fn sort_columns(
+        block: &DataBlock,
+        funcs: &[Function],
+        options: &[SortOptions],
+    ) -> FuseQueryResult<Vec<SortColumn>> {
+        let mut columns = Vec::with_capacity(funcs.len());
+        for (func, option) in funcs.iter().zip(options) {
+            let mut func = func.clone();
+            columns.push(SortColumn {
+                values: func.eval(block)?.to_array(block.num_rows())?,
+                options: Some(*option),
+            });
+        }
+        Ok(columns)
+    }
+
+    /// Merge the sorted blocks, returns the merged row indices of the concatenated block.
+    pub fn merge_indices(
+        columns: &[SortColumn],
+        ranges: &[(usize, usize)],
+        limit: Option<usize>,
+    ) -> FuseQueryResult<Vec<u32>> {
+        let ord_arrays = columns
+            .iter()
+            .map(|column| as_ordarray(&column.values))
+            .collect::<arrow::error::Result<Vec<_>>>()?;
+
+        let compare = |a: usize, b: usize| -> Ordering {
+            for (i, column) in columns.iter().enumerate() {
+                let options = column.options.unwrap_or_default();
+                let order = match (column.values.is_valid(a), column.values.is_valid(b)) {
+                    (true, true) => {
+                        let order = ord_arrays[i].cmp_value(a, b);
+                        if options.descending {
+                            order.reverse()
+                        } else {
+                            order
+                        }
+                    }
+                    (false, true) if options.nulls_first => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (true, false) if options.nulls_first => Ordering::Greater,
+                    (true, false) => Ordering::Less,
+                    (false, false) => Ordering::Equal,
+                };
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            Ordering::Equal
+        };
+
+        let rows: usize = ranges.iter().map(|(start, end)| end - start).sum();
+        let rows = limit.map_or(rows, |n| std::cmp::min(n, rows));
+        let mut cursors = ranges.to_vec();
+        let mut indices = Vec::with_capacity(rows);
+        while indices.len() < rows {
+            // The first smallest one wins to keep the merge stable.
+            let mut min: Option<usize> = None;
+            for (i, (pos, end)) in cursors.iter().enumerate() {
+                if pos >= end {
+                    continue;
+                }
+                min = match min {
+                    Some(m) if compare(*pos, cursors[m].0) != Ordering::Less => Some(m),
+                    _ => Some(i),
+                };
+            }
+
+            match min {
+                Some(m) => {
+                    indices.push(cursors[m].0 as u32);
+                    cursors[m].0 += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(indices)
+    }
+}
+
+#[async_trait]
+impl IProcessor for MergeSortProcessor {
+    fn name(&self) -> &str {
+        "MergeSortProcessor"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.list.push(input);
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        if self.list.is_empty() {
+            return Err(FuseQueryError::Internal(
+                "Merge sort processor cannot be zero".to_string(),
+            ));
+        }
+
+        // Pull the sorted inputs in parallel, each one is buffered by a block.
+        let mut inputs = Vec::with_capacity(self.list.len());
+        for input in &self.list {
+            let input = input.clone();
+            let (sender, receiver) = mpsc::channel::<FuseQueryResult<DataBlock>>(1);
+            tokio::spawn(async move {
+                let mut stream = match input.execute().await {
+                    Err(e) => {
+                        sender.send(Err(e)).await.ok();
+                        return;
+                    }
+                    Ok(stream) => stream,
+                };
+                while let Some(item) = stream.next().await {
+                    if sender.send(item).await.is_err() {
+                        return;
+                    }
+                }
+            });
+            inputs.push(receiver);
+        }
+
+        let (sender, receiver) = mpsc::channel::<FuseQueryResult<DataBlock>>(1);
+        let merger = MergeSorter {
+            funcs: self.funcs.clone(),
+            options: self.options.clone(),
+            limit: self.limit,
+            inputs,
+            sender: sender.clone(),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = merger.merge().await {
+                sender.send(Err(e)).await.ok();
+            }
+        });
+        Ok(Box::pin(ChannelStream { input: receiver }))
+    }
+
+    fn format(
+        &self,
+        f: &mut std::fmt::Formatter,
+        setting: &mut FormatterSettings,
+    ) -> std::fmt::Result {
+        if setting.indent > 0 {
+            writeln!(f)?;
+            for _ in 0..setting.indent {
+                write!(f, "{}", setting.indent_char)?;
+            }
+        }
+        write!(
+            f,
+            "{} Merge ({} × {} {}) to ({} × {})",
+            setting.prefix,
+            setting.prev_name,
+            setting.prev_ways,
+            if setting.prev_ways == 1 {
+                "processor"
+            } else {
+                "processors"
+            },
+            self.name(),
+            setting.ways
+        )
+    }
+}
+
+/// The cursor of one sorted input at the current row of its current block.
+struct MergeCursor {
+    input: usize,
+    // The offset of the block in the blocks of the current output block.
+    base: usize,
+    block: DataBlock,
+    columns: Vec<SortColumn>,
+    row: usize,
+    // The sort keys of the current row.
+    keys: Vec<DataValue>,
+    options: Vec<SortOptions>,
+}
+
+impl MergeCursor {
+    fn try_create(
+        input: usize,
+        block: DataBlock,
+        funcs: &[Function],
+        options: &[SortOptions],
+    ) -> FuseQueryResult<Self> {
+        let columns = MergeSortProcessor::sort_columns(&block, funcs, options)?;
+        // The sort keys must be of the orderable types.
+        for column in &columns {
+            as_ordarray(&column.values)?;
+        }
+        let mut cursor = MergeCursor {
+            input,
+            base: 0,
+            block,
+            columns,
+            row: 0,
+            keys: vec![],
+            options: options.to_vec(),
+        };
+        cursor.load_keys()?;
+        Ok(cursor)
+    }
+
+    fn load_keys(&mut self) -> FuseQueryResult<()> {
+        self.keys = self
+            .columns
+            .iter()
+            .map(|column| DataValue::try_from_array(&column.values, self.row))
+            .collect::<FuseQueryResult<Vec<_>>>()?;
+        Ok(())
+    }
+
+    /// Move to the next row, false if the block is finished.
+    fn advance(&mut self) -> FuseQueryResult<bool> {
+        self.row += 1;
+        if self.row >= self.block.num_rows() {
+            return Ok(false);
+        }
+        self.load_keys()?;
+        Ok(true)
+    }
+}
+
+// The order of the values of one sort key, which is the order of the arrays in merge_indices:
+// the NaN is the largest float and the negative zero is before the zero.
+fn compare_sort_values(a: &DataValue, b: &DataValue, options: &SortOptions) -> Ordering {
+    let total_cmp_f32 = |a: f32, b: f32| {
+        let canonical = |v: f32| if v.is_nan() { f32::NAN } else { v };
+        canonical(a).total_cmp(&canonical(b))
+    };
+    let total_cmp_f64 = |a: f64, b: f64| {
+        let canonical = |v: f64| if v.is_nan() { f64::NAN } else { v };
+        canonical(a).total_cmp(&canonical(b))
+    };
+
+    let order = match (a.is_null(), b.is_null()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) if options.nulls_first => return Ordering::Less,
+        (true, false) => return Ordering::Greater,
+        (false, true) if options.nulls_first => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => match (a, b) {
+            (DataValue::Boolean(Some(a)), DataValue::Boolean(Some(b))) => a.cmp(b),
+            (DataValue::Int8(Some(a)), DataValue::Int8(Some(b))) => a.cmp(b),
+            (DataValue::Int16(Some(a)), DataValue::Int16(Some(b))) => a.cmp(b),
+            (DataValue::Int32(Some(a)), DataValue::Int32(Some(b))) => a.cmp(b),
+            (DataValue::Int64(Some(a)), DataValue::Int64(Some(b))) => a.cmp(b),
+            (DataValue::UInt8(Some(a)), DataValue::UInt8(Some(b))) => a.cmp(b),
+            (DataValue::UInt16(Some(a)), DataValue::UInt16(Some(b))) => a.cmp(b),
+            (DataValue::UInt32(Some(a)), DataValue::UInt32(Some(b))) => a.cmp(b),
+            (DataValue::UInt64(Some(a)), DataValue::UInt64(Some(b))) => a.cmp(b),
+            (DataValue::Float32(Some(a)), DataValue::Float32(Some(b))) => total_cmp_f32(*a, *b),
+            (DataValue::Float64(Some(a)), DataValue::Float64(Some(b))) => total_cmp_f64(*a, *b),
+            (DataValue::String(Some(a)), DataValue::String(Some(b))) => a.cmp(b),
+            (DataValue::Date32(Some(a)), DataValue::Date32(Some(b))) => a.cmp(b),
+            (DataValue::Timestamp(Some(a)), DataValue::Timestamp(Some(b))) => a.cmp(b),
+            (DataValue::Decimal(Some(a), ..), DataValue::Decimal(Some(b), ..)) => a.cmp(b),
+            _ => Ordering::Equal,
+        },
+    };
+    if options.descending {
+        order.reverse()
+    } else {
+        order
+    }
+}
+
+impl Ord for MergeCursor {
+    // The smallest row is the greatest cursor of the max-heap,
+    // the rows of the same keys are in the order of the inputs to keep the merge stable.
+    fn cmp(&self, other: &Self) -> Ordering {
+        for ((a, b), options) in self.keys.iter().zip(&other.keys).zip(&self.options) {
+            let order = compare_sort_values(a, b, options);
+            if order != Ordering::Equal {
+                return order.reverse();
+            }
+        }
+        other.input.cmp(&self.input)
+    }
+}
+
+impl PartialOrd for MergeCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for MergeCursor {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MergeCursor {}
+
+/// The streaming k-way merge of the sorted inputs, the heap holds the cursor of each input
+/// and the merged rows are sent as the blocks of MERGE_SORT_BLOCK_SIZE rows.
+struct MergeSorter {
+    funcs: Vec<Function>,
+    options: Vec<SortOptions>,
+    limit: Option<usize>,
+    inputs: Vec<mpsc::Receiver<FuseQueryResult<DataBlock>>>,
+    sender: mpsc::Sender<FuseQueryResult<DataBlock>>,
+}
+
+impl MergeSorter {
+    // The cursor of the next non-empty block of the input, None if the input is finished.
+    async fn next_cursor(&mut self, input: usize) -> FuseQueryResult<Option<MergeCursor>> {
+        while let Some(block) = self.inputs[input].next().await {
+            let block = block?;
+            if block.num_rows() > 0 {
+                return Ok(Some(MergeCursor::try_create(
+                    input,
+                    block,
+                    &self.funcs,
+                    &self.options,
+                )?));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn merge(mut self) -> FuseQueryResult<()> {
+        let mut remain = self.limit.unwrap_or(usize::MAX);
+        // The blocks of the rows of the current output block and their total rows.
+        let mut blocks: Vec<DataBlock> = vec![];
+        let mut rows = 0;
+        let mut indices: Vec<u32> = vec![];
+
+        let mut heap = BinaryHeap::with_capacity(self.inputs.len());
+        for input in 0..self.inputs.len() {
+            if let Some(mut cursor) = self.next_cursor(input).await? {
+                cursor.base = rows;
+                rows += cursor.block.num_rows();
+                blocks.push(cursor.block.clone());
+                heap.push(cursor);
+            }
+        }
+
+        while remain > 0 {
+            let mut cursor = match heap.pop() {
+                Some(cursor) => cursor,
+                None => break,
+            };
+            indices.push((cursor.base + cursor.row) as u32);
+            remain -= 1;
+
+            if cursor.advance()? {
+                heap.push(cursor);
+            } else if let Some(mut next) = self.next_cursor(cursor.input).await? {
+                next.base = rows;
+                rows += next.block.num_rows();
+                blocks.push(next.block.clone());
+                heap.push(next);
+            }
+
+            if indices.len() == MERGE_SORT_BLOCK_SIZE {
+                let block = DataBlock::concat_blocks(&blocks)?.take(&indices)?;
+                if self.sender.send(Ok(block)).await.is_err() {
+                    return Ok(());
+                }
+                indices.clear();
+
+                // Only the blocks of the cursors are kept for the next output block.
+                blocks.clear();
+                rows = 0;
+                let mut cursors = heap.into_vec();
+                for cursor in cursors.iter_mut() {
+                    cursor.base = rows;
+                    rows += cursor.block.num_rows();
+                    blocks.push(cursor.block.clone());
+                }
+                heap = BinaryHeap::from(cursors);
+            }
+        }
+
+        if !indices.is_empty() {
+            let block = DataBlock::concat_blocks(&blocks)?.take(&indices)?;
+            self.sender.send(Ok(block)).await.ok();
+        }
+        Ok(())
+    }
+}
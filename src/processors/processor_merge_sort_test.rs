@@ -0,0 +1,61 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_merge_sort() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::datavalues::*;
+    use crate::planners::*;
+    use crate::processors::*;
+    use crate::testdata;
+
+    let test_source = testdata::NumberTestData::create();
+    let order_by = vec![ExpressionPlan::Sort {
+        expr: Box::new(field("number")),
+        asc: true,
+        nulls_first: false,
+    }];
+
+    // Each source is the ascending numbers of many blocks.
+    let tests = vec![
+        (None, vec![10000, 10000, 10000, 10000, 10000]),
+        (Some(15000), vec![10000, 5000]),
+    ];
+    for (limit, expect) in tests {
+        let mut pipeline = Pipeline::create();
+        let a = test_source.number_source_transform_for_test(25000)?;
+        pipeline.add_source(Arc::new(a))?;
+        let b = test_source.number_source_transform_for_test(25000)?;
+        pipeline.add_source(Arc::new(b))?;
+        pipeline.merge_processor_with(|| {
+            Ok(Box::new(MergeSortProcessor::try_create(
+                order_by.clone(),
+                limit,
+            )?))
+        })?;
+
+        let stream = pipeline.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        let actual = blocks
+            .iter()
+            .map(|block| block.num_rows())
+            .collect::<Vec<_>>();
+        assert_eq!(expect, actual);
+
+        let mut values = vec![];
+        for block in &blocks {
+            let array = block
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap();
+            values.extend(array.value_slice(0, array.len()).to_vec());
+        }
+        let expect = (0..values.len() as u64).map(|i| i / 2).collect::<Vec<_>>();
+        assert_eq!(expect, values);
+    }
+    Ok(())
+}
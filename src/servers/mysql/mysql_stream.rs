@@ -5,8 +5,10 @@
 use msql_srv::*;
 
 use crate::datablocks::DataBlock;
+use crate::datavalues;
+use crate::datavalues::DataValue;
 use crate::error::{FuseQueryError, FuseQueryResult};
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, TimeUnit};
 use arrow::util::display::array_value_to_string;
 
 pub struct MySQLStream {
@@ -48,12 +50,31 @@ impl MySQLStream {
                     coltype: ColumnType::MYSQL_TYPE_FLOAT,
                     colflags: ColumnFlags::empty(),
                 },
-                DataType::Utf8 => Column {
+                // The arrays and the tuples are written as the strings such as [1, 2] and (1, 'a').
+                DataType::Utf8 | DataType::List(_) | DataType::Struct(_) => Column {
                     table: "".to_string(),
                     column: field.name().to_string(),
                     coltype: ColumnType::MYSQL_TYPE_VARCHAR,
                     colflags: ColumnFlags::empty(),
                 },
+                DataType::Date32(_) => Column {
+                    table: "".to_string(),
+                    column: field.name().to_string(),
+                    coltype: ColumnType::MYSQL_TYPE_DATE,
+                    colflags: ColumnFlags::empty(),
+                },
+                DataType::Timestamp(TimeUnit::Second, _) => Column {
+                    table: "".to_string(),
+                    column: field.name().to_string(),
+                    coltype: ColumnType::MYSQL_TYPE_DATETIME,
+                    colflags: ColumnFlags::empty(),
+                },
+                data_type if datavalues::is_decimal(data_type) => Column {
+                    table: "".to_string(),
+                    column: field.name().to_string(),
+                    coltype: ColumnType::MYSQL_TYPE_NEWDECIMAL,
+                    colflags: ColumnFlags::empty(),
+                },
                 _ => {
                     return Err(FuseQueryError::Internal(format!(
                         "Unsupported column type:{:?}",
@@ -73,7 +94,17 @@ impl MySQLStream {
                     let mut row = Vec::with_capacity(cols_num);
                     for c in 0..cols_num {
                         let column = block.column(c);
-                        row.push(array_value_to_string(column, r)?);
+                        // The arrow display prints the temporal values and the decimals as the numbers,
+                        // and it has no display of the arrays and the tuples.
+                        match column.data_type() {
+                            DataType::Date32(_)
+                            | DataType::Timestamp(_, _)
+                            | DataType::List(_)
+                            | DataType::Struct(_) => {
+                                row.push(DataValue::try_from_array(column, r)?.to_string())
+                            }
+                            _ => row.push(array_value_to_string(column, r)?),
+                        }
                     }
                     row_writer.write_row(row)?;
                 }
@@ -4,20 +4,37 @@
 
 mod transform_aggregate_test;
 mod transform_filter_test;
+mod transform_join_test;
 mod transform_limit_test;
 mod transform_projection_test;
+mod transform_sort_test;
 mod transform_source_test;
+mod transform_top_n_test;
 
 mod transform_aggregate_final;
 mod transform_aggregate_partial;
+mod transform_cast;
 mod transform_filter;
+mod transform_join_build;
+mod transform_join_probe;
 mod transform_limit;
+mod transform_materialize;
 mod transform_projection;
+mod transform_sort_partial;
 mod transform_source;
+mod transform_top_n_partial;
+mod transform_window;
 
 pub use self::transform_aggregate_final::AggregateFinalTransform;
 pub use self::transform_aggregate_partial::AggregatePartialTransform;
+pub use self::transform_cast::CastTransform;
 pub use self::transform_filter::FilterTransform;
+pub use self::transform_join_build::{HashJoinBuildTransform, JoinHashTable};
+pub use self::transform_join_probe::HashJoinProbeTransform;
 pub use self::transform_limit::LimitTransform;
+pub use self::transform_materialize::MaterializeTransform;
 pub use self::transform_projection::ProjectionTransform;
+pub use self::transform_sort_partial::SortPartialTransform;
 pub use self::transform_source::SourceTransform;
+pub use self::transform_top_n_partial::TopNPartialTransform;
+pub use self::transform_window::WindowTransform;
@@ -2,6 +2,7 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -9,26 +10,34 @@ use tokio::stream::StreamExt;
 
 use crate::datablocks::DataBlock;
 use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues;
 use crate::datavalues::{DataSchemaRef, DataValue};
 use crate::error::FuseQueryResult;
 use crate::functions::Function;
 use crate::planners::ExpressionPlan;
 use crate::processors::{EmptyProcessor, IProcessor};
 
+/// Merge the partial states(see AggregatePartialTransform) of all the pipes by group key.
 pub struct AggregateFinalTransform {
+    group_exprs_len: usize,
     funcs: Vec<Function>,
     schema: DataSchemaRef,
     input: Arc<dyn IProcessor>,
 }
 
 impl AggregateFinalTransform {
-    pub fn try_create(schema: DataSchemaRef, exprs: Vec<ExpressionPlan>) -> FuseQueryResult<Self> {
-        let mut funcs = Vec::with_capacity(exprs.len());
-        for expr in &exprs {
+    pub fn try_create(
+        schema: DataSchemaRef,
+        group_exprs: Vec<ExpressionPlan>,
+        aggr_exprs: Vec<ExpressionPlan>,
+    ) -> FuseQueryResult<Self> {
+        let mut funcs = Vec::with_capacity(aggr_exprs.len());
+        for expr in &aggr_exprs {
             funcs.push(expr.to_function()?);
         }
 
         Ok(AggregateFinalTransform {
+            group_exprs_len: group_exprs.len(),
             funcs,
             schema,
             input: Arc::new(EmptyProcessor::create()),
@@ -48,26 +57,77 @@ impl IProcessor for AggregateFinalTransform {
     }
 
     async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
-        let mut funcs = self.funcs.clone();
+        // The groups of (group values, aggregate functions) in the first-seen order,
+        // indexed by the keys of the group values.
+        let mut groups: Vec<(Vec<DataValue>, Vec<Function>)> = vec![];
+        let mut group_indices: HashMap<Vec<u8>, usize> = HashMap::new();
+
         let mut stream = self.input.execute().await?;
         while let Some(block) = stream.next().await {
             let block = block?;
+            let group_columns = &block.columns()[..self.group_exprs_len];
+            let keys = datavalues::data_array_row_keys(group_columns)?;
+
+            for row in 0..block.num_rows() {
+                // Without GROUP BY, the states of all the pipes are merged into one group.
+                let key = match self.group_exprs_len {
+                    0 => &[] as &[u8],
+                    _ => keys.key(row),
+                };
+                let index = match group_indices.get(key) {
+                    Some(index) => *index,
+                    None => {
+                        let mut group_values = Vec::with_capacity(group_columns.len());
+                        for column in group_columns {
+                            group_values.push(DataValue::try_from_array(column, row)?);
+                        }
+                        groups.push((group_values, self.funcs.clone()));
+                        group_indices.insert(key.to_vec(), groups.len() - 1);
+                        groups.len() - 1
+                    }
+                };
 
-            for (i, func) in funcs.iter_mut().enumerate() {
-                if let DataValue::String(Some(serialized)) =
-                    DataValue::try_from_array(block.column(0), i)?
-                {
-                    let deserialized: DataValue = serde_json::from_str(&serialized)?;
-                    if let DataValue::Struct(states) = deserialized {
-                        func.merge_state(&states)?;
+                for (i, func) in groups[index].1.iter_mut().enumerate() {
+                    if let DataValue::String(Some(serialized)) =
+                        DataValue::try_from_array(block.column(self.group_exprs_len + i), row)?
+                    {
+                        let deserialized: DataValue = serde_json::from_str(&serialized)?;
+                        if let DataValue::Struct(states) = deserialized {
+                            func.merge_state(&states)?;
+                        }
                     }
                 }
             }
         }
 
-        let mut arrays = Vec::with_capacity(funcs.len());
-        for func in &funcs {
-            arrays.push(func.merge_result()?.to_array(1)?);
+        // Without GROUP BY there is always one result row.
+        if self.group_exprs_len == 0 && groups.is_empty() {
+            groups.push((vec![], self.funcs.clone()));
+        }
+
+        let fields = self.schema.fields();
+        let mut columns: Vec<Vec<DataValue>> = vec![Vec::with_capacity(groups.len()); fields.len()];
+        for (group_values, funcs) in &groups {
+            for (i, value) in group_values.iter().enumerate() {
+                columns[i].push(value.clone());
+            }
+            for (i, func) in funcs.iter().enumerate() {
+                columns[self.group_exprs_len + i].push(func.merge_result()?);
+            }
+        }
+
+        let mut arrays = Vec::with_capacity(fields.len());
+        for (i, values) in columns.iter().enumerate() {
+            // The arrays are of the types of the schema, the values of the other types are cast,
+            // the NULLs such as the sum of empty input are the NULLs of the type.
+            let data_type = fields[i].data_type();
+            arrays.push(match values.iter().find(|v| !v.is_null()) {
+                Some(v) if &v.data_type() != data_type => datavalues::data_array_cast(
+                    &DataValue::try_into_data_array(values, &v.data_type())?,
+                    data_type,
+                )?,
+                _ => DataValue::try_into_data_array(values, data_type)?,
+            });
         }
         let block = DataBlock::create(self.schema.clone(), arrays);
         Ok(Box::pin(DataBlockStream::create(
@@ -2,6 +2,7 @@
 //
 // Code is licensed under AGPL License, Version 3.0.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -9,31 +10,60 @@ use tokio::stream::StreamExt;
 
 use crate::datablocks::DataBlock;
 use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
-use crate::datavalues::{DataSchemaRef, DataValue, StringArray};
+use crate::datavalues;
+use crate::datavalues::{DataField, DataSchema, DataSchemaRef, DataType, DataValue, StringArray};
 use crate::error::FuseQueryResult;
 use crate::functions::Function;
 use crate::planners::ExpressionPlan;
 use crate::processors::{EmptyProcessor, IProcessor};
 
+/// The partial aggregation of one pipe.
+///
+/// Output one row per group:
+/// the columns of the group values, which are merged by their binary keys,
+/// then the serialized states of the aggregate functions.
 pub struct AggregatePartialTransform {
-    funcs: Vec<Function>,
+    group_funcs: Vec<Function>,
+    aggr_funcs: Vec<Function>,
     schema: DataSchemaRef,
     input: Arc<dyn IProcessor>,
 }
 
 impl AggregatePartialTransform {
-    pub fn try_create(schema: DataSchemaRef, exprs: Vec<ExpressionPlan>) -> FuseQueryResult<Self> {
-        let mut funcs = Vec::with_capacity(exprs.len());
-        for expr in &exprs {
-            funcs.push(expr.to_function()?);
+    pub fn try_create(
+        schema: DataSchemaRef,
+        group_exprs: Vec<ExpressionPlan>,
+        aggr_exprs: Vec<ExpressionPlan>,
+    ) -> FuseQueryResult<Self> {
+        let mut group_funcs = Vec::with_capacity(group_exprs.len());
+        for expr in &group_exprs {
+            group_funcs.push(expr.to_function()?);
+        }
+
+        let mut aggr_funcs = Vec::with_capacity(aggr_exprs.len());
+        for expr in &aggr_exprs {
+            aggr_funcs.push(expr.to_function()?);
         }
 
         Ok(AggregatePartialTransform {
-            funcs,
+            group_funcs,
+            aggr_funcs,
             schema,
             input: Arc::new(EmptyProcessor::create()),
         })
     }
+
+    /// The schema of the partial states block.
+    fn partial_schema(&self) -> DataSchemaRef {
+        let mut fields = Vec::with_capacity(self.schema.fields().len());
+        for field in self.schema.fields().iter().take(self.group_funcs.len()) {
+            fields.push(field.clone());
+        }
+        for field in self.schema.fields().iter().skip(self.group_funcs.len()) {
+            fields.push(DataField::new(field.name(), DataType::Utf8, false));
+        }
+        Arc::new(DataSchema::new(fields))
+    }
 }
 
 #[async_trait]
@@ -48,32 +78,92 @@ impl IProcessor for AggregatePartialTransform {
     }
 
     async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
-        let mut funcs = self.funcs.clone();
+        // The groups of (group values, aggregate functions), indexed by the keys of the group values.
+        let mut groups: Vec<(Vec<DataValue>, Vec<Function>)> = vec![];
+        let mut group_indices: HashMap<Vec<u8>, usize> = HashMap::new();
+
+        // Without GROUP BY, all the rows are folded into one group even if the input is empty.
+        if self.group_funcs.is_empty() {
+            groups.push((vec![], self.aggr_funcs.clone()));
+        }
+
+        let mut group_funcs = self.group_funcs.clone();
+        let mut aggr_funcs = self.aggr_funcs.clone();
         let mut stream = self.input.execute().await?;
         while let Some(block) = stream.next().await {
             let block = block?;
+            let rows = block.num_rows();
 
-            for func in funcs.iter_mut() {
-                func.accumulate(&block)?;
+            if group_funcs.is_empty() {
+                for func in groups[0].1.iter_mut() {
+                    func.accumulate(&block)?;
+                }
+                continue;
+            }
+
+            let mut group_columns = Vec::with_capacity(group_funcs.len());
+            for func in group_funcs.iter_mut() {
+                group_columns.push(func.eval(&block)?.to_array(rows)?);
+            }
+            let keys = datavalues::data_array_row_keys(&group_columns)?;
+
+            // The arguments of the aggregators are evaluated once for all the groups of the block.
+            let mut aggr_args = Vec::with_capacity(aggr_funcs.len());
+            for func in aggr_funcs.iter_mut() {
+                let mut args = vec![];
+                func.eval_aggregate_args(&block, &mut args)?;
+                aggr_args.push(args);
+            }
+
+            for row in 0..rows {
+                let key = keys.key(row);
+                let index = match group_indices.get(key) {
+                    Some(index) => *index,
+                    None => {
+                        let mut group_values = Vec::with_capacity(group_columns.len());
+                        for column in &group_columns {
+                            group_values.push(DataValue::try_from_array(column, row)?);
+                        }
+                        groups.push((group_values, self.aggr_funcs.clone()));
+                        group_indices.insert(key.to_vec(), groups.len() - 1);
+                        groups.len() - 1
+                    }
+                };
+                for (func, args) in groups[index].1.iter_mut().zip(&aggr_args) {
+                    func.accumulate_row(&mut args.iter(), row)?;
+                }
             }
         }
 
-        let mut column = Vec::with_capacity(funcs.len());
-        for func in &funcs {
-            let states = DataValue::Struct(func.accumulate_result()?);
-            let serialized = serde_json::to_string(&states)?;
-            column.push(serialized);
+        let mut group_columns: Vec<Vec<DataValue>> =
+            vec![Vec::with_capacity(groups.len()); self.group_funcs.len()];
+        let mut state_columns: Vec<Vec<String>> =
+            vec![Vec::with_capacity(groups.len()); self.aggr_funcs.len()];
+        for (group_values, funcs) in groups {
+            for (i, value) in group_values.into_iter().enumerate() {
+                group_columns[i].push(value);
+            }
+            for (i, func) in funcs.iter().enumerate() {
+                let states = DataValue::Struct(func.accumulate_result()?);
+                state_columns[i].push(serde_json::to_string(&states)?);
+            }
         }
 
-        let column_str = column.iter().map(|x| x.as_str()).collect::<Vec<&str>>();
-        let block = DataBlock::create(
-            self.schema.clone(),
-            vec![Arc::new(StringArray::from(column_str))],
-        );
-        Ok(Box::pin(DataBlockStream::create(
-            self.schema.clone(),
-            None,
-            vec![block],
-        )))
+        let schema = self.partial_schema();
+        let mut arrays = Vec::with_capacity(schema.fields().len());
+        for (i, values) in group_columns.iter().enumerate() {
+            // The group values are of the types of the values, as the results of AggregateFinalTransform.
+            let data_type = match values.iter().find(|v| !v.is_null()) {
+                Some(v) => v.data_type(),
+                None => schema.field(i).data_type().clone(),
+            };
+            arrays.push(DataValue::try_into_data_array(values, &data_type)?);
+        }
+        for column in &state_columns {
+            let column_str = column.iter().map(|x| x.as_str()).collect::<Vec<&str>>();
+            arrays.push(Arc::new(StringArray::from(column_str)) as _);
+        }
+        let block = DataBlock::create(schema.clone(), arrays);
+        Ok(Box::pin(DataBlockStream::create(schema, None, vec![block])))
     }
 }
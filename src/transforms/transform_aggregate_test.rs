@@ -26,6 +26,7 @@ async fn test_transform_projection() -> crate::error::FuseQueryResult<()> {
                 ExpressionPlan::Function {
                     op: "sum".to_string(),
                     args: vec![planners::field("number")],
+                    functions: None,
                 },
                 planners::constant(2u64),
             )],
@@ -35,6 +36,7 @@ async fn test_transform_projection() -> crate::error::FuseQueryResult<()> {
         pipeline.add_simple_transform(|| {
             Ok(Box::new(AggregatePartialTransform::try_create(
                 plan.schema.clone(),
+                plan.group_expr.clone(),
                 plan.aggr_expr.clone(),
             )?))
         })?;
@@ -42,6 +44,7 @@ async fn test_transform_projection() -> crate::error::FuseQueryResult<()> {
         pipeline.add_simple_transform(|| {
             Ok(Box::new(AggregateFinalTransform::try_create(
                 plan.schema.clone(),
+                plan.group_expr.clone(),
                 plan.aggr_expr.clone(),
             )?))
         })?;
@@ -57,3 +60,82 @@ async fn test_transform_projection() -> crate::error::FuseQueryResult<()> {
     }
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_aggregate_group_by() -> crate::error::FuseQueryResult<()> {
+    use arrow::array::PrimitiveArrayOps;
+    use std::sync::Arc;
+    use tokio::stream::StreamExt;
+
+    use crate::datavalues::*;
+    use crate::planners::{self, *};
+    use crate::processors::*;
+    use crate::testdata;
+    use crate::transforms::*;
+
+    let test_source = testdata::NumberTestData::create();
+    let mut pipeline = Pipeline::create();
+
+    let a = test_source.number_source_transform_for_test(16)?;
+    pipeline.add_source(Arc::new(a))?;
+    let b = test_source.number_source_transform_for_test(16)?;
+    pipeline.add_source(Arc::new(b))?;
+
+    let group_key = ExpressionPlan::BinaryExpression {
+        left: Box::new(planners::field("number")),
+        op: "/".to_string(),
+        right: Box::new(planners::constant(4u64)),
+    };
+    if let PlanNode::Aggregate(plan) = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate(
+            vec![group_key],
+            vec![
+                ExpressionPlan::Function {
+                    op: "count".to_string(),
+                    args: vec![planners::field("number")],
+                    functions: None,
+                },
+                ExpressionPlan::Function {
+                    op: "sum".to_string(),
+                    args: vec![planners::field("number")],
+                    functions: None,
+                },
+            ],
+        )?
+        .build()?
+    {
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(AggregatePartialTransform::try_create(
+                plan.schema.clone(),
+                plan.group_expr.clone(),
+                plan.aggr_expr.clone(),
+            )?))
+        })?;
+        pipeline.merge_processor()?;
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(AggregateFinalTransform::try_create(
+                plan.schema.clone(),
+                plan.group_expr.clone(),
+                plan.aggr_expr.clone(),
+            )?))
+        })?;
+    }
+
+    let mut actual = vec![];
+    let mut stream = pipeline.execute().await?;
+    while let Some(v) = stream.next().await {
+        let v = v?;
+        let keys = v.column(0).as_any().downcast_ref::<UInt64Array>().unwrap();
+        let counts = v.column(1).as_any().downcast_ref::<UInt64Array>().unwrap();
+        let sums = v.column(2).as_any().downcast_ref::<UInt64Array>().unwrap();
+        for i in 0..v.num_rows() {
+            actual.push((keys.value(i), counts.value(i), sums.value(i)));
+        }
+    }
+    actual.sort_unstable();
+
+    // Each group has 4 numbers from both sources.
+    let expect = vec![(0, 8, 12), (1, 8, 44), (2, 8, 76), (3, 8, 108)];
+    assert_eq!(expect, actual);
+    Ok(())
+}
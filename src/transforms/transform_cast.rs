@@ -0,0 +1,68 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{ExpressionStream, SendableDataBlockStream};
+use crate::datavalues;
+use crate::datavalues::DataSchemaRef;
+use crate::error::FuseQueryResult;
+use crate::functions::Function;
+use crate::processors::{EmptyProcessor, IProcessor};
+
+/// Cast the columns of the blocks to the types and the names of the schema, such as the UNION inputs.
+pub struct CastTransform {
+    schema: DataSchemaRef,
+    input: Arc<dyn IProcessor>,
+}
+
+impl CastTransform {
+    pub fn try_create(schema: DataSchemaRef) -> FuseQueryResult<Self> {
+        Ok(CastTransform {
+            schema,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    pub fn expression_executor(
+        schema: &DataSchemaRef,
+        block: DataBlock,
+        _funcs: Vec<Function>,
+    ) -> FuseQueryResult<DataBlock> {
+        let mut columns = Vec::with_capacity(block.num_columns());
+        for (i, field) in schema.fields().iter().enumerate() {
+            let column = block.column(i);
+            if column.data_type() == field.data_type() {
+                columns.push(column.clone());
+            } else {
+                columns.push(datavalues::data_array_cast(column, field.data_type())?);
+            }
+        }
+        Ok(DataBlock::create(schema.clone(), columns))
+    }
+}
+
+#[async_trait]
+impl IProcessor for CastTransform {
+    fn name(&self) -> &str {
+        "CastTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        Ok(Box::pin(ExpressionStream::try_create(
+            self.input.execute().await?,
+            self.schema.clone(),
+            vec![],
+            CastTransform::expression_executor,
+        )?))
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use tokio::sync::Mutex;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues::{DataArrayRef, DataSchemaRef, DataValue};
+use crate::error::FuseQueryResult;
+use crate::functions::Function;
+use crate::planners::ExpressionPlan;
+use crate::processors::{EmptyProcessor, IProcessor};
+
+/// The hash table of the join build side.
+pub struct JoinHashTable {
+    /// All the rows of the build side, None if the build side is empty.
+    pub block: Option<DataBlock>,
+    /// Join key(serialized key values) -> the rows of the block.
+    pub rows: HashMap<String, Vec<u32>>,
+    /// The serialized keys but the last one -> whether the last key of any of the rows is NULL.
+    /// It's only built for the NULL-aware anti join, whose other keys are the correlated keys.
+    pub null_aware_keys: HashMap<String, bool>,
+}
+
+impl JoinHashTable {
+    /// Evaluate the key columns of the block.
+    pub fn key_columns(
+        funcs: &[Function],
+        block: &DataBlock,
+    ) -> FuseQueryResult<Vec<DataArrayRef>> {
+        let mut columns = Vec::with_capacity(funcs.len());
+        for func in funcs {
+            let mut func = func.clone();
+            columns.push(func.eval(block)?.to_array(block.num_rows())?);
+        }
+        Ok(columns)
+    }
+
+    /// The serialized key of the row, None if any key value is NULL which never matches.
+    ///
+    /// The numbers are serialized by value, so the keys of the different integer types match.
+    pub fn row_key(columns: &[DataArrayRef], row: usize) -> FuseQueryResult<Option<String>> {
+        let mut values = Vec::with_capacity(columns.len());
+        for column in columns {
+            if column.is_null(row) {
+                return Ok(None);
+            }
+            values.push(match DataValue::try_from_array(column, row)? {
+                DataValue::Int8(Some(v)) => serde_json::Value::from(v),
+                DataValue::Int16(Some(v)) => serde_json::Value::from(v),
+                DataValue::Int32(Some(v)) => serde_json::Value::from(v),
+                DataValue::Int64(Some(v)) => serde_json::Value::from(v),
+                DataValue::UInt8(Some(v)) => serde_json::Value::from(v),
+                DataValue::UInt16(Some(v)) => serde_json::Value::from(v),
+                DataValue::UInt32(Some(v)) => serde_json::Value::from(v),
+                DataValue::UInt64(Some(v)) => serde_json::Value::from(v),
+                other => serde_json::to_value(&other)?,
+            });
+        }
+        Ok(Some(serde_json::to_string(&values)?))
+    }
+}
+
+/// Build the hash table from the build side(the right input) of the join.
+///
+/// The table is built once and shared by all the HashJoinProbeTransforms.
+pub struct HashJoinBuildTransform {
+    funcs: Vec<Function>,
+    null_aware: bool,
+    schema: DataSchemaRef,
+    table: Mutex<Option<Arc<JoinHashTable>>>,
+    input: Arc<dyn IProcessor>,
+}
+
+impl HashJoinBuildTransform {
+    pub fn try_create(
+        schema: DataSchemaRef,
+        keys: Vec<ExpressionPlan>,
+        null_aware: bool,
+    ) -> FuseQueryResult<Self> {
+        let mut funcs = Vec::with_capacity(keys.len());
+        for key in &keys {
+            funcs.push(key.to_function()?);
+        }
+
+        Ok(HashJoinBuildTransform {
+            funcs,
+            null_aware,
+            schema,
+            table: Mutex::new(None),
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    pub fn schema(&self) -> DataSchemaRef {
+        self.schema.clone()
+    }
+
+    /// Get the hash table, build it from the input at the first time.
+    pub async fn hash_table(&self) -> FuseQueryResult<Arc<JoinHashTable>> {
+        let mut table = self.table.lock().await;
+        if let Some(ref table) = *table {
+            return Ok(table.clone());
+        }
+
+        let blocks = self.input.execute().await?.try_collect::<Vec<_>>().await?;
+        let blocks = blocks
+            .into_iter()
+            .filter(|block| block.num_columns() > 0 && block.num_rows() > 0)
+            .collect::<Vec<_>>();
+
+        let mut rows: HashMap<String, Vec<u32>> = HashMap::new();
+        let mut null_aware_keys: HashMap<String, bool> = HashMap::new();
+        let block = if blocks.is_empty() {
+            None
+        } else {
+            let block = DataBlock::concat_blocks(&blocks)?;
+            let columns = JoinHashTable::key_columns(&self.funcs, &block)?;
+            for row in 0..block.num_rows() {
+                if let Some(key) = JoinHashTable::row_key(&columns, row)? {
+                    rows.entry(key).or_default().push(row as u32);
+                }
+            }
+            if let (true, Some((last, others))) = (self.null_aware, columns.split_last()) {
+                for row in 0..block.num_rows() {
+                    if let Some(key) = JoinHashTable::row_key(others, row)? {
+                        *null_aware_keys.entry(key).or_default() |= last.is_null(row);
+                    }
+                }
+            }
+            Some(block)
+        };
+
+        let built = Arc::new(JoinHashTable {
+            block,
+            rows,
+            null_aware_keys,
+        });
+        *table = Some(built.clone());
+        Ok(built)
+    }
+}
+
+#[async_trait]
+impl IProcessor for HashJoinBuildTransform {
+    fn name(&self) -> &str {
+        "HashJoinBuildTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        let table = self.hash_table().await?;
+        let blocks = match table.block {
+            Some(ref block) => vec![block.clone()],
+            None => vec![],
+        };
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            blocks,
+        )))
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::stream::StreamExt;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues;
+use crate::datavalues::{DataArrayRef, DataField, DataSchemaRef, DataValue, UInt32Array};
+use crate::error::FuseQueryResult;
+use crate::functions::Function;
+use crate::planners::{ExpressionPlan, JoinType};
+use crate::processors::{EmptyProcessor, IProcessor};
+use crate::transforms::{HashJoinBuildTransform, JoinHashTable};
+
+/// Probe the hash table of HashJoinBuildTransform by the rows of the probe side(the left input).
+///
+/// The output rows are the left columns then the right columns,
+/// the unmatched rows of the outer side are filled with NULLs.
+/// The unmatched build rows of RIGHT/FULL join are output at the end,
+/// so there must be only one probe transform for them.
+/// The SEMI/ANTI join outputs the left columns of the matched/unmatched rows only.
+/// The NULL-aware ANTI join of `x NOT IN (subquery)` outputs the rows where it's true, not NULL.
+pub struct HashJoinProbeTransform {
+    schema: DataSchemaRef,
+    join_type: JoinType,
+    funcs: Vec<Function>,
+    build: Arc<HashJoinBuildTransform>,
+    input: Arc<dyn IProcessor>,
+}
+
+impl HashJoinProbeTransform {
+    pub fn try_create(
+        schema: DataSchemaRef,
+        join_type: JoinType,
+        keys: Vec<ExpressionPlan>,
+        build: Arc<HashJoinBuildTransform>,
+    ) -> FuseQueryResult<Self> {
+        let mut funcs = Vec::with_capacity(keys.len());
+        for key in &keys {
+            funcs.push(key.to_function()?);
+        }
+
+        Ok(HashJoinProbeTransform {
+            schema,
+            join_type,
+            funcs,
+            build,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// The columns of all NULLs.
+    fn null_columns(fields: &[DataField], rows: usize) -> FuseQueryResult<Vec<DataArrayRef>> {
+        let values = vec![DataValue::Null; rows];
+        fields
+            .iter()
+            .map(|field| DataValue::try_into_data_array(&values, field.data_type()))
+            .collect()
+    }
+
+    /// Take the rows of the build block, the None indices are NULLs.
+    fn take_build_columns(
+        table: &JoinHashTable,
+        fields: &[DataField],
+        indices: Vec<Option<u32>>,
+    ) -> FuseQueryResult<Vec<DataArrayRef>> {
+        match table.block {
+            Some(ref block) => {
+                let indices = UInt32Array::from(indices);
+                let mut columns = Vec::with_capacity(block.num_columns());
+                for column in block.columns() {
+                    columns.push(datavalues::data_array_take(column, &indices)?);
+                }
+                Ok(columns)
+            }
+            None => Self::null_columns(fields, indices.len()),
+        }
+    }
+
+    /// Whether `x NOT IN (subquery)` is true, x is the last key and the others are the correlated keys.
+    /// It's NULL if x is NULL or the subquery has NULL, and the subquery is not empty.
+    fn not_in(
+        table: &JoinHashTable,
+        key_columns: &[DataArrayRef],
+        row: usize,
+        matched: bool,
+    ) -> FuseQueryResult<bool> {
+        let (last, others) = match key_columns.split_last() {
+            Some(columns) => columns,
+            None => return Ok(!matched),
+        };
+        // The rows of the subquery are the build rows of the same correlated keys.
+        let has_null = match JoinHashTable::row_key(others, row)? {
+            Some(key) => match table.null_aware_keys.get(&key) {
+                Some(has_null) => *has_null,
+                None => return Ok(true),
+            },
+            None => return Ok(true),
+        };
+        Ok(!matched && !has_null && !last.is_null(row))
+    }
+}
+
+#[async_trait]
+impl IProcessor for HashJoinProbeTransform {
+    fn name(&self) -> &str {
+        "HashJoinProbeTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        let table = self.build.hash_table().await?;
+        let build_rows = table.block.as_ref().map_or(0, |block| block.num_rows());
+        let mut build_matched = vec![false; build_rows];
+
+        let semi_or_anti = matches!(
+            self.join_type,
+            JoinType::Semi | JoinType::Anti | JoinType::NullAwareAnti
+        );
+        let fields = self.schema.fields();
+        let (left_fields, right_fields) = if semi_or_anti {
+            (&fields[..], &fields[fields.len()..])
+        } else {
+            fields.split_at(fields.len() - self.build.schema().fields().len())
+        };
+        let left_outer = matches!(self.join_type, JoinType::Left | JoinType::Full);
+        let right_outer = matches!(self.join_type, JoinType::Right | JoinType::Full);
+
+        let mut blocks = vec![];
+        let mut stream = self.input.execute().await?;
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            if block.num_columns() == 0 || block.num_rows() == 0 {
+                continue;
+            }
+
+            let key_columns = JoinHashTable::key_columns(&self.funcs, &block)?;
+            let mut probe_indices = vec![];
+            let mut build_indices = vec![];
+            for row in 0..block.num_rows() {
+                let matched = match JoinHashTable::row_key(&key_columns, row)? {
+                    Some(key) => table.rows.get(&key),
+                    None => None,
+                };
+                if self.join_type == JoinType::NullAwareAnti {
+                    if Self::not_in(&table, &key_columns, row, matched.is_some())? {
+                        probe_indices.push(row as u32);
+                    }
+                    continue;
+                }
+                match matched {
+                    Some(_) if semi_or_anti => {
+                        if self.join_type == JoinType::Semi {
+                            probe_indices.push(row as u32);
+                        }
+                    }
+                    None if semi_or_anti => {
+                        if self.join_type == JoinType::Anti {
+                            probe_indices.push(row as u32);
+                        }
+                    }
+                    Some(rows) => {
+                        for build_row in rows {
+                            probe_indices.push(row as u32);
+                            build_indices.push(Some(*build_row));
+                            build_matched[*build_row as usize] = true;
+                        }
+                    }
+                    None if left_outer => {
+                        probe_indices.push(row as u32);
+                        build_indices.push(None);
+                    }
+                    None => {}
+                }
+            }
+
+            if probe_indices.is_empty() {
+                continue;
+            }
+            let mut columns = block.take(&probe_indices)?.columns().to_vec();
+            if !semi_or_anti {
+                columns.extend(Self::take_build_columns(
+                    &table,
+                    right_fields,
+                    build_indices,
+                )?);
+            }
+            blocks.push(DataBlock::create(self.schema.clone(), columns));
+        }
+
+        if right_outer {
+            let unmatched = build_matched
+                .iter()
+                .enumerate()
+                .filter(|(_, matched)| !**matched)
+                .map(|(row, _)| Some(row as u32))
+                .collect::<Vec<_>>();
+            if !unmatched.is_empty() {
+                let mut columns = Self::null_columns(left_fields, unmatched.len())?;
+                columns.extend(Self::take_build_columns(&table, right_fields, unmatched)?);
+                blocks.push(DataBlock::create(self.schema.clone(), columns));
+            }
+        }
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            blocks,
+        )))
+    }
+}
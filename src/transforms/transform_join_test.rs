@@ -0,0 +1,108 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_join() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::planners::*;
+    use crate::processors::*;
+    use crate::testdata;
+    use crate::transforms::*;
+
+    let test_source = testdata::NumberTestData::create();
+    let schema = test_source.number_schema_for_test()?;
+
+    // Build side.
+    let mut build = HashJoinBuildTransform::try_create(
+        schema.clone(),
+        vec![add(field("number"), constant(2))],
+        false,
+    )?;
+    build.connect_to(Arc::new(test_source.number_source_transform_for_test(4)?))?;
+    let build = Arc::new(build);
+
+    // Probe side.
+    let mut pipeline = Pipeline::create();
+    let a = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(a))?;
+    let b = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(b))?;
+
+    let join_schema = JoinPlan::join_schema(&schema, &schema, JoinType::Left);
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(HashJoinProbeTransform::try_create(
+            join_schema.clone(),
+            JoinType::Left,
+            vec![field("number")],
+            build.clone(),
+        )?))
+    })?;
+
+    let stream = pipeline.execute().await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+    let nulls: usize = blocks
+        .iter()
+        .map(|block| block.column(1).null_count())
+        .sum();
+    // 2,3 match 0,1 of the build side, 0,1 are filled with NULLs, in both probe pipes.
+    assert_eq!(8, rows);
+    assert_eq!(4, nulls);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_semi_anti_join() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::planners::*;
+    use crate::processors::*;
+    use crate::testdata;
+    use crate::transforms::*;
+
+    let test_source = testdata::NumberTestData::create();
+    let schema = test_source.number_schema_for_test()?;
+
+    // 1,2,3 of the probe side have matches.
+    let tests = vec![
+        (JoinType::Semi, 6),
+        (JoinType::Anti, 2),
+        (JoinType::NullAwareAnti, 2),
+    ];
+    for (join_type, expect) in tests {
+        let mut build = HashJoinBuildTransform::try_create(
+            schema.clone(),
+            vec![add(field("number"), constant(1))],
+            join_type == JoinType::NullAwareAnti,
+        )?;
+        build.connect_to(Arc::new(test_source.number_source_transform_for_test(4)?))?;
+        let build = Arc::new(build);
+
+        let mut pipeline = Pipeline::create();
+        let a = test_source.number_source_transform_for_test(4)?;
+        pipeline.add_source(Arc::new(a))?;
+        let b = test_source.number_source_transform_for_test(4)?;
+        pipeline.add_source(Arc::new(b))?;
+
+        let join_schema = JoinPlan::join_schema(&schema, &schema, join_type);
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(HashJoinProbeTransform::try_create(
+                join_schema.clone(),
+                join_type,
+                vec![field("number")],
+                build.clone(),
+            )?))
+        })?;
+
+        let stream = pipeline.execute().await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+        assert_eq!(expect, rows, "{}", join_type);
+        assert_eq!(1, blocks[0].num_columns());
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use tokio::sync::Mutex;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues::DataSchemaRef;
+use crate::error::FuseQueryResult;
+use crate::processors::{EmptyProcessor, IProcessor};
+
+/// Execute the input once and keep its blocks, the transform is shared by all the references
+/// of a materialized CTE and each execution outputs the same blocks.
+pub struct MaterializeTransform {
+    schema: DataSchemaRef,
+    blocks: Mutex<Option<Arc<Vec<DataBlock>>>>,
+    input: Arc<dyn IProcessor>,
+}
+
+impl MaterializeTransform {
+    pub fn try_create(schema: DataSchemaRef) -> FuseQueryResult<Self> {
+        Ok(MaterializeTransform {
+            schema,
+            blocks: Mutex::new(None),
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// Get the blocks, execute the input at the first time.
+    async fn materialized_blocks(&self) -> FuseQueryResult<Arc<Vec<DataBlock>>> {
+        let mut blocks = self.blocks.lock().await;
+        if let Some(ref blocks) = *blocks {
+            return Ok(blocks.clone());
+        }
+
+        let materialized = Arc::new(self.input.execute().await?.try_collect::<Vec<_>>().await?);
+        *blocks = Some(materialized.clone());
+        Ok(materialized)
+    }
+}
+
+#[async_trait]
+impl IProcessor for MaterializeTransform {
+    fn name(&self) -> &str {
+        "MaterializeTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        let blocks = self.materialized_blocks().await?;
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            blocks.as_ref().clone(),
+        )))
+    }
+}
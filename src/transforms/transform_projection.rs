@@ -7,6 +7,7 @@ use std::sync::Arc;
 
 use crate::datablocks::DataBlock;
 use crate::datastreams::{ExpressionStream, SendableDataBlockStream};
+use crate::datavalues;
 use crate::datavalues::DataSchemaRef;
 use crate::error::{FuseQueryError, FuseQueryResult};
 use crate::functions::Function;
@@ -48,10 +49,16 @@ impl ProjectionTransform {
         funcs: Vec<Function>,
     ) -> FuseQueryResult<DataBlock> {
         let mut arrays = Vec::with_capacity(funcs.len());
+        let mut unnested = Vec::with_capacity(funcs.len());
 
         for mut func in funcs {
+            unnested.push(func.is_array_join());
             arrays.push(func.eval(&block)?.to_array(block.num_rows())?);
         }
+        // The arrays of arrayJoin are unnested to the rows.
+        if unnested.iter().any(|v| *v) {
+            arrays = datavalues::data_array_unnest(&arrays, &unnested)?;
+        }
         Ok(DataBlock::create(projected_schema.clone(), arrays))
     }
 }
@@ -0,0 +1,69 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::compute::{lexsort_to_indices, SortOptions};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues::DataSchema;
+use crate::error::FuseQueryResult;
+use crate::functions::Function;
+use crate::planners::ExpressionPlan;
+use crate::processors::{EmptyProcessor, IProcessor, MergeSortProcessor};
+
+/// Sort all the blocks of one pipe, the sorted pipes are merged by MergeSortProcessor.
+pub struct SortPartialTransform {
+    funcs: Vec<Function>,
+    options: Vec<SortOptions>,
+    input: Arc<dyn IProcessor>,
+}
+
+impl SortPartialTransform {
+    pub fn try_create(order_by: Vec<ExpressionPlan>) -> FuseQueryResult<Self> {
+        let (funcs, options) = MergeSortProcessor::sort_descriptions(&order_by)?;
+        Ok(SortPartialTransform {
+            funcs,
+            options,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+}
+
+#[async_trait]
+impl IProcessor for SortPartialTransform {
+    fn name(&self) -> &str {
+        "SortPartialTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        let blocks = self.input.execute().await?.try_collect::<Vec<_>>().await?;
+
+        if blocks.is_empty() {
+            return Ok(Box::pin(DataBlockStream::create(
+                Arc::new(DataSchema::empty()),
+                None,
+                vec![],
+            )));
+        }
+
+        let block = DataBlock::concat_blocks(&blocks)?;
+        let columns = MergeSortProcessor::sort_columns(&block, &self.funcs, &self.options)?;
+        let indices = lexsort_to_indices(&columns)?;
+        let block = block.take(indices.value_slice(0, indices.len()))?;
+        Ok(Box::pin(DataBlockStream::create(
+            block.schema().clone(),
+            None,
+            vec![block],
+        )))
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_sort() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::datavalues::*;
+    use crate::planners::*;
+    use crate::processors::*;
+    use crate::testdata;
+    use crate::transforms::*;
+
+    let test_source = testdata::NumberTestData::create();
+    let mut pipeline = Pipeline::create();
+
+    let a = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(a))?;
+    let b = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(b))?;
+
+    if let PlanNode::Sort(plan) = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .sort(vec![ExpressionPlan::Sort {
+            expr: Box::new(field("number")),
+            asc: false,
+            nulls_first: false,
+        }])?
+        .build()?
+    {
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(SortPartialTransform::try_create(
+                plan.order_by.clone(),
+            )?))
+        })?;
+        pipeline.merge_processor_with(|| {
+            Ok(Box::new(MergeSortProcessor::try_create(
+                plan.order_by.clone(),
+                None,
+            )?))
+        })?;
+    }
+
+    let stream = pipeline.execute().await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let mut actual = vec![];
+    for block in &blocks {
+        let array = block
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        actual.extend(array.value_slice(0, array.len()).to_vec());
+    }
+    assert_eq!(vec![3, 3, 2, 2, 1, 1, 0, 0], actual);
+    Ok(())
+}
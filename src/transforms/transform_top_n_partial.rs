@@ -0,0 +1,100 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::compute::{lexsort_to_indices, SortOptions};
+use async_trait::async_trait;
+use tokio::stream::StreamExt;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues::DataSchema;
+use crate::error::FuseQueryResult;
+use crate::functions::Function;
+use crate::planners::ExpressionPlan;
+use crate::processors::{EmptyProcessor, IProcessor, MergeSortProcessor};
+
+/// The first n sorted rows of one pipe, the pipes are merged by MergeSortProcessor.
+///
+/// Only the current top n rows are kept between the blocks, so the memory is bounded by n.
+pub struct TopNPartialTransform {
+    funcs: Vec<Function>,
+    options: Vec<SortOptions>,
+    limit: usize,
+    input: Arc<dyn IProcessor>,
+}
+
+impl TopNPartialTransform {
+    pub fn try_create(order_by: Vec<ExpressionPlan>, limit: usize) -> FuseQueryResult<Self> {
+        let (funcs, options) = MergeSortProcessor::sort_descriptions(&order_by)?;
+        Ok(TopNPartialTransform {
+            funcs,
+            options,
+            limit,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// Sort the block and keep the first `limit` rows.
+    fn top_n(&self, block: &DataBlock) -> FuseQueryResult<DataBlock> {
+        let columns = MergeSortProcessor::sort_columns(block, &self.funcs, &self.options)?;
+        let indices = lexsort_to_indices(&columns)?;
+        let n = std::cmp::min(self.limit, indices.len());
+        block.take(indices.value_slice(0, n))
+    }
+
+    /// Merge the sorted top rows and the sorted rows of the new block, keep the first `limit` rows.
+    fn merge(&self, top: DataBlock, block: DataBlock) -> FuseQueryResult<DataBlock> {
+        let rows = top.num_rows();
+        let ranges = [(0, rows), (rows, rows + block.num_rows())];
+        let block = DataBlock::concat_blocks(&[top, block])?;
+        let columns = MergeSortProcessor::sort_columns(&block, &self.funcs, &self.options)?;
+        let indices = MergeSortProcessor::merge_indices(&columns, &ranges, Some(self.limit))?;
+        block.take(&indices)
+    }
+}
+
+#[async_trait]
+impl IProcessor for TopNPartialTransform {
+    fn name(&self) -> &str {
+        "TopNPartialTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        let mut top: Option<DataBlock> = None;
+        let mut stream = self.input.execute().await?;
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            if self.limit == 0 || block.num_rows() == 0 {
+                continue;
+            }
+
+            // Only the new block is sorted, the kept rows are sorted already.
+            let block = self.top_n(&block)?;
+            top = Some(match top {
+                Some(top) => self.merge(top, block)?,
+                None => block,
+            });
+        }
+
+        Ok(match top {
+            Some(block) => Box::pin(DataBlockStream::create(
+                block.schema().clone(),
+                None,
+                vec![block],
+            )),
+            None => Box::pin(DataBlockStream::create(
+                Arc::new(DataSchema::empty()),
+                None,
+                vec![],
+            )),
+        })
+    }
+}
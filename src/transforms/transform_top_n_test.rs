@@ -0,0 +1,133 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_top_n() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::datavalues::*;
+    use crate::planners::*;
+    use crate::processors::*;
+    use crate::testdata;
+    use crate::transforms::*;
+
+    let test_source = testdata::NumberTestData::create();
+    let mut pipeline = Pipeline::create();
+
+    let a = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(a))?;
+    let b = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(b))?;
+
+    if let PlanNode::TopN(plan) = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .top_n(
+            vec![ExpressionPlan::Sort {
+                expr: Box::new(field("number")),
+                asc: false,
+                nulls_first: false,
+            }],
+            3,
+        )?
+        .build()?
+    {
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(TopNPartialTransform::try_create(
+                plan.order_by.clone(),
+                plan.n,
+            )?))
+        })?;
+        pipeline.merge_processor_with(|| {
+            Ok(Box::new(MergeSortProcessor::try_create(
+                plan.order_by.clone(),
+                Some(plan.n),
+            )?))
+        })?;
+    }
+
+    let stream = pipeline.execute().await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let mut actual = vec![];
+    for block in &blocks {
+        let array = block
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        actual.extend(array.value_slice(0, array.len()).to_vec());
+    }
+    assert_eq!(vec![3, 3, 2], actual);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_top_n_by_keys() -> crate::error::FuseQueryResult<()> {
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    use crate::datavalues::*;
+    use crate::planners::*;
+    use crate::processors::*;
+    use crate::testdata;
+    use crate::transforms::*;
+
+    let test_source = testdata::NumberTestData::create();
+    let mut pipeline = Pipeline::create();
+
+    let a = test_source.number_source_transform_for_test(30000)?;
+    pipeline.add_source(Arc::new(a))?;
+    let b = test_source.number_source_transform_for_test(30000)?;
+    pipeline.add_source(Arc::new(b))?;
+
+    if let PlanNode::TopN(plan) = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .top_n(
+            vec![
+                ExpressionPlan::Sort {
+                    expr: Box::new(ExpressionPlan::BinaryExpression {
+                        left: Box::new(field("number")),
+                        op: "%".to_string(),
+                        right: Box::new(constant(7u64)),
+                    }),
+                    asc: true,
+                    nulls_first: false,
+                },
+                ExpressionPlan::Sort {
+                    expr: Box::new(field("number")),
+                    asc: false,
+                    nulls_first: false,
+                },
+            ],
+            4,
+        )?
+        .build()?
+    {
+        pipeline.add_simple_transform(|| {
+            Ok(Box::new(TopNPartialTransform::try_create(
+                plan.order_by.clone(),
+                plan.n,
+            )?))
+        })?;
+        pipeline.merge_processor_with(|| {
+            Ok(Box::new(MergeSortProcessor::try_create(
+                plan.order_by.clone(),
+                Some(plan.n),
+            )?))
+        })?;
+    }
+
+    let stream = pipeline.execute().await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let mut actual = vec![];
+    for block in &blocks {
+        let array = block
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        actual.extend(array.value_slice(0, array.len()).to_vec());
+    }
+    // The rows of many blocks of each pipe, number % 7 = 0 in the descending order.
+    assert_eq!(vec![29995, 29995, 29988, 29988], actual);
+    Ok(())
+}
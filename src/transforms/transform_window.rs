@@ -0,0 +1,313 @@
+// Copyright 2020 The FuseQuery Authors.
+//
+// Code is licensed under AGPL License, Version 3.0.
+
+use std::sync::Arc;
+
+use arrow::compute::lexsort_to_indices;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+
+use crate::datablocks::DataBlock;
+use crate::datastreams::{DataBlockStream, SendableDataBlockStream};
+use crate::datavalues::{DataSchemaRef, DataValue};
+use crate::error::{FuseQueryError, FuseQueryResult};
+use crate::planners::{ExpressionPlan, WindowFrame, WindowFrameBound, WindowFrameUnits};
+use crate::processors::{EmptyProcessor, IProcessor, MergeSortProcessor};
+
+/// Compute the window functions over all the input blocks of one pipe.
+///
+/// For each window function the rows are sorted by the partition keys then the order keys,
+/// the framed aggregates accumulate the rows of the frame with the aggregator functions.
+pub struct WindowTransform {
+    schema: DataSchemaRef,
+    window_exprs: Vec<ExpressionPlan>,
+    input: Arc<dyn IProcessor>,
+}
+
+impl WindowTransform {
+    pub fn try_create(
+        schema: DataSchemaRef,
+        window_exprs: Vec<ExpressionPlan>,
+    ) -> FuseQueryResult<Self> {
+        Ok(WindowTransform {
+            schema,
+            window_exprs,
+            input: Arc::new(EmptyProcessor::create()),
+        })
+    }
+
+    /// The values of the expressions per row.
+    fn row_values(
+        block: &DataBlock,
+        exprs: &[ExpressionPlan],
+    ) -> FuseQueryResult<Vec<Vec<DataValue>>> {
+        let rows = block.num_rows();
+        let mut columns = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            columns.push(expr.to_function()?.eval(block)?.to_array(rows)?);
+        }
+
+        let mut values = Vec::with_capacity(rows);
+        for row in 0..rows {
+            let mut row_values = Vec::with_capacity(columns.len());
+            for column in &columns {
+                row_values.push(DataValue::try_from_array(column, row)?);
+            }
+            values.push(row_values);
+        }
+        Ok(values)
+    }
+
+    /// The frame [start, end) of the row at position pos of the partition,
+    /// peers is the range of the rows with the same order keys as the row.
+    fn frame_range(
+        frame: &WindowFrame,
+        pos: usize,
+        len: usize,
+        peers: (usize, usize),
+    ) -> (usize, usize) {
+        let start = match (frame.units, frame.start) {
+            (_, WindowFrameBound::Preceding(None)) => 0,
+            (_, WindowFrameBound::Following(None)) => len,
+            (WindowFrameUnits::Range, _) => peers.0,
+            (WindowFrameUnits::Rows, WindowFrameBound::Preceding(Some(n))) => {
+                pos.saturating_sub(n as usize)
+            }
+            (WindowFrameUnits::Rows, WindowFrameBound::CurrentRow) => pos,
+            (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => pos + n as usize,
+        };
+        let end = match (frame.units, frame.end) {
+            (_, WindowFrameBound::Preceding(None)) => 0,
+            (_, WindowFrameBound::Following(None)) => len,
+            (WindowFrameUnits::Range, _) => peers.1,
+            (WindowFrameUnits::Rows, WindowFrameBound::Preceding(Some(n))) => {
+                (pos + 1).saturating_sub(n as usize)
+            }
+            (WindowFrameUnits::Rows, WindowFrameBound::CurrentRow) => pos + 1,
+            (WindowFrameUnits::Rows, WindowFrameBound::Following(Some(n))) => pos + n as usize + 1,
+        };
+        let end = end.min(len);
+        (start.min(end), end)
+    }
+
+    /// The offset of lag/lead, 1 by default.
+    fn offset(op: &str, args: &[ExpressionPlan]) -> FuseQueryResult<usize> {
+        match args.get(1) {
+            None => Ok(1),
+            Some(ExpressionPlan::Constant(DataValue::UInt64(Some(n)))) => Ok(*n as usize),
+            Some(other) => Err(FuseQueryError::Plan(format!(
+                "The offset of {} must be a non-negative integer constant, but got {:?}",
+                op, other
+            ))),
+        }
+    }
+
+    /// Compute one window function, the values are in the input row order.
+    fn compute(expr: &ExpressionPlan, block: &DataBlock) -> FuseQueryResult<Vec<DataValue>> {
+        let (func, partition_by, order_by, frame) = match expr {
+            ExpressionPlan::Window {
+                func,
+                partition_by,
+                order_by,
+                frame,
+            } => (func.as_ref(), partition_by, order_by, frame),
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Expression {:?} is not a window function",
+                    expr
+                )))
+            }
+        };
+        let (op, args) = match func {
+            ExpressionPlan::Function { op, args, .. } => (op.to_lowercase(), args),
+            _ => {
+                return Err(FuseQueryError::Internal(format!(
+                    "Unsupported window function: {:?}",
+                    func
+                )))
+            }
+        };
+
+        // Sort the rows by the partition keys then the order keys.
+        let rows = block.num_rows();
+        let mut sort_exprs: Vec<ExpressionPlan> = partition_by
+            .iter()
+            .map(|expr| ExpressionPlan::Sort {
+                expr: Box::new(expr.clone()),
+                asc: true,
+                nulls_first: false,
+            })
+            .collect();
+        sort_exprs.extend(order_by.iter().cloned());
+        let indices: Vec<u32> = if sort_exprs.is_empty() {
+            (0..rows as u32).collect()
+        } else {
+            let (funcs, options) = MergeSortProcessor::sort_descriptions(&sort_exprs)?;
+            let columns = MergeSortProcessor::sort_columns(block, &funcs, &options)?;
+            let indices = lexsort_to_indices(&columns)?;
+            indices.value_slice(0, indices.len()).to_vec()
+        };
+        let sorted = block.take(&indices)?;
+        let partition_keys = Self::row_values(&sorted, partition_by)?;
+        let order_keys = Self::row_values(&sorted, order_by)?;
+
+        // The arguments of lag/lead.
+        let (offset, arg_values, default_values) = match op.as_str() {
+            "lag" | "lead" => {
+                let arg_values = Self::row_values(&sorted, &args[0..1])?;
+                let default_values = if args.len() > 2 {
+                    Some(Self::row_values(&sorted, &args[2..3])?)
+                } else {
+                    None
+                };
+                (Self::offset(&op, args)?, arg_values, default_values)
+            }
+            _ => (0, vec![], None),
+        };
+        let aggregator = if func.is_aggregate_function() {
+            Some(func.to_function()?)
+        } else {
+            None
+        };
+        let frame = frame.unwrap_or_else(WindowFrame::default_frame);
+
+        let mut values = vec![DataValue::Null; rows];
+        let mut partition_start = 0;
+        while partition_start < rows {
+            let mut partition_end = partition_start + 1;
+            while partition_end < rows
+                && partition_keys[partition_end] == partition_keys[partition_start]
+            {
+                partition_end += 1;
+            }
+            let len = partition_end - partition_start;
+
+            // The peer ranges and the dense rank of the rows in the partition.
+            let mut peers = Vec::with_capacity(len);
+            let mut dense_ranks = Vec::with_capacity(len);
+            let mut peer_start = 0;
+            let mut dense_rank = 0;
+            while peer_start < len {
+                let mut peer_end = peer_start + 1;
+                while peer_end < len
+                    && order_keys[partition_start + peer_end]
+                        == order_keys[partition_start + peer_start]
+                {
+                    peer_end += 1;
+                }
+                dense_rank += 1;
+                for _ in peer_start..peer_end {
+                    peers.push((peer_start, peer_end));
+                    dense_ranks.push(dense_rank);
+                }
+                peer_start = peer_end;
+            }
+
+            // The running aggregate of the frames starting at the partition start.
+            let mut running = aggregator.clone();
+            let mut running_end = 0;
+            for pos in 0..len {
+                let row = partition_start + pos;
+                let value = match op.as_str() {
+                    "row_number" => DataValue::UInt64(Some(pos as u64 + 1)),
+                    "rank" => DataValue::UInt64(Some(peers[pos].0 as u64 + 1)),
+                    "dense_rank" => DataValue::UInt64(Some(dense_ranks[pos])),
+                    "lag" | "lead" => {
+                        let target = if op == "lag" {
+                            pos.checked_sub(offset)
+                        } else {
+                            Some(pos + offset).filter(|target| *target < len)
+                        };
+                        match (target, &default_values) {
+                            (Some(target), _) => arg_values[partition_start + target][0].clone(),
+                            (None, Some(default_values)) => default_values[row][0].clone(),
+                            (None, None) => DataValue::Null,
+                        }
+                    }
+                    _ => {
+                        let aggregator = aggregator.as_ref().ok_or_else(|| {
+                            FuseQueryError::Internal(format!("Unsupported window function: {}", op))
+                        })?;
+                        let (start, end) = Self::frame_range(&frame, pos, len, peers[pos]);
+                        let frame_indices = |from: usize, to: usize| {
+                            ((partition_start + from) as u32..(partition_start + to) as u32)
+                                .collect::<Vec<_>>()
+                        };
+                        let value = match running.as_mut() {
+                            Some(running) if start == 0 && end >= running_end => {
+                                if end > running_end {
+                                    let rows = frame_indices(running_end, end);
+                                    running.accumulate(&sorted.take(&rows)?)?;
+                                    running_end = end;
+                                }
+                                running.merge_result()?
+                            }
+                            _ => {
+                                let mut func = aggregator.clone();
+                                if end > start {
+                                    func.accumulate(&sorted.take(&frame_indices(start, end))?)?;
+                                }
+                                func.merge_result()?
+                            }
+                        };
+                        // The aggregates of the empty frame are NULL except count.
+                        match value {
+                            DataValue::Null if op == "count" => DataValue::UInt64(Some(0)),
+                            value => value,
+                        }
+                    }
+                };
+                values[indices[row] as usize] = value;
+            }
+            partition_start = partition_end;
+        }
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl IProcessor for WindowTransform {
+    fn name(&self) -> &str {
+        "WindowTransform"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn IProcessor>) -> FuseQueryResult<()> {
+        self.input = input;
+        Ok(())
+    }
+
+    async fn execute(&self) -> FuseQueryResult<SendableDataBlockStream> {
+        let blocks = self.input.execute().await?.try_collect::<Vec<_>>().await?;
+        if blocks.is_empty() {
+            return Ok(Box::pin(DataBlockStream::create(
+                self.schema.clone(),
+                None,
+                vec![],
+            )));
+        }
+
+        let block = DataBlock::concat_blocks(&blocks)?;
+        let mut columns = block.columns().to_vec();
+        for (i, expr) in self.window_exprs.iter().enumerate() {
+            let values = Self::compute(expr, &block)?;
+            // The aggregate result type follows the values like AggregateFinalTransform.
+            let data_type = match values.iter().find(|v| !matches!(v, DataValue::Null)) {
+                Some(v) => v.data_type(),
+                None => self
+                    .schema
+                    .field(block.num_columns() + i)
+                    .data_type()
+                    .clone(),
+            };
+            columns.push(DataValue::try_into_data_array(&values, &data_type)?);
+        }
+
+        let block = DataBlock::create(self.schema.clone(), columns);
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            vec![block],
+        )))
+    }
+}